@@ -0,0 +1,774 @@
+//! Headless command line frontend for the launcher.
+//!
+//! Runs on top of the launcher library without a webview, so build servers and
+//! dedicated machines can script profile installs and launches. Progress events
+//! that the UI normally receives are printed to stdout, either as readable text
+//! or as one JSON object per line.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use noriskclient_launcher_v3_lib::commands::file_command;
+use noriskclient_launcher_v3_lib::commands::profile_command::{self, CreateProfileParams};
+use noriskclient_launcher_v3_lib::error::{AppError, CommandError, Result};
+use noriskclient_launcher_v3_lib::integrations::mod_updates::{self, ModUpdateStatus};
+use noriskclient_launcher_v3_lib::integrations::modrinth;
+use noriskclient_launcher_v3_lib::minecraft::installer::{self, LaunchMode};
+use noriskclient_launcher_v3_lib::minecraft::minecraft_auth::AccountType;
+use noriskclient_launcher_v3_lib::state::event_state::{EventPayload, EventType};
+use noriskclient_launcher_v3_lib::state::profile_state::{ModLoader, Profile};
+use noriskclient_launcher_v3_lib::state::State;
+use serde::Serialize;
+use std::future::Future;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+#[derive(Parser)]
+#[command(name = "geg-launcher-cli", version, about = "Headless JS Launcher")]
+struct Cli {
+    /// How progress events and results are printed
+    #[arg(long, value_enum, global = true, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human readable lines
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage profiles
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+    /// Manage Minecraft accounts
+    #[command(subcommand)]
+    Account(AccountCommand),
+    /// Manage Modrinth mods of a profile
+    #[command(subcommand)]
+    Mods(ModsCommand),
+    /// Import modpacks
+    #[command(subcommand)]
+    Pack(PackCommand),
+    /// Maintain the shared content store
+    #[command(subcommand)]
+    Store(StoreCommand),
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// List all user profiles
+    List,
+    /// Create a new profile
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        game_version: String,
+        /// vanilla, fabric, quilt, forge or neoforge
+        #[arg(long, default_value = "vanilla")]
+        loader: String,
+        #[arg(long)]
+        loader_version: Option<String>,
+        /// Optional GEG pack id to select for the profile
+        #[arg(long)]
+        pack: Option<String>,
+        #[arg(long)]
+        shared_minecraft_folder: bool,
+    },
+    /// Download everything a profile needs without starting the game
+    Install { profile_id: Uuid },
+    /// Install (if needed) and print the resolved launch command without starting the game
+    DryRun {
+        profile_id: Uuid,
+        /// Also write a launch.sh into the profile's game directory
+        #[arg(long)]
+        script: bool,
+        /// Only use cached files, fail with a list of everything missing
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Install (if needed) and launch a profile
+    Launch {
+        profile_id: Uuid,
+        /// Join a singleplayer world directly
+        #[arg(long, conflicts_with = "server")]
+        world: Option<String>,
+        /// Connect to a server directly
+        #[arg(long)]
+        server: Option<String>,
+        /// Keep running until the game exits and use its exit code
+        #[arg(long)]
+        wait: bool,
+        /// Only use cached files, fail with a list of everything missing
+        #[arg(long)]
+        offline: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AccountCommand {
+    /// List all stored accounts
+    List,
+    /// Add an offline account and make it active
+    AddOffline { username: String },
+    /// Make an account the active one
+    Use { account_id: Uuid },
+}
+
+#[derive(Subcommand)]
+enum ModsCommand {
+    /// Add a Modrinth project to a profile (latest compatible version by default)
+    Add {
+        profile_id: Uuid,
+        /// Modrinth project id or slug
+        project: String,
+        /// Specific Modrinth version id
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Update all mods with updates enabled at once, all-or-nothing
+    Update {
+        profile_id: Uuid,
+        /// Only update this mod instance
+        #[arg(long)]
+        mod_id: Option<Uuid>,
+        /// Only print the planned version changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PackCommand {
+    /// Import a .mrpack, CurseForge .zip or .GEGpack file as a new profile
+    Import { file: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum StoreCommand {
+    /// Remove stored files that no profile or pack references anymore
+    Gc,
+}
+
+#[derive(Serialize)]
+struct ProfileSummary<'a> {
+    id: Uuid,
+    name: &'a str,
+    game_version: &'a str,
+    loader: &'a str,
+    loader_version: Option<&'a str>,
+    mods: usize,
+}
+
+#[derive(Serialize)]
+struct AccountSummary<'a> {
+    id: Uuid,
+    username: &'a str,
+    account_type: AccountType,
+    active: bool,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Library logging goes to stderr so stdout stays parseable
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"))
+        .target(env_logger::Target::Stderr)
+        .init();
+
+    let cli = Cli::parse();
+
+    if let Err(e) = State::init_headless().await {
+        eprintln!("Failed to initialize launcher state: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    match run(cli).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<ExitCode> {
+    let state = State::get().await?;
+    let mut events = state.event_state.subscribe();
+    let format = cli.format;
+
+    match cli.command {
+        Command::Profile(ProfileCommand::List) => {
+            let mut profiles = state.profile_manager.list_profiles().await?;
+            profiles.sort_by_key(|p| p.name.to_lowercase());
+            let summaries: Vec<ProfileSummary> = profiles.iter().map(summarize).collect();
+            match format {
+                OutputFormat::Json => print_json(&summaries),
+                OutputFormat::Text => {
+                    for p in &summaries {
+                        println!(
+                            "{}  {}  {} {}{}  ({} mods)",
+                            p.id,
+                            p.name,
+                            p.game_version,
+                            p.loader,
+                            p.loader_version
+                                .map(|v| format!(" {}", v))
+                                .unwrap_or_default(),
+                            p.mods
+                        );
+                    }
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Profile(ProfileCommand::Create {
+            name,
+            game_version,
+            loader,
+            loader_version,
+            pack,
+            shared_minecraft_folder,
+        }) => {
+            let params = CreateProfileParams {
+                name,
+                game_version,
+                loader,
+                loader_version,
+                selected_GEG_pack_id: pack,
+                use_shared_minecraft_folder: Some(shared_minecraft_folder),
+            };
+            let id = profile_command::create_profile(params)
+                .await
+                .map_err(command_error)?;
+            print_created(format, "profile_id", id);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Profile(ProfileCommand::Install { profile_id }) => {
+            let profile = find_profile(&state, profile_id).await?;
+            let credentials = state
+                .minecraft_account_manager_v2
+                .get_active_account()
+                .await
+                .unwrap_or(None);
+            with_events(&mut events, format, async {
+                installer::install_minecraft_version(
+                    &profile.game_version,
+                    profile.loader.as_str(),
+                    &profile,
+                    credentials,
+                    None,
+                    None,
+                    None,
+                    LaunchMode::InstallOnly,
+                    false,
+                )
+                .await
+            })
+            .await?;
+            print_status(format, "installed", profile_id);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Profile(ProfileCommand::DryRun {
+            profile_id,
+            script,
+            offline,
+        }) => {
+            let dry_run = with_events(&mut events, format, async {
+                profile_command::dry_run_profile_launch(profile_id, Some(script), Some(offline))
+                    .await
+                    .map_err(command_error)
+            })
+            .await?;
+            match format {
+                OutputFormat::Json => print_json(&dry_run),
+                OutputFormat::Text => {
+                    println!("Working dir: {}", dry_run.working_dir.display());
+                    if !dry_run.wrapper.is_empty() {
+                        println!("Wrapper: {}", shell_words::join(&dry_run.wrapper));
+                    }
+                    println!("Java: {}", dry_run.java_path.display());
+                    println!("Main class: {}", dry_run.main_class);
+                    println!("Environment:");
+                    for (key, value) in &dry_run.env {
+                        println!("  {}={}", key, value);
+                    }
+                    println!("JVM arguments:");
+                    for arg in &dry_run.jvm_args {
+                        println!("  {}", arg);
+                    }
+                    println!("Classpath:");
+                    for entry in &dry_run.classpath {
+                        let priority = entry
+                            .priority
+                            .map(|p| format!(", priority {}", p))
+                            .unwrap_or_default();
+                        println!("  {} ({:?}{})", entry.path, entry.source, priority);
+                    }
+                    println!("Game arguments:");
+                    for arg in &dry_run.game_args {
+                        println!("  {}", arg);
+                    }
+                    if let Some(script) = &dry_run.launch_script {
+                        println!("Launch script: {}", script.display());
+                    }
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Profile(ProfileCommand::Launch {
+            profile_id,
+            world,
+            server,
+            wait,
+            offline,
+        }) => {
+            let mut launch_events = state.event_state.subscribe();
+            let exit_code = with_events(&mut events, format, async {
+                profile_command::launch_profile(profile_id, world, server, None, None, Some(offline))
+                    .await
+                    .map_err(command_error)?;
+                wait_for_launch(&mut launch_events, profile_id).await?;
+                if wait {
+                    wait_for_exit(&mut launch_events, profile_id).await
+                } else {
+                    Ok(0)
+                }
+            })
+            .await?;
+            Ok(ExitCode::from(exit_code.clamp(0, 255) as u8))
+        }
+        Command::Account(AccountCommand::List) => {
+            let accounts = state
+                .minecraft_account_manager_v2
+                .get_all_accounts()
+                .await?;
+            let summaries: Vec<AccountSummary> = accounts
+                .iter()
+                .map(|a| AccountSummary {
+                    id: a.id,
+                    username: &a.username,
+                    account_type: a.account_type,
+                    active: a.active,
+                })
+                .collect();
+            match format {
+                OutputFormat::Json => print_json(&summaries),
+                OutputFormat::Text => {
+                    for a in &summaries {
+                        println!(
+                            "{} {}  {}  {:?}",
+                            if a.active { "*" } else { " " },
+                            a.id,
+                            a.username,
+                            a.account_type
+                        );
+                    }
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Account(AccountCommand::AddOffline { username }) => {
+            let account = state
+                .minecraft_account_manager_v2
+                .add_offline_account(&username)
+                .await?;
+            print_created(format, "account_id", account.id);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Account(AccountCommand::Use { account_id }) => {
+            state
+                .minecraft_account_manager_v2
+                .set_active_account(account_id)
+                .await?;
+            match format {
+                OutputFormat::Json => {
+                    print_json(&serde_json::json!({ "active_account": account_id }))
+                }
+                OutputFormat::Text => println!("Active account: {}", account_id),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Mods(ModsCommand::Add {
+            profile_id,
+            project,
+            version,
+        }) => {
+            let profile = state.profile_manager.get_profile(profile_id).await?;
+            let modrinth_version = match version {
+                Some(version_id) => modrinth::get_version_details(version_id).await?,
+                None => latest_compatible_version(&profile, &project)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::NotFound(format!(
+                            "No version of '{}' for {} {}",
+                            project,
+                            profile.loader.as_str(),
+                            profile.game_version
+                        ))
+                    })?,
+            };
+            let primary_file = modrinth_version
+                .files
+                .iter()
+                .find(|f| f.primary)
+                .or_else(|| modrinth_version.files.first())
+                .ok_or_else(|| AppError::ModrinthPrimaryFileNotFound {
+                    version_id: modrinth_version.id.clone(),
+                })?;
+
+            with_events(&mut events, format, async {
+                state
+                    .profile_manager
+                    .add_modrinth_mod(
+                        profile_id,
+                        modrinth_version.project_id.clone(),
+                        modrinth_version.id.clone(),
+                        primary_file.filename.clone(),
+                        primary_file.url.clone(),
+                        primary_file.hashes.sha1.clone(),
+                        Some(modrinth_version.name.clone()),
+                        Some(modrinth_version.version_number.clone()),
+                        Some(modrinth_version.loaders.clone()),
+                        Some(modrinth_version.game_versions.clone()),
+                        true,
+                    )
+                    .await
+            })
+            .await?;
+            print_status(format, "added", profile_id);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Mods(ModsCommand::Update {
+            profile_id,
+            mod_id,
+            dry_run,
+        }) => {
+            let result = with_events(
+                &mut events,
+                format,
+                mod_updates::update_mods(profile_id, mod_id, dry_run),
+            )
+            .await?;
+            if result.applied || result.rollback_error.is_some() {
+                state.event_state.trigger_profile_update(profile_id).await?;
+            }
+
+            match format {
+                OutputFormat::Json => print_json(&result),
+                OutputFormat::Text if result.plan.updates.is_empty() => {
+                    println!("All mods are up to date.")
+                }
+                OutputFormat::Text if dry_run => {
+                    for update in &result.plan.updates {
+                        println!(
+                            "{}: {} -> {}{}",
+                            update.name,
+                            update.from_version.as_deref().unwrap_or("?"),
+                            update.to_version,
+                            if update.major_version_change {
+                                " (major)"
+                            } else {
+                                ""
+                            }
+                        );
+                    }
+                }
+                OutputFormat::Text => {
+                    for r in &result.results {
+                        let status = match &r.status {
+                            ModUpdateStatus::Updated => "updated".to_string(),
+                            ModUpdateStatus::NotApplied => "not applied".to_string(),
+                            ModUpdateStatus::Failed { error } => format!("failed: {}", error),
+                        };
+                        println!(
+                            "{}: {} -> {} ({})",
+                            r.name,
+                            r.from_version.as_deref().unwrap_or("?"),
+                            r.to_version,
+                            status
+                        );
+                    }
+                    if let (Some(error), Some(snapshot_id)) =
+                        (&result.rollback_error, result.snapshot_id)
+                    {
+                        println!("Rolling back failed: {}", error);
+                        println!("Restore snapshot {} to undo the updated mods.", snapshot_id);
+                    } else if !result.applied {
+                        println!("No mods were changed.");
+                    }
+                }
+            }
+            if !dry_run && !result.plan.updates.is_empty() && !result.applied {
+                return Ok(ExitCode::FAILURE);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Pack(PackCommand::Import { file }) => {
+            let id = with_events(&mut events, format, async {
+                profile_command::import_profile(file.to_string_lossy().into_owned())
+                    .await
+                    .map_err(command_error)
+            })
+            .await?;
+            print_created(format, "profile_id", id);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Store(StoreCommand::Gc) => {
+            let report = file_command::collect_content_store_garbage()
+                .await
+                .map_err(command_error)?;
+            match format {
+                OutputFormat::Json => print_json(&report),
+                OutputFormat::Text => println!(
+                    "Removed {} blobs ({} bytes), kept {}",
+                    report.removed_blobs, report.freed_bytes, report.kept_blobs
+                ),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+/// Drives `operation` to completion while printing every event emitted in the meantime.
+async fn with_events<T>(
+    events: &mut broadcast::Receiver<EventPayload>,
+    format: OutputFormat,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::pin!(operation);
+    loop {
+        tokio::select! {
+            result = &mut operation => {
+                while let Ok(event) = events.try_recv() {
+                    print_event(format, &event);
+                }
+                return result;
+            }
+            received = events.recv() => match received {
+                Ok(event) => print_event(format, &event),
+                Err(RecvError::Lagged(skipped)) => {
+                    log::warn!("Event output lagged, skipped {} events", skipped);
+                }
+                Err(RecvError::Closed) => return operation.await,
+            }
+        }
+    }
+}
+
+/// Waits until the launch task spawned by `launch_profile` reports success or failure.
+async fn wait_for_launch(
+    events: &mut broadcast::Receiver<EventPayload>,
+    profile_id: Uuid,
+) -> Result<()> {
+    loop {
+        let event = next_event(events).await?;
+        if event.target_id != Some(profile_id) {
+            continue;
+        }
+        match event.event_type {
+            EventType::LaunchSuccessful => return Ok(()),
+            EventType::Error => {
+                return Err(AppError::ProcessSpawnFailed(
+                    event.error.unwrap_or(event.message),
+                ))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Waits for the game process of `profile_id` to exit and returns its exit code.
+async fn wait_for_exit(
+    events: &mut broadcast::Receiver<EventPayload>,
+    profile_id: Uuid,
+) -> Result<i32> {
+    loop {
+        let event = next_event(events).await?;
+        if event.event_type != EventType::MinecraftProcessExited {
+            continue;
+        }
+        // The exit payload is serialized into the message field
+        let Ok(payload) = serde_json::from_str::<serde_json::Value>(&event.message) else {
+            continue;
+        };
+        if payload["profile_id"].as_str() != Some(profile_id.to_string().as_str()) {
+            continue;
+        }
+        let exit_code = payload["exit_code"].as_i64().unwrap_or(-1) as i32;
+        let success = payload["success"].as_bool().unwrap_or(false);
+        return Ok(if success {
+            0
+        } else if exit_code == 0 {
+            1
+        } else {
+            exit_code
+        });
+    }
+}
+
+async fn next_event(events: &mut broadcast::Receiver<EventPayload>) -> Result<EventPayload> {
+    loop {
+        match events.recv().await {
+            Ok(event) => return Ok(event),
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => {
+                return Err(AppError::EventError("Event channel closed".to_string()))
+            }
+        }
+    }
+}
+
+async fn find_profile(state: &State, profile_id: Uuid) -> Result<Profile> {
+    if let Ok(profile) = state.profile_manager.get_profile(profile_id).await {
+        return Ok(profile);
+    }
+    state
+        .GEG_version_manager
+        .get_profile_by_id(profile_id)
+        .await
+        .ok_or(AppError::ProfileNotFound(profile_id))
+}
+
+/// Returns the newest Modrinth version of `project` matching the profile's loader and game version.
+async fn latest_compatible_version(
+    profile: &Profile,
+    project: &str,
+) -> Result<Option<modrinth::ModrinthVersion>> {
+    let loaders = match profile.loader {
+        ModLoader::Vanilla => None,
+        loader => Some(vec![loader.as_str().to_string()]),
+    };
+    let versions = modrinth::get_mod_versions(
+        project.to_string(),
+        loaders,
+        Some(vec![profile.game_version.clone()]),
+    )
+    .await?;
+    // Modrinth returns versions newest first
+    Ok(versions.into_iter().next())
+}
+
+fn summarize(profile: &Profile) -> ProfileSummary<'_> {
+    ProfileSummary {
+        id: profile.id,
+        name: &profile.name,
+        game_version: &profile.game_version,
+        loader: profile.loader.as_str(),
+        loader_version: profile.loader_version.as_deref(),
+        mods: profile.mods.len(),
+    }
+}
+
+fn print_event(format: OutputFormat, event: &EventPayload) {
+    match format {
+        OutputFormat::Json => print_json(event),
+        OutputFormat::Text => {
+            if event.event_type == EventType::MinecraftOutput {
+                println!("{}", event.message);
+                return;
+            }
+            let progress = event
+                .progress
+                .map(|p| format!(" ({:.0}%)", p * 100.0))
+                .unwrap_or_default();
+            match &event.error {
+                Some(error) => println!(
+                    "[{:?}] {}{} - error: {}",
+                    event.event_type, event.message, progress, error
+                ),
+                None => println!("[{:?}] {}{}", event.event_type, event.message, progress),
+            }
+        }
+    }
+}
+
+fn print_created(format: OutputFormat, key: &str, id: Uuid) {
+    match format {
+        OutputFormat::Json => print_json(&serde_json::json!({ key: id })),
+        OutputFormat::Text => println!("{}", id),
+    }
+}
+
+fn print_status(format: OutputFormat, status: &str, profile_id: Uuid) {
+    match format {
+        OutputFormat::Json => {
+            print_json(&serde_json::json!({ "status": status, "profile_id": profile_id }))
+        }
+        OutputFormat::Text => println!("Profile {}: {}", profile_id, status),
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string(value) {
+        Ok(line) => println!("{}", line),
+        Err(e) => eprintln!("Failed to serialize output: {}", e),
+    }
+}
+
+fn command_error(error: CommandError) -> AppError {
+    AppError::Other(error.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn parses_launch_options_and_rejects_conflicts() {
+        Cli::command().debug_assert();
+
+        let id = Uuid::new_v4().to_string();
+        let cli = Cli::try_parse_from([
+            "geg-launcher-cli",
+            "profile",
+            "launch",
+            id.as_str(),
+            "--server",
+            "play.example.net",
+            "--wait",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert!(cli.format == OutputFormat::Json);
+        match cli.command {
+            Command::Profile(ProfileCommand::Launch {
+                profile_id,
+                world,
+                server,
+                wait,
+                offline,
+            }) => {
+                assert_eq!(profile_id.to_string(), id);
+                assert_eq!(world, None);
+                assert_eq!(server.as_deref(), Some("play.example.net"));
+                assert!(wait && !offline);
+            }
+            _ => panic!("expected profile launch"),
+        }
+
+        // Quick play can only target a world or a server
+        assert!(Cli::try_parse_from([
+            "geg-launcher-cli",
+            "profile",
+            "launch",
+            id.as_str(),
+            "--world",
+            "New World",
+            "--server",
+            "play.example.net",
+        ])
+        .is_err());
+        assert!(
+            Cli::try_parse_from(["geg-launcher-cli", "profile", "launch", "not-a-uuid"]).is_err()
+        );
+    }
+}
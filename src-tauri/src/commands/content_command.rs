@@ -3,6 +3,7 @@ use crate::error::{AppError, CommandError};
 use crate::integrations::modrinth::ModrinthVersion; // Added for new payload
 use crate::integrations::curseforge::CurseForgeFile; // Added for CurseForge support
 use crate::integrations::unified_mod::UnifiedVersion; // Added for unified version support
+use crate::integrations::mod_dependencies; // Dependency check after adding/updating mods
 use crate::state::profile_state::ModSource;
 use crate::integrations::unified_mod::ModPlatform; // Import unified ModPlatform
 use crate::state::state_manager::State as AppStateManager;
@@ -768,7 +769,9 @@ pub async fn install_content_to_profile(
                     let profile_manager = &state.profile_manager;
 
                     // Use the new unified method for both platforms with dependency installation
-                    profile_manager.add_mod_from_payload(&payload, true).await.map_err(CommandError::from)
+                    profile_manager.add_mod_from_payload(&payload, true).await?;
+                    mod_dependencies::spawn_dependency_check(payload.profile_id);
+                    Ok(())
                 }
             }
         }
@@ -1158,8 +1161,9 @@ pub async fn switch_content_version(
                         payload.profile_id,
                         &payload,
                     )
-                    .await
-                    .map_err(CommandError::from)
+                    .await?;
+                mod_dependencies::spawn_dependency_check(payload.profile_id);
+                Ok(())
             } else {
                 // Local/custom mod file: replace the JAR in-place using the selected version
                 let primary_file = payload.new_version_details.files.iter()
@@ -1,5 +1,6 @@
 use crate::error::{AppError, CommandError};
 use crate::integrations::norisk_packs::NoriskModEntryDefinition;
+use crate::utils::content_store::{self, ContentStore, GarbageCollectionReport};
 use crate::utils::file_utils;
 use crate::utils::path_utils;
 use base64::{engine::general_purpose::STANDARD, Engine as _};
@@ -719,3 +720,15 @@ pub async fn get_image_preview(
         preview_height,
     })
 }
+
+/// Removes blobs from the global content store that no profile or pack references anymore.
+#[tauri::command]
+pub async fn collect_content_store_garbage() -> Result<GarbageCollectionReport, CommandError> {
+    info!("Collecting unused content store blobs");
+    let store = ContentStore::new();
+    let referenced = content_store::collect_profile_references(&store).await?;
+    let report = store
+        .collect_garbage(&referenced, content_store::DEFAULT_GC_MIN_AGE)
+        .await?;
+    Ok(report)
+}
@@ -1,5 +1,5 @@
 use crate::error::{AppError, CommandError};
-use crate::minecraft::minecraft_auth::Credentials;
+use crate::minecraft::minecraft_auth::{AccountStoreStatus, Credentials};
 use crate::state::state_manager::State;
 use chrono::{Duration, Utc};
 use tauri::plugin::TauriPlugin;
@@ -13,6 +13,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     tauri::plugin::Builder::<R>::new("minecraft_auth")
         .invoke_handler(tauri::generate_handler![
             begin_login,
+            add_offline_account,
             remove_account,
             get_active_account,
             set_active_account,
@@ -97,6 +98,17 @@ pub async fn begin_login<R: Runtime>(
     Ok(None)
 }
 
+/// Add an offline account with the given username and make it the active account
+#[tauri::command]
+pub async fn add_offline_account(username: String) -> Result<Credentials, CommandError> {
+    let state = State::get().await?;
+    let account = state
+        .minecraft_account_manager_v2
+        .add_offline_account(&username)
+        .await?;
+    Ok(account)
+}
+
 /// Remove a Minecraft account
 #[tauri::command]
 pub async fn remove_account(account_id: Uuid) -> Result<(), CommandError> {
@@ -140,3 +152,32 @@ pub async fn get_accounts() -> Result<Vec<Credentials>, CommandError> {
         .await?;
     Ok(accounts)
 }
+
+/// Get the encryption state of the account store (passphrase protected / locked)
+#[tauri::command]
+pub async fn get_account_store_status() -> Result<AccountStoreStatus, CommandError> {
+    let state = State::get().await?;
+    Ok(state.minecraft_account_manager_v2.store_status().await)
+}
+
+/// Unlock a passphrase-protected account store
+#[tauri::command]
+pub async fn unlock_account_store(passphrase: String) -> Result<(), CommandError> {
+    let state = State::get().await?;
+    state
+        .minecraft_account_manager_v2
+        .unlock(&passphrase)
+        .await?;
+    Ok(())
+}
+
+/// Set or (with `None`) remove the account store passphrase
+#[tauri::command]
+pub async fn set_account_store_passphrase(passphrase: Option<String>) -> Result<(), CommandError> {
+    let state = State::get().await?;
+    state
+        .minecraft_account_manager_v2
+        .set_passphrase(passphrase.as_deref())
+        .await?;
+    Ok(())
+}
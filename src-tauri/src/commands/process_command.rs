@@ -1,6 +1,10 @@
-use crate::error::CommandError;
+use crate::error::{AppError, CommandError};
+use crate::state::discord_state::DiscordState;
 use crate::state::process_state::ProcessMetadata;
 use crate::state::state_manager::State;
+use crate::utils::session_history_utils::{
+    self, PlaytimeStats, ProfilePlaytimeStats, SessionRecord,
+};
 use tauri::Manager;
 use uuid::Uuid;
 
@@ -37,6 +41,41 @@ pub async fn stop_process(process_id: Uuid) -> Result<(), CommandError> {
     Ok(())
 }
 
+/// Finished sessions of a profile, newest first.
+#[tauri::command]
+pub async fn get_profile_sessions(
+    profile_id: Uuid,
+    limit: Option<usize>,
+) -> Result<Vec<SessionRecord>, CommandError> {
+    Ok(session_history_utils::get_profile_sessions(profile_id, limit).await?)
+}
+
+/// Playtime and crash rate of a profile; `days` limits the "recent" counters.
+#[tauri::command]
+pub async fn get_profile_playtime_stats(
+    profile_id: Uuid,
+    days: u32,
+) -> Result<PlaytimeStats, CommandError> {
+    Ok(session_history_utils::get_profile_playtime_stats(profile_id, days).await?)
+}
+
+/// Playtime and crash rate of a Minecraft account across all profiles.
+#[tauri::command]
+pub async fn get_account_playtime_stats(
+    account_uuid: String,
+    days: u32,
+) -> Result<PlaytimeStats, CommandError> {
+    Ok(session_history_utils::get_account_playtime_stats(&account_uuid, days).await?)
+}
+
+/// Playtime of every profile with recorded sessions, most played first.
+#[tauri::command]
+pub async fn get_all_profile_playtime_stats(
+    days: u32,
+) -> Result<Vec<ProfilePlaytimeStats>, CommandError> {
+    Ok(session_history_utils::get_all_profile_playtime_stats(days).await?)
+}
+
 #[tauri::command]
 pub async fn get_full_log(process_id: Uuid) -> Result<String, CommandError> {
     let state = State::get().await?;
@@ -93,6 +132,20 @@ pub async fn set_discord_state(
     profile_name: Option<String>,
 ) -> Result<(), CommandError> {
     let state = State::get().await?;
-    //TODO
+    // In-game and installing states are tracked by the backend itself
+    let discord_state = match state_type.as_str() {
+        "idle" => DiscordState::Idle,
+        "browsing" => DiscordState::Browsing { profile_name },
+        other => {
+            return Err(CommandError::from(AppError::Other(format!(
+                "Unknown Discord state: {}",
+                other
+            ))))
+        }
+    };
+    state
+        .discord_manager
+        .set_state(discord_state, false)
+        .await?;
     Ok(())
 }
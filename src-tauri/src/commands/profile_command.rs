@@ -1,11 +1,17 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, CommandError};
 use crate::integrations::curseforge;
+use crate::integrations::instance_import::{self, ExternalImportResult, ExternalInstance};
+use crate::integrations::mod_dependencies::{self, DependencyReport, ProjectRef};
+use crate::integrations::mod_updates::{self, BatchUpdateResult};
 use crate::integrations::modrinth::ModrinthVersion;
 use crate::integrations::mrpack;
 use crate::integrations::norisk_packs::GEGModpacksConfig;
+use crate::integrations::pack_export::{self, PackExportFormat};
 use crate::integrations::norisk_versions::GEGVersionsConfig;
+use crate::minecraft::crash_analyzer::{self, CrashDiagnosis};
 use crate::minecraft::installer;
+use crate::minecraft::LaunchDryRun;
 use crate::minecraft::modloader::{ModloaderFactory, ResolvedLoaderVersion};
 use crate::state::event_state::{EventPayload, EventType};
 use crate::state::profile_state::{
@@ -13,7 +19,9 @@ use crate::state::profile_state::{
 };
 use crate::state::state_manager::State;
 use crate::utils::datapack_utils::DataPackInfo;
+use crate::utils::jar_metadata;
 use crate::utils::mc_utils::{self, WorldInfo};
+use crate::utils::options_utils::{self, OptionsApplyResult, OptionsPreset};
 use crate::utils::path_utils::find_unique_profile_segment;
 use crate::utils::profile_utils::{
     check_for_group_migration, CheckContentParams, ContentInstallStatus, ContentType as ProfileUtilContentType,
@@ -22,6 +30,12 @@ use crate::utils::profile_utils::{
 };
 use crate::utils::resourcepack_utils::ResourcePackInfo;
 use crate::utils::shaderpack_utils::ShaderPackInfo;
+use crate::utils::profile_snapshot_utils::{
+    self, ProfileSnapshot, SnapshotRollbackResult, SnapshotTrigger,
+};
+use crate::utils::repair_utils::{IntegrityReport, RepairOutcome};
+use crate::utils::server_list_utils::{self, ServerListEntry, ServerListPushResult};
+use crate::utils::world_backup_utils::{self, WorldBackupInfo, WorldBackupTrigger};
 use crate::utils::world_utils;
 use crate::utils::{
     datapack_utils, path_utils, profile_utils, repair_utils, resourcepack_utils, shaderpack_utils,
@@ -41,12 +55,12 @@ use uuid::Uuid;
 // DTOs für Command-Parameter
 #[derive(Deserialize)]
 pub struct CreateProfileParams {
-    name: String,
-    game_version: String,
-    loader: String,
-    loader_version: Option<String>,
-    selected_GEG_pack_id: Option<String>,
-    use_shared_minecraft_folder: Option<bool>,
+    pub name: String,
+    pub game_version: String,
+    pub loader: String,
+    pub loader_version: Option<String>,
+    pub selected_GEG_pack_id: Option<String>,
+    pub use_shared_minecraft_folder: Option<bool>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -84,6 +98,17 @@ pub struct ExportProfileParams {
     open_folder: bool, // Whether to open the exports folder after export
 }
 
+// Parameters for exporting a profile as .mrpack / CurseForge zip
+#[derive(Deserialize)]
+pub struct ExportProfilePackParams {
+    profile_id: Uuid,
+    file_name: String, // Base name without extension
+    format: PackExportFormat,
+    pack_version: Option<String>,
+    include_files: Option<Vec<PathBuf>>,
+    open_folder: bool,
+}
+
 // DTO for the new command
 #[derive(Deserialize)]
 pub struct CopyWorldParams {
@@ -164,13 +189,16 @@ pub async fn launch_profile(
     quick_play_multiplayer: Option<String>,
     migration_info: Option<profile_utils::MigrationInfo>,
     skip_last_played_update: Option<bool>,
+    offline: Option<bool>,
 ) -> Result<(), CommandError> {
+    let offline = offline.unwrap_or(false);
     log::info!(
-        "[Command] launch_profile called for ID: {}. QuickPlay Single: {:?}, QuickPlay Multi: {:?}, Migration: {:?}",
+        "[Command] launch_profile called for ID: {}. QuickPlay Single: {:?}, QuickPlay Multi: {:?}, Migration: {:?}, Offline: {}",
         id,
         quick_play_singleplayer,
         quick_play_multiplayer,
-        migration_info.is_some()
+        migration_info.is_some(),
+        offline
     );
 
     let state = State::get().await?;
@@ -243,11 +271,16 @@ pub async fn launch_profile(
 
     let version = profile.game_version.clone();
     let modloader = profile.loader.clone();
-    let credentials = match state
-        .minecraft_account_manager_v2
-        .get_active_account()
-        .await
-    {
+    let active_account = if offline {
+        // No token refresh offline, the stored credentials are used as they are
+        Ok(state
+            .minecraft_account_manager_v2
+            .get_active_account_without_refresh()
+            .await)
+    } else {
+        state.minecraft_account_manager_v2.get_active_account().await
+    };
+    let credentials = match active_account {
         Ok(Some(creds)) => Some(creds),
         Ok(None) => {
             return Err(CommandError::from(AppError::NoCredentialsError));
@@ -313,6 +346,8 @@ pub async fn launch_profile(
             quick_play_sp_clone,
             quick_play_mp_clone,
             migration_info_clone,
+            installer::LaunchMode::Launch,
+            offline,
         )
         .await;
 
@@ -385,6 +420,69 @@ pub async fn launch_profile(
     Ok(())
 }
 
+/// Installs whatever a profile is missing and returns the fully resolved launch command
+/// (java path, JVM args, classpath, game args, working dir, env) without starting the game.
+/// Secrets are redacted. With `write_launch_script` a `launch.sh` is written into the game directory.
+/// With `offline` only cached files are used, like `launch_profile` in offline mode.
+#[tauri::command]
+pub async fn dry_run_profile_launch(
+    id: Uuid,
+    write_launch_script: Option<bool>,
+    offline: Option<bool>,
+) -> Result<LaunchDryRun, CommandError> {
+    let offline = offline.unwrap_or(false);
+    log::info!("[Command] dry_run_profile_launch called for ID: {}", id);
+    let state = State::get().await?;
+
+    let profile = match state.profile_manager.get_profile(id).await {
+        Ok(profile) => profile,
+        Err(_) => state
+            .GEG_version_manager
+            .get_profile_by_id(id)
+            .await
+            .ok_or_else(|| {
+                AppError::Other(format!(
+                    "No profile or standard version found with ID {}",
+                    id
+                ))
+            })?,
+    };
+
+    // Credentials are optional here, without them the placeholder values end up in the command
+    let credentials = if offline {
+        state
+            .minecraft_account_manager_v2
+            .get_active_account_without_refresh()
+            .await
+    } else {
+        match state.minecraft_account_manager_v2.get_active_account().await {
+            Ok(creds) => creds,
+            Err(e) => {
+                warn!("Error getting active account for dry-run: {}", e);
+                None
+            }
+        }
+    };
+
+    let dry_run = installer::install_minecraft_version(
+        &profile.game_version,
+        profile.loader.as_str(),
+        &profile,
+        credentials,
+        None,
+        None,
+        None,
+        installer::LaunchMode::DryRun {
+            write_launch_script: write_launch_script.unwrap_or(false),
+        },
+        offline,
+    )
+    .await?
+    .ok_or_else(|| AppError::Other("Dry-run did not produce a launch command".to_string()))?;
+
+    Ok(dry_run)
+}
+
 /// Aborts an ongoing launch process for a profile.
 /// This is useful to cancel a profile installation/launch that's taking too long.
 #[tauri::command]
@@ -693,13 +791,30 @@ pub async fn delete_profile(id: Uuid) -> Result<(), CommandError> {
 }
 
 #[tauri::command]
-pub async fn repair_profile(id: Uuid) -> Result<(), CommandError> {
+pub async fn repair_profile(id: Uuid) -> Result<RepairOutcome, CommandError> {
     info!("Executing repair_profile command for profile {}", id);
     
-    // Call the actual repair function from repair_utils
-    repair_utils::repair_profile(id).await?;
-    
-    Ok(())
+    // Verify and redownload only what is missing or corrupt
+    Ok(repair_utils::repair_profile(id).await?)
+}
+
+#[tauri::command]
+pub async fn verify_profile_integrity(id: Uuid) -> Result<IntegrityReport, CommandError> {
+    info!("Executing verify_profile_integrity command for profile {}", id);
+    Ok(repair_utils::verify_profile_integrity(id).await?)
+}
+
+/// Matches the latest crash report and `latest.log` of a profile against known failure signatures
+#[tauri::command]
+pub async fn analyze_profile_crash(
+    profile_id: Uuid,
+    crash_report_content: Option<String>,
+) -> Result<CrashDiagnosis, CommandError> {
+    info!(
+        "Executing analyze_profile_crash command for profile {}",
+        profile_id
+    );
+    Ok(crash_analyzer::analyze_profile_crash(profile_id, crash_report_content.as_deref()).await?)
 }
 
 #[tauri::command]
@@ -906,9 +1021,63 @@ pub async fn update_modrinth_mod_version(
         .profile_manager
         .update_profile_modrinth_mod_version(profile_id, mod_instance_id, &new_version_details) // Pass details by reference
         .await?;
+    mod_dependencies::spawn_dependency_check(profile_id);
     Ok(())
 }
 
+/// Updates all mods with updates enabled at once, all-or-nothing.
+/// With `dry_run` only the planned version changes and their changelogs are returned.
+#[tauri::command]
+pub async fn update_all_profile_mods(
+    profile_id: Uuid,
+    dry_run: bool,
+) -> Result<BatchUpdateResult, CommandError> {
+    info!(
+        "Executing update_all_profile_mods command for profile {} (dry run: {})",
+        profile_id, dry_run
+    );
+    let result = mod_updates::update_all_mods(profile_id, dry_run).await?;
+
+    if result.applied || result.rollback_error.is_some() {
+        State::get()
+            .await?
+            .event_state
+            .trigger_profile_update(profile_id)
+            .await?;
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn check_profile_dependencies(
+    profile_id: Uuid,
+) -> Result<DependencyReport, CommandError> {
+    info!(
+        "Executing check_profile_dependencies command for profile {}",
+        profile_id
+    );
+    Ok(mod_dependencies::check_profile_dependencies(profile_id).await?)
+}
+
+/// Installs missing dependencies, all of them if `projects` is not given
+#[tauri::command]
+pub async fn install_missing_dependencies(
+    profile_id: Uuid,
+    projects: Option<Vec<ProjectRef>>,
+) -> Result<DependencyReport, CommandError> {
+    info!(
+        "Executing install_missing_dependencies command for profile {}",
+        profile_id
+    );
+    let report = mod_dependencies::install_missing_dependencies(profile_id, projects).await?;
+    State::get()
+        .await?
+        .event_state
+        .trigger_profile_update(profile_id)
+        .await?;
+    Ok(report)
+}
+
 // --- Custom Mod Commands ---
 
 #[tauri::command]
@@ -919,7 +1088,17 @@ pub async fn get_custom_mods(profile_id: Uuid) -> Result<Vec<CustomModInfo>, Com
     );
     let state: std::sync::Arc<State> = State::get().await?;
     let profile = state.profile_manager.get_profile(profile_id).await?;
-    Ok(state.profile_manager.list_custom_mods(&profile).await?)
+    let mut custom_mods = state.profile_manager.list_custom_mods(&profile).await?;
+    for custom_mod in &mut custom_mods {
+        match jar_metadata::inspect_jar(&custom_mod.path).await {
+            Ok(metadata) => custom_mod.metadata = Some(metadata),
+            Err(e) => warn!(
+                "Could not read metadata of custom mod {}: {}",
+                custom_mod.filename, e
+            ),
+        }
+    }
+    Ok(custom_mods)
 }
 
 #[tauri::command]
@@ -1262,6 +1441,60 @@ pub async fn import_profile(file_path_str: String) -> Result<Uuid, CommandError>
     Ok(new_profile_id)
 }
 
+/// Finds instances of Prism Launcher, the vanilla launcher and the Modrinth App.
+#[tauri::command]
+pub async fn discover_external_instances() -> Result<Vec<ExternalInstance>, CommandError> {
+    info!("Executing discover_external_instances command");
+    tokio::task::spawn_blocking(instance_import::discover_external_instances)
+        .await
+        .map_err(|e| {
+            CommandError::from(AppError::Other(format!(
+                "Instance discovery task failed: {}",
+                e
+            )))
+        })
+}
+
+/// Lists the instances in a folder picked by the user (e.g. a portable MultiMC).
+#[tauri::command]
+pub async fn read_external_instances(path: String) -> Result<Vec<ExternalInstance>, CommandError> {
+    info!("Executing read_external_instances command for {}", path);
+    tokio::task::spawn_blocking(move || instance_import::read_instances_at(&PathBuf::from(path)))
+        .await
+        .map_err(|e| {
+            CommandError::from(AppError::Other(format!(
+                "Instance discovery task failed: {}",
+                e
+            )))
+        })
+}
+
+/// Imports an instance of another launcher as a new profile.
+#[tauri::command]
+pub async fn import_external_instance(
+    instance: ExternalInstance,
+) -> Result<ExternalImportResult, CommandError> {
+    info!(
+        "Executing import_external_instance command for '{}'",
+        instance.name
+    );
+    let result = instance_import::import_external_instance(instance).await?;
+
+    let state = State::get().await?;
+    if let Err(e) = state
+        .event_state
+        .trigger_profile_update(result.profile_id)
+        .await
+    {
+        log::error!(
+            "Failed to emit TriggerProfileUpdate event for imported profile {}: {}",
+            result.profile_id,
+            e
+        );
+    }
+    Ok(result)
+}
+
 // Command to get all resourcepacks in a profile
 #[tauri::command]
 pub async fn get_local_resourcepacks(
@@ -1631,6 +1864,52 @@ pub async fn export_profile(
     Ok(result_path.to_string_lossy().to_string())
 }
 
+/// Exports a profile as Modrinth `.mrpack` or CurseForge modpack zip into the exports directory
+#[tauri::command]
+pub async fn export_profile_as_pack(
+    app_handle: tauri::AppHandle,
+    params: ExportProfilePackParams,
+) -> Result<String, CommandError> {
+    info!(
+        "Executing export_profile_as_pack command for profile {} ({:?})",
+        params.profile_id, params.format
+    );
+
+    let exports_dir = LAUNCHER_DIRECTORY.root_dir().join("exports");
+    TokioFs::create_dir_all(&exports_dir)
+        .await
+        .map_err(|e| CommandError::from(AppError::Io(e)))?;
+
+    let sanitized_name = sanitize(&params.file_name);
+    if sanitized_name.is_empty() {
+        return Err(CommandError::from(AppError::Other(
+            "Export filename is invalid after sanitization.".to_string(),
+        )));
+    }
+    let export_path =
+        exports_dir.join(format!("{}.{}", sanitized_name, params.format.extension()));
+
+    let result_path = pack_export::export_profile_to_pack(
+        params.profile_id,
+        params.format,
+        export_path,
+        params.pack_version,
+        params.include_files,
+    )
+    .await?;
+
+    if params.open_folder {
+        if let Err(e) = app_handle
+            .opener()
+            .open_path(exports_dir.to_string_lossy(), None::<&str>)
+        {
+            info!("Failed to open export directory: {}", e);
+        }
+    }
+
+    Ok(result_path.to_string_lossy().to_string())
+}
+
 /// Checks if a profile is currently being launched.
 /// Returns true if there's an active launch process for the given profile ID.
 #[tauri::command]
@@ -1906,6 +2185,72 @@ pub async fn get_servers_for_profile(
     Ok(mc_utils::get_profile_servers(profile_id).await?)
 }
 
+/// Adds a server to the profile's servers.dat, at `index` or at the end.
+#[tauri::command]
+pub async fn add_profile_server(
+    profile_id: Uuid,
+    server: ServerListEntry,
+    index: Option<usize>,
+) -> Result<Vec<mc_utils::ServerInfo>, CommandError> {
+    info!("Executing add_profile_server command for profile {}", profile_id);
+    Ok(server_list_utils::add_profile_server(profile_id, server, index).await?)
+}
+
+/// Edits the server at `index`, including its icon and resource pack policy.
+#[tauri::command]
+pub async fn update_profile_server(
+    profile_id: Uuid,
+    index: usize,
+    server: ServerListEntry,
+) -> Result<Vec<mc_utils::ServerInfo>, CommandError> {
+    info!(
+        "Executing update_profile_server command for profile {} (index {})",
+        profile_id, index
+    );
+    Ok(server_list_utils::update_profile_server(profile_id, index, server).await?)
+}
+
+#[tauri::command]
+pub async fn remove_profile_server(
+    profile_id: Uuid,
+    index: usize,
+) -> Result<Vec<mc_utils::ServerInfo>, CommandError> {
+    info!(
+        "Executing remove_profile_server command for profile {} (index {})",
+        profile_id, index
+    );
+    Ok(server_list_utils::remove_profile_server(profile_id, index).await?)
+}
+
+#[tauri::command]
+pub async fn move_profile_server(
+    profile_id: Uuid,
+    from: usize,
+    to: usize,
+) -> Result<Vec<mc_utils::ServerInfo>, CommandError> {
+    info!(
+        "Executing move_profile_server command for profile {} ({} -> {})",
+        profile_id, from, to
+    );
+    Ok(server_list_utils::move_profile_server(profile_id, from, to).await?)
+}
+
+/// Writes a curated server list into every profile of a group.
+#[tauri::command]
+pub async fn push_server_list_to_group(
+    group: String,
+    servers: Vec<ServerListEntry>,
+    replace: bool,
+) -> Result<Vec<ServerListPushResult>, CommandError> {
+    info!(
+        "Executing push_server_list_to_group command for group '{}' ({} servers, replace: {})",
+        group,
+        servers.len(),
+        replace
+    );
+    Ok(server_list_utils::push_server_list_to_group(&group, servers, replace).await?)
+}
+
 /// Copies a singleplayer world to another profile (or the same one) with a new name.
 #[tauri::command]
 pub async fn copy_world(params: CopyWorldParams) -> Result<String, CommandError> {
@@ -2055,6 +2400,139 @@ pub async fn delete_world(profile_id: Uuid, world_folder: String) -> Result<(),
     Ok(())
 }
 
+/// Snapshots a single world on demand using the profile's backup format.
+#[tauri::command]
+pub async fn create_world_backup(
+    profile_id: Uuid,
+    world_folder: String,
+) -> Result<WorldBackupInfo, CommandError> {
+    info!(
+        "Executing create_world_backup command for profile {}, world '{}'",
+        profile_id, world_folder
+    );
+    let state = State::get().await?;
+    let settings = state
+        .profile_manager
+        .get_profile(profile_id)
+        .await?
+        .settings
+        .world_backups;
+
+    Ok(world_backup_utils::create_world_backup(
+        profile_id,
+        &world_folder,
+        WorldBackupTrigger::Manual,
+        settings.format,
+        settings.max_backups_per_world,
+    )
+    .await?)
+}
+
+/// Lists world snapshots of a profile, optionally only those of one world.
+#[tauri::command]
+pub async fn list_world_backups(
+    profile_id: Uuid,
+    world_folder: Option<String>,
+) -> Result<Vec<WorldBackupInfo>, CommandError> {
+    Ok(world_backup_utils::list_world_backups(profile_id, world_folder.as_deref()).await?)
+}
+
+/// Restores a world snapshot into a new world folder and returns its name.
+#[tauri::command]
+pub async fn restore_world_backup(
+    profile_id: Uuid,
+    world_folder: String,
+    backup_id: String,
+    target_world_name: Option<String>,
+) -> Result<String, CommandError> {
+    info!(
+        "Executing restore_world_backup command for profile {}, world '{}', backup '{}'",
+        profile_id, world_folder, backup_id
+    );
+    let new_folder = world_backup_utils::restore_world_backup(
+        profile_id,
+        &world_folder,
+        &backup_id,
+        target_world_name.as_deref(),
+    )
+    .await?;
+
+    if let Ok(state) = State::get().await {
+        if let Err(e) = state.event_state.trigger_profile_update(profile_id).await {
+            warn!(
+                "Failed to emit profile update event after restoring world '{}' in profile {}: {}",
+                new_folder, profile_id, e
+            );
+        }
+    }
+    Ok(new_folder)
+}
+
+#[tauri::command]
+pub async fn delete_world_backup(
+    profile_id: Uuid,
+    world_folder: String,
+    backup_id: String,
+) -> Result<(), CommandError> {
+    Ok(world_backup_utils::delete_world_backup(profile_id, &world_folder, &backup_id).await?)
+}
+
+/// Snapshots the mod list, loader and config files of a profile on demand.
+#[tauri::command]
+pub async fn create_profile_snapshot(
+    profile_id: Uuid,
+    name: Option<String>,
+) -> Result<ProfileSnapshot, CommandError> {
+    info!(
+        "Executing create_profile_snapshot command for profile {}",
+        profile_id
+    );
+    let state = State::get().await?;
+    let profile = state.profile_manager.get_profile(profile_id).await?;
+    Ok(
+        profile_snapshot_utils::create_profile_snapshot(&profile, name, SnapshotTrigger::Manual)
+            .await?,
+    )
+}
+
+#[tauri::command]
+pub async fn list_profile_snapshots(
+    profile_id: Uuid,
+) -> Result<Vec<ProfileSnapshot>, CommandError> {
+    Ok(profile_snapshot_utils::list_profile_snapshots(profile_id).await?)
+}
+
+/// Restores the mod versions, loader and config files of a snapshot.
+#[tauri::command]
+pub async fn rollback_profile_snapshot(
+    profile_id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<SnapshotRollbackResult, CommandError> {
+    info!(
+        "Executing rollback_profile_snapshot command for profile {}, snapshot {}",
+        profile_id, snapshot_id
+    );
+    let result = profile_snapshot_utils::rollback_profile_snapshot(profile_id, snapshot_id).await?;
+
+    if let Ok(state) = State::get().await {
+        if let Err(e) = state.event_state.trigger_profile_update(profile_id).await {
+            warn!(
+                "Failed to emit profile update event after rollback of profile {}: {}",
+                profile_id, e
+            );
+        }
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn delete_profile_snapshot(
+    profile_id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<(), CommandError> {
+    Ok(profile_snapshot_utils::delete_profile_snapshot(profile_id, snapshot_id).await?)
+}
+
 // Added: Command to list screenshots for a profile
 #[tauri::command]
 pub async fn list_profile_screenshots(
@@ -2228,3 +2706,43 @@ pub async fn purge_trash(max_age_seconds: Option<u64>) -> Result<u64, CommandErr
     let removed = crate::utils::trash_utils::purge_expired(secs).await?;
     Ok(removed)
 }
+
+/// Lists all saved options.txt presets.
+#[tauri::command]
+pub async fn list_options_presets() -> Result<Vec<OptionsPreset>, CommandError> {
+    Ok(options_utils::list_options_presets().await?)
+}
+
+/// Saves the options.txt of a profile as a named preset, optionally only matching keys.
+#[tauri::command]
+pub async fn create_options_preset_from_profile(
+    profile_id: Uuid,
+    name: String,
+    keys: Option<Vec<String>>,
+) -> Result<OptionsPreset, CommandError> {
+    info!(
+        "Executing create_options_preset_from_profile command for profile {} (name: '{}')",
+        profile_id, name
+    );
+    Ok(options_utils::create_options_preset_from_profile(profile_id, name, keys).await?)
+}
+
+#[tauri::command]
+pub async fn delete_options_preset(preset_id: Uuid) -> Result<(), CommandError> {
+    info!("Executing delete_options_preset command for preset {}", preset_id);
+    Ok(options_utils::delete_options_preset(preset_id).await?)
+}
+
+/// Writes a preset into the options.txt of the given profiles.
+#[tauri::command]
+pub async fn apply_options_preset(
+    preset_id: Uuid,
+    profile_ids: Vec<Uuid>,
+) -> Result<Vec<OptionsApplyResult>, CommandError> {
+    info!(
+        "Executing apply_options_preset command for preset {} ({} profiles)",
+        preset_id,
+        profile_ids.len()
+    );
+    Ok(options_utils::apply_options_preset(preset_id, profile_ids).await?)
+}
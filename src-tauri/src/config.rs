@@ -1,3 +1,4 @@
+use crate::state::config_state::UrlRewrites;
 use directories::ProjectDirs;
 use once_cell::sync::Lazy;
 use reqwest::Client;
@@ -15,6 +16,10 @@ pub static LAUNCHER_DIRECTORY: Lazy<ProjectDirs> =
 pub static CUSTOM_GAME_DIR_CACHE: Lazy<RwLock<Option<Option<PathBuf>>>> = 
     Lazy::new(|| RwLock::new(None));
 
+/// Mirror table from the launcher config, readable without going through the state
+pub static URL_REWRITES_CACHE: Lazy<RwLock<UrlRewrites>> =
+    Lazy::new(|| RwLock::new(UrlRewrites::default()));
+
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 /// HTTP Client with launcher agent
@@ -74,3 +79,18 @@ pub fn update_custom_game_dir(path: Option<PathBuf>) {
         *guard = Some(path);
     }
 }
+
+/// Update the cached URL rewrite table
+pub fn update_url_rewrites(rewrites: UrlRewrites) {
+    if let Ok(mut guard) = URL_REWRITES_CACHE.write() {
+        *guard = rewrites;
+    }
+}
+
+/// URLs to try for `url`: configured mirrors first, then (if allowed) the original
+pub fn url_candidates(url: &str) -> Vec<String> {
+    match URL_REWRITES_CACHE.read() {
+        Ok(guard) => guard.candidates(url),
+        Err(_) => vec![url.to_string()],
+    }
+}
@@ -195,6 +195,9 @@ pub enum AppError {
         available_mb: u64,
         shortfall_mb: u64,
     },
+
+    #[error("Offline launch not possible, missing or corrupted:\n{}", .0.join("\n"))]
+    OfflineResourcesMissing(Vec<String>),
 }
 
 #[derive(Serialize, Debug)]
@@ -2205,6 +2205,40 @@ pub fn check_mod_update_by_file_index(
     }
 }
 
+/// Looks up files by their fingerprints and returns the exact matches
+/// (one per fingerprint that CurseForge knows).
+pub async fn get_fingerprint_matches(fingerprints: Vec<u64>) -> Result<Vec<CurseForgeFingerprintMatch>> {
+    if fingerprints.is_empty() {
+        return Ok(Vec::new());
+    }
+    let url = format!("{}/fingerprints", CURSEFORGE_API_BASE_URL);
+    info!("Looking up {} fingerprints on CurseForge", fingerprints.len());
+
+    let response = HTTP_CLIENT
+        .post(&url)
+        .header("x-api-key", CURSEFORGE_API_KEY)
+        .header("Accept", "application/json")
+        .json(&CurseForgeFingerprintRequest { fingerprints })
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("CurseForge fingerprint request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(AppError::Other(format!(
+            "CurseForge fingerprint API returned HTTP error {}: {}",
+            status, error_text
+        )));
+    }
+
+    let api_response: CurseForgeFingerprintApiResponse = response
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to parse CurseForge fingerprint response: {}", e)))?;
+    Ok(api_response.data.exact_matches)
+}
+
 /// Check for mod updates using CurseForge's fingerprint API
 /// This performs bulk update checking for multiple mods at once
 /// Filters results by game versions and loaders to find actual updates
@@ -0,0 +1,1025 @@
+//! Importers for instances of other launchers: Prism Launcher / MultiMC instances, the profiles
+//! of the vanilla launcher (`launcher_profiles.json`) and Modrinth App profiles.
+//!
+//! Discovery only reads the launchers' metadata. Importing hashes every mod jar and matches it
+//! against Modrinth (SHA1) and CurseForge (fingerprint), so matched mods become regular,
+//! updatable profile mods. Unmatched jars are carried over as custom mods, everything else in
+//! the game directory (configs, saves, resource packs, options) is copied into the new profile.
+
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::{AppError, Result};
+use crate::integrations::curseforge::{self, CurseForgeFile, CurseForgeFingerprintMatch};
+use crate::integrations::modrinth::{self, ModrinthVersion};
+use crate::minecraft::api::mc_api::MinecraftApiService;
+use crate::minecraft::downloads::mod_downloader::MOD_CACHE_DIR_NAME;
+use crate::state::profile_state::{
+    self, default_profile_path, Mod, ModLoader, ModSource, Profile, ProfileSettings, ProfileState,
+};
+use crate::state::state_manager::State;
+use crate::utils::{hash_utils, mc_utils, path_utils};
+use chrono::Utc;
+use log::{info, warn};
+use sanitize_filename::sanitize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+/// Top-level entries of a game directory that are not carried over into the new profile
+const SKIPPED_GAME_DIR_ENTRIES: &[&str] = &[
+    "mods",
+    "logs",
+    "crash-reports",
+    "versions",
+    "libraries",
+    "assets",
+    "natives",
+    "bin",
+    "webcache2",
+    ".fabric",
+    ".quilt",
+    ".mixin.out",
+    ".cache",
+    "launcher_profiles.json",
+    "launcher_accounts.json",
+    "launcher_settings.json",
+    "launcher_log.txt",
+    "usercache.json",
+    "runtime",
+    "profile.json",
+];
+const COPY_CONCURRENCY: usize = 8;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalLauncher {
+    PrismLauncher,
+    MultiMc,
+    VanillaLauncher,
+    ModrinthApp,
+}
+
+/// An instance of another launcher. Returned by discovery and passed back for the import,
+/// missing fields (e.g. an unknown game version) can be filled in by the user in between.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExternalInstance {
+    pub launcher: ExternalLauncher,
+    pub name: String,
+    /// The directory holding mods/, config/, saves/ ...
+    pub game_dir: PathBuf,
+    /// `latest-release` / `latest-snapshot` for such vanilla launcher profiles, resolved on
+    /// import. None if the launcher does not store it (newer Modrinth App profiles), the
+    /// import then infers it from the matched mods.
+    pub game_version: Option<String>,
+    pub loader: ModLoader,
+    pub loader_version: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ExternalImportResult {
+    pub profile_id: Uuid,
+    pub modrinth_mods: usize,
+    pub curseforge_mods: usize,
+    /// Jars that matched no platform and were copied as custom mods
+    pub custom_mods: Vec<String>,
+}
+
+/// A jar in the mods folder of an external instance
+#[derive(Debug, Clone, PartialEq)]
+struct ModJar {
+    path: PathBuf,
+    /// File name without a `.disabled` suffix
+    file_name: String,
+    enabled: bool,
+}
+
+/// Key/value pairs of an INI style file like Prism's `instance.cfg`, sections are ignored
+fn parse_cfg(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('[') && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct MmcPack {
+    components: Vec<MmcComponent>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MmcComponent {
+    uid: String,
+    version: Option<String>,
+    cached_version: Option<String>,
+}
+
+/// Game version, loader and loader version from the components of `mmc-pack.json`
+fn parse_mmc_pack(content: &str) -> Result<(Option<String>, ModLoader, Option<String>)> {
+    let pack: MmcPack = serde_json::from_str(content)?;
+    let mut game_version = None;
+    let mut loader = (ModLoader::Vanilla, None);
+    for component in pack.components {
+        let version = component.version.or(component.cached_version);
+        match component.uid.as_str() {
+            "net.minecraft" => game_version = version,
+            "net.fabricmc.fabric-loader" => loader = (ModLoader::Fabric, version),
+            "org.quiltmc.quilt-loader" => loader = (ModLoader::Quilt, version),
+            "net.minecraftforge" => loader = (ModLoader::Forge, version),
+            "net.neoforged" => loader = (ModLoader::NeoForge, version),
+            _ => {}
+        }
+    }
+    Ok((game_version, loader.0, loader.1))
+}
+
+/// Reads a Prism Launcher / MultiMC instance folder (the one containing `instance.cfg`).
+pub fn read_prism_instance(instance_dir: &Path) -> Result<ExternalInstance> {
+    let cfg = parse_cfg(&std::fs::read_to_string(instance_dir.join("instance.cfg"))?);
+    let (game_version, loader, loader_version) = parse_mmc_pack(&std::fs::read_to_string(
+        instance_dir.join("mmc-pack.json"),
+    )?)?;
+
+    // The launcher's config file lies next to the instances folder
+    let root = instance_dir.parent().and_then(Path::parent);
+    let launcher = if root.is_some_and(|root| root.join("multimc.cfg").is_file()) {
+        ExternalLauncher::MultiMc
+    } else {
+        ExternalLauncher::PrismLauncher
+    };
+    let game_dir = [".minecraft", "minecraft"]
+        .iter()
+        .map(|name| instance_dir.join(name))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| instance_dir.join(".minecraft"));
+
+    Ok(ExternalInstance {
+        launcher,
+        name: cfg
+            .get("name")
+            .cloned()
+            .unwrap_or_else(|| dir_name(instance_dir)),
+        game_dir,
+        game_version,
+        loader,
+        loader_version,
+    })
+}
+
+/// Game version, loader and loader version encoded in a vanilla launcher version id,
+/// e.g. `fabric-loader-0.15.11-1.20.1`, `1.20.1-forge-47.2.0` or `neoforge-21.1.65`
+fn parse_version_id(id: &str) -> (Option<String>, ModLoader, Option<String>) {
+    for (prefix, loader) in [
+        ("fabric-loader-", ModLoader::Fabric),
+        ("quilt-loader-", ModLoader::Quilt),
+    ] {
+        if let Some(rest) = id.strip_prefix(prefix) {
+            return match rest.split_once('-') {
+                Some((loader_version, game_version)) => (
+                    Some(game_version.to_string()),
+                    loader,
+                    Some(loader_version.to_string()),
+                ),
+                None => (None, loader, Some(rest.to_string())),
+            };
+        }
+    }
+    if let Some((game_version, forge_version)) = id.split_once("-forge-") {
+        return (
+            Some(game_version.to_string()),
+            ModLoader::Forge,
+            Some(forge_version.to_string()),
+        );
+    }
+    if let Some(neoforge_version) = id.strip_prefix("neoforge-") {
+        // NeoForge 21.1.x is built for 1.21.1, 21.0.x for 1.21
+        let mut parts = neoforge_version.split('.');
+        let game_version = match (parts.next(), parts.next()) {
+            (Some(major), Some("0")) => Some(format!("1.{}", major)),
+            (Some(major), Some(minor)) => Some(format!("1.{}.{}", major, minor)),
+            _ => None,
+        };
+        return (
+            game_version,
+            ModLoader::NeoForge,
+            Some(neoforge_version.to_string()),
+        );
+    }
+    (Some(id.to_string()), ModLoader::Vanilla, None)
+}
+
+#[derive(Deserialize)]
+struct LauncherProfiles {
+    profiles: HashMap<String, LauncherProfile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LauncherProfile {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type", default)]
+    profile_type: String,
+    last_version_id: Option<String>,
+    game_dir: Option<PathBuf>,
+}
+
+/// Reads the installations of the vanilla launcher from `<minecraft_dir>/launcher_profiles.json`.
+pub fn read_vanilla_profiles(minecraft_dir: &Path) -> Result<Vec<ExternalInstance>> {
+    let content = std::fs::read_to_string(minecraft_dir.join("launcher_profiles.json"))?;
+    let launcher_profiles: LauncherProfiles = serde_json::from_str(&content)?;
+
+    let mut instances: Vec<ExternalInstance> = launcher_profiles
+        .profiles
+        .into_values()
+        .map(|profile| {
+            let version_id = profile
+                .last_version_id
+                .unwrap_or_else(|| profile.profile_type.clone());
+            let (mut game_version, loader, loader_version) = parse_version_id(&version_id);
+
+            // The installed version json knows the game version it builds upon
+            let version_json = minecraft_dir
+                .join("versions")
+                .join(&version_id)
+                .join(format!("{}.json", version_id));
+            if let Some(inherits_from) = std::fs::read_to_string(version_json)
+                .ok()
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+                .and_then(|json| json["inheritsFrom"].as_str().map(str::to_string))
+            {
+                game_version = Some(inherits_from);
+            }
+
+            let name = match (profile.name.is_empty(), profile.profile_type.as_str()) {
+                (false, _) => profile.name,
+                (true, "latest-release") => "Latest release".to_string(),
+                (true, "latest-snapshot") => "Latest snapshot".to_string(),
+                (true, _) => version_id.clone(),
+            };
+            ExternalInstance {
+                launcher: ExternalLauncher::VanillaLauncher,
+                name,
+                game_dir: profile
+                    .game_dir
+                    .unwrap_or_else(|| minecraft_dir.to_path_buf()),
+                game_version,
+                loader,
+                loader_version,
+            }
+        })
+        .collect();
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(instances)
+}
+
+#[derive(Deserialize)]
+struct ModrinthAppProfile {
+    metadata: ModrinthAppMetadata,
+}
+
+#[derive(Deserialize)]
+struct ModrinthAppMetadata {
+    name: String,
+    game_version: String,
+    loader: String,
+    loader_version: Option<ModrinthAppLoaderVersion>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthAppLoaderVersion {
+    id: String,
+}
+
+/// Reads a Modrinth App profile folder. Older app versions keep a `profile.json` in it, newer
+/// ones store the metadata in their database, then only name and mods are known here.
+pub fn read_modrinth_app_profile(profile_dir: &Path) -> Result<ExternalInstance> {
+    let mut instance = ExternalInstance {
+        launcher: ExternalLauncher::ModrinthApp,
+        name: dir_name(profile_dir),
+        game_dir: profile_dir.to_path_buf(),
+        game_version: None,
+        loader: ModLoader::Vanilla,
+        loader_version: None,
+    };
+
+    let profile_json = profile_dir.join("profile.json");
+    if profile_json.is_file() {
+        let profile: ModrinthAppProfile =
+            serde_json::from_str(&std::fs::read_to_string(profile_json)?)?;
+        instance.name = profile.metadata.name;
+        instance.game_version = Some(profile.metadata.game_version);
+        instance.loader = ModLoader::from_str(&profile.metadata.loader)?;
+        instance.loader_version = profile.metadata.loader_version.map(|version| version.id);
+    }
+    Ok(instance)
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Instances folder of a Prism/MultiMC data directory, honouring a custom `InstanceDir`
+fn prism_instances_dir(data_dir: &Path, cfg_name: &str) -> PathBuf {
+    let instance_dir = std::fs::read_to_string(data_dir.join(cfg_name))
+        .ok()
+        .and_then(|content| parse_cfg(&content).remove("InstanceDir"))
+        .unwrap_or_else(|| "instances".to_string());
+    data_dir.join(instance_dir)
+}
+
+/// Reads whatever instances a folder picked by the user contains: a single Prism/MultiMC
+/// instance, a Prism/MultiMC data or instances folder, a `.minecraft` folder with
+/// `launcher_profiles.json`, a Modrinth App profile or its profiles folder.
+pub fn read_instances_at(path: &Path) -> Vec<ExternalInstance> {
+    let log_error = |e: AppError| warn!("Skipping instance in {}: {}", path.display(), e);
+
+    if path.join("instance.cfg").is_file() {
+        return read_prism_instance(path)
+            .map_err(log_error)
+            .into_iter()
+            .collect();
+    }
+    if path.join("launcher_profiles.json").is_file() {
+        return read_vanilla_profiles(path)
+            .map_err(log_error)
+            .unwrap_or_default();
+    }
+    if path.join("profile.json").is_file() {
+        return read_modrinth_app_profile(path)
+            .map_err(log_error)
+            .into_iter()
+            .collect();
+    }
+    for cfg_name in ["prismlauncher.cfg", "multimc.cfg"] {
+        if path.join(cfg_name).is_file() {
+            return read_instances_at(&prism_instances_dir(path, cfg_name));
+        }
+    }
+
+    let children = subdirectories(path);
+    if children
+        .iter()
+        .any(|child| child.join("instance.cfg").is_file())
+    {
+        return children
+            .iter()
+            .filter(|child| child.join("instance.cfg").is_file())
+            .filter_map(|child| read_prism_instance(child).map_err(log_error).ok())
+            .collect();
+    }
+    // A Modrinth App profiles folder, newer profiles only have their content folders
+    children
+        .iter()
+        .filter(|child| child.join("profile.json").is_file() || child.join("mods").is_dir())
+        .filter_map(|child| read_modrinth_app_profile(child).map_err(log_error).ok())
+        .collect()
+}
+
+/// Finds instances of Prism Launcher, the vanilla launcher and the Modrinth App in their
+/// default locations. MultiMC is portable and has to be picked manually.
+pub fn discover_external_instances() -> Vec<ExternalInstance> {
+    let mut instances = Vec::new();
+
+    if let Some(data_dir) = dirs::data_dir() {
+        let prism = data_dir.join("PrismLauncher");
+        if prism.is_dir() {
+            instances.extend(read_instances_at(&prism_instances_dir(
+                &prism,
+                "prismlauncher.cfg",
+            )));
+        }
+    }
+
+    let minecraft_dir = mc_utils::get_default_minecraft_dir();
+    if minecraft_dir.join("launcher_profiles.json").is_file() {
+        match read_vanilla_profiles(&minecraft_dir) {
+            Ok(profiles) => instances.extend(profiles),
+            Err(e) => warn!("Could not read the vanilla launcher profiles: {}", e),
+        }
+    }
+
+    let mut modrinth_dirs: Vec<PathBuf> = [
+        dirs::data_dir().map(|dir| dir.join("ModrinthApp").join("profiles")),
+        dirs::config_dir().map(|dir| dir.join("com.modrinth.theseus").join("profiles")),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|dir| dir.is_dir())
+    .collect();
+    modrinth_dirs.dedup();
+    for dir in modrinth_dirs {
+        instances.extend(read_instances_at(&dir));
+    }
+
+    info!(
+        "Discovered {} instances of other launchers",
+        instances.len()
+    );
+    instances
+}
+
+/// The `.jar` and `.jar.disabled` files of a mods folder
+fn collect_mod_jars(mods_dir: &Path) -> Vec<ModJar> {
+    let mut jars: Vec<ModJar> = std::fs::read_dir(mods_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            let (file_name, enabled) = match name.strip_suffix(".disabled") {
+                Some(base) => (base.to_string(), false),
+                None => (name, true),
+            };
+            file_name.ends_with(".jar").then_some(ModJar {
+                path,
+                file_name,
+                enabled,
+            })
+        })
+        .collect();
+    jars.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    jars
+}
+
+struct HashedJar {
+    jar: ModJar,
+    sha1: String,
+    fingerprint: u64,
+}
+
+fn modrinth_mod(hashed: &HashedJar, version: &ModrinthVersion, loader: ModLoader) -> Option<Mod> {
+    let file = version
+        .files
+        .iter()
+        .find(|file| file.hashes.sha1.as_deref() == Some(hashed.sha1.as_str()))?;
+    Some(Mod {
+        id: Uuid::new_v4(),
+        source: ModSource::Modrinth {
+            project_id: version.project_id.clone(),
+            version_id: version.id.clone(),
+            file_name: file.filename.clone(),
+            download_url: file.url.clone(),
+            file_hash_sha1: Some(hashed.sha1.clone()),
+        },
+        enabled: hashed.jar.enabled,
+        display_name: Some(version.name.clone()),
+        version: Some(version.version_number.clone()),
+        game_versions: Some(version.game_versions.clone()),
+        file_name_override: None,
+        associated_loader: Some(loader),
+        modpack_origin: None,
+        updates_enabled: true,
+    })
+}
+
+fn curseforge_mod(
+    hashed: &HashedJar,
+    file: &CurseForgeFile,
+    project_name: Option<&String>,
+    loader: ModLoader,
+) -> Mod {
+    Mod {
+        id: Uuid::new_v4(),
+        source: ModSource::CurseForge {
+            project_id: file.modId.to_string(),
+            file_id: file.id.to_string(),
+            file_name: file.fileName.clone(),
+            download_url: file.downloadUrl.clone(),
+            file_hash_sha1: Some(hashed.sha1.clone()),
+            file_fingerprint: Some(file.fileFingerprint),
+        },
+        enabled: hashed.jar.enabled,
+        display_name: Some(
+            project_name
+                .cloned()
+                .unwrap_or_else(|| file.displayName.clone()),
+        ),
+        version: Some(file.displayName.clone()),
+        game_versions: Some(file.gameVersions.clone()),
+        file_name_override: None,
+        associated_loader: Some(loader),
+        modpack_origin: None,
+        updates_enabled: true,
+    }
+}
+
+/// The only game version and loader all matched Modrinth versions support, if there is one
+fn infer_from_versions(versions: &[&ModrinthVersion]) -> (Option<String>, Option<ModLoader>) {
+    let common = |values: fn(&ModrinthVersion) -> &Vec<String>| {
+        let mut iter = versions
+            .iter()
+            .map(|version| values(version).iter().cloned().collect::<HashSet<String>>());
+        let first = iter.next()?;
+        let shared: Vec<String> = iter
+            .fold(first, |acc, set| acc.intersection(&set).cloned().collect())
+            .into_iter()
+            .collect();
+        (shared.len() == 1).then(|| shared[0].clone())
+    };
+    (
+        common(|version| &version.game_versions),
+        common(|version| &version.loaders).and_then(|loader| ModLoader::from_str(&loader).ok()),
+    )
+}
+
+/// Splits the jars into those with a Modrinth version (paired with their mod) and the rest
+fn match_modrinth_jars(
+    hashed: Vec<HashedJar>,
+    versions: &HashMap<String, ModrinthVersion>,
+    loader: ModLoader,
+) -> (Vec<(Mod, PathBuf)>, Vec<HashedJar>) {
+    let mut matched = Vec::new();
+    let mut unmatched = Vec::new();
+    for hashed_jar in hashed {
+        match versions
+            .get(&hashed_jar.sha1)
+            .and_then(|version| modrinth_mod(&hashed_jar, version, loader))
+        {
+            Some(mod_info) => matched.push((mod_info, hashed_jar.jar.path)),
+            None => unmatched.push(hashed_jar),
+        }
+    }
+    (matched, unmatched)
+}
+
+/// Splits the jars into those with a CurseForge fingerprint match (paired with their mod)
+/// and the rest
+fn match_curseforge_jars(
+    hashed: Vec<HashedJar>,
+    matches: &[CurseForgeFingerprintMatch],
+    project_names: &HashMap<u32, String>,
+    loader: ModLoader,
+) -> (Vec<(Mod, PathBuf)>, Vec<HashedJar>) {
+    let mut matched = Vec::new();
+    let mut unmatched = Vec::new();
+    for hashed_jar in hashed {
+        match matches
+            .iter()
+            .find(|m| m.file.fileFingerprint == hashed_jar.fingerprint)
+        {
+            Some(found) => {
+                let mod_info = curseforge_mod(
+                    &hashed_jar,
+                    &found.file,
+                    project_names.get(&found.id),
+                    loader,
+                );
+                matched.push((mod_info, hashed_jar.jar.path));
+            }
+            None => unmatched.push(hashed_jar),
+        }
+    }
+    (matched, unmatched)
+}
+
+/// Fills the new instance directory: the game directory, matched jars into the mod cache
+/// (so the first launch needs no download) and unmatched ones as custom mods.
+/// Returns the file names of the custom mods.
+async fn copy_instance_files(
+    game_dir: &Path,
+    instance_path: &Path,
+    matched: &[(Mod, PathBuf)],
+    unmatched: &[HashedJar],
+) -> Result<Vec<String>> {
+    fs::create_dir_all(instance_path).await?;
+    copy_game_dir(game_dir, instance_path).await?;
+
+    let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+    fs::create_dir_all(&mod_cache_dir).await?;
+    for (mod_info, jar_path) in matched {
+        let cache_path =
+            mod_cache_dir.join(profile_state::get_mod_cache_filename(&mod_info.source)?);
+        if !cache_path.exists() {
+            fs::copy(jar_path, &cache_path).await?;
+        }
+    }
+
+    let custom_mods_dir = instance_path.join("custom_mods");
+    fs::create_dir_all(&custom_mods_dir).await?;
+    let mut custom_mods = Vec::new();
+    for hashed_jar in unmatched {
+        let file_name = dir_name(&hashed_jar.jar.path);
+        fs::copy(&hashed_jar.jar.path, custom_mods_dir.join(&file_name)).await?;
+        custom_mods.push(file_name);
+    }
+    Ok(custom_mods)
+}
+
+/// Copies a game directory except mods and launcher internals into `target`
+async fn copy_game_dir(game_dir: &Path, target: &Path) -> Result<()> {
+    let semaphore = Arc::new(Semaphore::new(COPY_CONCURRENCY));
+    let mut entries = fs::read_dir(game_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if SKIPPED_GAME_DIR_ENTRIES.contains(&name.as_str()) {
+            continue;
+        }
+        let source = entry.path();
+        let destination = target.join(&name);
+        if source.is_dir() {
+            fs::create_dir_all(&destination).await?;
+            path_utils::copy_dir_recursively(&source, &destination, semaphore.clone()).await?;
+        } else {
+            fs::copy(&source, &destination).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Converts an external instance into a new profile and returns what was matched.
+pub async fn import_external_instance(
+    mut instance: ExternalInstance,
+) -> Result<ExternalImportResult> {
+    info!(
+        "Importing {:?} instance '{}' from {}",
+        instance.launcher,
+        instance.name,
+        instance.game_dir.display()
+    );
+    if !instance.game_dir.is_dir() {
+        return Err(AppError::NotFound(format!(
+            "Game directory {}",
+            instance.game_dir.display()
+        )));
+    }
+
+    // Vanilla launcher installations share one mods folder, a vanilla one does not own it.
+    // Other instances without a loader (e.g. Modrinth App profiles without metadata) get it
+    // from the matched mods.
+    let shares_mods_dir = instance.launcher == ExternalLauncher::VanillaLauncher
+        && instance.loader == ModLoader::Vanilla;
+    let infer_loader = instance.loader == ModLoader::Vanilla && !shares_mods_dir;
+    let jars = if shares_mods_dir {
+        Vec::new()
+    } else {
+        collect_mod_jars(&instance.game_dir.join("mods"))
+    };
+
+    let mut hashed = Vec::new();
+    for jar in jars {
+        let bytes = fs::read(&jar.path).await?;
+        hashed.push(HashedJar {
+            sha1: hash_utils::calculate_sha1_from_bytes(&bytes),
+            fingerprint: hash_utils::calculate_curseforge_fingerprint(&bytes) as u64,
+            jar,
+        });
+    }
+
+    let modrinth_versions = match modrinth::get_versions_by_hashes(
+        hashed.iter().map(|h| h.sha1.clone()).collect(),
+        "sha1",
+    )
+    .await
+    {
+        Ok(versions) => versions,
+        Err(e) => {
+            warn!("Modrinth hash lookup failed, continuing without: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let (inferred_version, inferred_loader) =
+        infer_from_versions(&modrinth_versions.values().collect::<Vec<_>>());
+    if infer_loader {
+        instance.loader = inferred_loader.unwrap_or(ModLoader::Vanilla);
+    }
+    // A vanilla profile can't load the jars, ask for the loader instead of dropping them
+    if instance.loader == ModLoader::Vanilla && !hashed.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "The mod loader of '{}' could not be determined from its {} mods, please select it",
+            instance.name,
+            hashed.len()
+        )));
+    }
+    instance.game_version = match instance.game_version.take() {
+        Some(latest) if latest.starts_with("latest-") => {
+            let manifest = MinecraftApiService::new().get_version_manifest().await?;
+            Some(if latest == "latest-snapshot" {
+                manifest.latest.snapshot
+            } else {
+                manifest.latest.release
+            })
+        }
+        Some(version) => Some(version),
+        None => inferred_version,
+    };
+    let game_version = instance.game_version.clone().ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "The Minecraft version of '{}' could not be determined, please select it",
+            instance.name
+        ))
+    })?;
+
+    let (mut matched, mut unmatched) =
+        match_modrinth_jars(hashed, &modrinth_versions, instance.loader);
+    let modrinth_mods = matched.len();
+
+    if !unmatched.is_empty() {
+        let matches =
+            curseforge::get_fingerprint_matches(unmatched.iter().map(|h| h.fingerprint).collect())
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(
+                        "CurseForge fingerprint lookup failed, continuing without: {}",
+                        e
+                    );
+                    Vec::new()
+                });
+        let project_names: HashMap<u32, String> =
+            match curseforge::get_mods_by_ids(matches.iter().map(|m| m.id).collect(), None).await {
+                Ok(response) => response
+                    .data
+                    .into_iter()
+                    .map(|project| (project.id, project.name))
+                    .collect(),
+                Err(e) => {
+                    warn!("Could not fetch CurseForge project names: {}", e);
+                    HashMap::new()
+                }
+            };
+
+        let (curseforge_matched, still_unmatched) =
+            match_curseforge_jars(unmatched, &matches, &project_names, instance.loader);
+        matched.extend(curseforge_matched);
+        unmatched = still_unmatched;
+    }
+    let curseforge_mods = matched.len() - modrinth_mods;
+
+    let base_profiles_dir = default_profile_path();
+    let mut segment = sanitize(&instance.name);
+    if segment.is_empty() {
+        segment = format!("imported-{}", Utc::now().timestamp_millis());
+    }
+    let profile = Profile {
+        id: Uuid::new_v4(),
+        name: instance.name.clone(),
+        path: path_utils::find_unique_profile_segment(&base_profiles_dir, &segment).await?,
+        game_version,
+        loader: instance.loader,
+        loader_version: instance.loader_version.clone(),
+        created: Utc::now(),
+        last_played: None,
+        settings: ProfileSettings::default(),
+        state: ProfileState::NotInstalled,
+        mods: matched
+            .iter()
+            .map(|(mod_info, _)| mod_info.clone())
+            .collect(),
+        selected_GEG_pack_id: None,
+        disabled_GEG_mods_detailed: HashSet::new(),
+        source_standard_profile_id: None,
+        group: None,
+        is_standard_version: false,
+        use_shared_minecraft_folder: false,
+        description: None,
+        GEG_information: None,
+        banner: None,
+        background: None,
+        modpack_info: None,
+    };
+
+    // Everything is copied before the profile is registered, so a failed import leaves nothing
+    // behind. The profile path is unique, the directory did not exist before.
+    let state = State::get().await?;
+    let instance_path = state
+        .profile_manager
+        .calculate_instance_path_for_profile(&profile)?;
+    let created = async {
+        let custom_mods =
+            copy_instance_files(&instance.game_dir, &instance_path, &matched, &unmatched).await?;
+        let profile_id = state.profile_manager.create_profile(profile).await?;
+        Ok::<_, AppError>((profile_id, custom_mods))
+    }
+    .await;
+    let (profile_id, custom_mods) = match created {
+        Ok(created) => created,
+        Err(e) => {
+            if let Err(cleanup_error) = fs::remove_dir_all(&instance_path).await {
+                warn!(
+                    "Failed to remove partially imported instance {}: {}",
+                    instance_path.display(),
+                    cleanup_error
+                );
+            }
+            return Err(e);
+        }
+    };
+
+    info!(
+        "Imported '{}' as profile {}: {} Modrinth, {} CurseForge, {} custom mods",
+        instance.name,
+        profile_id,
+        modrinth_mods,
+        curseforge_mods,
+        custom_mods.len()
+    );
+    Ok(ExternalImportResult {
+        profile_id,
+        modrinth_mods,
+        curseforge_mods,
+        custom_mods,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(path: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/instance_import")
+            .join(path)
+    }
+
+    #[test]
+    fn test_reads_prism_instance_and_its_mods() {
+        let instances = read_instances_at(&fixture("prism"));
+        assert_eq!(instances.len(), 1);
+        let instance = &instances[0];
+        assert_eq!(instance.launcher, ExternalLauncher::PrismLauncher);
+        assert_eq!(instance.name, "Fabric Pack");
+        assert_eq!(instance.game_version.as_deref(), Some("1.20.1"));
+        assert_eq!(instance.loader, ModLoader::Fabric);
+        assert_eq!(instance.loader_version.as_deref(), Some("0.15.11"));
+        assert!(instance.game_dir.ends_with("Fabric Pack/.minecraft"));
+
+        let jars = collect_mod_jars(&instance.game_dir.join("mods"));
+        let summary: Vec<_> = jars
+            .iter()
+            .map(|jar| (jar.file_name.as_str(), jar.enabled))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("lithium-fabric-mc1.20.1-0.11.2.jar", false),
+                ("sodium-fabric-0.5.8+mc1.20.1.jar", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reads_vanilla_launcher_profiles() {
+        let minecraft_dir = fixture("vanilla");
+        let instances = read_vanilla_profiles(&minecraft_dir).unwrap();
+        let find = |name: &str| instances.iter().find(|i| i.name == name).unwrap();
+
+        let fabric = find("fabric-loader-1.20.1");
+        assert_eq!(fabric.game_version.as_deref(), Some("1.20.1"));
+        assert_eq!(fabric.loader, ModLoader::Fabric);
+        assert_eq!(fabric.loader_version.as_deref(), Some("0.15.11"));
+        assert_eq!(fabric.game_dir, minecraft_dir);
+
+        let forge = find("Forge");
+        assert_eq!(forge.loader, ModLoader::Forge);
+        assert_eq!(forge.loader_version.as_deref(), Some("47.2.0"));
+        assert_eq!(forge.game_dir, PathBuf::from("/games/forge-1.20.1"));
+
+        let neoforge = find("NeoForge");
+        assert_eq!(neoforge.loader, ModLoader::NeoForge);
+        assert_eq!(neoforge.game_version.as_deref(), Some("1.21.1"));
+
+        let latest = find("Latest release");
+        assert_eq!(latest.loader, ModLoader::Vanilla);
+        assert_eq!(latest.game_version.as_deref(), Some("latest-release"));
+    }
+
+    #[test]
+    fn test_reads_modrinth_app_profiles() {
+        let instances = read_instances_at(&fixture("modrinth_app/profiles"));
+        assert_eq!(instances.len(), 2);
+
+        let legacy = &instances[0];
+        assert_eq!(legacy.name, "Legacy Pack");
+        assert_eq!(legacy.game_version.as_deref(), Some("1.19.2"));
+        assert_eq!(legacy.loader, ModLoader::Quilt);
+        assert_eq!(legacy.loader_version.as_deref(), Some("0.19.2"));
+
+        let new = &instances[1];
+        assert_eq!(new.name, "New Pack");
+        assert_eq!(new.game_version, None);
+        assert_eq!(new.loader, ModLoader::Vanilla);
+    }
+
+    fn modrinth_version(sha1: &str, game_versions: &[&str], loaders: &[&str]) -> ModrinthVersion {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("{}-version", sha1),
+            "project_id": format!("{}-project", sha1),
+            "author_id": null,
+            "featured": false,
+            "name": sha1,
+            "version_number": "1.0.0",
+            "changelog": null,
+            "dependencies": [],
+            "game_versions": game_versions,
+            "version_type": "release",
+            "loaders": loaders,
+            "files": [{
+                "hashes": { "sha1": sha1, "sha512": null },
+                "url": format!("https://cdn.modrinth.com/{}.jar", sha1),
+                "filename": format!("{}.jar", sha1),
+                "primary": true,
+                "size": 1,
+                "file_type": null
+            }],
+            "date_published": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap()
+    }
+
+    fn hashed_jar(name: &str, sha1: &str, fingerprint: u64) -> HashedJar {
+        HashedJar {
+            jar: ModJar {
+                path: PathBuf::from(format!("/mods/{}", name)),
+                file_name: name.to_string(),
+                enabled: true,
+            },
+            sha1: sha1.to_string(),
+            fingerprint,
+        }
+    }
+
+    #[test]
+    fn test_infers_only_unambiguous_version_and_loader() {
+        let sodium = modrinth_version("sodium", &["1.20.1", "1.20.2"], &["fabric", "quilt"]);
+        let lithium = modrinth_version("lithium", &["1.20.1"], &["fabric"]);
+        assert_eq!(
+            infer_from_versions(&[&sodium, &lithium]),
+            (Some("1.20.1".to_string()), Some(ModLoader::Fabric))
+        );
+        // Both loaders remain possible with only sodium
+        assert_eq!(infer_from_versions(&[&sodium]), (None, None));
+        assert_eq!(infer_from_versions(&[]), (None, None));
+    }
+
+    #[test]
+    fn test_splits_matched_and_unmatched_jars() {
+        let versions = HashMap::from([(
+            "aaa".to_string(),
+            modrinth_version("aaa", &["1.20.1"], &["fabric"]),
+        )]);
+        let jars = vec![
+            hashed_jar("modrinth.jar", "aaa", 1),
+            hashed_jar("curseforge.jar", "bbb", 2),
+            hashed_jar("custom.jar", "ccc", 3),
+        ];
+
+        let (matched, unmatched) = match_modrinth_jars(jars, &versions, ModLoader::Fabric);
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].1, PathBuf::from("/mods/modrinth.jar"));
+        assert!(matches!(
+            &matched[0].0.source,
+            ModSource::Modrinth { project_id, .. } if project_id == "aaa-project"
+        ));
+        assert_eq!(unmatched.len(), 2);
+
+        let file: CurseForgeFile = serde_json::from_value(serde_json::json!({
+            "id": 10, "gameId": 432, "modId": 20, "isAvailable": true,
+            "displayName": "CF Mod 1.0", "fileName": "cf-mod-1.0.jar", "releaseType": 1,
+            "fileStatus": 4, "hashes": [], "fileDate": "2024-01-01T00:00:00Z",
+            "fileLength": 1, "downloadCount": 0, "fileSizeOnDisk": null,
+            "downloadUrl": "https://edge.forgecdn.net/cf-mod-1.0.jar",
+            "gameVersions": ["1.20.1"], "sortableGameVersions": [], "dependencies": [],
+            "exposeAsAlternative": null, "parentProjectFileId": null, "alternateFileId": null,
+            "isServerPack": null, "serverPackFileId": null, "isEarlyAccessContent": null,
+            "earlyAccessEndDate": null, "fileFingerprint": 2, "modules": []
+        }))
+        .unwrap();
+        let matches = vec![CurseForgeFingerprintMatch {
+            id: 20,
+            file,
+            latest_files: Vec::new(),
+        }];
+        let names = HashMap::from([(20, "CF Mod".to_string())]);
+
+        let (matched, unmatched) =
+            match_curseforge_jars(unmatched, &matches, &names, ModLoader::Fabric);
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].0.display_name.as_deref(), Some("CF Mod"));
+        assert_eq!(matched[0].1, PathBuf::from("/mods/curseforge.jar"));
+        // Jars found on neither platform are kept for the custom mods folder
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].jar.file_name, "custom.jar");
+    }
+}
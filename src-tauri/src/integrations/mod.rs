@@ -1,8 +1,12 @@
 pub mod curseforge;
+pub mod instance_import;
+pub mod mod_dependencies;
+pub mod mod_updates;
 pub mod modrinth;
 pub mod mrpack;
 pub mod norisk_packs;
 pub mod norisk_versions;
+pub mod pack_export;
 pub mod unified_mod;
 
 pub use norisk_packs::*;
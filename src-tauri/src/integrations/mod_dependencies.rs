@@ -0,0 +1,1204 @@
+//! Dependency resolution and conflict detection for the Modrinth/CurseForge mods of a profile.
+//!
+//! The declared dependencies of every enabled mod are fetched from its platform, required ones
+//! are followed transitively (resolving a version for the profile's loader and game version),
+//! `incompatible` relations are checked against the installed mods and projects added twice
+//! are reported as duplicates. Mods without platform metadata (custom jars, URL/Maven mods) are
+//! checked through the metadata in their jars, which also reveals jars built for another loader.
+
+use crate::commands::content_command::InstallContentPayload;
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::Result;
+use crate::integrations::unified_mod::{
+    self, ModPlatform, UnifiedDependency, UnifiedDependencyType, UnifiedModVersionsParams,
+    UnifiedVersion, UnifiedVersionType,
+};
+use crate::integrations::{curseforge, modrinth};
+use crate::minecraft::downloads::mod_downloader::MOD_CACHE_DIR_NAME;
+use crate::minecraft::downloads::mod_resolver::{self, TargetMod};
+use crate::state::event_state::{EventPayload, EventType};
+use crate::state::profile_state::{self, ModLoader, ModSource, Profile};
+use crate::state::state_manager::State;
+use crate::utils::hash_utils;
+use crate::utils::jar_metadata::{self, JarDependencyKind, JarMetadata, PLATFORM_MOD_IDS};
+use crate::utils::profile_utils::ContentType;
+use futures::future::join_all;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Results of the last successful platform lookups, one file per profile
+const CHECK_CACHE_DIR: &str = "dependency_checks";
+
+/// A project on one of the mod platforms
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProjectRef {
+    pub platform: ModPlatform,
+    pub project_id: String,
+}
+
+impl ProjectRef {
+    pub fn from_source(source: &ModSource) -> Option<Self> {
+        match source {
+            ModSource::Modrinth { project_id, .. } => Some(Self {
+                platform: ModPlatform::Modrinth,
+                project_id: project_id.clone(),
+            }),
+            ModSource::CurseForge { project_id, .. } => Some(Self {
+                platform: ModPlatform::CurseForge,
+                project_id: project_id.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Parses the canonical keys used by the mod resolver (`modrinth:<id>`, `curseforge:<id>`)
+    pub fn from_canonical_key(key: &str) -> Option<Self> {
+        let (platform, project_id) = key.split_once(':')?;
+        let platform = match platform {
+            "modrinth" => ModPlatform::Modrinth,
+            "curseforge" => ModPlatform::CurseForge,
+            _ => return None,
+        };
+        Some(Self {
+            platform,
+            project_id: project_id.to_string(),
+        })
+    }
+}
+
+/// A mod of the profile taking part in a dependency relation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DependencyMod {
+    /// `None` for dependencies that are not installed yet
+    pub mod_id: Option<Uuid>,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MissingDependency {
+    pub project: ProjectRef,
+    pub required_by: Vec<DependencyMod>,
+    /// The dependency is in the profile but disabled, enabling it is enough
+    pub disabled_mod_id: Option<Uuid>,
+    /// Best version for the profile's loader and game version, `None` if there is none
+    pub candidate: Option<UnifiedVersion>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModConflict {
+    pub declared_by: DependencyMod,
+    pub incompatible_with: DependencyMod,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateProject {
+    pub project: ProjectRef,
+    pub mods: Vec<DependencyMod>,
+}
+
+/// A mod ID required by a jar's own metadata that no other jar provides
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MissingModId {
+    pub required_by: DependencyMod,
+    pub mod_id: String,
+    pub version_range: Option<String>,
+}
+
+/// A jar that only has metadata for loaders the profile can't run
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct WrongLoaderJar {
+    pub jar: DependencyMod,
+    pub loaders: Vec<ModLoader>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DependencyReport {
+    pub profile_id: Uuid,
+    pub missing: Vec<MissingDependency>,
+    pub conflicts: Vec<ModConflict>,
+    pub duplicates: Vec<DuplicateProject>,
+    /// Dependencies declared in jars that are not on a platform (custom, URL and Maven mods)
+    pub missing_mod_ids: Vec<MissingModId>,
+    pub wrong_loader: Vec<WrongLoaderJar>,
+    /// Mods whose metadata could not be fetched, their dependencies are unknown
+    pub unchecked: Vec<DependencyMod>,
+}
+
+impl DependencyReport {
+    pub fn is_satisfied(&self) -> bool {
+        self.missing.is_empty()
+            && self.conflicts.is_empty()
+            && self.duplicates.is_empty()
+            && self.missing_mod_ids.is_empty()
+            && self.wrong_loader.is_empty()
+    }
+}
+
+/// Findings that need the Modrinth/CurseForge APIs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct PlatformCheck {
+    missing: Vec<MissingDependency>,
+    conflicts: Vec<ModConflict>,
+    unchecked: Vec<DependencyMod>,
+}
+
+/// A `PlatformCheck` and the mod set it was made for
+#[derive(Serialize, Deserialize, Debug)]
+struct CachedPlatformCheck {
+    mod_set_key: String,
+    check: PlatformCheck,
+}
+
+/// A mod jar on disk that takes part in the jar metadata checks
+#[derive(Debug, Clone)]
+pub struct LocalJar {
+    pub owner: DependencyMod,
+    pub path: PathBuf,
+    /// Dependencies come from Modrinth/CurseForge, the jar's own ones are not checked again
+    pub declared_on_platform: bool,
+}
+
+#[derive(Debug, Clone)]
+struct InstalledMod {
+    id: Uuid,
+    name: String,
+    enabled: bool,
+}
+
+impl InstalledMod {
+    fn as_dependency_mod(&self) -> DependencyMod {
+        DependencyMod {
+            mod_id: Some(self.id),
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// Bookkeeping while walking the dependency graph, kept free of network access
+struct Resolution {
+    installed: HashMap<ProjectRef, Vec<InstalledMod>>,
+    /// Projects provided outside the profile's mod list, e.g. by the selected pack
+    provided: HashSet<ProjectRef>,
+    missing: Vec<MissingDependency>,
+    conflicts: Vec<ModConflict>,
+}
+
+impl Resolution {
+    fn new(profile: &Profile, provided: HashSet<ProjectRef>) -> Self {
+        let mut installed: HashMap<ProjectRef, Vec<InstalledMod>> = HashMap::new();
+        for mod_info in &profile.mods {
+            if let Some(project) = ProjectRef::from_source(&mod_info.source) {
+                installed.entry(project).or_default().push(InstalledMod {
+                    id: mod_info.id,
+                    name: mod_info
+                        .display_name
+                        .clone()
+                        .unwrap_or_else(|| mod_info.id.to_string()),
+                    enabled: mod_info.enabled,
+                });
+            }
+        }
+        Self {
+            installed,
+            provided,
+            missing: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    fn is_present(&self, project: &ProjectRef) -> bool {
+        self.provided.contains(project)
+            || self
+                .installed
+                .get(project)
+                .is_some_and(|mods| mods.iter().any(|m| m.enabled))
+    }
+
+    /// Records the dependencies declared by `requester`.
+    /// Returns the required projects that were not known to be missing before.
+    fn apply(
+        &mut self,
+        requester: &DependencyMod,
+        platform: &ModPlatform,
+        dependencies: &[UnifiedDependency],
+    ) -> Vec<ProjectRef> {
+        let mut newly_missing = Vec::new();
+        for dependency in dependencies {
+            let Some(project_id) = &dependency.project_id else {
+                continue;
+            };
+            let project = ProjectRef {
+                platform: platform.clone(),
+                project_id: project_id.clone(),
+            };
+
+            match dependency.dependency_type {
+                UnifiedDependencyType::Required => {
+                    if self.is_present(&project) {
+                        continue;
+                    }
+                    if let Some(existing) = self.missing.iter_mut().find(|m| m.project == project) {
+                        if !existing.required_by.contains(requester) {
+                            existing.required_by.push(requester.clone());
+                        }
+                        continue;
+                    }
+                    let disabled_mod_id = self
+                        .installed
+                        .get(&project)
+                        .and_then(|mods| mods.first())
+                        .map(|m| m.id);
+                    self.missing.push(MissingDependency {
+                        project: project.clone(),
+                        required_by: vec![requester.clone()],
+                        disabled_mod_id,
+                        candidate: None,
+                    });
+                    newly_missing.push(project);
+                }
+                UnifiedDependencyType::Incompatible => {
+                    let Some(mods) = self.installed.get(&project) else {
+                        continue;
+                    };
+                    for installed in mods.iter().filter(|m| m.enabled) {
+                        let conflict = ModConflict {
+                            declared_by: requester.clone(),
+                            incompatible_with: installed.as_dependency_mod(),
+                        };
+                        if !self.conflicts.contains(&conflict) {
+                            self.conflicts.push(conflict);
+                        }
+                    }
+                }
+                UnifiedDependencyType::Optional | UnifiedDependencyType::Embedded => {}
+            }
+        }
+        newly_missing
+    }
+
+    fn duplicates(&self) -> Vec<DuplicateProject> {
+        let mut duplicates: Vec<DuplicateProject> = self
+            .installed
+            .iter()
+            .filter_map(|(project, mods)| {
+                let enabled: Vec<DependencyMod> = mods
+                    .iter()
+                    .filter(|m| m.enabled)
+                    .map(InstalledMod::as_dependency_mod)
+                    .collect();
+                (enabled.len() > 1).then(|| DuplicateProject {
+                    project: project.clone(),
+                    mods: enabled,
+                })
+            })
+            .collect();
+        duplicates.sort_by(|a, b| a.project.project_id.cmp(&b.project.project_id));
+        duplicates
+    }
+}
+
+/// Checks the loader metadata of the jars against each other
+fn check_jar_metadata(
+    loader: ModLoader,
+    game_version: &str,
+    jars: &[(LocalJar, JarMetadata)],
+) -> (Vec<MissingModId>, Vec<WrongLoaderJar>) {
+    let loadable = |m: &jar_metadata::JarModMetadata| {
+        jar_metadata::loader_can_load(loader, m.loader, game_version)
+    };
+
+    let mut provided: HashSet<&str> = PLATFORM_MOD_IDS.iter().copied().collect();
+    for (_, metadata) in jars {
+        // Jar-in-jar mods are loaded too, e.g. the Fabric API modules bundled by many mods
+        for m in metadata
+            .mods
+            .iter()
+            .chain(&metadata.nested_mods)
+            .filter(|m| loadable(m))
+        {
+            provided.insert(&m.mod_id);
+            provided.extend(m.provides.iter().map(String::as_str));
+        }
+    }
+    // Old Fabric mods still depend on the legacy `fabric` ID of Fabric API
+    if provided.contains("fabric-api") {
+        provided.insert("fabric");
+    }
+
+    let mut missing = Vec::new();
+    let mut wrong_loader = Vec::new();
+    for (jar, metadata) in jars {
+        if !metadata.is_loadable_by(loader, game_version) {
+            wrong_loader.push(WrongLoaderJar {
+                jar: jar.owner.clone(),
+                loaders: metadata.loaders(),
+            });
+            continue;
+        }
+        if jar.declared_on_platform {
+            continue;
+        }
+        for m in metadata.mods.iter().filter(|m| loadable(m)) {
+            for dependency in &m.dependencies {
+                if dependency.kind != JarDependencyKind::Required
+                    || provided.contains(dependency.mod_id.as_str())
+                {
+                    continue;
+                }
+                let entry = MissingModId {
+                    required_by: jar.owner.clone(),
+                    mod_id: dependency.mod_id.clone(),
+                    version_range: dependency.version_range.clone(),
+                };
+                if !missing.contains(&entry) {
+                    missing.push(entry);
+                }
+            }
+        }
+    }
+    (missing, wrong_loader)
+}
+
+/// Dependency check inputs for the mods a sync places in the game's mods folder:
+/// the platform projects among them (pack mods included) and their jars.
+pub fn target_mod_inputs(
+    profile: &Profile,
+    target_mods: &[TargetMod],
+) -> (HashSet<ProjectRef>, Vec<LocalJar>) {
+    let provided = target_mods
+        .iter()
+        .filter_map(|m| ProjectRef::from_canonical_key(&m.mod_id))
+        .collect();
+    let jars = target_mods
+        .iter()
+        .map(|m| LocalJar {
+            owner: DependencyMod {
+                mod_id: profile
+                    .mods
+                    .iter()
+                    .find(|p| {
+                        profile_state::get_profile_mod_filename(&p.source)
+                            .ok()
+                            .as_ref()
+                            == Some(&m.filename)
+                    })
+                    .map(|p| p.id),
+                name: m.filename.clone(),
+            },
+            path: m.cache_path.clone(),
+            declared_on_platform: ProjectRef::from_canonical_key(&m.mod_id).is_some(),
+        })
+        .collect();
+    (provided, jars)
+}
+
+/// Projects of the other platform that the profile's enabled Modrinth/CurseForge mods are also
+/// published as, matched by SHA1 on Modrinth and by fingerprint on CurseForge.
+/// A dependency on such a project is satisfied without installing it twice.
+async fn cross_platform_projects(profile: &Profile) -> HashSet<ProjectRef> {
+    let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+    let mut sha1s = Vec::new();
+    let mut fingerprints = Vec::new();
+    for mod_info in profile.mods.iter().filter(|m| m.enabled) {
+        match &mod_info.source {
+            ModSource::CurseForge {
+                file_hash_sha1: Some(sha1),
+                ..
+            } => sha1s.push(sha1.clone()),
+            ModSource::Modrinth { .. } => {
+                let Ok(filename) = profile_state::get_profile_mod_filename(&mod_info.source) else {
+                    continue;
+                };
+                // Cached per file, jars that did not change are not read again
+                let path = mod_cache_dir.join(filename);
+                if let Ok(fingerprint) = jar_metadata::cached_curseforge_fingerprint(&path).await {
+                    fingerprints.push(fingerprint as u64);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut projects = HashSet::new();
+    match modrinth::get_versions_by_hashes(sha1s, "sha1").await {
+        Ok(versions) => projects.extend(versions.into_values().map(|v| ProjectRef {
+            platform: ModPlatform::Modrinth,
+            project_id: v.project_id,
+        })),
+        Err(e) => warn!("Failed to match CurseForge mods on Modrinth: {}", e),
+    }
+    match curseforge::get_fingerprint_matches(fingerprints).await {
+        Ok(matches) => projects.extend(matches.into_iter().map(|m| ProjectRef {
+            platform: ModPlatform::CurseForge,
+            project_id: m.file.modId.to_string(),
+        })),
+        Err(e) => warn!("Failed to match Modrinth mods on CurseForge: {}", e),
+    }
+    projects
+}
+
+/// Loaders whose mods run on the profile's loader
+fn compatible_loaders(loader: &ModLoader) -> Vec<String> {
+    match loader {
+        ModLoader::Quilt => vec!["quilt".to_string(), "fabric".to_string()],
+        other => vec![other.as_str().to_string()],
+    }
+}
+
+/// Fills in the project of Modrinth dependencies that only name a version
+async fn complete_dependencies(version: &mut UnifiedVersion) {
+    for dependency in &mut version.dependencies {
+        if dependency.project_id.is_some() || version.source != ModPlatform::Modrinth {
+            continue;
+        }
+        if let Some(version_id) = &dependency.version_id {
+            match modrinth::get_version_details(version_id.clone()).await {
+                Ok(details) => dependency.project_id = Some(details.project_id),
+                Err(e) => warn!(
+                    "Could not resolve project of dependency version {}: {}",
+                    version_id, e
+                ),
+            }
+        }
+    }
+}
+
+/// Versions of the installed, enabled mods keyed by mod ID
+async fn fetch_installed_versions(profile: &Profile) -> HashMap<Uuid, UnifiedVersion> {
+    let mut modrinth_ids: Vec<(Uuid, String)> = Vec::new();
+    let mut curseforge_ids: Vec<(Uuid, u32)> = Vec::new();
+    for mod_info in profile.mods.iter().filter(|m| m.enabled) {
+        match &mod_info.source {
+            ModSource::Modrinth { version_id, .. } => {
+                modrinth_ids.push((mod_info.id, version_id.clone()))
+            }
+            ModSource::CurseForge { file_id, .. } => {
+                if let Ok(file_id) = file_id.parse() {
+                    curseforge_ids.push((mod_info.id, file_id));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut versions = HashMap::new();
+    match modrinth::get_versions_by_ids(modrinth_ids.iter().map(|(_, v)| v.clone()).collect()).await
+    {
+        Ok(fetched) => {
+            for version in fetched {
+                for (mod_id, _) in modrinth_ids.iter().filter(|(_, v)| *v == version.id) {
+                    versions.insert(*mod_id, UnifiedVersion::from(version.clone()));
+                }
+            }
+        }
+        Err(e) => warn!(
+            "Failed to fetch Modrinth versions for dependency check: {}",
+            e
+        ),
+    }
+    match curseforge::get_files_by_ids(curseforge_ids.iter().map(|(_, f)| *f).collect()).await {
+        Ok(fetched) => {
+            for file in fetched {
+                for (mod_id, _) in curseforge_ids.iter().filter(|(_, f)| *f == file.id) {
+                    versions.insert(*mod_id, UnifiedVersion::from(file.clone()));
+                }
+            }
+        }
+        Err(e) => warn!(
+            "Failed to fetch CurseForge files for dependency check: {}",
+            e
+        ),
+    }
+
+    for version in versions.values_mut() {
+        complete_dependencies(version).await;
+    }
+    versions
+}
+
+/// Newest version of `project` for the profile, releases preferred over betas and alphas
+async fn find_candidate(project: &ProjectRef, profile: &Profile) -> Option<UnifiedVersion> {
+    let params = UnifiedModVersionsParams {
+        source: project.platform.clone(),
+        project_id: project.project_id.clone(),
+        loaders: Some(compatible_loaders(&profile.loader)),
+        game_versions: Some(vec![profile.game_version.clone()]),
+        limit: None,
+        offset: None,
+    };
+    match unified_mod::get_mod_versions_unified(params).await {
+        Ok(response) => {
+            let mut candidate = response.versions.into_iter().max_by(|a, b| {
+                (a.release_type == UnifiedVersionType::Release)
+                    .cmp(&(b.release_type == UnifiedVersionType::Release))
+                    .then_with(|| a.date_published.cmp(&b.date_published))
+            })?;
+            complete_dependencies(&mut candidate).await;
+            Some(candidate)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to find a version of dependency {} for {} {}: {}",
+                project.project_id,
+                profile.loader.as_str(),
+                profile.game_version,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Looks up the declared dependencies of the enabled platform mods and follows required ones.
+/// Projects installed from the other platform count as present.
+async fn check_platform_dependencies(
+    profile: &Profile,
+    mut provided: HashSet<ProjectRef>,
+) -> PlatformCheck {
+    provided.extend(cross_platform_projects(profile).await);
+    let mut resolution = Resolution::new(profile, provided);
+
+    let versions = fetch_installed_versions(profile).await;
+    let mut unchecked = Vec::new();
+    let mut pending = Vec::new();
+    for mod_info in profile.mods.iter().filter(|m| m.enabled) {
+        let Some(project) = ProjectRef::from_source(&mod_info.source) else {
+            continue;
+        };
+        let requester = DependencyMod {
+            mod_id: Some(mod_info.id),
+            name: mod_info
+                .display_name
+                .clone()
+                .unwrap_or_else(|| mod_info.id.to_string()),
+        };
+        match versions.get(&mod_info.id) {
+            Some(version) => pending.extend(resolution.apply(
+                &requester,
+                &project.platform,
+                &version.dependencies,
+            )),
+            None => unchecked.push(requester),
+        }
+    }
+
+    // Follow required dependencies of the versions that would be installed
+    let mut visited: HashSet<ProjectRef> = HashSet::new();
+    while !pending.is_empty() {
+        let batch: Vec<ProjectRef> = pending
+            .drain(..)
+            .filter(|project| visited.insert(project.clone()))
+            .collect();
+        let candidates =
+            join_all(batch.iter().map(|project| find_candidate(project, profile))).await;
+
+        for (project, candidate) in batch.into_iter().zip(candidates) {
+            let Some(candidate) = candidate else {
+                continue;
+            };
+            let requester = DependencyMod {
+                mod_id: None,
+                name: candidate.name.clone(),
+            };
+            pending.extend(resolution.apply(
+                &requester,
+                &project.platform,
+                &candidate.dependencies,
+            ));
+            if let Some(missing) = resolution.missing.iter_mut().find(|m| m.project == project) {
+                missing.candidate = Some(candidate);
+            }
+        }
+    }
+
+    PlatformCheck {
+        missing: resolution.missing,
+        conflicts: resolution.conflicts,
+        unchecked,
+    }
+}
+
+/// Identifies everything the platform lookups depend on: loader, game version,
+/// the profile's mods with their sources and the provided projects.
+fn mod_set_key(profile: &Profile, provided: &HashSet<ProjectRef>) -> String {
+    let mut mods: Vec<String> = profile
+        .mods
+        .iter()
+        .map(|m| {
+            format!(
+                "{}|{}|{}|{}",
+                m.id,
+                m.enabled,
+                m.display_name.as_deref().unwrap_or_default(),
+                serde_json::to_string(&m.source).unwrap_or_default()
+            )
+        })
+        .collect();
+    mods.sort();
+    let mut provided: Vec<String> = provided
+        .iter()
+        .map(|p| format!("{:?}:{}", p.platform, p.project_id))
+        .collect();
+    provided.sort();
+
+    let input = format!(
+        "{}\n{}\n{}\n{}",
+        profile.loader.as_str(),
+        profile.game_version,
+        mods.join("\n"),
+        provided.join("\n")
+    );
+    hash_utils::calculate_sha1_from_bytes(input.as_bytes())
+}
+
+fn check_cache_path(profile_id: Uuid) -> PathBuf {
+    LAUNCHER_DIRECTORY
+        .meta_dir()
+        .join(CHECK_CACHE_DIR)
+        .join(format!("{}.json", profile_id))
+}
+
+async fn read_cached_check(profile_id: Uuid, mod_set_key: &str) -> Option<PlatformCheck> {
+    let raw = tokio::fs::read(check_cache_path(profile_id)).await.ok()?;
+    let cached: CachedPlatformCheck = serde_json::from_slice(&raw).ok()?;
+    (cached.mod_set_key == mod_set_key).then_some(cached.check)
+}
+
+async fn write_cached_check(
+    profile_id: Uuid,
+    mod_set_key: String,
+    check: &PlatformCheck,
+) -> Result<()> {
+    let path = check_cache_path(profile_id);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let cached = CachedPlatformCheck {
+        mod_set_key,
+        check: check.clone(),
+    };
+    tokio::fs::write(&path, serde_json::to_vec(&cached)?).await?;
+    Ok(())
+}
+
+async fn run_check(
+    profile: &Profile,
+    provided: HashSet<ProjectRef>,
+    jars: Vec<LocalJar>,
+    reuse_cached: bool,
+) -> Result<DependencyReport> {
+    // Vanilla profiles don't load mods at all
+    if profile.loader == ModLoader::Vanilla {
+        return Ok(DependencyReport {
+            profile_id: profile.id,
+            missing: Vec::new(),
+            conflicts: Vec::new(),
+            duplicates: Vec::new(),
+            missing_mod_ids: Vec::new(),
+            wrong_loader: Vec::new(),
+            unchecked: Vec::new(),
+        });
+    }
+
+    let duplicates = Resolution::new(profile, provided.clone()).duplicates();
+
+    let mut inspected = Vec::new();
+    for jar in jars {
+        match jar_metadata::inspect_jar(&jar.path).await {
+            Ok(metadata) => inspected.push((jar, metadata)),
+            Err(e) => debug!("Could not read metadata of {:?}: {}", jar.path, e),
+        }
+    }
+    let (missing_mod_ids, wrong_loader) =
+        check_jar_metadata(profile.loader, &profile.game_version, &inspected);
+
+    let key = mod_set_key(profile, &provided);
+    let cached = if reuse_cached {
+        read_cached_check(profile.id, &key).await
+    } else {
+        None
+    };
+    let platform = match cached {
+        Some(check) => {
+            debug!(
+                "Mods of profile {} unchanged since the last dependency check, skipping platform lookups",
+                profile.id
+            );
+            check
+        }
+        None => {
+            let check = check_platform_dependencies(profile, provided).await;
+            // Incomplete checks (some mods unchecked) are not reused
+            if check.unchecked.is_empty() {
+                if let Err(e) = write_cached_check(profile.id, key, &check).await {
+                    warn!("Failed to cache dependency check of {}: {}", profile.id, e);
+                }
+            }
+            check
+        }
+    };
+
+    info!(
+        "Dependency check for profile {}: {} missing, {} conflicts, {} duplicates, {} missing mod IDs, {} wrong loader, {} unchecked",
+        profile.id,
+        platform.missing.len(),
+        platform.conflicts.len(),
+        duplicates.len(),
+        missing_mod_ids.len(),
+        wrong_loader.len(),
+        platform.unchecked.len()
+    );
+
+    Ok(DependencyReport {
+        profile_id: profile.id,
+        missing: platform.missing,
+        conflicts: platform.conflicts,
+        duplicates,
+        missing_mod_ids,
+        wrong_loader,
+        unchecked: platform.unchecked,
+    })
+}
+
+/// Checks the dependencies of a profile's mods.
+/// `provided` lists projects that are loaded without being in the profile's mod list (pack mods),
+/// `jars` the mod files whose own metadata is checked. Both come from `target_mod_inputs`.
+/// Projects installed from the other platform count as present.
+pub async fn check_dependencies(
+    profile: &Profile,
+    provided: HashSet<ProjectRef>,
+    jars: Vec<LocalJar>,
+) -> Result<DependencyReport> {
+    run_check(profile, provided, jars, false).await
+}
+
+/// Same as `check_dependencies`, but the platform lookups are skipped if the mod set did not
+/// change since the last complete check. Used before every launch.
+pub async fn check_dependencies_before_launch(
+    profile: &Profile,
+    provided: HashSet<ProjectRef>,
+    jars: Vec<LocalJar>,
+) -> Result<DependencyReport> {
+    run_check(profile, provided, jars, true).await
+}
+
+pub async fn check_profile_dependencies(profile_id: Uuid) -> Result<DependencyReport> {
+    let state = State::get().await?;
+    let profile = state.profile_manager.get_profile(profile_id).await?;
+
+    // Same mod list the next launch would sync, so pack mods count as provided
+    let pack_config = match &profile.selected_GEG_pack_id {
+        Some(_) => Some(state.GEG_pack_manager.get_config().await),
+        None => None,
+    };
+    let custom_mods = state.profile_manager.list_custom_mods(&profile).await?;
+    let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+    let target_mods = mod_resolver::resolve_target_mods(
+        &profile,
+        pack_config.as_ref(),
+        Some(&custom_mods),
+        &profile.game_version,
+        profile.loader.as_str(),
+        &mod_cache_dir,
+    )
+    .await?;
+
+    let (provided, jars) = target_mod_inputs(&profile, &target_mods);
+    check_dependencies(&profile, provided, jars).await
+}
+
+/// Emits a `DependencyIssues` event with the report if anything is unsatisfied
+pub async fn emit_dependency_issues(report: &DependencyReport) -> Result<()> {
+    if report.is_satisfied() {
+        return Ok(());
+    }
+    let state = State::get().await?;
+    state
+        .emit_event(EventPayload {
+            event_id: Uuid::new_v4(),
+            event_type: EventType::DependencyIssues,
+            target_id: Some(report.profile_id),
+            message: serde_json::to_string(report)?,
+            progress: None,
+            error: None,
+        })
+        .await
+}
+
+/// Re-checks a profile in the background after its mods changed
+pub fn spawn_dependency_check(profile_id: Uuid) {
+    tokio::spawn(async move {
+        match check_profile_dependencies(profile_id).await {
+            Ok(report) => {
+                if let Err(e) = emit_dependency_issues(&report).await {
+                    warn!("Failed to emit dependency issues for {}: {}", profile_id, e);
+                }
+            }
+            Err(e) => warn!("Dependency check for profile {} failed: {}", profile_id, e),
+        }
+    });
+}
+
+/// Installs the missing dependencies (or only `projects`, if given) and returns the new report.
+/// Dependencies that are in the profile but disabled are enabled instead.
+pub async fn install_missing_dependencies(
+    profile_id: Uuid,
+    projects: Option<Vec<ProjectRef>>,
+) -> Result<DependencyReport> {
+    let state = State::get().await?;
+    let report = check_profile_dependencies(profile_id).await?;
+
+    for missing in report.missing {
+        if projects
+            .as_ref()
+            .is_some_and(|wanted| !wanted.contains(&missing.project))
+        {
+            continue;
+        }
+
+        if let Some(mod_id) = missing.disabled_mod_id {
+            info!(
+                "Enabling disabled dependency {} in profile {}",
+                missing.project.project_id, profile_id
+            );
+            state
+                .profile_manager
+                .set_mod_enabled(profile_id, mod_id, true)
+                .await?;
+            continue;
+        }
+
+        let Some(candidate) = missing.candidate else {
+            warn!(
+                "No compatible version of dependency {} found, skipping",
+                missing.project.project_id
+            );
+            continue;
+        };
+        let Some(file) = candidate
+            .files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| candidate.files.first())
+        else {
+            warn!("Dependency version {} has no files, skipping", candidate.id);
+            continue;
+        };
+
+        debug!(
+            "Installing dependency {} ({}) into profile {}",
+            candidate.name, candidate.id, profile_id
+        );
+        let payload = InstallContentPayload {
+            profile_id,
+            project_id: candidate.project_id.clone(),
+            version_id: candidate.id.clone(),
+            file_name: file.filename.clone(),
+            download_url: file.url.clone(),
+            file_hash_sha1: file.hashes.get("sha1").cloned(),
+            file_fingerprint: file.fingerprint,
+            content_name: Some(candidate.name.clone()),
+            version_number: Some(candidate.version_number.clone()),
+            content_type: ContentType::Mod,
+            loaders: Some(candidate.loaders.clone()),
+            game_versions: Some(candidate.game_versions.clone()),
+            source: candidate.source.clone(),
+        };
+        // The transitive set is already part of the report, no need to recurse here
+        state
+            .profile_manager
+            .add_mod_from_payload(&payload, false)
+            .await?;
+    }
+
+    check_profile_dependencies(profile_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::profile_state::Mod;
+
+    fn modrinth_mod(project_id: &str, name: &str, enabled: bool) -> Mod {
+        Mod {
+            id: Uuid::new_v4(),
+            source: ModSource::Modrinth {
+                project_id: project_id.to_string(),
+                version_id: format!("{}-v1", project_id),
+                file_name: format!("{}.jar", project_id),
+                download_url: String::new(),
+                file_hash_sha1: None,
+            },
+            enabled,
+            display_name: Some(name.to_string()),
+            version: None,
+            game_versions: None,
+            file_name_override: None,
+            associated_loader: None,
+            modpack_origin: None,
+            updates_enabled: true,
+        }
+    }
+
+    fn dependency(project_id: &str, dependency_type: UnifiedDependencyType) -> UnifiedDependency {
+        UnifiedDependency {
+            project_id: Some(project_id.to_string()),
+            version_id: None,
+            file_name: None,
+            dependency_type,
+        }
+    }
+
+    fn project(project_id: &str) -> ProjectRef {
+        ProjectRef {
+            platform: ModPlatform::Modrinth,
+            project_id: project_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolution_tracks_missing_conflicts_and_duplicates() {
+        let mut profile: Profile = serde_json::from_value(serde_json::json!({
+            "name": "Test",
+            "path": "test",
+            "game_version": "1.20.1",
+            "loader": "fabric",
+            "loader_version": null,
+            "last_played": null,
+            "description": null,
+            "GEG_information": null
+        }))
+        .unwrap();
+        profile.mods = vec![
+            modrinth_mod("iris", "Iris", true),
+            modrinth_mod("sodium", "Sodium", false),
+            modrinth_mod("optifabric", "OptiFabric", true),
+            modrinth_mod("jei", "JEI", true),
+            modrinth_mod("jei", "JEI (copy)", true),
+        ];
+        let iris = DependencyMod {
+            mod_id: Some(profile.mods[0].id),
+            name: "Iris".to_string(),
+        };
+
+        let mut resolution = Resolution::new(&profile, HashSet::from([project("fabric-api")]));
+        let newly_missing = resolution.apply(
+            &iris,
+            &ModPlatform::Modrinth,
+            &[
+                dependency("sodium", UnifiedDependencyType::Required),
+                dependency("fabric-api", UnifiedDependencyType::Required),
+                dependency("optifabric", UnifiedDependencyType::Incompatible),
+                dependency("indium", UnifiedDependencyType::Optional),
+            ],
+        );
+
+        // Disabled Sodium is missing but can simply be enabled, the pack provides Fabric API
+        assert_eq!(newly_missing, vec![project("sodium")]);
+        assert_eq!(
+            resolution.missing[0].disabled_mod_id,
+            Some(profile.mods[1].id)
+        );
+        assert_eq!(resolution.conflicts.len(), 1);
+        assert_eq!(resolution.conflicts[0].incompatible_with.name, "OptiFabric");
+
+        // A second requester is recorded, but the project is not reported as new again
+        let candidate = DependencyMod {
+            mod_id: None,
+            name: "Sodium Extra".to_string(),
+        };
+        assert!(resolution
+            .apply(
+                &candidate,
+                &ModPlatform::Modrinth,
+                &[dependency("sodium", UnifiedDependencyType::Required)]
+            )
+            .is_empty());
+        assert_eq!(resolution.missing[0].required_by, vec![iris, candidate]);
+
+        let duplicates = resolution.duplicates();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].project, project("jei"));
+        assert_eq!(duplicates[0].mods.len(), 2);
+    }
+
+    #[test]
+    fn test_jar_metadata_reports_missing_ids_and_wrong_loader() {
+        use crate::utils::jar_metadata::{JarDependency, JarModMetadata};
+
+        fn jar(
+            name: &str,
+            mod_id: &str,
+            loader: ModLoader,
+            depends: &[&str],
+            on_platform: bool,
+        ) -> (LocalJar, JarMetadata) {
+            (
+                LocalJar {
+                    owner: DependencyMod {
+                        mod_id: None,
+                        name: name.to_string(),
+                    },
+                    path: PathBuf::from(name),
+                    declared_on_platform: on_platform,
+                },
+                JarMetadata {
+                    sha1: String::new(),
+                    mods: vec![JarModMetadata {
+                        mod_id: mod_id.to_string(),
+                        name: None,
+                        version: None,
+                        description: None,
+                        loader,
+                        minecraft_versions: None,
+                        dependencies: depends
+                            .iter()
+                            .map(|id| JarDependency {
+                                mod_id: id.to_string(),
+                                version_range: None,
+                                kind: JarDependencyKind::Required,
+                            })
+                            .collect(),
+                        provides: Vec::new(),
+                        icon: None,
+                    }],
+                    nested_mods: Vec::new(),
+                },
+            )
+        }
+
+        let mut jars = vec![
+            jar("fabric-api.jar", "fabric-api", ModLoader::Fabric, &[], true),
+            // Legacy `fabric` ID is satisfied by Fabric API, `cloth-config` is missing
+            jar(
+                "custom.jar",
+                "custom",
+                ModLoader::Fabric,
+                &[
+                    "fabric",
+                    "fabricloader",
+                    "minecraft",
+                    "cloth-config",
+                    "fabric-api-base",
+                ],
+                false,
+            ),
+            // Platform mods are checked through their platform metadata instead
+            jar("iris.jar", "iris", ModLoader::Fabric, &["sodium"], true),
+            jar(
+                "create.jar",
+                "create",
+                ModLoader::Forge,
+                &["flywheel"],
+                false,
+            ),
+        ];
+
+        // Bundled as jar-in-jar by Fabric API
+        jars[0].1.nested_mods = vec![JarModMetadata {
+            mod_id: "fabric-api-base".to_string(),
+            ..jars[0].1.mods[0].clone()
+        }];
+
+        let (missing, wrong_loader) = check_jar_metadata(ModLoader::Quilt, "1.20.1", &jars);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].mod_id, "cloth-config");
+        assert_eq!(missing[0].required_by.name, "custom.jar");
+        assert_eq!(wrong_loader.len(), 1);
+        assert_eq!(wrong_loader[0].jar.name, "create.jar");
+        assert_eq!(wrong_loader[0].loaders, vec![ModLoader::Forge]);
+    }
+
+    #[test]
+    fn test_project_ref_from_canonical_key() {
+        assert_eq!(
+            ProjectRef::from_canonical_key("modrinth:P7dR8mSH"),
+            Some(project("P7dR8mSH"))
+        );
+        assert_eq!(
+            ProjectRef::from_canonical_key("curseforge:238222").map(|p| p.platform),
+            Some(ModPlatform::CurseForge)
+        );
+        assert_eq!(ProjectRef::from_canonical_key("local:some.jar"), None);
+    }
+
+    #[test]
+    fn test_target_mod_inputs_include_pack_and_custom_mods() {
+        let mut profile: Profile = serde_json::from_value(serde_json::json!({
+            "name": "Test",
+            "path": "test",
+            "game_version": "1.20.1",
+            "loader": "fabric",
+            "loader_version": null,
+            "last_played": null,
+            "description": null,
+            "GEG_information": null
+        }))
+        .unwrap();
+        profile.mods = vec![modrinth_mod("sodium", "Sodium", true)];
+        let target = |mod_id: &str, filename: &str| TargetMod {
+            mod_id: mod_id.to_string(),
+            filename: filename.to_string(),
+            cache_path: PathBuf::from(filename),
+        };
+        let target_mods = vec![
+            target("modrinth:sodium", "sodium.jar"),
+            // Pack mod, not in the profile's mod list
+            target("modrinth:fabric-api", "fabric-api.jar"),
+            target("local:custom.jar", "custom.jar"),
+        ];
+
+        let (provided, jars) = target_mod_inputs(&profile, &target_mods);
+        assert_eq!(
+            provided,
+            HashSet::from([project("sodium"), project("fabric-api")])
+        );
+        assert_eq!(jars.len(), 3);
+        assert_eq!(jars[0].owner.mod_id, Some(profile.mods[0].id));
+        assert!(jars[0].declared_on_platform);
+        assert_eq!(jars[1].owner.mod_id, None);
+        assert!(jars[1].declared_on_platform);
+        assert_eq!(jars[2].owner.mod_id, None);
+        assert!(!jars[2].declared_on_platform);
+    }
+
+    #[test]
+    fn test_mod_set_key_changes_with_the_mod_set_only() {
+        let mut profile: Profile = serde_json::from_value(serde_json::json!({
+            "name": "Test",
+            "path": "test",
+            "game_version": "1.20.1",
+            "loader": "fabric",
+            "loader_version": null,
+            "last_played": null,
+            "description": null,
+            "GEG_information": null
+        }))
+        .unwrap();
+        profile.mods = vec![
+            modrinth_mod("sodium", "Sodium", true),
+            modrinth_mod("iris", "Iris", true),
+        ];
+        let provided = HashSet::from([project("fabric-api")]);
+        let key = mod_set_key(&profile, &provided);
+
+        // Order of the mod list doesn't matter
+        profile.mods.reverse();
+        assert_eq!(mod_set_key(&profile, &provided), key);
+
+        profile.mods[0].enabled = false;
+        let disabled = mod_set_key(&profile, &provided);
+        assert_ne!(disabled, key);
+        profile.mods[0].enabled = true;
+
+        if let ModSource::Modrinth { version_id, .. } = &mut profile.mods[0].source {
+            *version_id = "iris-v2".to_string();
+        }
+        assert_ne!(mod_set_key(&profile, &provided), key);
+
+        assert_ne!(mod_set_key(&profile, &HashSet::new()), key);
+    }
+}
@@ -0,0 +1,918 @@
+//! Batch updates of all Modrinth/CurseForge mods of a profile that have updates enabled.
+//!
+//! Planning finds the newest compatible version of every mod (hash lookup on Modrinth,
+//! fingerprints on CurseForge), collects the changelogs between the installed and the target
+//! version and flags updates that cross a major version. Applying downloads all new files into
+//! the mod cache in parallel first and only touches the profile if every download succeeded;
+//! a snapshot taken beforehand is rolled back if switching the versions fails halfway.
+//! Switching runs one mod at a time: every switch rewrites the whole profile and may install
+//! new dependencies, so concurrent switches would race on both.
+
+use crate::error::{AppError, Result};
+use crate::integrations::modrinth::{self, ModrinthVersion};
+use crate::integrations::unified_mod::ModPlatform;
+use crate::integrations::{curseforge, mod_dependencies};
+use crate::minecraft::downloads::mod_downloader::ModDownloadService;
+use crate::state::profile_state::{self, Mod, ModLoader, ModSource, Profile};
+use crate::state::state_manager::State;
+use crate::utils::profile_snapshot_utils::{self, SnapshotTrigger};
+use async_trait::async_trait;
+use futures::future::join_all;
+use log::{error, info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Changelog of one version between the installed and the target version
+#[derive(Serialize, Debug, Clone)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub date: Option<String>,
+    pub changelog: String,
+}
+
+#[derive(Debug, Clone)]
+enum UpdateTarget {
+    Modrinth(Box<ModrinthVersion>),
+    CurseForge(Box<curseforge::CurseForgeFile>),
+}
+
+impl UpdateTarget {
+    fn source(&self) -> Option<ModSource> {
+        match self {
+            UpdateTarget::Modrinth(version) => {
+                let file = version.files.iter().find(|f| f.primary)?;
+                Some(ModSource::Modrinth {
+                    project_id: version.project_id.clone(),
+                    version_id: version.id.clone(),
+                    file_name: file.filename.clone(),
+                    download_url: file.url.clone(),
+                    file_hash_sha1: file.hashes.sha1.clone(),
+                })
+            }
+            UpdateTarget::CurseForge(file) => Some(ModSource::CurseForge {
+                project_id: file.modId.to_string(),
+                file_id: file.id.to_string(),
+                file_name: file.fileName.clone(),
+                download_url: file.downloadUrl.clone(),
+                file_hash_sha1: file
+                    .hashes
+                    .iter()
+                    .find(|h| h.algo == 1)
+                    .map(|h| h.value.clone()),
+                file_fingerprint: Some(file.fileFingerprint),
+            }),
+        }
+    }
+}
+
+/// A version transition the batch update would perform
+#[derive(Serialize, Debug, Clone)]
+pub struct PlannedModUpdate {
+    pub mod_id: Uuid,
+    pub name: String,
+    pub platform: ModPlatform,
+    pub project_id: String,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub to_version_id: String,
+    pub file_name: String,
+    /// Newest first, from the target version down to the one after the installed version
+    pub changelogs: Vec<ChangelogEntry>,
+    /// The leading version component changes (the minor one for 0.x versions)
+    pub major_version_change: bool,
+    #[serde(skip)]
+    target: UpdateTarget,
+}
+
+/// A mod with updates enabled that could not be checked
+#[derive(Serialize, Debug, Clone)]
+pub struct SkippedModUpdate {
+    pub mod_id: Uuid,
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ModUpdatePlan {
+    pub profile_id: Uuid,
+    pub updates: Vec<PlannedModUpdate>,
+    pub skipped: Vec<SkippedModUpdate>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum ModUpdateStatus {
+    Updated,
+    /// Downloaded fine, but not applied because another update failed
+    NotApplied,
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ModUpdateResult {
+    pub mod_id: Uuid,
+    pub name: String,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    #[serde(flatten)]
+    pub status: ModUpdateStatus,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BatchUpdateResult {
+    pub plan: ModUpdatePlan,
+    pub dry_run: bool,
+    /// True if every planned update was applied. If false, nothing was changed unless
+    /// `rollback_error` is set
+    pub applied: bool,
+    /// Snapshot taken before applying, can be used to undo the whole batch
+    pub snapshot_id: Option<Uuid>,
+    /// Set if an update failed and restoring `snapshot_id` failed as well. The mods reported
+    /// as updated are still switched, the snapshot has to be restored manually.
+    pub rollback_error: Option<String>,
+    pub results: Vec<ModUpdateResult>,
+}
+
+fn mod_name(mod_info: &Mod) -> String {
+    mod_info.display_name.clone().unwrap_or_else(|| {
+        profile_state::get_profile_mod_filename(&mod_info.source)
+            .unwrap_or_else(|_| mod_info.id.to_string())
+    })
+}
+
+/// Leading numeric components of a version string, skipping Minecraft versions and tags
+/// such as `mc1.20.1-0.5.3`, `0.92.2+1.20.1` or `jei-1.21.1-neoforge-19.22.1.316`.
+fn version_core(version: &str, game_versions: &[String]) -> Option<Vec<u64>> {
+    version
+        .split(['-', '+', ' ', '_'])
+        .filter_map(|token| {
+            let start = token.find(|c: char| c.is_ascii_digit())?;
+            let numeric: String = token[start..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            let numeric = numeric.trim_end_matches('.');
+            if game_versions.iter().any(|gv| gv == numeric) {
+                return None;
+            }
+            numeric
+                .split('.')
+                .map(|part| part.parse().ok())
+                .collect::<Option<Vec<u64>>>()
+        })
+        .next()
+}
+
+/// Whether going from `from` to `to` crosses a major version. Versions below 1.0 treat the
+/// minor component as major, unparsable versions never count as a major change.
+fn crosses_major_version(from: &str, to: &str, game_versions: &[String]) -> bool {
+    let (Some(from), Some(to)) = (
+        version_core(from, game_versions),
+        version_core(to, game_versions),
+    ) else {
+        return false;
+    };
+    let significant = if from[0] == 0 && to[0] == 0 { 1 } else { 0 };
+    let component = |core: &[u64]| core.get(significant).copied().unwrap_or(0);
+    from[0] != to[0] || component(&from) != component(&to)
+}
+
+/// Modrinth changelogs from the target version down to (excluding) the installed one
+async fn modrinth_changelogs(
+    profile: &Profile,
+    installed_version_id: &str,
+    target: &ModrinthVersion,
+) -> Vec<ChangelogEntry> {
+    let entry = |version: &ModrinthVersion| {
+        version.changelog.as_ref().map(|changelog| ChangelogEntry {
+            version: version.version_number.clone(),
+            date: Some(version.date_published.clone()),
+            changelog: changelog.clone(),
+        })
+    };
+
+    let versions = match modrinth::get_mod_versions(
+        target.project_id.clone(),
+        Some(vec![profile.loader.as_str().to_string()]),
+        Some(vec![profile.game_version.clone()]),
+    )
+    .await
+    {
+        Ok(versions) => versions,
+        Err(e) => {
+            warn!(
+                "Could not fetch versions of {} for the changelog: {}",
+                target.project_id, e
+            );
+            return entry(target).into_iter().collect();
+        }
+    };
+
+    // Modrinth returns versions newest first
+    let Some(start) = versions.iter().position(|v| v.id == target.id) else {
+        return entry(target).into_iter().collect();
+    };
+    versions[start..]
+        .iter()
+        .take_while(|v| v.id != installed_version_id)
+        .filter_map(entry)
+        .collect()
+}
+
+async fn curseforge_changelog(file: &curseforge::CurseForgeFile) -> Vec<ChangelogEntry> {
+    match curseforge::get_file_changelog(file.modId, file.id).await {
+        Ok(changelog) if !changelog.trim().is_empty() => vec![ChangelogEntry {
+            version: file.displayName.clone(),
+            date: Some(file.fileDate.clone()),
+            changelog,
+        }],
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            warn!("Could not fetch changelog of file {}: {}", file.id, e);
+            Vec::new()
+        }
+    }
+}
+
+async fn plan_update(
+    profile: &Profile,
+    mod_info: &Mod,
+    file_name: String,
+    target: UpdateTarget,
+) -> PlannedModUpdate {
+    let (platform, project_id, to_version, to_version_id, changelogs) = match &target {
+        UpdateTarget::Modrinth(version) => {
+            let installed = match &mod_info.source {
+                ModSource::Modrinth { version_id, .. } => version_id.as_str(),
+                _ => "",
+            };
+            (
+                ModPlatform::Modrinth,
+                version.project_id.clone(),
+                version.version_number.clone(),
+                version.id.clone(),
+                modrinth_changelogs(profile, installed, version).await,
+            )
+        }
+        UpdateTarget::CurseForge(file) => (
+            ModPlatform::CurseForge,
+            file.modId.to_string(),
+            file.displayName.clone(),
+            file.id.to_string(),
+            curseforge_changelog(file).await,
+        ),
+    };
+    let mut known_game_versions = mod_info.game_versions.clone().unwrap_or_default();
+    known_game_versions.push(profile.game_version.clone());
+
+    PlannedModUpdate {
+        mod_id: mod_info.id,
+        name: mod_name(mod_info),
+        platform,
+        project_id,
+        major_version_change: mod_info
+            .version
+            .as_deref()
+            .is_some_and(|from| crosses_major_version(from, &to_version, &known_game_versions)),
+        from_version: mod_info.version.clone(),
+        to_version,
+        to_version_id,
+        file_name,
+        changelogs,
+        target,
+    }
+}
+
+/// Finds the available updates of all mods with updates enabled, without changing anything.
+pub async fn plan_profile_updates(profile: &Profile) -> Result<ModUpdatePlan> {
+    let mut plan = ModUpdatePlan {
+        profile_id: profile.id,
+        updates: Vec::new(),
+        skipped: Vec::new(),
+    };
+    if profile.loader == ModLoader::Vanilla {
+        return Ok(plan);
+    }
+
+    let loaders = vec![profile.loader.as_str().to_string()];
+    let game_versions = vec![profile.game_version.clone()];
+    let mut modrinth_mods = HashMap::new();
+    let mut curseforge_mods = HashMap::new();
+
+    for mod_info in profile.mods.iter().filter(|m| m.updates_enabled) {
+        let skip_reason = match &mod_info.source {
+            ModSource::Modrinth {
+                file_hash_sha1: Some(sha1),
+                ..
+            } => {
+                modrinth_mods.insert(sha1.clone(), mod_info);
+                None
+            }
+            ModSource::CurseForge {
+                file_fingerprint: Some(fingerprint),
+                ..
+            } => {
+                curseforge_mods.insert(*fingerprint, mod_info);
+                None
+            }
+            ModSource::Modrinth { .. } => Some("No SHA1 hash known for the installed file"),
+            ModSource::CurseForge { .. } => Some("No fingerprint known for the installed file"),
+            _ => None,
+        };
+        if let Some(reason) = skip_reason {
+            plan.skipped.push(SkippedModUpdate {
+                mod_id: mod_info.id,
+                name: mod_name(mod_info),
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    let modrinth_request = modrinth::ModrinthBulkUpdateRequestBody {
+        hashes: modrinth_mods.keys().cloned().collect(),
+        algorithm: "sha1".to_string(),
+        loaders: loaders.clone(),
+        game_versions: game_versions.clone(),
+    };
+    let (modrinth_updates, curseforge_updates) = tokio::join!(
+        async {
+            if modrinth_mods.is_empty() {
+                return Ok(HashMap::new());
+            }
+            modrinth::check_bulk_updates(modrinth_request).await
+        },
+        curseforge::check_mod_updates_bulk(
+            curseforge_mods.keys().copied().collect(),
+            &game_versions,
+            &loaders,
+        )
+    );
+
+    let mut candidates: Vec<(&Mod, UpdateTarget)> = Vec::new();
+    for (hash, version) in modrinth_updates? {
+        let Some(mod_info) = modrinth_mods.get(&hash) else {
+            continue;
+        };
+        if let ModSource::Modrinth { version_id, .. } = &mod_info.source {
+            if *version_id == version.id {
+                continue;
+            }
+        }
+        candidates.push((mod_info, UpdateTarget::Modrinth(Box::new(version))));
+    }
+
+    let curseforge_updates: Vec<_> = curseforge_updates?
+        .into_iter()
+        .filter(|update| {
+            match curseforge_mods
+                .get(&update.original_fingerprint)
+                .map(|mod_info| &mod_info.source)
+            {
+                Some(ModSource::CurseForge { file_id, .. }) => {
+                    *file_id != update.file_id.to_string()
+                }
+                _ => false,
+            }
+        })
+        .collect();
+    if !curseforge_updates.is_empty() {
+        let files = curseforge::get_files_by_ids(
+            curseforge_updates
+                .iter()
+                .map(|update| update.file_id)
+                .collect(),
+        )
+        .await?;
+        for update in curseforge_updates {
+            let mod_info = curseforge_mods[&update.original_fingerprint];
+            match files.iter().find(|file| file.id == update.file_id) {
+                Some(file) => {
+                    candidates.push((mod_info, UpdateTarget::CurseForge(Box::new(file.clone()))))
+                }
+                None => plan.skipped.push(SkippedModUpdate {
+                    mod_id: mod_info.id,
+                    name: mod_name(mod_info),
+                    reason: format!("CurseForge file {} could not be fetched", update.file_id),
+                }),
+            }
+        }
+    }
+
+    let mut planned = Vec::new();
+    for (mod_info, target) in candidates {
+        match target.source() {
+            Some(source) => planned.push((
+                mod_info,
+                profile_state::get_profile_mod_filename(&source).unwrap_or_default(),
+                target,
+            )),
+            None => plan.skipped.push(SkippedModUpdate {
+                mod_id: mod_info.id,
+                name: mod_name(mod_info),
+                reason: "The new version has no primary file".to_string(),
+            }),
+        }
+    }
+
+    plan.updates =
+        join_all(planned.into_iter().map(|(mod_info, file_name, target)| {
+            plan_update(profile, mod_info, file_name, target)
+        }))
+        .await;
+
+    info!(
+        "Planned {} mod updates for profile {} ({} skipped)",
+        plan.updates.len(),
+        profile.id,
+        plan.skipped.len()
+    );
+    Ok(plan)
+}
+
+/// Applies `update` through the profile manager, which also installs new required dependencies
+async fn apply_update(profile_id: Uuid, update: &PlannedModUpdate) -> Result<()> {
+    let state = State::get().await?;
+    match &update.target {
+        UpdateTarget::Modrinth(version) => {
+            state
+                .profile_manager
+                .update_profile_modrinth_mod_version(profile_id, update.mod_id, version)
+                .await
+        }
+        UpdateTarget::CurseForge(file) => {
+            state
+                .profile_manager
+                .update_profile_curseforge_mod_version(profile_id, update.mod_id, file)
+                .await
+        }
+    }
+}
+
+fn result_for(update: &PlannedModUpdate, status: ModUpdateStatus) -> ModUpdateResult {
+    ModUpdateResult {
+        mod_id: update.mod_id,
+        name: update.name.clone(),
+        from_version: update.from_version.clone(),
+        to_version: update.to_version.clone(),
+        status,
+    }
+}
+
+/// Side effects of applying a batch, so the all-or-nothing logic in `apply_batch` does not
+/// depend on the launcher state
+#[async_trait]
+trait BatchUpdateBackend: Sync {
+    /// Fetches the new file of `update` into the mod cache
+    async fn download(&self, update: &PlannedModUpdate) -> Result<()>;
+    /// Snapshots the profile before the first update is applied
+    async fn snapshot(&self) -> Result<Uuid>;
+    async fn apply(&self, update: &PlannedModUpdate) -> Result<()>;
+    async fn rollback(&self, snapshot_id: Uuid) -> Result<()>;
+}
+
+/// Applies updates to a profile of the profile manager
+struct ProfileUpdateBackend<'a> {
+    profile: &'a Profile,
+    downloader: ModDownloadService,
+}
+
+#[async_trait]
+impl BatchUpdateBackend for ProfileUpdateBackend<'_> {
+    async fn download(&self, update: &PlannedModUpdate) -> Result<()> {
+        match update.target.source() {
+            Some(source) => self
+                .downloader
+                .download_mod_to_cache(&source)
+                .await
+                .map(|_| ()),
+            None => Err(AppError::Other(format!(
+                "Version {} has no primary file",
+                update.to_version
+            ))),
+        }
+    }
+
+    async fn snapshot(&self) -> Result<Uuid> {
+        profile_snapshot_utils::create_profile_snapshot(
+            self.profile,
+            None,
+            SnapshotTrigger::BeforeBulkUpdate,
+        )
+        .await
+        .map(|snapshot| snapshot.id)
+    }
+
+    async fn apply(&self, update: &PlannedModUpdate) -> Result<()> {
+        apply_update(self.profile.id, update).await
+    }
+
+    async fn rollback(&self, snapshot_id: Uuid) -> Result<()> {
+        profile_snapshot_utils::rollback_profile_snapshot(self.profile.id, snapshot_id)
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Updates all mods with updates enabled, or only returns the plan if `dry_run` is set.
+/// Either every planned update is applied or the profile is left as it was.
+pub async fn update_all_mods(profile_id: Uuid, dry_run: bool) -> Result<BatchUpdateResult> {
+    update_mods(profile_id, None, dry_run).await
+}
+
+/// Like `update_all_mods`, but with `only_mod` set the batch is limited to that mod.
+pub async fn update_mods(
+    profile_id: Uuid,
+    only_mod: Option<Uuid>,
+    dry_run: bool,
+) -> Result<BatchUpdateResult> {
+    let state = State::get().await?;
+    let profile = state.profile_manager.get_profile(profile_id).await?;
+    let mut plan = plan_profile_updates(&profile).await?;
+    if let Some(mod_id) = only_mod {
+        plan.updates.retain(|update| update.mod_id == mod_id);
+        plan.skipped.retain(|skipped| skipped.mod_id == mod_id);
+    }
+
+    let mut result = BatchUpdateResult {
+        plan,
+        dry_run,
+        applied: false,
+        snapshot_id: None,
+        rollback_error: None,
+        results: Vec::new(),
+    };
+    if dry_run || result.plan.updates.is_empty() {
+        return Ok(result);
+    }
+    if state.process_manager.is_profile_running(profile_id).await {
+        return Err(AppError::InvalidOperation(
+            "Cannot update mods while the profile is running".to_string(),
+        ));
+    }
+
+    let backend = ProfileUpdateBackend {
+        profile: &profile,
+        downloader: ModDownloadService::new(),
+    };
+    apply_batch(&backend, &mut result).await?;
+
+    if result.applied {
+        info!(
+            "Updated {} mods of profile {}",
+            result.results.len(),
+            profile_id
+        );
+        mod_dependencies::spawn_dependency_check(profile_id);
+    }
+    Ok(result)
+}
+
+/// Downloads all planned files in parallel, then applies the updates one by one and
+/// rolls back to the snapshot taken in between as soon as one of them fails. Applying is
+/// serial because each update saves the whole profile and may add dependencies to it.
+async fn apply_batch(
+    backend: &impl BatchUpdateBackend,
+    result: &mut BatchUpdateResult,
+) -> Result<()> {
+    let profile_id = result.plan.profile_id;
+
+    // Fetch every new file first, the profile is only touched once all of them are cached
+    let downloads = join_all(
+        result
+            .plan
+            .updates
+            .iter()
+            .map(|update| backend.download(update)),
+    )
+    .await;
+
+    if downloads.iter().any(|download| download.is_err()) {
+        error!(
+            "Batch update of profile {} aborted, not all files could be downloaded",
+            profile_id
+        );
+        result.results = result
+            .plan
+            .updates
+            .iter()
+            .zip(downloads)
+            .map(|(update, download)| {
+                let status = match download {
+                    Ok(()) => ModUpdateStatus::NotApplied,
+                    Err(e) => ModUpdateStatus::Failed {
+                        error: e.to_string(),
+                    },
+                };
+                result_for(update, status)
+            })
+            .collect();
+        return Ok(());
+    }
+
+    let snapshot_id = backend.snapshot().await?;
+    result.snapshot_id = Some(snapshot_id);
+
+    let mut failure = None;
+    for update in &result.plan.updates {
+        if failure.is_some() {
+            result
+                .results
+                .push(result_for(update, ModUpdateStatus::NotApplied));
+            continue;
+        }
+        match backend.apply(update).await {
+            Ok(()) => result
+                .results
+                .push(result_for(update, ModUpdateStatus::Updated)),
+            Err(e) => {
+                error!("Updating '{}' failed, rolling back: {}", update.name, e);
+                result.results.push(result_for(
+                    update,
+                    ModUpdateStatus::Failed {
+                        error: e.to_string(),
+                    },
+                ));
+                failure = Some(e);
+            }
+        }
+    }
+
+    if failure.is_some() {
+        if let Err(e) = backend.rollback(snapshot_id).await {
+            error!(
+                "Rolling back profile {} to snapshot {} failed: {}",
+                profile_id, snapshot_id, e
+            );
+            result.rollback_error = Some(e.to_string());
+            return Ok(());
+        }
+        for entry in result.results.iter_mut() {
+            if entry.status == ModUpdateStatus::Updated {
+                entry.status = ModUpdateStatus::NotApplied;
+            }
+        }
+        return Ok(());
+    }
+
+    result.applied = true;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::modrinth::{ModrinthFile, ModrinthHashes, ModrinthVersionType};
+    use std::sync::Mutex;
+
+    /// In-memory profile: installed version per mod and the calls made, in order
+    struct FakeBackend {
+        versions: Mutex<HashMap<Uuid, String>>,
+        snapshots: Mutex<HashMap<Uuid, HashMap<Uuid, String>>>,
+        calls: Mutex<Vec<String>>,
+        failing_mod: Option<Uuid>,
+        failing_rollback: bool,
+    }
+
+    impl FakeBackend {
+        fn new(versions: HashMap<Uuid, String>, failing_mod: Option<Uuid>) -> Self {
+            Self {
+                versions: Mutex::new(versions),
+                snapshots: Mutex::new(HashMap::new()),
+                calls: Mutex::new(Vec::new()),
+                failing_mod,
+                failing_rollback: false,
+            }
+        }
+
+        fn call(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[async_trait]
+    impl BatchUpdateBackend for FakeBackend {
+        async fn download(&self, update: &PlannedModUpdate) -> Result<()> {
+            self.call(format!("download {}", update.name));
+            Ok(())
+        }
+
+        async fn snapshot(&self) -> Result<Uuid> {
+            self.call("snapshot".to_string());
+            let id = Uuid::new_v4();
+            let versions = self.versions.lock().unwrap().clone();
+            self.snapshots.lock().unwrap().insert(id, versions);
+            Ok(id)
+        }
+
+        async fn apply(&self, update: &PlannedModUpdate) -> Result<()> {
+            self.call(format!("apply {}", update.name));
+            if self.failing_mod == Some(update.mod_id) {
+                return Err(AppError::Other(format!("{} is broken", update.name)));
+            }
+            self.versions
+                .lock()
+                .unwrap()
+                .insert(update.mod_id, update.to_version.clone());
+            Ok(())
+        }
+
+        async fn rollback(&self, snapshot_id: Uuid) -> Result<()> {
+            self.call("rollback".to_string());
+            if self.failing_rollback {
+                return Err(AppError::Other("snapshot is unreadable".to_string()));
+            }
+            let versions = self.snapshots.lock().unwrap()[&snapshot_id].clone();
+            *self.versions.lock().unwrap() = versions;
+            Ok(())
+        }
+    }
+
+    fn planned_update(mod_id: Uuid, name: &str, to_version: &str) -> PlannedModUpdate {
+        let version = ModrinthVersion {
+            id: format!("{}-{}", name, to_version),
+            project_id: name.to_string(),
+            author_id: None,
+            featured: false,
+            name: to_version.to_string(),
+            version_number: to_version.to_string(),
+            changelog: None,
+            dependencies: Vec::new(),
+            game_versions: vec!["1.21.1".to_string()],
+            version_type: ModrinthVersionType::Release,
+            loaders: vec!["fabric".to_string()],
+            files: vec![ModrinthFile {
+                hashes: ModrinthHashes {
+                    sha512: None,
+                    sha1: None,
+                },
+                url: format!("https://cdn.modrinth.com/{}.jar", name),
+                filename: format!("{}.jar", name),
+                primary: true,
+                size: 0,
+                file_type: None,
+            }],
+            date_published: "2024-01-01T00:00:00Z".to_string(),
+            downloads: 0,
+        };
+        PlannedModUpdate {
+            mod_id,
+            name: name.to_string(),
+            platform: ModPlatform::Modrinth,
+            project_id: name.to_string(),
+            from_version: Some("1.0.0".to_string()),
+            to_version: to_version.to_string(),
+            to_version_id: version.id.clone(),
+            file_name: format!("{}.jar", name),
+            changelogs: Vec::new(),
+            major_version_change: false,
+            target: UpdateTarget::Modrinth(Box::new(version)),
+        }
+    }
+
+    fn batch(names: &[&str]) -> (BatchUpdateResult, HashMap<Uuid, String>) {
+        let updates: Vec<_> = names
+            .iter()
+            .map(|name| planned_update(Uuid::new_v4(), name, "2.0.0"))
+            .collect();
+        let installed = updates
+            .iter()
+            .map(|update| (update.mod_id, "1.0.0".to_string()))
+            .collect();
+        let result = BatchUpdateResult {
+            plan: ModUpdatePlan {
+                profile_id: Uuid::new_v4(),
+                updates,
+                skipped: Vec::new(),
+            },
+            dry_run: false,
+            applied: false,
+            snapshot_id: None,
+            rollback_error: None,
+            results: Vec::new(),
+        };
+        (result, installed)
+    }
+
+    #[tokio::test]
+    async fn test_failed_update_rolls_back_the_whole_batch() {
+        let (mut result, installed) = batch(&["sodium", "lithium", "iris"]);
+        let failing = result.plan.updates[1].mod_id;
+        let backend = FakeBackend::new(installed.clone(), Some(failing));
+
+        apply_batch(&backend, &mut result).await.unwrap();
+
+        assert!(!result.applied);
+        assert_eq!(*backend.versions.lock().unwrap(), installed);
+        let statuses: Vec<_> = result.results.iter().map(|r| r.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ModUpdateStatus::NotApplied,
+                ModUpdateStatus::Failed {
+                    error: AppError::Other("lithium is broken".to_string()).to_string()
+                },
+                ModUpdateStatus::NotApplied,
+            ]
+        );
+
+        let calls = backend.calls.lock().unwrap().clone();
+        assert_eq!(
+            calls,
+            vec![
+                "download sodium",
+                "download lithium",
+                "download iris",
+                "snapshot",
+                "apply sodium",
+                "apply lithium",
+                "rollback",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_rollback_keeps_the_results() {
+        let (mut result, installed) = batch(&["sodium", "lithium", "iris"]);
+        let failing = result.plan.updates[1].mod_id;
+        let mut backend = FakeBackend::new(installed, Some(failing));
+        backend.failing_rollback = true;
+
+        apply_batch(&backend, &mut result).await.unwrap();
+
+        assert!(!result.applied);
+        assert!(result.snapshot_id.is_some());
+        assert_eq!(
+            result.rollback_error,
+            Some(AppError::Other("snapshot is unreadable".to_string()).to_string())
+        );
+        // sodium stays switched, the results say so
+        let statuses: Vec<_> = result.results.iter().map(|r| r.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ModUpdateStatus::Updated,
+                ModUpdateStatus::Failed {
+                    error: AppError::Other("lithium is broken".to_string()).to_string()
+                },
+                ModUpdateStatus::NotApplied,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_successful_batch_applies_every_update() {
+        let (mut result, installed) = batch(&["sodium", "lithium"]);
+        let backend = FakeBackend::new(installed, None);
+
+        apply_batch(&backend, &mut result).await.unwrap();
+
+        assert!(result.applied);
+        assert!(result.snapshot_id.is_some());
+        assert!(result
+            .results
+            .iter()
+            .all(|r| r.status == ModUpdateStatus::Updated));
+        assert!(backend
+            .versions
+            .lock()
+            .unwrap()
+            .values()
+            .all(|version| version == "2.0.0"));
+    }
+
+    #[test]
+    fn test_version_core_skips_minecraft_versions_and_tags() {
+        let game_versions = vec!["1.20.1".to_string(), "1.21.1".to_string()];
+        assert_eq!(
+            version_core("mc1.20.1-0.5.3", &game_versions),
+            Some(vec![0, 5, 3])
+        );
+        assert_eq!(
+            version_core("0.92.2+1.20.1", &game_versions),
+            Some(vec![0, 92, 2])
+        );
+        assert_eq!(
+            version_core("jei-1.21.1-neoforge-19.22.1.316", &game_versions),
+            Some(vec![19, 22, 1, 316])
+        );
+        assert_eq!(version_core("v2.0.", &game_versions), Some(vec![2, 0]));
+        assert_eq!(version_core("latest", &game_versions), None);
+    }
+
+    #[test]
+    fn test_major_version_changes() {
+        let game_versions = vec!["1.20.1".to_string()];
+        assert!(crosses_major_version("4.9.2", "5.0.0", &game_versions));
+        assert!(!crosses_major_version("4.9.2", "4.10.0", &game_versions));
+        assert!(crosses_major_version(
+            "0.5.3+1.20.1",
+            "0.6.0+1.20.1",
+            &game_versions
+        ));
+        assert!(!crosses_major_version("0.5.3", "0.5.11", &game_versions));
+        assert!(!crosses_major_version("latest", "5.0.0", &game_versions));
+    }
+}
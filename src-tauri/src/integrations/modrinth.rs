@@ -598,6 +598,56 @@ pub async fn get_version_details(version_id: String) -> Result<ModrinthVersion>
     Ok(version_details)
 }
 
+/// Fetches several versions in one request.
+/// https://docs.modrinth.com/api-spec/#tag/versions/operation/getVersions
+pub async fn get_versions_by_ids(version_ids: Vec<String>) -> Result<Vec<ModrinthVersion>> {
+    if version_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = reqwest::Client::new();
+    let url = format!("{}/versions", MODRINTH_API_BASE_URL);
+    let ids_param = serde_json::to_string(&version_ids)
+        .map_err(|e| AppError::Other(format!("Failed to serialize version IDs: {}", e)))?;
+
+    log::info!("Getting {} Modrinth versions by ID", version_ids.len());
+
+    let response = client
+        .get(url)
+        .query(&[("ids", ids_param)])
+        .header(
+            "User-Agent",
+            format!(
+                "GEG-Launcher/{} (contact@GEG.de)",
+                env!("CARGO_PKG_VERSION")
+            ),
+        )
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Modrinth API request for versions failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read error body".to_string());
+        log::error!(
+            "Modrinth API error getting versions by ID ({}): {}",
+            status,
+            error_text
+        );
+        return Err(AppError::Other(format!(
+            "Modrinth API returned error {} getting versions: {}",
+            status, error_text
+        )));
+    }
+
+    response.json::<Vec<ModrinthVersion>>().await.map_err(|e| {
+        AppError::Other(format!("Failed to parse Modrinth versions response: {}", e))
+    })
+}
+
 /// Fetches ALL compatible versions for a list of Modrinth projects concurrently,
 /// using specific filters for each project.
 ///
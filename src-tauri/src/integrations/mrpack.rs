@@ -34,6 +34,7 @@ pub struct ModrinthIndex {
     pub game: String,            // e.g., "minecraft"
     pub version_id: String,      // Pack version identifier
     pub name: String,            // Pack name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>, // Optional description
     pub files: Vec<ModrinthIndexFile>,
     pub dependencies: HashMap<String, String>, // Key: dependency ID (e.g., "minecraft", "fabric-loader"), Value: version string
@@ -45,6 +46,7 @@ pub struct ModrinthIndex {
 pub struct ModrinthIndexFile {
     pub path: String, // Target path within the instance (e.g., "mods/fabric-api.jar")
     pub hashes: HashMap<String, String>, // Key: hash algorithm ("sha1", "sha512"), Value: hash string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>, // Environment constraints ("client", "server")
     pub downloads: Vec<String>,          // List of download URLs
     pub file_size: u64,                  // File size in bytes
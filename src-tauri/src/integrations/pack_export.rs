@@ -0,0 +1,803 @@
+//! Exports profiles as Modrinth `.mrpack` and CurseForge modpack zips.
+//! Mods that can be referenced remotely become file entries in the manifest,
+//! everything else (custom mods, configs, ...) is bundled under `overrides/`.
+
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::{AppError, Result};
+use crate::integrations::curseforge::{
+    CurseForgeManifest, CurseForgeManifestFile, CurseForgeMinecraft, CurseForgeModLoader,
+};
+use crate::integrations::modrinth;
+use crate::integrations::mrpack::{
+    ModrinthIndex, ModrinthIndexFile, FABRIC_LOADER_DEPENDENCY, FORGE_DEPENDENCY,
+    MINECRAFT_DEPENDENCY, NEOFORGE_DEPENDENCY, QUILT_LOADER_DEPENDENCY,
+};
+use crate::minecraft::downloads::mod_downloader::{ModDownloadService, MOD_CACHE_DIR_NAME};
+use crate::state::profile_state::{self, ModLoader, ModSource, Profile};
+use crate::state::state_manager::State;
+use crate::utils::hash_utils;
+use crate::utils::profile_utils::collect_all_files_recursive;
+use async_zip::tokio::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use futures_lite::io::AsyncWriteExt;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncReadExt;
+use uuid::Uuid;
+
+/// Hosts a `.mrpack` may download from; files from anywhere else have to be bundled.
+/// https://support.modrinth.com/en/articles/8802351-modrinth-modpack-format-mrpack
+const MRPACK_ALLOWED_DOWNLOAD_HOSTS: &[&str] = &[
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackExportFormat {
+    Modrinth,
+    CurseForge,
+}
+
+impl PackExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PackExportFormat::Modrinth => "mrpack",
+            PackExportFormat::CurseForge => "zip",
+        }
+    }
+
+    fn manifest_name(&self) -> &'static str {
+        match self {
+            PackExportFormat::Modrinth => "modrinth.index.json",
+            PackExportFormat::CurseForge => "manifest.json",
+        }
+    }
+}
+
+/// Side support of a file: "required", "optional" or "unsupported"
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackEnv {
+    pub client: String,
+    pub server: String,
+}
+
+/// A mod jar on disk together with everything the pack manifests need to know about it
+#[derive(Debug, Clone)]
+pub struct PackModFile {
+    /// `ModSource::Local` for mods from `custom_mods`
+    pub source: ModSource,
+    pub enabled: bool,
+    pub file_name: String,
+    pub file_path: PathBuf,
+    pub sha1: String,
+    pub sha512: String,
+    pub size: u64,
+    pub env: PackEnv,
+}
+
+impl PackModFile {
+    pub async fn from_file(
+        source: ModSource,
+        enabled: bool,
+        file_name: String,
+        file_path: PathBuf,
+        env: PackEnv,
+    ) -> Result<Self> {
+        let size = fs::metadata(&file_path).await?.len();
+        let sha1 = hash_utils::calculate_sha1_from_file(&file_path).await?;
+        let sha512 = hash_utils::calculate_sha512_from_file(&file_path).await?;
+
+        Ok(Self {
+            source,
+            enabled,
+            file_name,
+            file_path,
+            sha1,
+            sha512,
+            size,
+            env,
+        })
+    }
+
+    /// Path inside the instance; disabled mods keep the `.disabled` suffix our importers look for
+    fn instance_path(&self) -> String {
+        if self.enabled {
+            format!("mods/{}", self.file_name)
+        } else {
+            format!("mods/{}.disabled", self.file_name)
+        }
+    }
+
+    fn download_url(&self) -> Option<&str> {
+        match &self.source {
+            ModSource::Modrinth { download_url, .. }
+            | ModSource::CurseForge { download_url, .. } => Some(download_url.as_str()),
+            _ => None,
+        }
+    }
+
+    fn as_override(&self) -> PackOverride {
+        PackOverride {
+            zip_path: self.instance_path(),
+            file_path: self.file_path.clone(),
+        }
+    }
+}
+
+/// A file bundled under `overrides/`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackOverride {
+    /// Path relative to the instance (and to `overrides/`), always with forward slashes
+    pub zip_path: String,
+    pub file_path: PathBuf,
+}
+
+fn is_mrpack_download_allowed(url: &str) -> bool {
+    url::Url::parse(url)
+        .ok()
+        .filter(|parsed| parsed.scheme() == "https")
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+        .is_some_and(|host| MRPACK_ALLOWED_DOWNLOAD_HOSTS.contains(&host.as_str()))
+}
+
+fn require_loader_version(profile: &Profile) -> Result<String> {
+    profile.loader_version.clone().ok_or_else(|| {
+        AppError::Other(format!(
+            "Profile '{}' has no fixed {} version, which pack exports require",
+            profile.name,
+            profile.loader.as_str()
+        ))
+    })
+}
+
+/// Builds `modrinth.index.json` for `profile`. Mods hosted on an allowed domain become remote
+/// file entries, all others are returned as overrides.
+pub fn build_modrinth_index(
+    profile: &Profile,
+    version_id: &str,
+    mods: &[PackModFile],
+) -> Result<(ModrinthIndex, Vec<PackOverride>)> {
+    let mut dependencies = HashMap::new();
+    dependencies.insert(
+        MINECRAFT_DEPENDENCY.to_string(),
+        profile.game_version.clone(),
+    );
+    let loader_key = match profile.loader {
+        ModLoader::Vanilla => None,
+        ModLoader::Fabric => Some(FABRIC_LOADER_DEPENDENCY),
+        ModLoader::Quilt => Some(QUILT_LOADER_DEPENDENCY),
+        ModLoader::Forge => Some(FORGE_DEPENDENCY),
+        ModLoader::NeoForge => Some(NEOFORGE_DEPENDENCY),
+    };
+    if let Some(key) = loader_key {
+        dependencies.insert(key.to_string(), require_loader_version(profile)?);
+    }
+
+    let mut files = Vec::new();
+    let mut overrides = Vec::new();
+    for mod_file in mods {
+        match mod_file
+            .download_url()
+            .filter(|url| is_mrpack_download_allowed(url))
+        {
+            Some(url) => files.push(ModrinthIndexFile {
+                path: mod_file.instance_path(),
+                hashes: HashMap::from([
+                    ("sha1".to_string(), mod_file.sha1.clone()),
+                    ("sha512".to_string(), mod_file.sha512.clone()),
+                ]),
+                env: Some(HashMap::from([
+                    ("client".to_string(), mod_file.env.client.clone()),
+                    ("server".to_string(), mod_file.env.server.clone()),
+                ])),
+                downloads: vec![url.to_string()],
+                file_size: mod_file.size,
+            }),
+            None => overrides.push(mod_file.as_override()),
+        }
+    }
+
+    let index = ModrinthIndex {
+        format_version: 1,
+        game: "minecraft".to_string(),
+        version_id: version_id.to_string(),
+        name: profile.name.clone(),
+        summary: profile.description.clone(),
+        files,
+        dependencies,
+    };
+    Ok((index, overrides))
+}
+
+/// Builds the CurseForge `manifest.json` for `profile`. CurseForge mods become project/file
+/// references, all others are returned as overrides.
+pub fn build_curseforge_manifest(
+    profile: &Profile,
+    version: &str,
+    mods: &[PackModFile],
+) -> Result<(CurseForgeManifest, Vec<PackOverride>)> {
+    let mod_loaders = match profile.loader {
+        ModLoader::Vanilla => Vec::new(),
+        loader => vec![CurseForgeModLoader {
+            id: format!("{}-{}", loader.as_str(), require_loader_version(profile)?),
+            primary: Some(true),
+        }],
+    };
+
+    let mut files = Vec::new();
+    let mut overrides = Vec::new();
+    for mod_file in mods {
+        let ids = match &mod_file.source {
+            ModSource::CurseForge {
+                project_id,
+                file_id,
+                ..
+            } => project_id
+                .parse::<u32>()
+                .ok()
+                .zip(file_id.parse::<u32>().ok()),
+            _ => None,
+        };
+        match ids {
+            Some((project_id, file_id)) => files.push(CurseForgeManifestFile {
+                project_id,
+                file_id,
+                required: mod_file.enabled,
+            }),
+            None => overrides.push(mod_file.as_override()),
+        }
+    }
+
+    let manifest = CurseForgeManifest {
+        minecraft: CurseForgeMinecraft {
+            version: profile.game_version.clone(),
+            mod_loaders,
+            recommended_ram: Some(profile.settings.memory.max as u64),
+        },
+        manifest_type: "minecraftModpack".to_string(),
+        manifest_version: 1,
+        name: profile.name.clone(),
+        version: Some(version.to_string()),
+        author: None,
+        description: profile.description.clone(),
+        files,
+        overrides: Some("overrides".to_string()),
+    };
+    Ok((manifest, overrides))
+}
+
+/// Writes `manifest` and all overrides into a new zip at `output_path`
+pub async fn write_pack_archive(
+    output_path: &Path,
+    format: PackExportFormat,
+    manifest: &[u8],
+    overrides: &[PackOverride],
+) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut file = fs::File::create(output_path).await?;
+    let mut writer = ZipFileWriter::with_tokio(&mut file);
+
+    let manifest_builder =
+        ZipEntryBuilder::new(format.manifest_name().into(), Compression::Deflate);
+    writer
+        .write_entry_whole(manifest_builder, manifest)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to write pack manifest to zip: {}", e)))?;
+
+    for pack_override in overrides {
+        debug!("Adding override: {}", pack_override.zip_path);
+        let mut source_file = fs::File::open(&pack_override.file_path).await?;
+
+        let zip_path = format!("overrides/{}", pack_override.zip_path);
+        let builder = ZipEntryBuilder::new(zip_path.into(), Compression::Deflate);
+        let mut entry_writer = writer
+            .write_entry_stream(builder)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to create zip entry stream: {}", e)))?;
+
+        let mut buffer = [0u8; 8192];
+        loop {
+            let n = source_file.read(&mut buffer).await?;
+            if n == 0 {
+                break;
+            }
+            entry_writer
+                .write_all(&buffer[..n])
+                .await
+                .map_err(|e| AppError::Other(format!("Failed to write chunk: {}", e)))?;
+        }
+
+        entry_writer
+            .close()
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to close zip entry: {}", e)))?;
+    }
+
+    writer
+        .close()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to finalize zip file: {}", e)))?;
+    Ok(())
+}
+
+/// Maps Modrinth's project side support to the server env of a pack file
+fn server_env_from_side(server_side: Option<&str>) -> String {
+    match server_side {
+        Some("unsupported") => "unsupported",
+        Some("optional") => "optional",
+        _ => "required",
+    }
+    .to_string()
+}
+
+/// Collects all mod jars of a profile: platform, URL and Maven mods from the mod cache (downloaded if missing)
+/// and the files in `custom_mods`. URL and Maven mods have no platform download and end up in `overrides/mods`.
+async fn collect_pack_mods(state: &State, profile: &Profile) -> Result<Vec<PackModFile>> {
+    let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+
+    // Side hints for Modrinth mods; the export still works without them
+    let modrinth_project_ids: Vec<String> = profile
+        .mods
+        .iter()
+        .filter_map(|m| match &m.source {
+            ModSource::Modrinth { project_id, .. } => Some(project_id.clone()),
+            _ => None,
+        })
+        .collect();
+    let server_sides: HashMap<String, String> =
+        match modrinth::get_multiple_projects(modrinth_project_ids).await {
+            Ok(projects) => projects
+                .into_iter()
+                .map(|project| (project.id, project.server_side))
+                .collect(),
+            Err(e) => {
+                warn!("Could not fetch Modrinth side support for export: {}", e);
+                HashMap::new()
+            }
+        };
+
+    let downloader = ModDownloadService::new();
+    let mut pack_mods = Vec::new();
+    for mod_info in &profile.mods {
+        let server_side = match &mod_info.source {
+            ModSource::Modrinth { project_id, .. } => {
+                server_sides.get(project_id).map(String::as_str)
+            }
+            ModSource::CurseForge { .. } | ModSource::Url { .. } | ModSource::Maven { .. } => None,
+            ModSource::Local { .. } => continue, // Exported from custom_mods below
+            ModSource::Embedded { name } => {
+                warn!(
+                    "Skipping mod {:?} in pack export: embedded mod '{}' is provided by the launcher",
+                    mod_info.display_name, name
+                );
+                continue;
+            }
+        };
+
+        let file_name = profile_state::get_profile_mod_filename(&mod_info.source)?;
+        let cache_path =
+            mod_cache_dir.join(profile_state::get_mod_cache_filename(&mod_info.source)?);
+        if !cache_path.exists() {
+            info!("Downloading {} into the mod cache for export", file_name);
+            downloader.download_mod_to_cache(&mod_info.source).await?;
+        }
+
+        let env = PackEnv {
+            client: if mod_info.enabled {
+                "required"
+            } else {
+                "optional"
+            }
+            .to_string(),
+            server: server_env_from_side(server_side),
+        };
+        pack_mods.push(
+            PackModFile::from_file(
+                mod_info.source.clone(),
+                mod_info.enabled,
+                file_name,
+                cache_path,
+                env,
+            )
+            .await?,
+        );
+    }
+
+    for custom_mod in state.profile_manager.list_custom_mods(profile).await? {
+        let env = PackEnv {
+            client: if custom_mod.is_enabled {
+                "required"
+            } else {
+                "optional"
+            }
+            .to_string(),
+            server: server_env_from_side(None),
+        };
+        pack_mods.push(
+            PackModFile::from_file(
+                ModSource::Local {
+                    file_name: custom_mod.filename.clone(),
+                },
+                custom_mod.is_enabled,
+                custom_mod.filename,
+                custom_mod.path,
+                env,
+            )
+            .await?,
+        );
+    }
+
+    Ok(pack_mods)
+}
+
+/// Adds the files of `include_files` (files or directories inside `instance_path`) as overrides.
+/// The pack's mods are skipped: they are manifest entries or overrides already, and a second
+/// copy would be loaded as a duplicate after an import.
+async fn add_included_files(
+    instance_path: &Path,
+    include_files: Vec<PathBuf>,
+    pack_mods: &[PackModFile],
+    overrides: &mut Vec<PackOverride>,
+) -> Result<()> {
+    let mod_paths: HashSet<String> = pack_mods
+        .iter()
+        .flat_map(|m| {
+            [
+                format!("mods/{}", m.file_name),
+                format!("mods/{}.disabled", m.file_name),
+            ]
+        })
+        .collect();
+
+    for include_path in include_files {
+        let mut files = Vec::new();
+        if include_path.is_dir() {
+            collect_all_files_recursive(&include_path, &mut files).await?;
+        } else if include_path.is_file() {
+            files.push(include_path);
+        }
+
+        for file_path in files {
+            let Ok(rel_path) = file_path.strip_prefix(instance_path) else {
+                warn!("Skipping {:?}: not inside the profile directory", file_path);
+                continue;
+            };
+            let zip_path = rel_path.to_string_lossy().replace('\\', "/");
+            if mod_paths.contains(&zip_path) {
+                debug!("Skipping {}: already part of the pack's mods", zip_path);
+                continue;
+            }
+            if !overrides.iter().any(|o| o.zip_path == zip_path) {
+                overrides.push(PackOverride {
+                    zip_path,
+                    file_path,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Exports a profile as `.mrpack` or CurseForge zip to `output_path`.
+/// `include_files` are paths inside the profile (files or directories) that get bundled as overrides.
+pub async fn export_profile_to_pack(
+    profile_id: Uuid,
+    format: PackExportFormat,
+    output_path: PathBuf,
+    pack_version: Option<String>,
+    include_files: Option<Vec<PathBuf>>,
+) -> Result<PathBuf> {
+    info!("Exporting profile {} as {:?} pack", profile_id, format);
+
+    let state = State::get().await?;
+    let profile = state.profile_manager.get_profile(profile_id).await?;
+    let instance_path = state
+        .profile_manager
+        .get_profile_instance_path(profile_id)
+        .await?;
+    let pack_version = pack_version.unwrap_or_else(|| "1.0.0".to_string());
+
+    let pack_mods = collect_pack_mods(&state, &profile).await?;
+    let (manifest, mut overrides) = match format {
+        PackExportFormat::Modrinth => {
+            let (index, overrides) = build_modrinth_index(&profile, &pack_version, &pack_mods)?;
+            (serde_json::to_vec_pretty(&index)?, overrides)
+        }
+        PackExportFormat::CurseForge => {
+            let (manifest, overrides) =
+                build_curseforge_manifest(&profile, &pack_version, &pack_mods)?;
+            (serde_json::to_vec_pretty(&manifest)?, overrides)
+        }
+    };
+
+    let _permit = state.io_semaphore.acquire().await;
+    add_included_files(
+        &instance_path,
+        include_files.unwrap_or_default(),
+        &pack_mods,
+        &mut overrides,
+    )
+    .await?;
+
+    write_pack_archive(&output_path, format, &manifest, &overrides).await?;
+    info!(
+        "Exported profile '{}' with {} mods and {} override files to {}",
+        profile.name,
+        pack_mods.len(),
+        overrides.len(),
+        output_path.display()
+    );
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::curseforge::process_curseforge_pack_from_zip;
+    use crate::integrations::mrpack::process_mrpack;
+    use crate::state::profile_state::{ProfileSettings, ProfileState};
+
+    fn test_profile() -> Profile {
+        Profile {
+            id: Uuid::new_v4(),
+            name: "Export Test".to_string(),
+            path: "export-test".to_string(),
+            game_version: "1.21.1".to_string(),
+            loader: ModLoader::Fabric,
+            loader_version: Some("0.16.5".to_string()),
+            created: chrono::Utc::now(),
+            last_played: None,
+            settings: ProfileSettings::default(),
+            state: ProfileState::Installed,
+            mods: Vec::new(),
+            selected_GEG_pack_id: None,
+            disabled_GEG_mods_detailed: HashSet::new(),
+            source_standard_profile_id: None,
+            group: None,
+            is_standard_version: false,
+            use_shared_minecraft_folder: false,
+            description: Some("Round trip".to_string()),
+            GEG_information: None,
+            banner: None,
+            background: None,
+            modpack_info: None,
+        }
+    }
+
+    async fn pack_mods(dir: &Path) -> Vec<PackModFile> {
+        let env = PackEnv {
+            client: "required".to_string(),
+            server: "required".to_string(),
+        };
+        let sources = [
+            (
+                "sodium.jar",
+                true,
+                ModSource::Modrinth {
+                    project_id: "AANobbMI".to_string(),
+                    version_id: "tFw0iWAk".to_string(),
+                    file_name: "sodium.jar".to_string(),
+                    download_url:
+                        "https://cdn.modrinth.com/data/AANobbMI/versions/tFw0iWAk/sodium.jar"
+                            .to_string(),
+                    file_hash_sha1: None,
+                },
+            ),
+            (
+                "jei.jar",
+                true,
+                ModSource::CurseForge {
+                    project_id: "238222".to_string(),
+                    file_id: "6829086".to_string(),
+                    file_name: "jei.jar".to_string(),
+                    download_url: "https://edge.forgecdn.net/files/6829/86/jei.jar".to_string(),
+                    file_hash_sha1: None,
+                    file_fingerprint: None,
+                },
+            ),
+            (
+                "custom.jar",
+                false,
+                ModSource::Local {
+                    file_name: "custom.jar".to_string(),
+                },
+            ),
+        ];
+
+        let mut mods = Vec::new();
+        for (file_name, enabled, source) in sources {
+            let path = dir.join(file_name);
+            fs::write(&path, format!("jar {}", file_name))
+                .await
+                .unwrap();
+            mods.push(
+                PackModFile::from_file(source, enabled, file_name.to_string(), path, env.clone())
+                    .await
+                    .unwrap(),
+            );
+        }
+        mods
+    }
+
+    /// An instance whose `mods/` holds the synced jars of the pack's enabled mods, plus a config
+    async fn instance_with_synced_mods(dir: &Path, mods: &[PackModFile]) -> PathBuf {
+        let instance = dir.join("instance");
+        fs::create_dir_all(instance.join("mods")).await.unwrap();
+        fs::create_dir_all(instance.join("config")).await.unwrap();
+        for mod_file in mods.iter().filter(|m| m.enabled) {
+            fs::copy(&mod_file.file_path, instance.join(mod_file.instance_path()))
+                .await
+                .unwrap();
+        }
+        fs::write(instance.join("config").join("sodium.json"), "{}")
+            .await
+            .unwrap();
+        instance
+    }
+
+    /// Paths of the mods an import places in the instance: remote entries plus override mods
+    fn imported_mod_paths(remote: Vec<String>, entries: &HashSet<String>) -> Vec<String> {
+        let mut paths: Vec<String> = remote
+            .into_iter()
+            .chain(
+                entries
+                    .iter()
+                    .filter_map(|e| e.strip_prefix("overrides/"))
+                    .filter(|e| e.starts_with("mods/"))
+                    .map(str::to_string),
+            )
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn exported_mod_paths(mods: &[PackModFile]) -> Vec<String> {
+        let mut paths: Vec<String> = mods.iter().map(PackModFile::instance_path).collect();
+        paths.sort();
+        paths
+    }
+
+    async fn zip_entries(path: &Path) -> HashSet<String> {
+        let file = fs::File::open(path).await.unwrap();
+        let mut reader = tokio::io::BufReader::new(file);
+        let zip = async_zip::tokio::read::seek::ZipFileReader::with_tokio(&mut reader)
+            .await
+            .unwrap();
+        zip.file()
+            .entries()
+            .iter()
+            .map(|e| e.filename().as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_mrpack_export_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = test_profile();
+        let mods = pack_mods(dir.path()).await;
+
+        let (index, mut overrides) = build_modrinth_index(&profile, "2.0.0", &mods).unwrap();
+        // The user includes the whole mods folder, which also holds the remote mods
+        let instance = instance_with_synced_mods(dir.path(), &mods).await;
+        add_included_files(
+            &instance,
+            vec![instance.join("mods"), instance.join("config")],
+            &mods,
+            &mut overrides,
+        )
+        .await
+        .unwrap();
+        let pack_path = dir.path().join("export.mrpack");
+        let manifest = serde_json::to_vec(&index).unwrap();
+        write_pack_archive(
+            &pack_path,
+            PackExportFormat::Modrinth,
+            &manifest,
+            &overrides,
+        )
+        .await
+        .unwrap();
+
+        let (imported, imported_index) = process_mrpack(pack_path.clone()).await.unwrap();
+        assert_eq!(imported.name, profile.name);
+        assert_eq!(imported.game_version, profile.game_version);
+        assert_eq!(imported.loader, ModLoader::Fabric);
+        assert_eq!(imported.loader_version, profile.loader_version);
+
+        // Only the Modrinth-hosted mod is a remote file, with both hashes the spec requires
+        assert_eq!(imported_index.files.len(), 1);
+        let remote = &imported_index.files[0];
+        assert_eq!(remote.path, "mods/sodium.jar");
+        assert_eq!(remote.hashes.get("sha1"), Some(&mods[0].sha1));
+        assert_eq!(remote.hashes.get("sha512"), Some(&mods[0].sha512));
+        assert_eq!(remote.file_size, mods[0].size);
+        assert!(remote.env.is_some());
+
+        let entries = zip_entries(&pack_path).await;
+        assert!(entries.contains("overrides/config/sodium.json"));
+        let remote_paths = imported_index
+            .files
+            .iter()
+            .map(|f| f.path.clone())
+            .collect();
+        assert_eq!(
+            imported_mod_paths(remote_paths, &entries),
+            exported_mod_paths(&mods)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_curseforge_export_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = test_profile();
+        let mods = pack_mods(dir.path()).await;
+
+        let (manifest, mut overrides) =
+            build_curseforge_manifest(&profile, "2.0.0", &mods).unwrap();
+        let instance = instance_with_synced_mods(dir.path(), &mods).await;
+        add_included_files(
+            &instance,
+            vec![instance.join("mods"), instance.join("config")],
+            &mods,
+            &mut overrides,
+        )
+        .await
+        .unwrap();
+        let pack_path = dir.path().join("export.zip");
+        let manifest_json = serde_json::to_vec(&manifest).unwrap();
+        write_pack_archive(
+            &pack_path,
+            PackExportFormat::CurseForge,
+            &manifest_json,
+            &overrides,
+        )
+        .await
+        .unwrap();
+
+        let (imported, imported_manifest) =
+            process_curseforge_pack_from_zip(&pack_path).await.unwrap();
+        assert_eq!(imported.game_version, profile.game_version);
+        assert_eq!(imported.loader, ModLoader::Fabric);
+        assert_eq!(imported.loader_version, profile.loader_version);
+
+        let references: Vec<(u32, u32)> = imported_manifest
+            .files
+            .iter()
+            .map(|f| (f.project_id, f.file_id))
+            .collect();
+        assert_eq!(references, vec![(238222, 6829086)]);
+
+        // The referenced CurseForge file is installed as the jar it was exported from
+        let entries = zip_entries(&pack_path).await;
+        assert!(entries.contains("overrides/config/sodium.json"));
+        let referenced_paths = mods
+            .iter()
+            .filter(|m| matches!(m.source, ModSource::CurseForge { .. }))
+            .map(PackModFile::instance_path)
+            .collect();
+        assert_eq!(
+            imported_mod_paths(referenced_paths, &entries),
+            exported_mod_paths(&mods)
+        );
+    }
+
+    #[test]
+    fn test_mrpack_download_hosts() {
+        assert!(is_mrpack_download_allowed(
+            "https://cdn.modrinth.com/data/AANobbMI/versions/x/sodium.jar"
+        ));
+        assert!(!is_mrpack_download_allowed(
+            "https://edge.forgecdn.net/files/1/2/a.jar"
+        ));
+        assert!(!is_mrpack_download_allowed("http://cdn.modrinth.com/a.jar"));
+    }
+}
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use log::{debug, error, info, warn};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ModPlatform {
     Modrinth,
     CurseForge,
@@ -1313,6 +1313,22 @@ pub async fn switch_modpack_version(request: ModpackSwitchRequest) -> Result<Mod
     let mut profile = profile_manager.get_profile(request.profile_id).await?;
     info!("Loaded profile '{}' for modpack switching", profile.name);
 
+    // Snapshot the worlds before the new modpack version replaces mods and configs
+    if profile.settings.world_backups.before_modpack_switch {
+        crate::utils::world_backup_utils::backup_profile_worlds(
+            &profile,
+            crate::utils::world_backup_utils::WorldBackupTrigger::BeforeModpackSwitch,
+        )
+        .await;
+    }
+
+    // Keep the current mod set so the switch can be rolled back
+    crate::utils::profile_snapshot_utils::snapshot_before(
+        &profile,
+        crate::utils::profile_snapshot_utils::SnapshotTrigger::BeforeModpackSwitch,
+    )
+    .await;
+
     // Extract platform from modpack_source and process accordingly
     let (minecraft_version, loader, loader_version, mods, curseforge_manifest) = match &request.modpack_source {
         crate::state::profile_state::ModPackSource::Modrinth { .. } => {
@@ -24,11 +24,13 @@ use utils::debug_utils;
 use utils::updater_utils;
 
 use crate::commands::process_command::{
-    get_full_log, get_process, get_processes, get_processes_by_profile, open_log_window,
-    set_discord_state, stop_process,
+    get_account_playtime_stats, get_all_profile_playtime_stats, get_full_log, get_process,
+    get_processes, get_processes_by_profile, get_profile_playtime_stats, get_profile_sessions,
+    open_log_window, set_discord_state, stop_process,
 };
 use commands::minecraft_auth_command::{
-    begin_login, get_accounts, get_active_account, remove_account, set_active_account,
+    add_offline_account, begin_login, get_account_store_status, get_accounts, get_active_account,
+    remove_account, set_account_store_passphrase, set_active_account, unlock_account_store,
 };
 use commands::minecraft_command::{
     add_skin,
@@ -55,7 +57,7 @@ use commands::profile_command::{
     abort_profile_launch, add_modrinth_content_to_profile, add_modrinth_mod_to_profile,
     batch_check_content_installed, check_for_group_migration_command, check_world_lock_status, copy_profile, copy_world,
     create_profile, delete_custom_mod, delete_mod_from_profile, delete_profile, delete_world,
-    export_profile, get_all_profiles_and_last_played, get_custom_mods, get_local_content,
+    dry_run_profile_launch, export_profile, export_profile_as_pack, get_all_profiles_and_last_played, get_custom_mods, get_local_content,
     get_local_datapacks, get_local_resourcepacks, get_local_shaderpacks, get_log_file_content,
     get_GEG_packs, get_GEG_packs_resolved, get_profile, get_profile_directory_structure,
     get_profile_latest_log_content, get_profile_log_files, get_servers_for_profile,
@@ -66,6 +68,14 @@ use commands::profile_command::{
     resolve_loader_version, search_profiles, set_custom_mod_enabled, set_GEG_mod_status,
     set_profile_mod_enabled, update_datapack_from_modrinth, update_modrinth_mod_version,
     update_profile, update_resourcepack_from_modrinth, update_shaderpack_from_modrinth,
+    verify_profile_integrity, create_world_backup, list_world_backups, restore_world_backup,
+    delete_world_backup, analyze_profile_crash, check_profile_dependencies,
+    install_missing_dependencies, create_profile_snapshot, list_profile_snapshots,
+    rollback_profile_snapshot, delete_profile_snapshot, update_all_profile_mods,
+    discover_external_instances, read_external_instances, import_external_instance,
+    add_profile_server, update_profile_server, remove_profile_server, move_profile_server,
+    push_server_list_to_group, list_options_presets, create_options_preset_from_profile,
+    delete_options_preset, apply_options_preset,
 };
 
 // Use statements for registered commands only
@@ -334,23 +344,33 @@ async fn main() {
             update_profile,
             delete_profile,
             repair_profile,
+            verify_profile_integrity,
             resolve_loader_version,
             list_profiles,
             search_profiles,
             get_minecraft_versions,
             launch_profile,
             abort_profile_launch,
+            dry_run_profile_launch,
             is_profile_launching,
             get_processes,
             get_process,
             get_processes_by_profile,
             stop_process,
+            get_profile_sessions,
+            get_profile_playtime_stats,
+            get_account_playtime_stats,
+            get_all_profile_playtime_stats,
             open_log_window,
             begin_login,
+            add_offline_account,
             remove_account,
             get_active_account,
             set_active_account,
             get_accounts,
+            get_account_store_status,
+            unlock_account_store,
+            set_account_store_passphrase,
             search_modrinth_mods,
             search_modrinth_projects,
             search_mods_unified_command,
@@ -400,6 +420,7 @@ async fn main() {
             get_profile_directory_structure,
             copy_profile,
             export_profile,
+            export_profile_as_pack,
             get_launcher_config,
             set_launcher_config,
             get_launcher_directory,
@@ -440,10 +461,34 @@ async fn main() {
             validate_java_path_command,
             get_worlds_for_profile,
             get_servers_for_profile,
+            add_profile_server,
+            update_profile_server,
+            remove_profile_server,
+            move_profile_server,
+            push_server_list_to_group,
+            list_options_presets,
+            create_options_preset_from_profile,
+            delete_options_preset,
+            apply_options_preset,
             copy_world,
             check_world_lock_status,
             ping_minecraft_server,
             delete_world,
+            create_world_backup,
+            list_world_backups,
+            restore_world_backup,
+            delete_world_backup,
+            analyze_profile_crash,
+            check_profile_dependencies,
+            install_missing_dependencies,
+            create_profile_snapshot,
+            list_profile_snapshots,
+            rollback_profile_snapshot,
+            delete_profile_snapshot,
+            update_all_profile_mods,
+            discover_external_instances,
+            read_external_instances,
+            import_external_instance,
             get_profile_log_files,
             get_log_file_content,
             list_profile_screenshots,
@@ -467,6 +512,7 @@ async fn main() {
             commands::minecraft_command::add_skin_locally,
             commands::minecraft_command::get_base64_from_skin_source_command,
             commands::file_command::get_image_preview,
+            commands::file_command::collect_content_store_garbage,
             download_template_and_open_explorer,
             get_all_profiles_and_last_played,
             get_local_content,
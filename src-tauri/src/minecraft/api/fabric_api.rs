@@ -1,4 +1,5 @@
-use crate::config::{ProjectDirsExt, HTTP_CLIENT, LAUNCHER_DIRECTORY};
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::utils::download_utils::DownloadUtils;
 use crate::error::Result;
 use crate::minecraft::dto::fabric_meta::FabricVersionInfo;
 use log::{debug, error};
@@ -33,7 +34,7 @@ impl FabricApi {
         let url = format!("{}/versions/loader/{}", base_url, minecraft_version);
         debug!("Fetching Fabric versions from: {}", url);
 
-        let response = HTTP_CLIENT.get(&url).send().await.map_err(|e| {
+        let response = DownloadUtils::get_with_mirrors(&url).await.map_err(|e| {
             crate::error::AppError::FabricError(format!("Failed to fetch Fabric versions: {}", e))
         })?;
 
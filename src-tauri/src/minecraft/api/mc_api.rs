@@ -1,4 +1,5 @@
 use crate::config::{ProjectDirsExt, HTTP_CLIENT, LAUNCHER_DIRECTORY};
+use crate::utils::download_utils::DownloadUtils;
 use crate::error::{AppError, Result};
 use crate::minecraft::dto::minecraft_profile::MinecraftProfile;
 use crate::minecraft::dto::piston_meta::PistonMeta;
@@ -46,8 +47,7 @@ impl MinecraftApiService {
     async fn fetch_and_cache_manifest(cache_path: &PathBuf) -> Result<VersionManifest> {
         debug!("Fetching Minecraft version manifest from: {}", VERSION_MANIFEST_URL);
         
-        let response = HTTP_CLIENT.get(VERSION_MANIFEST_URL)
-            .send()
+        let response = DownloadUtils::get_with_mirrors(VERSION_MANIFEST_URL)
             .await
             .map_err(AppError::MinecraftApi)?;
 
@@ -113,11 +113,46 @@ impl MinecraftApiService {
         Self::fetch_and_cache_manifest(&cache_path).await
     }
 
+    /// Returns the cached version manifest without touching the network (offline launch).
+    /// Fails with `OfflineResourcesMissing` if there is no usable cache.
+    pub async fn get_cached_version_manifest(&self) -> Result<VersionManifest> {
+        let cache_path = self.cache_dir.join("version_manifest.json");
+        Self::read_cache_file(&cache_path, "Minecraft version manifest").await
+    }
+
+    /// Returns the cached piston meta for `url` without touching the network (offline launch).
+    /// Fails with `OfflineResourcesMissing` if there is no usable cache.
+    pub async fn get_cached_piston_meta(&self, url: &str) -> Result<PistonMeta> {
+        let cache_path = self.piston_meta_cache_path(url);
+        Self::read_cache_file(&cache_path, &format!("Piston meta for {}", url)).await
+    }
+
+    async fn read_cache_file<T: serde::de::DeserializeOwned>(
+        cache_path: &PathBuf,
+        what: &str,
+    ) -> Result<T> {
+        let cached_data = tokio_fs::read_to_string(cache_path).await.map_err(|e| {
+            AppError::OfflineResourcesMissing(vec![format!(
+                "{} ({}): {}",
+                what,
+                cache_path.display(),
+                e
+            )])
+        })?;
+        serde_json::from_str::<T>(&cached_data).map_err(|e| {
+            AppError::OfflineResourcesMissing(vec![format!(
+                "{} ({}): cache is corrupted: {}",
+                what,
+                cache_path.display(),
+                e
+            )])
+        })
+    }
+
     async fn fetch_and_cache_piston_meta(cache_path: &PathBuf, url: &str) -> Result<PistonMeta> {
         debug!("Fetching Piston Meta from: {}", url);
         
-        let response = HTTP_CLIENT.get(url)
-            .send()
+        let response = DownloadUtils::get_with_mirrors(url)
             .await
             .map_err(AppError::MinecraftApi)?;
 
@@ -147,12 +182,16 @@ impl MinecraftApiService {
         }
     }
 
-    pub async fn get_piston_meta(&self, url: &str) -> Result<PistonMeta> {
+    fn piston_meta_cache_path(&self, url: &str) -> PathBuf {
         // Create cache filename from URL hash
         let mut hasher = Sha1::new();
         hasher.update(url.as_bytes());
         let hash = format!("{:x}", hasher.finalize());
-        let cache_path = self.cache_dir.join(format!("piston_meta_{}.json", hash));
+        self.cache_dir.join(format!("piston_meta_{}.json", hash))
+    }
+
+    pub async fn get_piston_meta(&self, url: &str) -> Result<PistonMeta> {
+        let cache_path = self.piston_meta_cache_path(url);
 
         if cache_path.exists() {
             debug!("Cache hit for Piston Meta: {:?}", cache_path);
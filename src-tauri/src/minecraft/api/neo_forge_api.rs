@@ -1,4 +1,5 @@
-use crate::config::{ProjectDirsExt, HTTP_CLIENT, LAUNCHER_DIRECTORY};
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::utils::download_utils::DownloadUtils;
 use crate::error::{AppError, Result};
 use crate::minecraft::dto::neo_forge_maven_meta::NeoForgeMavenMetadata;
 use log::{debug, error, info};
@@ -31,8 +32,7 @@ impl NeoForgeApi {
     async fn fetch_and_cache_metadata(base_url: &str, cache_path: &PathBuf) -> Result<NeoForgeMavenMetadata> {
         debug!("Fetching NeoForge metadata from: {}", base_url);
 
-        let response = HTTP_CLIENT.get(base_url)
-            .send()
+        let response = DownloadUtils::get_with_mirrors(base_url)
             .await
             .map_err(|e| AppError::ForgeError(format!("Failed to fetch NeoForge versions: {}", e)))?;
 
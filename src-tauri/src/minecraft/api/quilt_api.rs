@@ -1,4 +1,5 @@
-use crate::config::{ProjectDirsExt, HTTP_CLIENT, LAUNCHER_DIRECTORY};
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::utils::download_utils::DownloadUtils;
 use crate::error::Result;
 use crate::minecraft::dto::quilt_meta::QuiltVersionInfo;
 use log::{debug, error};
@@ -33,7 +34,7 @@ impl QuiltApi {
         let url = format!("{}/versions/loader/{}", base_url, minecraft_version);
         debug!("Fetching Quilt versions from: {}", url);
 
-        let response = HTTP_CLIENT.get(&url).send().await.map_err(|e| {
+        let response = DownloadUtils::get_with_mirrors(&url).await.map_err(|e| {
             crate::error::AppError::QuiltError(format!("Failed to fetch Quilt versions: {}", e))
         })?;
 
@@ -0,0 +1,222 @@
+//! At-rest encryption for `accounts.json`.
+//!
+//! The store is sealed with AES-256-GCM. The key is derived with Argon2id from a machine-bound
+//! secret (machineid-rs) and, if the user set one, a passphrase. A copied `accounts.json` is
+//! therefore useless on another machine, and with a passphrase also useless to other local users.
+
+use crate::error::{AppError, Result};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::Argon2;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use machineid_rs::{Encryption, HWIDComponent, IdBuilder};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Marks an encrypted store and is bound to the ciphertext as associated data
+const STORE_FORMAT: &str = "GEG-accounts-aes256gcm-argon2id-v1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// On-disk layout of an encrypted account store
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncryptedAccountStore {
+    pub format: String,
+    pub salt: String,
+    pub passphrase_protected: bool,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl EncryptedAccountStore {
+    /// Returns the envelope if `data` is an encrypted store, `None` for legacy plaintext stores
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(data)
+            .ok()
+            .filter(|store| store.format == STORE_FORMAT)
+    }
+
+    pub fn salt(&self) -> Result<Vec<u8>> {
+        decode_field("salt", &self.salt)
+    }
+}
+
+/// Derived encryption key, cached by the auth store so Argon2 only runs once per unlock
+#[derive(Clone)]
+pub struct AccountStoreKey {
+    key: [u8; 32],
+    salt: Vec<u8>,
+    passphrase_protected: bool,
+}
+
+impl std::fmt::Debug for AccountStoreKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccountStoreKey")
+            .field("passphrase_protected", &self.passphrase_protected)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AccountStoreKey {
+    /// Derives a key for a new store (fresh random salt)
+    pub fn generate(machine_secret: &str, passphrase: Option<&str>) -> Result<Self> {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(machine_secret, passphrase, salt)
+    }
+
+    /// Derives the key for an existing store from its salt
+    pub fn derive(machine_secret: &str, passphrase: Option<&str>, salt: Vec<u8>) -> Result<Self> {
+        let passphrase = passphrase.filter(|p| !p.is_empty());
+
+        let mut password = machine_secret.as_bytes().to_vec();
+        if let Some(passphrase) = passphrase {
+            password.push(0);
+            password.extend_from_slice(passphrase.as_bytes());
+        }
+
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(&password, &salt, &mut key)
+            .map_err(|e| {
+                AppError::AccountError(format!("Failed to derive account store key: {}", e))
+            })?;
+
+        Ok(Self {
+            key,
+            salt,
+            passphrase_protected: passphrase.is_some(),
+        })
+    }
+
+    pub fn passphrase_protected(&self) -> bool {
+        self.passphrase_protected
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .cipher()?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: STORE_FORMAT.as_bytes(),
+                },
+            )
+            .map_err(|_| AppError::AccountError("Failed to encrypt account store".to_string()))?;
+
+        let store = EncryptedAccountStore {
+            format: STORE_FORMAT.to_string(),
+            salt: BASE64_STANDARD.encode(&self.salt),
+            passphrase_protected: self.passphrase_protected,
+            nonce: BASE64_STANDARD.encode(nonce),
+            ciphertext: BASE64_STANDARD.encode(ciphertext),
+        };
+        Ok(serde_json::to_vec_pretty(&store)?)
+    }
+
+    /// Fails for a wrong key (other machine, wrong passphrase) as well as for tampered data
+    pub fn decrypt(&self, store: &EncryptedAccountStore) -> Result<Vec<u8>> {
+        let nonce = decode_field("nonce", &store.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(AppError::AccountError(
+                "Account store has an invalid nonce".to_string(),
+            ));
+        }
+        let ciphertext = decode_field("ciphertext", &store.ciphertext)?;
+
+        self.cipher()?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: store.format.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                AppError::AccountError(
+                    "Account store could not be decrypted (wrong passphrase or different machine)"
+                        .to_string(),
+                )
+            })
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm> {
+        Aes256Gcm::new_from_slice(&self.key)
+            .map_err(|e| AppError::AccountError(format!("Invalid account store key: {}", e)))
+    }
+}
+
+fn decode_field(name: &str, value: &str) -> Result<Vec<u8>> {
+    BASE64_STANDARD.decode(value).map_err(|e| {
+        AppError::AccountError(format!(
+            "Account store field '{}' is not valid base64: {}",
+            name, e
+        ))
+    })
+}
+
+/// Machine-bound secret the store key is derived from
+pub fn machine_secret() -> Result<String> {
+    IdBuilder::new(Encryption::SHA256)
+        .add_component(HWIDComponent::SystemID)
+        .build("GEG-account-store")
+        .map_err(|e| AppError::AccountError(format!("HWID Error {:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACHINE: &str = "test-machine-secret";
+
+    #[test]
+    fn test_round_trip_with_and_without_passphrase() {
+        for passphrase in [None, Some("hunter2")] {
+            let key = AccountStoreKey::generate(MACHINE, passphrase).unwrap();
+            let sealed = key.encrypt(br#"{"accounts":[],"token":null}"#).unwrap();
+            assert!(!String::from_utf8_lossy(&sealed).contains("token"));
+
+            let store = EncryptedAccountStore::parse(&sealed).unwrap();
+            assert_eq!(store.passphrase_protected, passphrase.is_some());
+
+            let reloaded =
+                AccountStoreKey::derive(MACHINE, passphrase, store.salt().unwrap()).unwrap();
+            assert_eq!(
+                reloaded.decrypt(&store).unwrap(),
+                br#"{"accounts":[],"token":null}"#
+            );
+        }
+    }
+
+    #[test]
+    fn test_wrong_key_or_tampering_is_rejected() {
+        let key = AccountStoreKey::generate(MACHINE, Some("hunter2")).unwrap();
+        let store = EncryptedAccountStore::parse(&key.encrypt(b"secret").unwrap()).unwrap();
+        let salt = store.salt().unwrap();
+
+        let wrong_passphrase =
+            AccountStoreKey::derive(MACHINE, Some("hunter3"), salt.clone()).unwrap();
+        assert!(wrong_passphrase.decrypt(&store).is_err());
+
+        let other_machine =
+            AccountStoreKey::derive("other-machine", Some("hunter2"), salt).unwrap();
+        assert!(other_machine.decrypt(&store).is_err());
+
+        let mut tampered = store.clone();
+        let mut ciphertext = BASE64_STANDARD.decode(&tampered.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        tampered.ciphertext = BASE64_STANDARD.encode(ciphertext);
+        assert!(key.decrypt(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_store_is_not_mistaken_for_encrypted() {
+        assert!(EncryptedAccountStore::parse(br#"{"accounts":[],"token":null}"#).is_none());
+    }
+}
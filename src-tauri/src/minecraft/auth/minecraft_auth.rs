@@ -29,6 +29,13 @@ use uuid::Uuid;
 
 use crate::config::{ProjectDirsExt, HTTP_CLIENT, LAUNCHER_DIRECTORY};
 use crate::minecraft::api::NoriskApi;
+use crate::minecraft::auth::account_store_crypto::{
+    machine_secret, AccountStoreKey, EncryptedAccountStore,
+};
+use crate::utils::backup_utils::{self, BackupConfig};
+
+/// Backup category (subfolder of the backup root) for `accounts.json`
+const ACCOUNT_BACKUP_CATEGORY: &str = "accounts";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GEGTokenClaims {
@@ -36,6 +43,19 @@ pub struct GEGTokenClaims {
     username: String,
 }
 
+/// Access token placed on the command line for offline accounts.
+pub const OFFLINE_ACCESS_TOKEN: &str = "0";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountType {
+    /// Account signed in through the Microsoft device-code flow
+    #[default]
+    Microsoft,
+    /// Local account with a chosen username, no authentication involved
+    Offline,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Credentials {
     pub id: Uuid,
@@ -45,6 +65,21 @@ pub struct Credentials {
     pub expires: DateTime<Utc>,
     pub GEG_credentials: GEGCredentials,
     pub active: bool,
+    #[serde(default)]
+    pub account_type: AccountType,
+}
+
+impl Credentials {
+    pub fn is_offline(&self) -> bool {
+        self.account_type == AccountType::Offline
+    }
+
+    /// Derives the UUID vanilla uses for offline players: a name-based (v3) UUID
+    /// of `"OfflinePlayer:<name>"` without a namespace.
+    pub fn offline_uuid(username: &str) -> Uuid {
+        let digest = md5::Md5::digest(format!("OfflinePlayer:{}", username).as_bytes());
+        uuid::Builder::from_md5_bytes(digest.into()).into_uuid()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -174,6 +209,19 @@ pub struct MinecraftAuthStore {
     accounts: Arc<RwLock<Vec<Credentials>>>,
     store_path: PathBuf,
     token: Arc<RwLock<Option<SaveDeviceToken>>>,
+    /// Key of the encrypted store; `None` while a passphrase-protected store is still locked
+    store_key: Arc<RwLock<Option<AccountStoreKey>>>,
+    /// Folder that receives the backups `save` writes of `accounts.json`
+    backup_dir: PathBuf,
+    /// Source of the machine-bound secret the store key is derived from
+    machine_secret: fn() -> Result<String>,
+}
+
+/// Encryption state of `accounts.json`, shown in the account settings
+#[derive(Serialize, Debug, Clone)]
+pub struct AccountStoreStatus {
+    pub passphrase_protected: bool,
+    pub locked: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -190,17 +238,30 @@ impl MinecraftAuthStore {
             std::fs::create_dir_all(&accounts_path)?;
         }
 
-        let store_path = accounts_path.join("accounts.json");
-        let manager = Self {
-            accounts: Arc::new(RwLock::new(Vec::new())),
-            store_path: store_path,
-            token: Arc::new(RwLock::new(None)),
-        };
-
+        let manager = Self::with_paths(
+            accounts_path.join("accounts.json"),
+            backup_utils::get_backup_root().join(ACCOUNT_BACKUP_CATEGORY),
+            machine_secret,
+        );
         manager.load().await?;
         Ok(manager)
     }
 
+    fn with_paths(
+        store_path: PathBuf,
+        backup_dir: PathBuf,
+        machine_secret: fn() -> Result<String>,
+    ) -> Self {
+        Self {
+            accounts: Arc::new(RwLock::new(Vec::new())),
+            store_path,
+            token: Arc::new(RwLock::new(None)),
+            store_key: Arc::new(RwLock::new(None)),
+            backup_dir,
+            machine_secret,
+        }
+    }
+
     pub async fn load(&self) -> Result<()> {
         info!("[Storage] Starting load operation");
 
@@ -210,14 +271,56 @@ impl MinecraftAuthStore {
                 self.store_path.display()
             );
             info!("[Storage] Reading account data");
-            let data = fs::read_to_string(&self.store_path).await?;
+            let raw = fs::read(&self.store_path).await?;
             info!(
                 "[Storage] Successfully read data, length: {} bytes",
-                data.len()
+                raw.len()
             );
 
+            let mut migrate_plaintext = false;
+            let data = match EncryptedAccountStore::parse(&raw) {
+                Some(encrypted) if encrypted.passphrase_protected => {
+                    info!("[Storage] Account store is passphrase protected, waiting for unlock");
+                    *self.store_key.write().await = None;
+                    return Ok(());
+                }
+                Some(encrypted) => {
+                    info!("[Storage] Decrypting account data");
+                    let key = AccountStoreKey::derive(
+                        &(self.machine_secret)()?,
+                        None,
+                        encrypted.salt()?,
+                    )?;
+                    match key.decrypt(&encrypted) {
+                        Ok(plaintext) => {
+                            *self.store_key.write().await = Some(key);
+                            plaintext
+                        }
+                        Err(e) => {
+                            // Move the file aside so the next save cannot replace the accounts
+                            let unreadable_path = self.store_path.with_extension(format!(
+                                "json.undecryptable-{}",
+                                Utc::now().timestamp()
+                            ));
+                            error!(
+                                "[Storage] {}. Moving account data to {} and resetting to empty state.",
+                                e,
+                                unreadable_path.display()
+                            );
+                            fs::rename(&self.store_path, &unreadable_path).await?;
+                            return Ok(());
+                        }
+                    }
+                }
+                None => {
+                    info!("[Storage] Found plaintext account store, it will be encrypted");
+                    migrate_plaintext = true;
+                    raw
+                }
+            };
+
             info!("[Storage] Deserializing account data");
-            let store: AccountStore = match serde_json::from_str(&data) {
+            let store: AccountStore = match serde_json::from_slice(&data) {
                 Ok(store) => {
                     info!("[Storage] Successfully deserialized data");
                     store
@@ -255,6 +358,10 @@ impl MinecraftAuthStore {
                 *token_guard = store.token;
             }
             info!("[Storage] Device token restored");
+
+            if migrate_plaintext {
+                self.migrate_plaintext_store().await?;
+            }
         } else {
             info!("[Storage] No account file found, starting with empty accounts");
         }
@@ -283,20 +390,137 @@ impl MinecraftAuthStore {
         };
 
         info!("[Storage] Serializing data to JSON");
-        let data = serde_json::to_string_pretty(&store)?;
+        let data = serde_json::to_vec(&store)?;
         info!("[Storage] Successfully serialized data");
 
+        let encrypted = {
+            let mut key_guard = self.store_key.write().await;
+            if key_guard.is_none() {
+                if self.is_locked_on_disk().await {
+                    return Err(AppError::AccountError(
+                        "Account store is locked, enter the passphrase first".to_string(),
+                    ));
+                }
+                *key_guard = Some(AccountStoreKey::generate(&(self.machine_secret)()?, None)?);
+            }
+            key_guard.as_ref().unwrap().encrypt(&data)?
+        };
+
         info!(
-            "[Storage] Writing data to file: {}",
+            "[Storage] Writing encrypted data to file: {}",
             self.store_path.display()
         );
-        fs::write(&self.store_path, data).await?;
+        backup_utils::safe_write_with_backup_in(
+            &self.backup_dir,
+            &self.store_path,
+            encrypted,
+            &BackupConfig::default(),
+        )
+        .await?;
         info!("[Storage] Successfully wrote data to file");
 
         info!("[Storage] Save operation completed successfully");
         Ok(())
     }
 
+    /// True if `accounts.json` is passphrase protected and not unlocked yet
+    async fn is_locked_on_disk(&self) -> bool {
+        match fs::read(&self.store_path).await {
+            Ok(raw) => EncryptedAccountStore::parse(&raw)
+                .is_some_and(|encrypted| encrypted.passphrase_protected),
+            Err(_) => false,
+        }
+    }
+
+    /// Rewrites a legacy plaintext store encrypted and drops the plaintext copies
+    /// that `safe_write_with_backup` left in the backup folder.
+    async fn migrate_plaintext_store(&self) -> Result<()> {
+        info!("[Storage] Migrating plaintext account store to encrypted format");
+        self.save().await?;
+        self.purge_account_backups(|raw| EncryptedAccountStore::parse(raw).is_some())
+            .await
+    }
+
+    /// Deletes the account backups that `keep` rejects
+    async fn purge_account_backups(&self, keep: impl Fn(&[u8]) -> bool) -> Result<()> {
+        for (backup_path, _) in
+            backup_utils::list_backups_in(&self.backup_dir, &self.store_path).await?
+        {
+            let keep_backup = fs::read(&backup_path)
+                .await
+                .map(|raw| keep(&raw))
+                .unwrap_or(false);
+            if !keep_backup {
+                info!(
+                    "[Storage] Removing account backup {}",
+                    backup_path.display()
+                );
+                fs::remove_file(&backup_path).await?;
+                let _ = fs::remove_file(backup_path.with_extension("backup.meta")).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn store_status(&self) -> AccountStoreStatus {
+        let key = self.store_key.read().await;
+        match key.as_ref() {
+            Some(key) => AccountStoreStatus {
+                passphrase_protected: key.passphrase_protected(),
+                locked: false,
+            },
+            None => {
+                let locked = self.is_locked_on_disk().await;
+                AccountStoreStatus {
+                    passphrase_protected: locked,
+                    locked,
+                }
+            }
+        }
+    }
+
+    /// Decrypts a passphrase-protected store and loads its accounts
+    pub async fn unlock(&self, passphrase: &str) -> Result<()> {
+        let raw = fs::read(&self.store_path).await?;
+        let encrypted = EncryptedAccountStore::parse(&raw)
+            .filter(|encrypted| encrypted.passphrase_protected)
+            .ok_or_else(|| {
+                AppError::AccountError("Account store is not passphrase protected".to_string())
+            })?;
+
+        let key = AccountStoreKey::derive(
+            &(self.machine_secret)()?,
+            Some(passphrase),
+            encrypted.salt()?,
+        )?;
+        let store: AccountStore = serde_json::from_slice(&key.decrypt(&encrypted)?)?;
+
+        *self.accounts.write().await = store.accounts;
+        *self.token.write().await = store.token;
+        *self.store_key.write().await = Some(key);
+        info!("[Storage] Account store unlocked");
+        Ok(())
+    }
+
+    /// Sets (or with `None` removes) the passphrase and re-encrypts the store with a fresh key.
+    /// Older backups are deleted, they are encrypted with the previous key and could still be read with it.
+    pub async fn set_passphrase(&self, passphrase: Option<&str>) -> Result<()> {
+        if self.store_key.read().await.is_none() && self.is_locked_on_disk().await {
+            return Err(AppError::AccountError(
+                "Account store is locked, enter the current passphrase first".to_string(),
+            ));
+        }
+
+        let key = AccountStoreKey::generate(&(self.machine_secret)()?, passphrase)?;
+        info!(
+            "[Storage] Re-encrypting account store (passphrase protected: {})",
+            key.passphrase_protected()
+        );
+        *self.store_key.write().await = Some(key);
+        self.save().await?;
+        self.purge_account_backups(|_| false).await
+    }
+
     async fn refresh_and_get_device_token(
         &self,
         current_date: DateTime<Utc>,
@@ -495,6 +719,7 @@ impl MinecraftAuthStore {
                     experimental: None,
                 },
             },
+            account_type: AccountType::Microsoft,
         };
 
         info!(
@@ -513,6 +738,14 @@ impl MinecraftAuthStore {
         force_update: bool,
         experimental_mode: bool,
     ) -> Result<Credentials> {
+        if creds.is_offline() {
+            info!(
+                "[Token Refresh] Skipping GEG token refresh for offline account: {}",
+                creds.username
+            );
+            return Ok(creds.clone());
+        }
+
         info!(
             "[Token Refresh] Starting GEG token refresh check for user: {}",
             creds.username
@@ -678,6 +911,7 @@ impl MinecraftAuthStore {
             expires: oauth_token.date + Duration::seconds(oauth_token.value.expires_in as i64),
             GEG_credentials: creds.clone().GEG_credentials,
             active: creds.clone().active,
+            account_type: AccountType::Microsoft,
         };
 
         info!("[Token Refresh] Updating account in storage");
@@ -687,6 +921,60 @@ impl MinecraftAuthStore {
         Ok(Some(val))
     }
 
+    /// Adds (or re-activates) an offline account with the given username and makes it active.
+    pub async fn add_offline_account(&self, username: &str) -> Result<Credentials> {
+        let username = username.trim();
+        if !is_valid_offline_username(username) {
+            return Err(AppError::AccountError(format!(
+                "Invalid offline username '{}': use 1-16 letters, digits or underscores",
+                username
+            )));
+        }
+
+        let id = Credentials::offline_uuid(username);
+        info!(
+            "[Account Manager] Adding offline account '{}' with UUID {}",
+            username, id
+        );
+
+        if let Some(existing) = self.get_account_by_id(id).await? {
+            if !existing.is_offline() {
+                return Err(AppError::AccountError(format!(
+                    "A Microsoft account with UUID {} already exists",
+                    id
+                )));
+            }
+        }
+
+        let credentials = Credentials {
+            id,
+            username: username.to_string(),
+            access_token: OFFLINE_ACCESS_TOKEN.to_string(),
+            refresh_token: String::new(),
+            expires: DateTime::<Utc>::MAX_UTC,
+            GEG_credentials: GEGCredentials {
+                production: None,
+                experimental: None,
+            },
+            active: true,
+            account_type: AccountType::Offline,
+        };
+
+        {
+            let mut accounts = self.accounts.write().await;
+            for account in accounts.iter_mut() {
+                account.active = false;
+            }
+            accounts.retain(|acc| acc.id != id);
+            accounts.push(credentials.clone());
+        } // Write-Lock wird hier freigegeben
+
+        self.save().await?;
+        info!("[Account Manager] Offline account saved and set as active");
+
+        Ok(credentials)
+    }
+
     pub async fn get_account_by_id(&self, id: Uuid) -> Result<Option<Credentials>> {
         let accounts = self.accounts.read().await;
         Ok(accounts.iter().find(|acc| acc.id == id).cloned())
@@ -729,6 +1017,11 @@ impl MinecraftAuthStore {
             "[Token Check] Starting token validation check for user: {}",
             creds.username
         );
+        if creds.is_offline() {
+            info!("[Token Check] Offline account, no tokens to validate");
+            return Ok(Some(creds.clone()));
+        }
+
         info!(
             "[Token Check] Microsoft token expires at: {}",
             creds.expires
@@ -866,6 +1159,17 @@ impl MinecraftAuthStore {
         }
     }
 
+    /// Returns the active account as stored, without refreshing any tokens (offline launch).
+    /// Falls back to the first account like `get_active_account`, but does not persist that choice.
+    pub async fn get_active_account_without_refresh(&self) -> Option<Credentials> {
+        let accounts = self.accounts.read().await;
+        accounts
+            .iter()
+            .find(|acc| acc.active)
+            .or_else(|| accounts.first())
+            .cloned()
+    }
+
     pub async fn remove_account(&self, id: Uuid) -> Result<()> {
         info!("[Account Manager] Starting account removal for ID: {}", id);
 
@@ -1557,9 +1861,137 @@ fn get_date_header(headers: &HeaderMap) -> DateTime<Utc> {
         .map_or(Utc::now(), |x| x.with_timezone(&Utc))
 }
 
+/// Vanilla only accepts 1-16 letters, digits or underscores as player names
+fn is_valid_offline_username(username: &str) -> bool {
+    (1..=16).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 fn generate_oauth_challenge() -> String {
     let mut rng = rand::thread_rng();
 
     let bytes: Vec<u8> = (0..64).map(|_| rng.gen::<u8>()).collect();
     bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_accounts_use_vanilla_uuid_and_name_rules() {
+        assert_eq!(
+            Credentials::offline_uuid("Notch").to_string(),
+            "b50ad385-829d-3141-a216-7e7d7539ba7f"
+        );
+        assert_ne!(
+            Credentials::offline_uuid("notch"),
+            Credentials::offline_uuid("Notch")
+        );
+
+        assert!(is_valid_offline_username("Steve_123"));
+        assert!(is_valid_offline_username("abcdefghijklmnop"));
+        assert!(!is_valid_offline_username(""));
+        assert!(!is_valid_offline_username("abcdefghijklmnopq"));
+        assert!(!is_valid_offline_username("Steve Jobs"));
+        assert!(!is_valid_offline_username("Stève"));
+    }
+
+    fn test_machine_secret() -> Result<String> {
+        Ok("test-machine-secret".to_string())
+    }
+
+    /// Store and backups in a temp dir, keyed with a fixed secret instead of the real machine id
+    fn temp_store(dir: &tempfile::TempDir) -> MinecraftAuthStore {
+        MinecraftAuthStore::with_paths(
+            dir.path().join("accounts.json"),
+            dir.path().join("backups"),
+            test_machine_secret,
+        )
+    }
+
+    async fn account_backups(store: &MinecraftAuthStore) -> Vec<Vec<u8>> {
+        let mut backups = Vec::new();
+        for (path, _) in backup_utils::list_backups_in(&store.backup_dir, &store.store_path)
+            .await
+            .unwrap()
+        {
+            backups.push(fs::read(path).await.unwrap());
+        }
+        backups
+    }
+
+    #[tokio::test]
+    async fn plaintext_store_is_encrypted_and_plaintext_backups_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = temp_store(&dir);
+        let offline = temp_store(&dir);
+        let credentials = offline.add_offline_account("Steve").await.unwrap();
+        let plaintext = serde_json::to_vec(&AccountStore {
+            accounts: vec![credentials],
+            token: None,
+        })
+        .unwrap();
+        fs::write(&store.store_path, &plaintext).await.unwrap();
+
+        store.load().await.unwrap();
+
+        assert_eq!(store.get_all_accounts().await.unwrap()[0].username, "Steve");
+        let raw = fs::read(&store.store_path).await.unwrap();
+        assert!(EncryptedAccountStore::parse(&raw).is_some());
+        let backups = account_backups(&store).await;
+        assert!(backups
+            .iter()
+            .all(|raw| EncryptedAccountStore::parse(raw).is_some()));
+    }
+
+    #[tokio::test]
+    async fn undecryptable_store_is_moved_aside_instead_of_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = temp_store(&dir);
+        let foreign_key = AccountStoreKey::generate("another machine", None).unwrap();
+        let encrypted = foreign_key.encrypt(b"{\"accounts\":[]}").unwrap();
+        fs::write(&store.store_path, &encrypted).await.unwrap();
+
+        store.load().await.unwrap();
+        assert!(store.get_all_accounts().await.unwrap().is_empty());
+        assert!(!store.store_path.exists());
+
+        let moved: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.to_string_lossy()
+                    .contains("accounts.json.undecryptable-")
+            })
+            .collect();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(std::fs::read(&moved[0]).unwrap(), encrypted);
+    }
+
+    #[tokio::test]
+    async fn passphrase_locks_store_and_removes_machine_key_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = temp_store(&dir);
+        store.add_offline_account("Steve").await.unwrap();
+        store.add_offline_account("Alex").await.unwrap();
+        assert!(!account_backups(&store).await.is_empty());
+
+        store.set_passphrase(Some("hunter2")).await.unwrap();
+        assert!(account_backups(&store).await.is_empty());
+
+        let reopened = temp_store(&dir);
+        reopened.load().await.unwrap();
+        let status = reopened.store_status().await;
+        assert!(status.passphrase_protected && status.locked);
+        assert!(reopened.get_all_accounts().await.unwrap().is_empty());
+        assert!(reopened.add_offline_account("Notch").await.is_err());
+
+        assert!(reopened.unlock("wrong").await.is_err());
+        reopened.unlock("hunter2").await.unwrap();
+        assert_eq!(reopened.get_all_accounts().await.unwrap().len(), 2);
+        assert!(!reopened.store_status().await.locked);
+    }
+}
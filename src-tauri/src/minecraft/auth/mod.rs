@@ -1 +1,2 @@
+pub mod account_store_crypto;
 pub mod minecraft_auth;
@@ -0,0 +1,797 @@
+//! Local crash analysis over crash reports and `latest.log`.
+//!
+//! Known failure signatures are matched with regexes, mapped to the profile's mods where the
+//! log names a mod, and turned into suggested actions the UI can offer.
+
+use crate::error::Result;
+use crate::state::profile_state::{self, CustomModInfo, Profile};
+use crate::state::state_manager::State;
+use lazy_static::lazy_static;
+use log::{debug, info};
+use regex::Regex;
+use serde::Serialize;
+use std::io::SeekFrom;
+use std::path::Path;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use uuid::Uuid;
+
+/// Only the tail of huge logs is analyzed, the failure is almost always at the end
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+
+lazy_static! {
+    static ref FABRIC_MISSING_DEPENDENCY: Regex = Regex::new(
+        r"Mod '([^']+)' \(([\w\-.]+)\)[^\n]*? requires ([^\n]*?) of (?:mod )?(?:'[^']*' )?\(?([\w\-.]+)\)?, which is missing"
+    ).unwrap();
+    static ref FORGE_MISSING_DEPENDENCY: Regex = Regex::new(
+        r"Mod ID: '([^']+)', Requested by: '([^']+)', Expected range: '([^']*)', Actual version: '\[MISSING\]'"
+    ).unwrap();
+    static ref FABRIC_WRONG_JAVA: Regex = Regex::new(
+        r"requires version (\d+)[^\n]*? of [^\n]*?\(java\), but only the wrong version is present: (\d+)"
+    ).unwrap();
+    static ref CLASS_FILE_VERSION: Regex = Regex::new(
+        r"class file version (\d+)\.\d+\), this version of the Java Runtime only recognizes class file versions up to (\d+)\.\d+"
+    ).unwrap();
+    static ref MIXIN_APPLY_FOR_MOD: Regex =
+        Regex::new(r"Mixin apply for mod ([\w\-.]+) failed ([\w\-.]+\.json)").unwrap();
+    static ref MIXIN_FROM_MOD: Regex =
+        Regex::new(r"([\w\-.]+\.json):[\w$.]+ from mod ([\w\-.]+)").unwrap();
+    static ref MIXIN_APPLY_FAILED: Regex =
+        Regex::new(r"Mixin apply failed ([\w\-.]+\.json)").unwrap();
+    static ref OUT_OF_MEMORY: Regex =
+        Regex::new(r"java\.lang\.OutOfMemoryError(?::[^\n]*)?").unwrap();
+    static ref FORGE_DUPLICATE_MOD: Regex =
+        Regex::new(r"Mod ID: '([^']+)' from mod files: ([^\n]+)").unwrap();
+    static ref DUPLICATE_MOD: Regex =
+        Regex::new(r"(?i)duplicate mod(?: id)?:?\s+'?([a-z][a-z0-9_\-]*)'?").unwrap();
+    static ref GRAPHICS_DRIVER: Regex = Regex::new(
+        r"(?i)(The driver does not appear to support OpenGL|Pixel format not accelerated|No OpenGL context found in the current thread|Couldn't set pixel format|GLFW error 6554[23][^\n]*)"
+    ).unwrap();
+    static ref GRAPHICS_NATIVE_FRAME: Regex = Regex::new(
+        r"(?i)\[(atio6axx\.dll|atioglxx\.dll|nvoglv(?:32|64)\.dll|ig[0-9a-z]+icd(?:32|64)\.dll|libnvidia-glcore\.so[^\]\s+]*|radeonsi_dri\.so|iris_dri\.so)"
+    ).unwrap();
+    static ref WRONG_LOADER: Regex = Regex::new(
+        r"(?i)([^\s'/\\]+\.jar) (?:is an? |was built for )(fabric|forge|neoforge|quilt)\b"
+    ).unwrap();
+}
+
+/// A known failure pattern found in a log
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CrashSignature {
+    MissingDependency {
+        /// Mod that declared the dependency, if the log names it
+        mod_id: Option<String>,
+        dependency: String,
+        /// Required version range, `None` for "any version"
+        version: Option<String>,
+    },
+    MixinFailure {
+        mod_id: Option<String>,
+        mixin_config: Option<String>,
+    },
+    WrongJavaVersion {
+        required: Option<u32>,
+        found: Option<u32>,
+    },
+    OutOfMemory,
+    DuplicateMod {
+        mod_id: String,
+        files: Vec<String>,
+    },
+    GraphicsDriver {
+        detail: String,
+    },
+    WrongLoader {
+        file: String,
+        built_for: String,
+    },
+}
+
+/// A mod of the profile a finding refers to
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ModRef {
+    Profile {
+        id: Uuid,
+        name: String,
+        enabled: bool,
+    },
+    Custom {
+        filename: String,
+        enabled: bool,
+    },
+}
+
+impl ModRef {
+    fn enabled(&self) -> bool {
+        match self {
+            ModRef::Profile { enabled, .. } | ModRef::Custom { enabled, .. } => *enabled,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SuggestedAction {
+    DisableMod {
+        target: ModRef,
+    },
+    EnableMod {
+        target: ModRef,
+    },
+    InstallDependency {
+        mod_id: String,
+        version: Option<String>,
+    },
+    RaiseMemory {
+        current_mb: u32,
+        suggested_mb: u32,
+    },
+    SwitchJava {
+        required_major: Option<u32>,
+    },
+    RemoveDuplicates {
+        mod_id: String,
+        files: Vec<String>,
+    },
+    UpdateGraphicsDriver,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CrashFinding {
+    pub signature: CrashSignature,
+    pub summary: String,
+    /// The log line the signature was found in
+    pub evidence: String,
+    pub mods: Vec<ModRef>,
+    pub actions: Vec<SuggestedAction>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CrashDiagnosis {
+    pub profile_id: Uuid,
+    /// Files the diagnosis is based on
+    pub sources: Vec<String>,
+    pub findings: Vec<CrashFinding>,
+}
+
+/// Finds all known signatures in `text`, each with the line it was found in
+pub fn detect_signatures(text: &str) -> Vec<(CrashSignature, String)> {
+    let mut found: Vec<(CrashSignature, String)> = Vec::new();
+    let mut push = |signature: CrashSignature, evidence: &str| {
+        if !found.iter().any(|(existing, _)| *existing == signature) {
+            found.push((signature, line_of(text, evidence)));
+        }
+    };
+
+    for caps in FABRIC_WRONG_JAVA.captures_iter(text) {
+        push(
+            CrashSignature::WrongJavaVersion {
+                required: caps[1].parse().ok(),
+                found: caps[2].parse().ok(),
+            },
+            caps.get(0).unwrap().as_str(),
+        );
+    }
+    for caps in CLASS_FILE_VERSION.captures_iter(text) {
+        // Class file version 52 is Java 8, every major release adds one
+        let to_java = |v: &str| v.parse::<u32>().ok().and_then(|v| v.checked_sub(44));
+        push(
+            CrashSignature::WrongJavaVersion {
+                required: to_java(&caps[1]),
+                found: to_java(&caps[2]),
+            },
+            caps.get(0).unwrap().as_str(),
+        );
+    }
+
+    for caps in FABRIC_MISSING_DEPENDENCY.captures_iter(text) {
+        // Java itself is declared as a dependency, handled above
+        if &caps[4] == "java" {
+            continue;
+        }
+        let version = caps[3]
+            .strip_prefix("version ")
+            .map(|v| v.trim().to_string());
+        push(
+            CrashSignature::MissingDependency {
+                mod_id: Some(caps[2].to_string()),
+                dependency: caps[4].to_string(),
+                version,
+            },
+            caps.get(0).unwrap().as_str(),
+        );
+    }
+    for caps in FORGE_MISSING_DEPENDENCY.captures_iter(text) {
+        let range = caps[3].trim();
+        push(
+            CrashSignature::MissingDependency {
+                mod_id: Some(caps[2].to_string()),
+                dependency: caps[1].to_string(),
+                version: (!range.is_empty()).then(|| range.to_string()),
+            },
+            caps.get(0).unwrap().as_str(),
+        );
+    }
+
+    for caps in MIXIN_APPLY_FOR_MOD.captures_iter(text) {
+        push(
+            CrashSignature::MixinFailure {
+                mod_id: Some(caps[1].to_string()),
+                mixin_config: Some(caps[2].to_string()),
+            },
+            caps.get(0).unwrap().as_str(),
+        );
+    }
+    for caps in MIXIN_FROM_MOD.captures_iter(text) {
+        push(
+            CrashSignature::MixinFailure {
+                mod_id: Some(caps[2].to_string()),
+                mixin_config: Some(caps[1].to_string()),
+            },
+            caps.get(0).unwrap().as_str(),
+        );
+    }
+    for caps in MIXIN_APPLY_FAILED.captures_iter(text) {
+        let config = caps[1].to_string();
+        // Skip configs already reported with their mod
+        if found.iter().any(|(s, _)| {
+            matches!(s, CrashSignature::MixinFailure { mixin_config: Some(c), .. } if *c == config)
+        }) {
+            continue;
+        }
+        found.push((
+            CrashSignature::MixinFailure {
+                mod_id: mod_id_from_mixin_config(&config),
+                mixin_config: Some(config),
+            },
+            line_of(text, caps.get(0).unwrap().as_str()),
+        ));
+    }
+
+    if let Some(m) = OUT_OF_MEMORY.find(text) {
+        found.push((CrashSignature::OutOfMemory, line_of(text, m.as_str())));
+    }
+
+    for caps in FORGE_DUPLICATE_MOD.captures_iter(text) {
+        let files = caps[2]
+            .split(',')
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty())
+            .collect();
+        found.push((
+            CrashSignature::DuplicateMod {
+                mod_id: caps[1].to_string(),
+                files,
+            },
+            line_of(text, caps.get(0).unwrap().as_str()),
+        ));
+    }
+    for caps in DUPLICATE_MOD.captures_iter(text) {
+        let mod_id = caps[1].to_string();
+        let already_known = found.iter().any(
+            |(s, _)| matches!(s, CrashSignature::DuplicateMod { mod_id: id, .. } if *id == mod_id),
+        );
+        if already_known || ["id", "ids", "mods"].contains(&mod_id.to_lowercase().as_str()) {
+            continue;
+        }
+        found.push((
+            CrashSignature::DuplicateMod {
+                mod_id,
+                files: Vec::new(),
+            },
+            line_of(text, caps.get(0).unwrap().as_str()),
+        ));
+    }
+
+    if let Some(m) = GRAPHICS_DRIVER
+        .find(text)
+        .or_else(|| GRAPHICS_NATIVE_FRAME.find(text))
+    {
+        found.push((
+            CrashSignature::GraphicsDriver {
+                detail: m.as_str().trim_start_matches('[').to_string(),
+            },
+            line_of(text, m.as_str()),
+        ));
+    }
+
+    for caps in WRONG_LOADER.captures_iter(text) {
+        let signature = CrashSignature::WrongLoader {
+            file: caps[1].to_string(),
+            built_for: caps[2].to_lowercase(),
+        };
+        if !found.iter().any(|(s, _)| *s == signature) {
+            found.push((signature, line_of(text, caps.get(0).unwrap().as_str())));
+        }
+    }
+
+    found
+}
+
+/// `sodium.mixins.json` / `mixins.sodium.json` -> `sodium`
+fn mod_id_from_mixin_config(config: &str) -> Option<String> {
+    config
+        .trim_end_matches(".json")
+        .split('.')
+        .find(|part| !part.is_empty() && *part != "mixins" && *part != "mixin")
+        .map(str::to_string)
+}
+
+/// The full line containing `needle`, trimmed, for display
+fn line_of(text: &str, needle: &str) -> String {
+    let start = text.find(needle).unwrap_or(0);
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+    text[line_start..line_end].trim().to_string()
+}
+
+/// Lowercase alphanumerics only, so `Sodium Extra`, `sodium-extra` and `sodium_extra` compare equal
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Mod name part of a jar filename: `sodium-extra-0.5.1+mc1.20.1.jar` -> `sodiumextra`
+fn filename_base(filename: &str) -> String {
+    let stem = filename
+        .trim_end_matches(".disabled")
+        .trim_end_matches(".jar");
+    let mut base = String::new();
+    for (index, token) in stem.split(['-', '_', '+', ' ']).enumerate() {
+        let lower = token.to_ascii_lowercase();
+        let is_version = lower.chars().next().is_none_or(|c| c.is_ascii_digit())
+            || ((lower.starts_with("mc") || lower.starts_with('v'))
+                && lower
+                    .trim_start_matches("mc")
+                    .trim_start_matches('v')
+                    .starts_with(|c: char| c.is_ascii_digit()));
+        let is_loader =
+            index > 0 && ["fabric", "forge", "neoforge", "quilt"].contains(&lower.as_str());
+        if index > 0 && (is_version || is_loader) {
+            break;
+        }
+        base.push_str(&lower);
+    }
+    normalize(&base)
+}
+
+/// Finds the profile mods a mod ID or jar filename from a log refers to
+fn match_mods(profile: &Profile, custom_mods: &[CustomModInfo], name: &str) -> Vec<ModRef> {
+    let wanted = if name.ends_with(".jar") {
+        filename_base(name)
+    } else {
+        normalize(name)
+    };
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    for mod_info in &profile.mods {
+        let filename = profile_state::get_profile_mod_filename(&mod_info.source).ok();
+        let by_name = mod_info
+            .display_name
+            .as_deref()
+            .is_some_and(|n| normalize(n) == wanted);
+        let by_file = filename
+            .as_deref()
+            .is_some_and(|f| f == name || filename_base(f) == wanted);
+        if by_name || by_file {
+            matches.push(ModRef::Profile {
+                id: mod_info.id,
+                name: mod_info
+                    .display_name
+                    .clone()
+                    .or(filename)
+                    .unwrap_or_else(|| mod_info.id.to_string()),
+                enabled: mod_info.enabled,
+            });
+        }
+    }
+    for custom in custom_mods {
+        if custom.filename == name || filename_base(&custom.filename) == wanted {
+            matches.push(ModRef::Custom {
+                filename: custom.filename.clone(),
+                enabled: custom.is_enabled,
+            });
+        }
+    }
+    matches
+}
+
+/// Maps detected signatures to the profile's mods and settings
+pub fn diagnose(
+    profile: &Profile,
+    custom_mods: &[CustomModInfo],
+    signatures: Vec<(CrashSignature, String)>,
+) -> Vec<CrashFinding> {
+    signatures
+        .into_iter()
+        .map(|(signature, evidence)| {
+            let (summary, mods, actions) = match &signature {
+                CrashSignature::MissingDependency {
+                    mod_id,
+                    dependency,
+                    version,
+                } => {
+                    let requesters = mod_id
+                        .as_deref()
+                        .map(|id| match_mods(profile, custom_mods, id))
+                        .unwrap_or_default();
+                    let installed = match_mods(profile, custom_mods, dependency);
+                    let actions = match installed.iter().find(|m| !m.enabled()) {
+                        Some(disabled) => vec![SuggestedAction::EnableMod {
+                            target: disabled.clone(),
+                        }],
+                        None => vec![SuggestedAction::InstallDependency {
+                            mod_id: dependency.clone(),
+                            version: version.clone(),
+                        }],
+                    };
+                    (
+                        format!(
+                            "{} requires '{}'{}, which is missing",
+                            mod_id.as_deref().unwrap_or("A mod"),
+                            dependency,
+                            version
+                                .as_deref()
+                                .map(|v| format!(" ({})", v))
+                                .unwrap_or_default()
+                        ),
+                        requesters,
+                        actions,
+                    )
+                }
+                CrashSignature::MixinFailure {
+                    mod_id,
+                    mixin_config,
+                } => {
+                    let mods = mod_id
+                        .as_deref()
+                        .map(|id| match_mods(profile, custom_mods, id))
+                        .unwrap_or_default();
+                    let actions = disable_actions(&mods);
+                    (
+                        format!(
+                            "Mixin {} of {} failed to apply, the mod is likely incompatible with this version or another mod",
+                            mixin_config.as_deref().unwrap_or("?"),
+                            mod_id.as_deref().unwrap_or("an unknown mod")
+                        ),
+                        mods,
+                        actions,
+                    )
+                }
+                CrashSignature::WrongJavaVersion { required, found } => (
+                    match (required, found) {
+                        (Some(required), Some(found)) => {
+                            format!("Java {} is required but Java {} was used", required, found)
+                        }
+                        (Some(required), None) => format!("Java {} is required", required),
+                        _ => "The game was started with an unsupported Java version".to_string(),
+                    },
+                    Vec::new(),
+                    vec![SuggestedAction::SwitchJava {
+                        required_major: *required,
+                    }],
+                ),
+                CrashSignature::OutOfMemory => {
+                    let current_mb = profile.settings.memory.max;
+                    (
+                        format!("The game ran out of memory with {} MB", current_mb),
+                        Vec::new(),
+                        vec![SuggestedAction::RaiseMemory {
+                            current_mb,
+                            suggested_mb: suggested_memory_mb(current_mb),
+                        }],
+                    )
+                }
+                CrashSignature::DuplicateMod { mod_id, files } => {
+                    let mods = match_mods(profile, custom_mods, mod_id);
+                    (
+                        format!("'{}' is installed more than once", mod_id),
+                        mods,
+                        vec![SuggestedAction::RemoveDuplicates {
+                            mod_id: mod_id.clone(),
+                            files: files.clone(),
+                        }],
+                    )
+                }
+                CrashSignature::GraphicsDriver { detail } => (
+                    format!("Graphics driver or OpenGL problem: {}", detail),
+                    Vec::new(),
+                    vec![SuggestedAction::UpdateGraphicsDriver],
+                ),
+                CrashSignature::WrongLoader { file, built_for } => {
+                    let mods = match_mods(profile, custom_mods, file);
+                    let actions = disable_actions(&mods);
+                    (
+                        format!(
+                            "{} is a {} mod but the profile uses {}",
+                            file,
+                            built_for,
+                            profile.loader.as_str()
+                        ),
+                        mods,
+                        actions,
+                    )
+                }
+            };
+
+            CrashFinding {
+                signature,
+                summary,
+                evidence,
+                mods,
+                actions,
+            }
+        })
+        .collect()
+}
+
+fn disable_actions(mods: &[ModRef]) -> Vec<SuggestedAction> {
+    mods.iter()
+        .filter(|m| m.enabled())
+        .map(|m| SuggestedAction::DisableMod { target: m.clone() })
+        .collect()
+}
+
+/// At least 1.5x and 2 GB more, rounded up to 512 MB steps
+fn suggested_memory_mb(current_mb: u32) -> u32 {
+    let target = (current_mb.saturating_mul(3) / 2).max(current_mb.saturating_add(2048));
+    target.div_ceil(512).saturating_mul(512)
+}
+
+/// Last `max_bytes` of a file; only that part is read, modded logs can be hundreds of MB
+async fn read_tail(path: &Path, max_bytes: u64) -> Option<String> {
+    let mut file = fs::File::open(path).await.ok()?;
+    let len = file.metadata().await.ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes)))
+        .await
+        .ok()?;
+    let mut bytes = Vec::with_capacity(len.min(max_bytes) as usize);
+    file.take(max_bytes).read_to_end(&mut bytes).await.ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Newest file in `<instance>/crash-reports`
+async fn newest_crash_report(instance_path: &Path) -> Option<std::path::PathBuf> {
+    let mut entries = fs::read_dir(instance_path.join("crash-reports"))
+        .await
+        .ok()?;
+    let mut newest = None;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Ok(modified) = entry.metadata().await.and_then(|m| m.modified()) else {
+            continue;
+        };
+        if newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+            newest = Some((modified, entry.path()));
+        }
+    }
+    newest.map(|(_, path)| path)
+}
+
+/// Analyzes the latest crash of a profile: the given crash report content (or the newest crash
+/// report on disk) together with `logs/latest.log`.
+pub async fn analyze_profile_crash(
+    profile_id: Uuid,
+    crash_report_content: Option<&str>,
+) -> Result<CrashDiagnosis> {
+    let state = State::get().await?;
+    let profile = state.profile_manager.get_profile(profile_id).await?;
+    let instance_path = state
+        .profile_manager
+        .get_profile_instance_path(profile_id)
+        .await?;
+    let custom_mods = state
+        .profile_manager
+        .list_custom_mods(&profile)
+        .await
+        .unwrap_or_default();
+
+    let mut sources = Vec::new();
+    let mut text = String::new();
+    match crash_report_content {
+        Some(content) => {
+            sources.push("crash report".to_string());
+            text.push_str(content);
+        }
+        None => {
+            if let Some(path) = newest_crash_report(&instance_path).await {
+                if let Some(content) = read_tail(&path, MAX_LOG_BYTES).await {
+                    sources.push(path.display().to_string());
+                    text.push_str(&content);
+                }
+            }
+        }
+    }
+    let latest_log = instance_path.join("logs").join("latest.log");
+    if let Some(content) = read_tail(&latest_log, MAX_LOG_BYTES).await {
+        sources.push(latest_log.display().to_string());
+        text.push('\n');
+        text.push_str(&content);
+    }
+
+    let signatures = detect_signatures(&text);
+    debug!(
+        "Crash signatures for profile {}: {:?}",
+        profile_id, signatures
+    );
+    let findings = diagnose(&profile, &custom_mods, signatures);
+    info!(
+        "Crash analysis for profile {} found {} known problem(s)",
+        profile_id,
+        findings.len()
+    );
+
+    Ok(CrashDiagnosis {
+        profile_id,
+        sources,
+        findings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::profile_state::{ModLoader, ModSource};
+
+    fn profile_with_mods() -> Profile {
+        let mut profile: Profile = serde_json::from_value(serde_json::json!({
+            "name": "Test",
+            "path": "test",
+            "game_version": "1.20.1",
+            "loader": "fabric",
+            "loader_version": null,
+            "last_played": null,
+            "description": null,
+            "GEG_information": null
+        }))
+        .unwrap();
+        for (name, file, enabled) in [
+            ("Sodium", "sodium-fabric-0.5.3+mc1.20.1.jar", true),
+            ("Sodium Extra", "sodium-extra-0.5.1+mc1.20.1.jar", true),
+            ("Fabric API", "fabric-api-0.91.0+1.20.1.jar", false),
+        ] {
+            profile.mods.push(profile_state::Mod {
+                id: Uuid::new_v4(),
+                source: ModSource::Modrinth {
+                    project_id: name.to_string(),
+                    version_id: "v".to_string(),
+                    file_name: file.to_string(),
+                    download_url: String::new(),
+                    file_hash_sha1: None,
+                },
+                enabled,
+                display_name: Some(name.to_string()),
+                version: None,
+                game_versions: None,
+                file_name_override: None,
+                associated_loader: Some(ModLoader::Fabric),
+                modpack_origin: None,
+                updates_enabled: true,
+            });
+        }
+        profile
+    }
+
+    #[test]
+    fn test_detects_common_signatures() {
+        let log = "\
+[main/ERROR]: Incompatible mods found!
+ - Mod 'Sodium Extra' (sodium-extra) 0.5.1 requires version 0.5.0 or later of mod 'Sodium' (sodium), which is missing!
+ - Mod 'Iris' (iris) 1.6.4 requires any version of fabric-api, which is missing!
+ - Mod 'Sodium' (sodium) 0.5.3 requires version 17 or later of 'OpenJDK 64-Bit Server VM' (java), but only the wrong version is present: 8!
+Caused by: org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: Mixin [lithium.mixins.json:ai.MixinBrain from mod lithium] failed
+java.lang.OutOfMemoryError: Java heap space
+Found duplicate mods:
+\tMod ID: 'jei' from mod files: jei-1.20.1-a.jar, jei-1.20.1-b.jar
+GLFW error 65542: WGL: The driver does not appear to support OpenGL
+File mods/create-1.20.1-0.5.1.jar is a Forge mod and cannot be loaded
+";
+        let signatures: Vec<CrashSignature> =
+            detect_signatures(log).into_iter().map(|(s, _)| s).collect();
+
+        assert!(signatures.contains(&CrashSignature::MissingDependency {
+            mod_id: Some("sodium-extra".into()),
+            dependency: "sodium".into(),
+            version: Some("0.5.0 or later".into()),
+        }));
+        assert!(signatures.contains(&CrashSignature::MissingDependency {
+            mod_id: Some("iris".into()),
+            dependency: "fabric-api".into(),
+            version: None,
+        }));
+        assert!(signatures.contains(&CrashSignature::WrongJavaVersion {
+            required: Some(17),
+            found: Some(8),
+        }));
+        assert!(signatures.contains(&CrashSignature::MixinFailure {
+            mod_id: Some("lithium".into()),
+            mixin_config: Some("lithium.mixins.json".into()),
+        }));
+        assert!(signatures.contains(&CrashSignature::OutOfMemory));
+        assert!(signatures.contains(&CrashSignature::DuplicateMod {
+            mod_id: "jei".into(),
+            files: vec!["jei-1.20.1-a.jar".into(), "jei-1.20.1-b.jar".into()],
+        }));
+        assert!(signatures
+            .iter()
+            .any(|s| matches!(s, CrashSignature::GraphicsDriver { .. })));
+        assert!(signatures.contains(&CrashSignature::WrongLoader {
+            file: "create-1.20.1-0.5.1.jar".into(),
+            built_for: "forge".into(),
+        }));
+        // The java requirement is not reported as a missing mod
+        assert!(!signatures.iter().any(
+            |s| matches!(s, CrashSignature::MissingDependency { dependency, .. } if dependency == "java")
+        ));
+    }
+
+    #[test]
+    fn test_class_file_version_maps_to_java_release() {
+        let log = "java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 61.0";
+        assert_eq!(
+            detect_signatures(log)[0].0,
+            CrashSignature::WrongJavaVersion {
+                required: Some(21),
+                found: Some(17),
+            }
+        );
+    }
+
+    #[test]
+    fn test_findings_map_to_profile_mods() {
+        let profile = profile_with_mods();
+        let sodium_id = profile.mods[0].id;
+        let fabric_api_id = profile.mods[2].id;
+        let log = "\
+ - Mod 'Iris' (iris) 1.6.4 requires any version of fabric-api, which is missing!
+Mixin apply for mod sodium failed sodium.mixins.json:features.MixinChunk -> net.minecraft.class_2818
+java.lang.OutOfMemoryError: Java heap space";
+
+        let findings = diagnose(&profile, &[], detect_signatures(log));
+
+        // Fabric API is in the profile but disabled: enable it instead of installing it again
+        assert!(matches!(
+            &findings[0].actions[..],
+            [SuggestedAction::EnableMod { target: ModRef::Profile { id, .. } }] if *id == fabric_api_id
+        ));
+        // Only Sodium, not Sodium Extra
+        assert_eq!(findings[1].mods.len(), 1);
+        assert!(matches!(
+            &findings[1].actions[..],
+            [SuggestedAction::DisableMod { target: ModRef::Profile { id, .. } }] if *id == sodium_id
+        ));
+        assert_eq!(
+            findings[2].actions,
+            vec![SuggestedAction::RaiseMemory {
+                current_mb: 2048,
+                suggested_mb: 4096,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_only_the_tail_of_a_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("latest.log");
+        fs::write(&log, "early line\nfailure at the end\n")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            read_tail(&log, 19).await.as_deref(),
+            Some("failure at the end\n")
+        );
+        assert_eq!(
+            read_tail(&log, 1024).await.as_deref(),
+            Some("early line\nfailure at the end\n")
+        );
+        assert_eq!(read_tail(&dir.path().join("missing.log"), 1024).await, None);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::utils::download_utils::DownloadConfig;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A file an installed profile needs on disk, with everything known to verify and refetch it.
+/// The download services list these so offline checks, integrity checks and repairs agree on
+/// what belongs where.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectedFile {
+    pub name: String,
+    pub path: PathBuf,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    /// `None` if the file cannot be fetched on its own (e.g. loader libraries from an installer)
+    pub url: Option<String>,
+}
+
+impl ExpectedFile {
+    /// Download config that verifies everything known about the file
+    pub fn download_config(&self) -> DownloadConfig {
+        let mut config = DownloadConfig::new().with_retries(3);
+        if let Some(sha1) = &self.sha1 {
+            config = config.with_sha1(sha1.clone());
+        }
+        if let Some(size) = self.size {
+            config = config.with_size(size);
+        }
+        config
+    }
+}
@@ -1,8 +1,10 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::Result;
+use crate::minecraft::downloads::ExpectedFile;
 use crate::minecraft::dto::fabric_meta::{FabricLibrary, FabricVersionInfo};
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
-use futures::stream::StreamExt;
+use crate::state::download_state::DownloadScheduler;
+use futures::stream::{self, StreamExt};
 use log::info;
 use std::path::PathBuf;
 use tokio::fs;
@@ -10,7 +12,6 @@ use tokio::fs;
 pub struct FabricLibrariesDownloadService {
     base_path: PathBuf,
     libraries_path: PathBuf,
-    concurrent_downloads: usize,
 }
 
 impl FabricLibrariesDownloadService {
@@ -18,14 +19,9 @@ impl FabricLibrariesDownloadService {
         Self {
             base_path: LAUNCHER_DIRECTORY.meta_dir().join("fabric"),
             libraries_path: LAUNCHER_DIRECTORY.meta_dir().join("libraries"),
-            concurrent_downloads: 10, // Default value
         }
     }
 
-    pub fn set_concurrent_downloads(&mut self, count: usize) {
-        self.concurrent_downloads = count;
-    }
-
     pub fn print_version_info(&self, version: &FabricVersionInfo) {
         info!("\n=== Fabric Version ===");
         info!("Loader:");
@@ -158,13 +154,10 @@ impl FabricLibrariesDownloadService {
         }
 
         info!("Found {} components to download", all_libraries.len());
-        info!(
-            "Downloading with {} concurrent downloads",
-            self.concurrent_downloads
-        );
 
         // Create a stream of download tasks
-        let downloads = futures::stream::iter(all_libraries.into_iter())
+        let fan_out = DownloadScheduler::global_fan_out().await;
+        let downloads = stream::iter(all_libraries)
             .map(|library| {
                 let self_clone = self;
                 async move {
@@ -194,7 +187,7 @@ impl FabricLibrariesDownloadService {
                     result
                 }
             })
-            .buffer_unordered(self.concurrent_downloads);
+            .buffer_unordered(fan_out);
 
         // Execute all downloads and collect results
         let results: Vec<Result<()>> = downloads.collect().await;
@@ -343,4 +336,42 @@ impl FabricLibrariesDownloadService {
             )))
         }
     }
+
+    /// Libraries for which the launcher meta lists a SHA1 or size, so integrity checks can verify them
+    pub fn expected_library_files(&self, version: &FabricVersionInfo) -> Vec<ExpectedFile> {
+        version
+            .launcher_meta
+            .libraries
+            .common
+            .iter()
+            .chain(version.launcher_meta.libraries.client.iter())
+            .filter(|library| library.sha1.is_some() || library.size.is_some())
+            .filter_map(|library| {
+                let parts: Vec<&str> = library.name.split(':').collect();
+                if parts.len() != 3 {
+                    return None;
+                }
+                let (group, artifact, version) = (parts[0], parts[1], parts[2]);
+                let relative_path = format!(
+                    "{}/{}/{}/{}-{}.jar",
+                    group.replace('.', "/"),
+                    artifact,
+                    version,
+                    artifact,
+                    version
+                );
+                let base_url = library
+                    .url
+                    .as_deref()
+                    .unwrap_or("https://repo1.maven.org/maven2/");
+                Some(ExpectedFile {
+                    name: format!("Fabric library {}", library.name),
+                    path: self.libraries_path.join(&relative_path),
+                    sha1: library.sha1.clone(),
+                    size: library.size,
+                    url: Some(format!("{}{}", base_url, relative_path)),
+                })
+            })
+            .collect()
+    }
 }
@@ -1,4 +1,5 @@
-use crate::config::{ProjectDirsExt, HTTP_CLIENT, LAUNCHER_DIRECTORY};
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::utils::download_utils::DownloadUtils;
 use crate::error::{AppError, Result};
 use crate::minecraft::dto::forge_install_profile::ForgeInstallProfile;
 use crate::minecraft::dto::forge_meta::ForgeVersion;
@@ -50,7 +51,7 @@ impl ForgeInstallerDownloadService {
 
         // Lade die JAR herunter
         info!("Downloading from: {}", url);
-        let response = HTTP_CLIENT.get(&url).send().await.map_err(|e| {
+        let response = DownloadUtils::get_with_mirrors(&url).await.map_err(|e| {
             AppError::Download(format!("Failed to download Forge installer: {}", e))
         })?;
 
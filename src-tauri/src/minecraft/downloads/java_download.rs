@@ -108,11 +108,10 @@ impl JavaDownloadService {
         let download_url = if distribution.requires_api_response() {
             info!("Fetching actual download URL from Zulu API...");
             let client = reqwest::Client::new();
-            let response = client
-                .get(&initial_url)
-                .header("Accept", "application/json")
-                .send()
-                .await
+            let response = DownloadUtils::send_with_mirrors(&initial_url, |url| {
+                client.get(url).header("Accept", "application/json")
+            })
+            .await
                 .map_err(|e| AppError::JavaDownload(format!("Failed to fetch Zulu API: {}", e)))?;
 
             if !response.status().is_success() {
@@ -1,6 +1,8 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::Result;
 use crate::minecraft::dto::piston_meta::LoggingClient;
+use crate::minecraft::downloads::ExpectedFile;
+use crate::minecraft::offline::OfflineCheck;
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
 use log::info;
 use std::path::PathBuf;
@@ -24,9 +26,30 @@ impl MinecraftLoggingDownloadService {
         }
     }
 
+    pub fn get_logging_config_path(&self, logging: &LoggingClient) -> PathBuf {
+        self.logging_configs_path.join(&logging.file.id)
+    }
+
+    pub fn expected_logging_config(&self, logging: &LoggingClient) -> ExpectedFile {
+        ExpectedFile {
+            name: format!("Logging config {}", logging.file.id),
+            path: self.get_logging_config_path(logging),
+            sha1: Some(logging.file.sha1.clone()),
+            size: Some(logging.file.size as u64),
+            url: Some(logging.file.url.clone()),
+        }
+    }
+
+    /// Verifies (presence and SHA1) the logging config without downloading it.
+    pub async fn verify_logging_config(&self, logging: &LoggingClient, check: &mut OfflineCheck) {
+        check
+            .check_expected(&self.expected_logging_config(logging))
+            .await;
+    }
+
     pub async fn download_logging_config(&self, logging: &LoggingClient) -> Result<PathBuf> {
         let file_name = logging.file.id.clone();
-        let target_path = self.logging_configs_path.join(&file_name);
+        let target_path = self.get_logging_config_path(logging);
 
         info!("[Logging Config Download] Downloading logging config: {}", file_name);
 
@@ -0,0 +1,471 @@
+use crate::error::{AppError, Result};
+use crate::utils::download_utils::DownloadUtils;
+use log::{debug, warn};
+use serde::Deserialize;
+
+/// Dynamic versions resolved through `maven-metadata.xml`
+const VERSION_LATEST: &str = "latest";
+const VERSION_RELEASE: &str = "release";
+const SNAPSHOT_SUFFIX: &str = "-SNAPSHOT";
+
+/// Maven coordinates in the form `group:artifact:version[:classifier][@extension]`,
+/// e.g. `net.fabricmc.fabric-api:fabric-api:0.91.0+1.20.1`.
+/// The version may be `latest` or `release` to follow the repository metadata.
+/// Version ranges (`[1.0,2.0)`) are not supported, there is no file to download for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MavenCoordinates {
+    pub group_id: String,
+    pub artifact_id: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    pub extension: String,
+}
+
+impl MavenCoordinates {
+    pub fn parse(coordinates: &str) -> Result<Self> {
+        let (coordinates, extension) = match coordinates.split_once('@') {
+            Some((coordinates, extension)) => (coordinates, extension.to_string()),
+            None => (coordinates, "jar".to_string()),
+        };
+
+        let parts: Vec<&str> = coordinates.split(':').collect();
+        if !(3..=4).contains(&parts.len())
+            || parts.iter().any(|p| p.is_empty())
+            || extension.is_empty()
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid Maven coordinates '{}', expected group:artifact:version[:classifier][@extension]",
+                coordinates
+            )));
+        }
+
+        if parts[2].starts_with(['[', '(']) {
+            return Err(AppError::InvalidInput(format!(
+                "Maven version ranges are not supported in '{}', use a concrete version, latest or release",
+                coordinates
+            )));
+        }
+
+        Ok(Self {
+            group_id: parts[0].to_string(),
+            artifact_id: parts[1].to_string(),
+            version: parts[2].to_string(),
+            classifier: parts.get(3).map(|c| c.to_string()),
+            extension,
+        })
+    }
+
+    /// True if the version has to be looked up in `maven-metadata.xml`
+    pub fn is_dynamic(&self) -> bool {
+        self.version == VERSION_LATEST || self.version == VERSION_RELEASE
+    }
+
+    /// True if the artifact behind the version may change: dynamic or `-SNAPSHOT` versions.
+    /// Pinned release versions never change, so a cached file can be used as-is.
+    pub fn is_changing(&self) -> bool {
+        self.is_dynamic() || self.version.ends_with(SNAPSHOT_SUFFIX)
+    }
+
+    /// Filename of the artifact for the version as written in the coordinates.
+    /// Dynamic versions keep a stable name (`fabric-api-latest.jar`), so the cached file is
+    /// replaced in place when the repository publishes a new version.
+    pub fn file_name(&self) -> String {
+        self.file_name_for(&self.version)
+    }
+
+    /// Name in the shared mod cache. Prefixed with the group, artifacts of different groups
+    /// may have the same name and version.
+    pub fn cache_file_name(&self) -> String {
+        format!("{}.{}", self.group_id, self.file_name())
+    }
+
+    fn file_name_for(&self, version: &str) -> String {
+        match &self.classifier {
+            Some(classifier) => format!(
+                "{}-{}-{}.{}",
+                self.artifact_id, version, classifier, self.extension
+            ),
+            None => format!("{}-{}.{}", self.artifact_id, version, self.extension),
+        }
+    }
+
+    fn artifact_dir_url(&self, repository_url: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            repository_url.trim_end_matches('/'),
+            self.group_id.replace('.', "/"),
+            self.artifact_id
+        )
+    }
+
+    /// Download URL of the artifact for a concrete, non-snapshot version
+    pub fn artifact_url(&self, repository_url: &str, version: &str) -> String {
+        self.artifact_url_for(repository_url, version, version)
+    }
+
+    /// Download URL of the file `file_version` in the folder of `version`. They differ for
+    /// snapshots, which are stored under timestamped names (`1.3.0-20240101.120000-3`).
+    fn artifact_url_for(&self, repository_url: &str, version: &str, file_version: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.artifact_dir_url(repository_url),
+            version,
+            self.file_name_for(file_version)
+        )
+    }
+
+    /// Resolves the version and returns it together with the download URL of the artifact.
+    /// `-SNAPSHOT` versions are looked up in the version's `maven-metadata.xml`; repositories
+    /// without one store the snapshot under its plain name.
+    pub async fn resolve_artifact_url(&self, repository_url: &str) -> Result<(String, String)> {
+        let version = self.resolve_version(repository_url).await?;
+        if !version.ends_with(SNAPSHOT_SUFFIX) {
+            let url = self.artifact_url(repository_url, &version);
+            return Ok((version, url));
+        }
+
+        let metadata_url = format!(
+            "{}/{}/maven-metadata.xml",
+            self.artifact_dir_url(repository_url),
+            version
+        );
+        let file_version = match fetch_metadata(&metadata_url).await? {
+            Some(xml) => MavenMetadata::parse(&xml)?
+                .snapshot_file_version(self.classifier.as_deref(), &self.extension),
+            None => None,
+        };
+        let file_version = file_version.unwrap_or_else(|| {
+            debug!(
+                "No timestamped snapshot listed in {}, using the plain file name",
+                metadata_url
+            );
+            version.clone()
+        });
+        let url = self.artifact_url_for(repository_url, &version, &file_version);
+        Ok((version, url))
+    }
+
+    /// Returns the concrete version, looking up `latest`/`release` in the repository metadata
+    pub async fn resolve_version(&self, repository_url: &str) -> Result<String> {
+        if !self.is_dynamic() {
+            return Ok(self.version.clone());
+        }
+
+        let metadata_url = format!(
+            "{}/maven-metadata.xml",
+            self.artifact_dir_url(repository_url)
+        );
+        debug!(
+            "Resolving Maven version '{}' from {}",
+            self.version, metadata_url
+        );
+
+        let xml = fetch_metadata(&metadata_url).await?.ok_or_else(|| {
+            AppError::Download(format!("Failed to fetch {}: not found", metadata_url))
+        })?;
+        MavenMetadata::parse(&xml)?
+            .pick(&self.version)
+            .ok_or_else(|| {
+                AppError::Download(format!(
+                    "{} lists no '{}' version for {}:{}",
+                    metadata_url, self.version, self.group_id, self.artifact_id
+                ))
+            })
+    }
+}
+
+/// Fetches a `maven-metadata.xml`, `None` if the repository has none at `url`
+async fn fetch_metadata(url: &str) -> Result<Option<String>> {
+    let response = DownloadUtils::get_with_mirrors(url)
+        .await
+        .map_err(|e| AppError::Download(format!("Failed to fetch {}: {}", url, e)))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(AppError::Download(format!(
+            "Failed to fetch {}: Status {}",
+            url,
+            response.status()
+        )));
+    }
+    response
+        .text()
+        .await
+        .map(Some)
+        .map_err(|e| AppError::Download(format!("Failed to read {}: {}", url, e)))
+}
+
+/// The parts of `maven-metadata.xml` needed to resolve dynamic versions and snapshot files
+#[derive(Debug, Deserialize)]
+struct MavenMetadata {
+    versioning: Option<MavenVersioning>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenVersioning {
+    latest: Option<String>,
+    release: Option<String>,
+    versions: Option<MavenVersions>,
+    #[serde(rename = "snapshotVersions")]
+    snapshot_versions: Option<MavenSnapshotVersions>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenVersions {
+    #[serde(default)]
+    version: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenSnapshotVersions {
+    #[serde(default, rename = "snapshotVersion")]
+    snapshot_version: Vec<MavenSnapshotVersion>,
+}
+
+/// One file of a snapshot, `value` is the timestamped version in its file name
+#[derive(Debug, Deserialize)]
+struct MavenSnapshotVersion {
+    classifier: Option<String>,
+    extension: String,
+    value: String,
+}
+
+impl MavenMetadata {
+    fn parse(xml: &str) -> Result<Self> {
+        quick_xml::de::from_str(xml)
+            .map_err(|e| AppError::Other(format!("Invalid maven-metadata.xml: {}", e)))
+    }
+
+    /// `<latest>`/`<release>` if present, otherwise the last listed version (metadata is oldest first)
+    fn pick(self, dynamic_version: &str) -> Option<String> {
+        let versioning = self.versioning?;
+        let explicit = if dynamic_version == VERSION_RELEASE {
+            versioning.release
+        } else {
+            versioning.latest
+        };
+        explicit
+            .filter(|v| !v.trim().is_empty())
+            .or_else(|| versioning.versions.and_then(|v| v.version.last().cloned()))
+    }
+
+    /// Timestamped version of the snapshot file with this classifier and extension
+    fn snapshot_file_version(self, classifier: Option<&str>, extension: &str) -> Option<String> {
+        self.versioning?
+            .snapshot_versions?
+            .snapshot_version
+            .into_iter()
+            .find(|file| {
+                file.classifier.as_deref().filter(|c| !c.is_empty()) == classifier
+                    && file.extension == extension
+            })
+            .map(|file| file.value)
+    }
+}
+
+/// Fetches the `.sha1` sidecar Maven repositories publish next to each file.
+/// Returns `None` if the repository has none, the file is then downloaded unverified.
+pub async fn fetch_sha1_sidecar(artifact_url: &str) -> Option<String> {
+    let sidecar_url = format!("{}.sha1", artifact_url);
+    let response = match DownloadUtils::get_with_mirrors(&sidecar_url).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            debug!("No SHA1 sidecar at {} ({})", sidecar_url, response.status());
+            return None;
+        }
+        Err(e) => {
+            warn!("Failed to fetch SHA1 sidecar {}: {}", sidecar_url, e);
+            return None;
+        }
+    };
+
+    let body = response.text().await.ok()?;
+    let sha1 = parse_sha1_sidecar(&body);
+    if sha1.is_none() {
+        warn!("Ignoring malformed SHA1 sidecar {}", sidecar_url);
+    }
+    sha1
+}
+
+/// Sidecars contain the hash, sometimes followed by the filename (`sha1sum` output)
+fn parse_sha1_sidecar(body: &str) -> Option<String> {
+    body.split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|hash| hash.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_coordinates_and_urls() {
+        let plain =
+            MavenCoordinates::parse("net.fabricmc.fabric-api:fabric-api:0.91.0+1.20.1").unwrap();
+        assert_eq!(plain.file_name(), "fabric-api-0.91.0+1.20.1.jar");
+        assert_eq!(
+            plain.cache_file_name(),
+            "net.fabricmc.fabric-api.fabric-api-0.91.0+1.20.1.jar"
+        );
+        assert_eq!(
+            plain.artifact_url("https://maven.fabricmc.net/", &plain.version),
+            "https://maven.fabricmc.net/net/fabricmc/fabric-api/fabric-api/0.91.0+1.20.1/fabric-api-0.91.0+1.20.1.jar"
+        );
+
+        let classified =
+            MavenCoordinates::parse("gg.example:client-mod:latest:fabric@zip").unwrap();
+        assert!(classified.is_dynamic());
+        assert_eq!(classified.file_name(), "client-mod-latest-fabric.zip");
+        assert_eq!(
+            classified.artifact_url("https://maven.example.gg", "1.2.0"),
+            "https://maven.example.gg/gg/example/client-mod/1.2.0/client-mod-1.2.0-fabric.zip"
+        );
+
+        assert!(matches!(
+            MavenCoordinates::parse("gg.example:client-mod"),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            MavenCoordinates::parse("gg.example::1.0"),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_metadata_resolves_latest_and_release() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<metadata>
+  <groupId>gg.example</groupId>
+  <artifactId>client-mod</artifactId>
+  <versioning>
+    <latest>1.3.0-SNAPSHOT</latest>
+    <release>1.2.0</release>
+    <versions>
+      <version>1.1.0</version>
+      <version>1.2.0</version>
+      <version>1.3.0-SNAPSHOT</version>
+    </versions>
+    <lastUpdated>20240101000000</lastUpdated>
+  </versioning>
+</metadata>"#;
+        assert_eq!(
+            MavenMetadata::parse(xml)
+                .unwrap()
+                .pick(VERSION_LATEST)
+                .as_deref(),
+            Some("1.3.0-SNAPSHOT")
+        );
+        assert_eq!(
+            MavenMetadata::parse(xml)
+                .unwrap()
+                .pick(VERSION_RELEASE)
+                .as_deref(),
+            Some("1.2.0")
+        );
+
+        let without_release = r#"<metadata><versioning><versions>
+            <version>1.0</version><version>1.1</version>
+        </versions></versioning></metadata>"#;
+        assert_eq!(
+            MavenMetadata::parse(without_release)
+                .unwrap()
+                .pick(VERSION_RELEASE)
+                .as_deref(),
+            Some("1.1")
+        );
+    }
+
+    #[test]
+    fn test_snapshot_files_use_timestamped_names() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<metadata modelVersion="1.1.0">
+  <groupId>gg.example</groupId>
+  <artifactId>client-mod</artifactId>
+  <version>1.3.0-SNAPSHOT</version>
+  <versioning>
+    <snapshot>
+      <timestamp>20240101.120000</timestamp>
+      <buildNumber>3</buildNumber>
+    </snapshot>
+    <lastUpdated>20240101120000</lastUpdated>
+    <snapshotVersions>
+      <snapshotVersion>
+        <extension>jar</extension>
+        <value>1.3.0-20240101.120000-3</value>
+        <updated>20240101120000</updated>
+      </snapshotVersion>
+      <snapshotVersion>
+        <classifier>fabric</classifier>
+        <extension>jar</extension>
+        <value>1.3.0-20240101.115500-2</value>
+        <updated>20240101115500</updated>
+      </snapshotVersion>
+      <snapshotVersion>
+        <extension>pom</extension>
+        <value>1.3.0-20240101.120000-3</value>
+        <updated>20240101120000</updated>
+      </snapshotVersion>
+    </snapshotVersions>
+  </versioning>
+</metadata>"#;
+        assert_eq!(
+            MavenMetadata::parse(xml)
+                .unwrap()
+                .snapshot_file_version(None, "jar")
+                .as_deref(),
+            Some("1.3.0-20240101.120000-3")
+        );
+        assert_eq!(
+            MavenMetadata::parse(xml)
+                .unwrap()
+                .snapshot_file_version(Some("fabric"), "jar")
+                .as_deref(),
+            Some("1.3.0-20240101.115500-2")
+        );
+        assert_eq!(
+            MavenMetadata::parse(xml)
+                .unwrap()
+                .snapshot_file_version(None, "zip"),
+            None
+        );
+
+        let coordinates = MavenCoordinates::parse("gg.example:client-mod:latest").unwrap();
+        assert_eq!(
+            coordinates.artifact_url_for(
+                "https://maven.example.gg",
+                "1.3.0-SNAPSHOT",
+                "1.3.0-20240101.120000-3"
+            ),
+            "https://maven.example.gg/gg/example/client-mod/1.3.0-SNAPSHOT/client-mod-1.3.0-20240101.120000-3.jar"
+        );
+    }
+
+    #[test]
+    fn test_only_pinned_release_versions_are_stable() {
+        let version =
+            |v: &str| MavenCoordinates::parse(&format!("gg.example:client-mod:{}", v)).unwrap();
+        assert!(!version("1.2.0").is_changing());
+        assert!(!version("0.91.0+1.20.1").is_changing());
+        assert!(version("latest").is_changing());
+        assert!(version("release").is_changing());
+        assert!(version("1.3.0-SNAPSHOT").is_changing());
+        assert!(matches!(
+            MavenCoordinates::parse("gg.example:client-mod:[1.0,2.0)"),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_sha1_sidecar() {
+        let hash = "a9993e364706816aba3e25717850c26c9cd0d89d";
+        assert_eq!(parse_sha1_sidecar(hash).as_deref(), Some(hash));
+        assert_eq!(
+            parse_sha1_sidecar(&format!("{}  client-mod-1.2.0.jar\n", hash.to_uppercase()))
+                .as_deref(),
+            Some(hash)
+        );
+        assert_eq!(parse_sha1_sidecar("<html>Not Found</html>"), None);
+    }
+}
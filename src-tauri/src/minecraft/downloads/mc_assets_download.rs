@@ -1,11 +1,14 @@
 use crate::config::{ProjectDirsExt, HTTP_CLIENT, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
 use crate::minecraft::dto::piston_meta::{AssetIndex, AssetIndexContent, AssetObject};
+use crate::minecraft::downloads::ExpectedFile;
+use crate::minecraft::offline::OfflineCheck;
 use crate::state::event_state::{EventPayload, EventType};
 use crate::state::State;
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
 use crate::utils::mc_utils;
-use futures::stream::{iter, StreamExt};
+use crate::state::download_state::DownloadScheduler;
+use futures::stream::{self, StreamExt};
 use log::{debug, error, info, trace, warn};
 use reqwest;
 use std::path::PathBuf;
@@ -15,12 +18,12 @@ use tokio::fs;
 use uuid::Uuid;
 
 const ASSETS_DIR: &str = "assets";
-const DEFAULT_CONCURRENT_DOWNLOADS: usize = 12;
+/// Above this many missing assets, an offline check reports a summary instead of every name
+const MAX_LISTED_MISSING_ASSETS: usize = 10;
 // concurrent_assets is not used in this implementation
 
 pub struct MinecraftAssetsDownloadService {
     assets_path: PathBuf,
-    concurrent_downloads: usize,
 }
 
 impl MinecraftAssetsDownloadService {
@@ -32,16 +35,9 @@ impl MinecraftAssetsDownloadService {
         );
         Self {
             assets_path,
-            concurrent_downloads: DEFAULT_CONCURRENT_DOWNLOADS,
         }
     }
 
-    /// Sets the number of concurrent downloads to use
-    pub fn with_concurrent_downloads(mut self, concurrent_downloads: usize) -> Self {
-        self.concurrent_downloads = concurrent_downloads;
-        self
-    }
-
     /// Download Minecraft assets with progress events
     pub async fn download_assets_with_progress(
         &self,
@@ -210,8 +206,8 @@ impl MinecraftAssetsDownloadService {
         }
 
         info!(
-            "[Assets Download] Processing tasks with {} concurrent downloads...",
-            self.concurrent_downloads
+            "[Assets Download] Processing {} tasks through the download scheduler...",
+            job_count
         );
 
         // Create progress tracking event
@@ -226,8 +222,9 @@ impl MinecraftAssetsDownloadService {
             .await?;
         }
 
-        let results: Vec<Result<()>> = iter(downloads)
-            .buffer_unordered(self.concurrent_downloads)
+        let fan_out = DownloadScheduler::global_fan_out().await;
+        let results: Vec<Result<()>> = stream::iter(downloads)
+            .buffer_unordered(fan_out)
             .inspect(|_| {
                 // Update progress after each download completes
                 let completed = completed_counter.fetch_add(0, Ordering::SeqCst); // Just read current value
@@ -316,6 +313,100 @@ impl MinecraftAssetsDownloadService {
             .await
     }
 
+    pub fn expected_asset_index(&self, asset_index: &AssetIndex) -> ExpectedFile {
+        ExpectedFile {
+            name: format!("Asset index {}", asset_index.id),
+            path: self
+                .assets_path
+                .join("indexes")
+                .join(format!("{}.json", asset_index.id)),
+            sha1: Some(asset_index.sha1.clone()),
+            size: Some(asset_index.size as u64),
+            url: Some(asset_index.url.clone()),
+        }
+    }
+
+    /// Objects listed in an asset index; they are stored under their SHA1
+    pub fn expected_asset_objects(&self, index: &AssetIndexContent) -> Vec<ExpectedFile> {
+        index
+            .objects
+            .iter()
+            .map(|(name, object)| ExpectedFile {
+                name: format!("Asset {}", name),
+                path: self.asset_object_path(&object.hash),
+                sha1: Some(object.hash.clone()),
+                size: Some(object.size as u64),
+                url: Some(format!(
+                    "https://resources.download.minecraft.net/{}/{}",
+                    &object.hash[..2],
+                    object.hash
+                )),
+            })
+            .collect()
+    }
+
+    /// Reads an already downloaded asset index
+    pub async fn read_local_asset_index(&self, asset_index: &AssetIndex) -> Result<AssetIndexContent> {
+        let content = fs::read(self.expected_asset_index(asset_index).path).await?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    fn asset_object_path(&self, hash: &str) -> PathBuf {
+        self.assets_path.join("objects").join(&hash[..2]).join(hash)
+    }
+
+    /// Verifies the asset index (SHA1) and its objects without downloading anything.
+    /// Objects are named after their hash, so only presence and size are checked for them.
+    pub async fn verify_assets(&self, asset_index: &AssetIndex, check: &mut OfflineCheck) {
+        let index_file = self.expected_asset_index(asset_index);
+        if !check.check_expected(&index_file).await {
+            return;
+        }
+
+        let index = match self.read_local_asset_index(asset_index).await {
+            Ok(index) => index,
+            Err(e) => {
+                check.add_missing(format!(
+                    "{} ({}): {}",
+                    index_file.name,
+                    index_file.path.display(),
+                    e
+                ));
+                return;
+            }
+        };
+
+        let mut missing_objects: Vec<String> = Vec::new();
+        for (name, object) in &index.objects {
+            let object_path = self.asset_object_path(&object.hash);
+            let valid = fs::metadata(&object_path)
+                .await
+                .map(|m| m.len() == object.size as u64)
+                .unwrap_or(false);
+            if !valid {
+                missing_objects.push(name.clone());
+            }
+        }
+
+        if missing_objects.is_empty() {
+            return;
+        }
+        missing_objects.sort();
+        if missing_objects.len() <= MAX_LISTED_MISSING_ASSETS {
+            for name in missing_objects {
+                check.add_missing(format!("Asset {} (index {})", name, asset_index.id));
+            }
+        } else {
+            check.add_missing(format!(
+                "{} of {} assets from index {} (e.g. {})",
+                missing_objects.len(),
+                index.objects.len(),
+                asset_index.id,
+                missing_objects[..MAX_LISTED_MISSING_ASSETS].join(", ")
+            ));
+        }
+    }
+
     async fn download_asset_index(&self, asset_index: &AssetIndex) -> Result<AssetIndexContent> {
         let index_path = self
             .assets_path
@@ -1,6 +1,8 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::Result;
 use crate::minecraft::dto::piston_meta::DownloadInfo;
+use crate::minecraft::downloads::ExpectedFile;
+use crate::minecraft::offline::OfflineCheck;
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
 use log::info;
 use std::path::PathBuf;
@@ -41,4 +43,29 @@ impl MinecraftClientDownloadService {
         info!("Downloaded client jar to: {}", target_path.display());
         Ok(())
     }
+
+    pub fn expected_client(&self, client_info: &DownloadInfo, version_id: &str) -> ExpectedFile {
+        ExpectedFile {
+            name: format!("Minecraft client {}", version_id),
+            path: self
+                .base_path
+                .join(version_id)
+                .join(format!("{}.jar", version_id)),
+            sha1: Some(client_info.sha1.clone()),
+            size: Some(client_info.size as u64),
+            url: Some(client_info.url.clone()),
+        }
+    }
+
+    /// Verifies (presence and SHA1) the client jar without downloading it.
+    pub async fn verify_client(
+        &self,
+        client_info: &DownloadInfo,
+        version_id: &str,
+        check: &mut OfflineCheck,
+    ) {
+        check
+            .check_expected(&self.expected_client(client_info, version_id))
+            .await;
+    }
 }
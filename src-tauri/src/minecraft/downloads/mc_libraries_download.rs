@@ -1,17 +1,19 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::Result;
 use crate::minecraft::dto::piston_meta::{DownloadInfo, Library};
+use crate::minecraft::launch::rules::RuleProcessor;
+use crate::minecraft::downloads::ExpectedFile;
+use crate::minecraft::offline::OfflineCheck;
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
-use futures::stream::{iter, StreamExt};
+use crate::state::download_state::DownloadScheduler;
+use futures::stream::{self, StreamExt};
 use std::path::PathBuf;
 
 const LIBRARIES_DIR: &str = "libraries";
-const DEFAULT_CONCURRENT_DOWNLOADS: usize = 12;
 const DEFAULT_CONCURRENT_LIBRARIES: usize = 12;
 
 pub struct MinecraftLibrariesDownloadService {
     base_path: PathBuf,
-    concurrent_downloads: usize,
     concurrent_libraries: usize,
 }
 
@@ -20,16 +22,10 @@ impl MinecraftLibrariesDownloadService {
         let base_path = LAUNCHER_DIRECTORY.meta_dir().join(LIBRARIES_DIR);
         Self {
             base_path,
-            concurrent_downloads: DEFAULT_CONCURRENT_DOWNLOADS,
             concurrent_libraries: DEFAULT_CONCURRENT_LIBRARIES,
         }
     }
 
-    pub fn with_concurrent_downloads(mut self, concurrent_downloads: usize) -> Self {
-        self.concurrent_downloads = concurrent_downloads;
-        self
-    }
-
     pub async fn download_libraries(&self, libraries: &[Library]) -> Result<()> {
         let futures = libraries.iter().map(|library| {
             let self_clone = self;
@@ -57,8 +53,9 @@ impl MinecraftLibrariesDownloadService {
             }
         }
 
-        let results: Vec<Result<()>> = iter(downloads)
-            .buffer_unordered(self.concurrent_downloads)
+        let fan_out = DownloadScheduler::global_fan_out().await;
+        let results: Vec<Result<()>> = stream::iter(downloads)
+            .buffer_unordered(fan_out)
             .collect()
             .await;
 
@@ -81,6 +78,58 @@ impl MinecraftLibrariesDownloadService {
         DownloadUtils::download_file(&download_info.url, &target_path, config).await
     }
 
+    /// Library jars the current OS needs (natives excluded, see `expected_natives`)
+    pub fn expected_libraries(&self, libraries: &[Library]) -> Vec<ExpectedFile> {
+        libraries
+            .iter()
+            .filter(|library| RuleProcessor::should_include_library(&library.rules))
+            .filter_map(|library| {
+                let artifact = library.downloads.artifact.as_ref()?;
+                Some(self.expected_file(format!("Library {}", library.name), artifact))
+            })
+            .collect()
+    }
+
+    /// Native classifier jars of the libraries the current OS needs
+    pub fn expected_natives(&self, libraries: &[Library]) -> Vec<ExpectedFile> {
+        libraries
+            .iter()
+            .filter(|library| RuleProcessor::should_include_library(&library.rules))
+            .flat_map(|library| {
+                library
+                    .downloads
+                    .classifiers
+                    .iter()
+                    .flatten()
+                    .map(move |(classifier, download_info)| {
+                        self.expected_file(
+                            format!("Library {} ({})", library.name, classifier),
+                            download_info,
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    fn expected_file(&self, name: String, download_info: &DownloadInfo) -> ExpectedFile {
+        ExpectedFile {
+            name,
+            path: self.get_library_path(download_info),
+            sha1: Some(download_info.sha1.clone()),
+            size: Some(download_info.size as u64),
+            url: Some(download_info.url.clone()),
+        }
+    }
+
+    /// Verifies (presence and SHA1) the libraries the current OS needs without downloading anything.
+    pub async fn verify_libraries(&self, libraries: &[Library], check: &mut OfflineCheck) {
+        let mut expected = self.expected_libraries(libraries);
+        expected.extend(self.expected_natives(libraries));
+        for file in &expected {
+            check.check_expected(file).await;
+        }
+    }
+
     fn get_library_path(&self, download_info: &DownloadInfo) -> PathBuf {
         let url = &download_info.url;
         let path = url
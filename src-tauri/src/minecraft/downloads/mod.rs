@@ -1,8 +1,10 @@
+pub mod expected_file;
 pub mod fabric_libraries_download;
 pub mod forge_installer_download;
 pub mod forge_libraries_download;
 pub mod java_download;
 pub mod logging_config_download;
+pub mod maven_artifact;
 pub mod mc_assets_download;
 pub mod mc_client_download;
 pub mod mc_libraries_download;
@@ -15,6 +17,7 @@ pub mod norisk_assets_download;
 pub mod norisk_pack_downloader;
 pub mod quilt_libraries_download;
 
+pub use expected_file::ExpectedFile;
 pub use forge_installer_download::*;
 pub use forge_libraries_download::*;
 pub use logging_config_download::MinecraftLoggingDownloadService;
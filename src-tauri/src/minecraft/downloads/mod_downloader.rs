@@ -1,42 +1,39 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
+use crate::minecraft::downloads::maven_artifact::{self, MavenCoordinates};
 use crate::minecraft::downloads::mod_resolver::TargetMod;
+use crate::minecraft::downloads::ExpectedFile;
+use crate::minecraft::offline::OfflineCheck;
+use crate::utils::content_store::ContentStore;
 use crate::state::profile_state::{self, ModSource, Profile};
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
-use futures::stream::{iter, StreamExt};
+use crate::utils::jar_metadata::{self, JarModMetadata};
+use crate::state::download_state::DownloadScheduler;
+use futures::stream::{self, StreamExt};
 use log::{debug, error, info, warn};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use tokio::fs::{self, read_dir};
 use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
 
-const DEFAULT_CONCURRENT_MOD_DOWNLOADS: usize = 4;
-const MOD_CACHE_DIR_NAME: &str = "mod_cache";
+pub const MOD_CACHE_DIR_NAME: &str = "mod_cache";
 
-pub struct ModDownloadService {
-    concurrent_downloads: usize,
-}
+pub struct ModDownloadService;
 
 impl ModDownloadService {
     pub fn new() -> Self {
-        Self {
-            concurrent_downloads: DEFAULT_CONCURRENT_MOD_DOWNLOADS,
-        }
-    }
-
-    pub fn with_concurrency(concurrent_downloads: usize) -> Self {
-        Self {
-            concurrent_downloads,
-        }
+        Self
     }
 
     /// Downloads all enabled mods into the central mod cache.
     /// Creates the cache directory if it doesn't exist.
-    /// Verifies SHA1 hashes for Modrinth downloads if available.
+    /// Verifies SHA1 hashes if available: pinned for Modrinth, CurseForge and URL mods,
+    /// from the `.sha1` sidecar for Maven mods that are not cached yet or may have changed.
     pub async fn download_mods_to_cache(&self, profile: &Profile) -> Result<()> {
         info!(
-            "Checking/Downloading mods to cache for profile: '{}' (Concurrency: {})",
-            profile.name, self.concurrent_downloads
+            "Checking/Downloading mods to cache for profile: '{}'",
+            profile.name
         );
 
         let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
@@ -57,7 +54,7 @@ impl ModDownloadService {
             let cache_dir_clone = mod_cache_dir.clone();
             let source_clone = mod_info.source.clone();
 
-            let filename_result = profile_state::get_profile_mod_filename(&mod_info.source);
+            let filename_result = profile_state::get_mod_cache_filename(&mod_info.source);
 
             download_futures.push(async move {
                 let filename = match filename_result {
@@ -88,6 +85,7 @@ impl ModDownloadService {
                             &download_url,
                             &target_path,
                             file_hash_sha1.as_deref(),
+                            false,
                         )
                         .await
                         .map_err(|e| {
@@ -108,6 +106,7 @@ impl ModDownloadService {
                             &download_url,
                             &target_path,
                             file_hash_sha1.as_deref(),
+                            false,
                         )
                         .await
                         .map_err(|e| {
@@ -115,44 +114,60 @@ impl ModDownloadService {
                             e
                         })
                     }
-                    ModSource::Url { url, file_name, .. } => {
-                        let fname = file_name.as_deref().unwrap_or("unknown");
-                        debug!(
-                            "Skipping URL mod source (cache): {} from {}",
-                            display_name_opt.as_deref().unwrap_or(fname),
-                            url
+                    ModSource::Url {
+                        url,
+                        file_hash_sha1,
+                        ..
+                    } => {
+                        info!(
+                            "Preparing URL mod for cache: {} ({}) from {}",
+                            display_name, filename, url
                         );
-                        Ok(())
+                        if file_hash_sha1.is_none() {
+                            debug!("URL mod {} has no pinned SHA1, not verifying", display_name);
+                        }
+                        Self::download_and_verify_file(
+                            &url,
+                            &target_path,
+                            file_hash_sha1.as_deref(),
+                            false,
+                        )
+                        .await
+                        .map_err(|e| {
+                            error!("Failed cache mod {}: {}", display_name, e);
+                            e
+                        })
                     }
                     ModSource::Local { file_name } => {
                         debug!("Skipping local mod (cache check): {}", file_name);
                         Ok(())
                     }
-                    ModSource::Maven { .. } => {
-                        warn!(
-                            "Skipping Maven mod source (cache check - not implemented): {:?}",
-                            display_name_opt
-                        );
-                        Ok(())
-                    }
+                    ModSource::Maven {
+                        coordinates,
+                        repository_url,
+                    } => Self::download_maven_mod(
+                        &coordinates,
+                        repository_url.as_deref(),
+                        &target_path,
+                        display_name,
+                    )
+                    .await
+                    .map_err(|e| {
+                        error!("Failed cache mod {}: {}", display_name, e);
+                        e
+                    }),
                     ModSource::Embedded { name } => {
                         debug!("Skipping embedded mod (cache check): {}", name);
                         Ok(())
                     }
-                    _ => {
-                        debug!(
-                            "Skipping non-downloadable mod source type after filename check: {}",
-                            display_name
-                        );
-                        Ok(())
-                    }
                 }
             });
         }
 
         info!("Executing {} mod cache tasks...", download_futures.len());
-        let results: Vec<Result<()>> = iter(download_futures)
-            .buffer_unordered(self.concurrent_downloads)
+        let fan_out = DownloadScheduler::global_fan_out().await;
+        let results: Vec<Result<()>> = stream::iter(download_futures)
+            .buffer_unordered(fan_out)
             .collect()
             .await;
 
@@ -179,6 +194,115 @@ impl ModDownloadService {
         }
     }
 
+    /// Enabled downloadable mods with a known SHA1, as they should lie in the mod cache
+    /// Metadata of the cached jars of the profile's URL and Maven mods, by mod ID.
+    /// These mods have no platform metadata, their jar is the only source of name and version.
+    pub async fn cached_jar_metadata(&self, profile: &Profile) -> Vec<(Uuid, JarModMetadata)> {
+        let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+        let mut found = Vec::new();
+        for mod_info in &profile.mods {
+            if !matches!(
+                mod_info.source,
+                ModSource::Url { .. } | ModSource::Maven { .. }
+            ) {
+                continue;
+            }
+            let Ok(filename) = profile_state::get_mod_cache_filename(&mod_info.source) else {
+                continue;
+            };
+            let path = mod_cache_dir.join(filename);
+            if !path.is_file() {
+                continue;
+            }
+            match jar_metadata::inspect_jar(&path).await {
+                Ok(metadata) => {
+                    if let Some(jar_mod) = metadata.mods.into_iter().next() {
+                        found.push((mod_info.id, jar_mod));
+                    }
+                }
+                Err(e) => debug!("Could not read metadata of {:?}: {}", path, e),
+            }
+        }
+        found
+    }
+
+    pub fn expected_mods(&self, profile: &Profile) -> Vec<ExpectedFile> {
+        let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+
+        profile
+            .mods
+            .iter()
+            .filter(|m| m.enabled)
+            .filter_map(|mod_info| {
+                let (download_url, sha1) = match &mod_info.source {
+                    ModSource::Modrinth {
+                        download_url,
+                        file_hash_sha1: Some(sha1),
+                        ..
+                    }
+                    | ModSource::CurseForge {
+                        download_url,
+                        file_hash_sha1: Some(sha1),
+                        ..
+                    }
+                    | ModSource::Url {
+                        url: download_url,
+                        file_hash_sha1: Some(sha1),
+                        ..
+                    } => (download_url, sha1),
+                    _ => return None,
+                };
+                let filename = profile_state::get_mod_cache_filename(&mod_info.source).ok()?;
+                Some(ExpectedFile {
+                    name: format!(
+                        "Mod {}",
+                        mod_info.display_name.as_deref().unwrap_or(&filename)
+                    ),
+                    path: mod_cache_dir.join(&filename),
+                    sha1: Some(sha1.clone()),
+                    size: None,
+                    url: Some(download_url.clone()),
+                })
+            })
+            .collect()
+    }
+
+    /// Offline counterpart of `download_mods_to_cache`: verifies that every enabled, downloadable
+    /// mod is already in the cache (SHA1 checked if known) without downloading anything.
+    pub async fn verify_mods_in_cache(&self, profile: &Profile, check: &mut OfflineCheck) {
+        let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+
+        for mod_info in profile.mods.iter().filter(|m| m.enabled) {
+            let file_hash_sha1 = match &mod_info.source {
+                ModSource::Modrinth { file_hash_sha1, .. }
+                | ModSource::CurseForge { file_hash_sha1, .. }
+                | ModSource::Url { file_hash_sha1, .. } => file_hash_sha1.as_deref(),
+                // Sidecar hashes need the repository, presence is all that can be checked offline
+                ModSource::Maven { .. } => None,
+                // Not placed in the cache by download_mods_to_cache either
+                _ => continue,
+            };
+
+            match profile_state::get_mod_cache_filename(&mod_info.source) {
+                Ok(filename) => {
+                    let display_name = mod_info.display_name.as_deref().unwrap_or(&filename);
+                    check
+                        .check_file(
+                            &format!("Mod {}", display_name),
+                            &mod_cache_dir.join(&filename),
+                            file_hash_sha1,
+                        )
+                        .await;
+                }
+                Err(e) => check.add_missing(format!(
+                    "Mod {}: {}",
+                    mod_info.display_name.as_deref().unwrap_or("?"),
+                    e
+                )),
+            }
+        }
+    }
+
     /// Synchronizes mods from the central cache to the profile's actual game directory mods folder.
     /// Takes the resolved list of target mods to sync.
     pub async fn sync_mods_to_profile(
@@ -250,17 +374,31 @@ impl ModDownloadService {
             })?;
         }
 
+        let content_store = ContentStore::new();
         for filename in &mods_to_add {
             if let Some(cache_path) = required_mods.get(filename) {
                 let target_path = profile_mods_dir.join(filename);
-                info!("Copying mod to '{}': {}", profile_name, filename);
-                Self::robust_copy_file(cache_path, &target_path).await.map_err(|e| {
-                    error!(
-                        "Failed to copy {:?} to {:?}: {}",
-                        cache_path, target_path, e
-                    );
-                    e
-                })?;
+                info!("Linking mod to '{}': {}", profile_name, filename);
+                match content_store.link_file(cache_path, &target_path).await {
+                    Ok(method) => debug!("Placed {} via {:?}", filename, method),
+                    Err(e) => {
+                        warn!(
+                            "Failed to link {:?} from the content store ({}), copying instead",
+                            cache_path, e
+                        );
+                        // A failed link attempt may leave a stale file behind, never write through it
+                        if target_path.exists() {
+                            fs::remove_file(&target_path).await?;
+                        }
+                        Self::robust_copy_file(cache_path, &target_path).await.map_err(|e| {
+                            error!(
+                                "Failed to copy {:?} to {:?}: {}",
+                                cache_path, target_path, e
+                            );
+                            e
+                        })?;
+                    }
+                }
             } else {
                 error!(
                     "Cache path not found for required mod '{}'! This indicates an internal error.",
@@ -280,23 +418,134 @@ impl ModDownloadService {
         Ok(())
     }
 
+    /// Resolves a profile Maven mod against its repository and downloads it into the cache.
+    /// `latest`/`release` and the timestamped file names of snapshots are looked up in
+    /// `maven-metadata.xml`, the download is verified against the `.sha1` sidecar if the
+    /// repository publishes one.
+    async fn download_maven_mod(
+        coordinates: &str,
+        repository_url: Option<&str>,
+        target_path: &PathBuf,
+        display_name: &str,
+    ) -> Result<()> {
+        let repository_url = repository_url.ok_or_else(|| {
+            AppError::Download(format!(
+                "Maven mod '{}' ({}) has no repository_url",
+                display_name, coordinates
+            ))
+        })?;
+        let coordinates = MavenCoordinates::parse(coordinates)?;
+
+        // A pinned release never changes, skip the metadata and sidecar requests
+        if !coordinates.is_changing() && target_path.exists() {
+            debug!(
+                "Maven mod {} ({}) already in cache",
+                display_name, coordinates.version
+            );
+            return Ok(());
+        }
+
+        let (version, download_url) = match coordinates.resolve_artifact_url(repository_url).await {
+            Ok(resolved) => resolved,
+            // Keep launching offline with the last downloaded version
+            Err(e) if target_path.exists() => {
+                warn!(
+                    "Could not resolve '{}' for Maven mod {}, using cached file: {}",
+                    coordinates.version, display_name, e
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let sha1 = maven_artifact::fetch_sha1_sidecar(&download_url).await;
+        info!(
+            "Preparing Maven mod for cache: {} (Version: {}) from {}",
+            display_name, version, download_url
+        );
+
+        // A changing version without a sidecar cannot be compared to the cached file, refetch it
+        let force_overwrite = coordinates.is_changing() && sha1.is_none();
+        Self::download_and_verify_file(&download_url, target_path, sha1.as_deref(), force_overwrite)
+            .await
+    }
+
+    /// Downloads a single Modrinth, CurseForge, URL or Maven mod into the mod cache and returns its path.
+    /// Files that are already cached are not downloaded again.
+    pub async fn download_mod_to_cache(&self, source: &ModSource) -> Result<PathBuf> {
+        let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+        fs::create_dir_all(&mod_cache_dir).await?;
+        let target_path = mod_cache_dir.join(profile_state::get_mod_cache_filename(source)?);
+
+        let (url, sha1) = match source {
+            ModSource::Modrinth {
+                download_url,
+                file_hash_sha1,
+                ..
+            }
+            | ModSource::CurseForge {
+                download_url,
+                file_hash_sha1,
+                ..
+            } => (download_url, file_hash_sha1),
+            ModSource::Url {
+                url,
+                file_hash_sha1,
+                ..
+            } => (url, file_hash_sha1),
+            ModSource::Maven {
+                coordinates,
+                repository_url,
+            } => {
+                Self::download_maven_mod(
+                    coordinates,
+                    repository_url.as_deref(),
+                    &target_path,
+                    coordinates,
+                )
+                .await?;
+                return Ok(target_path);
+            }
+            _ => {
+                return Err(AppError::InvalidInput(format!(
+                    "Mod source {:?} cannot be downloaded into the mod cache",
+                    source
+                )))
+            }
+        };
+
+        Self::download_and_verify_file(url, &target_path, sha1.as_deref(), false).await?;
+        Ok(target_path)
+    }
+
     /// Downloads a file from a URL to a target path, optionally verifying its SHA1 hash.
     async fn download_and_verify_file(
         url: &str,
         target_path: &PathBuf,
         expected_sha1: Option<&str>,
+        force_overwrite: bool,
     ) -> Result<()> {
         // Use the new centralized download utility with SHA1 verification
         let mut config = DownloadConfig::new()
             .with_streaming(true)  // Mods can be large files
-            .with_retries(3);      // Built-in retry logic for network issues
+            .with_retries(3)       // Built-in retry logic for network issues
+            .with_force_overwrite(force_overwrite);
 
         // Add SHA1 verification if provided
         if let Some(sha1) = expected_sha1 {
             config = config.with_sha1(sha1);
         }
 
-        DownloadUtils::download_file(url, target_path, config).await
+        DownloadUtils::download_file(url, target_path, config).await?;
+
+        // Deduplicate through the content store, the cache file becomes a link of the blob
+        if let Err(e) = ContentStore::new()
+            .adopt_if_new(target_path, expected_sha1)
+            .await
+        {
+            warn!("Failed to add {:?} to the content store: {}", target_path, e);
+        }
+        Ok(())
     }
 
     /// fix for https://github.com/GEG/issues/issues/1487
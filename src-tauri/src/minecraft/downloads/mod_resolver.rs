@@ -5,7 +5,7 @@ use crate::state::profile_state::{
 };
 use log::{debug, info, warn};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::command;
 use uuid::Uuid;
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
@@ -60,11 +60,18 @@ async fn is_modrinth_project_id_blocked_by_config(project_id: &str, enable_flags
     }
 }
 
+// Profile file name and cache path of a profile mod; Maven and URL mods use a unique cache name
+fn profile_mod_files(source: &ModSource, mod_cache_dir: &Path) -> Result<(String, PathBuf)> {
+    let filename = profile_state::get_profile_mod_filename(source)?;
+    let cache_path = mod_cache_dir.join(profile_state::get_mod_cache_filename(source)?);
+    Ok((filename, cache_path))
+}
+
 // --- Unified helper function to add a mod to final_mods with all necessary checks ---
 async fn try_add_mod_to_final_list(
     canonical_key: String,
+    cache_path: PathBuf,
     filename: String,
-    mod_cache_dir: &PathBuf,
     final_mods: &mut HashMap<String, TargetMod>,
     mod_type_str: &str,
     mod_name: &str,
@@ -92,7 +99,6 @@ async fn try_add_mod_to_final_list(
     }
     
     // 3. Check if file exists in cache
-    let cache_path = mod_cache_dir.join(&filename);
     if !cache_path.exists() {
         warn!(
             "{} mod '{}' not found in cache at: {:?}. Skipping.",
@@ -233,8 +239,8 @@ pub async fn resolve_target_mods(
                                         let mod_name = mod_entry.display_name.as_deref().unwrap_or(&mod_entry.id);
                                         try_add_mod_to_final_list(
                                             canonical_key,
+                                            mod_cache_dir.join(&filename),
                                             filename,
-                                            mod_cache_dir,
                                             &mut final_mods,
                                             "pack Modrinth",
                                             mod_name,
@@ -272,8 +278,8 @@ pub async fn resolve_target_mods(
                                         let mod_name = mod_entry.display_name.as_deref().unwrap_or(&mod_entry.id);
                                         try_add_mod_to_final_list(
                                             canonical_key,
+                                            mod_cache_dir.join(&filename),
                                             filename,
-                                            mod_cache_dir,
                                             &mut final_mods,
                                             "pack URL",
                                             mod_name,
@@ -321,8 +327,8 @@ pub async fn resolve_target_mods(
                                         let mod_name = mod_entry.display_name.as_deref().unwrap_or(&mod_entry.id);
                                         try_add_mod_to_final_list(
                                             canonical_key,
+                                            mod_cache_dir.join(&filename),
                                             filename,
-                                            mod_cache_dir,
                                             &mut final_mods,
                                             "pack Maven",
                                             mod_name,
@@ -419,14 +425,14 @@ pub async fn resolve_target_mods(
             ModSource::Modrinth { project_id, .. } => {
                 // Common logic for sources that can override pack mods
                 if let Some(canonical_key) = get_canonical_key_profile(&mod_info.source) {
-                    match profile_state::get_profile_mod_filename(&mod_info.source) {
-                        Ok(filename) => {
+                    match profile_mod_files(&mod_info.source, mod_cache_dir) {
+                        Ok((filename, cache_path)) => {
                             let mod_id_string = mod_info.id.to_string();
                             let mod_name = mod_info.display_name.as_deref().unwrap_or(&mod_id_string);
                             try_add_mod_to_final_list(
                                 canonical_key,
+                                cache_path,
                                 filename,
-                                mod_cache_dir,
                                 &mut final_mods,
                                 "profile Modrinth",
                                 mod_name,
@@ -457,14 +463,14 @@ pub async fn resolve_target_mods(
             ModSource::CurseForge { project_id, .. } => {
                 // Common logic for sources that can override pack mods
                 if let Some(canonical_key) = get_canonical_key_profile(&mod_info.source) {
-                    match profile_state::get_profile_mod_filename(&mod_info.source) {
-                        Ok(filename) => {
+                    match profile_mod_files(&mod_info.source, mod_cache_dir) {
+                        Ok((filename, cache_path)) => {
                             let mod_id_string = mod_info.id.to_string();
                             let mod_name = mod_info.display_name.as_deref().unwrap_or(&mod_id_string);
                             try_add_mod_to_final_list(
                                 canonical_key,
+                                cache_path,
                                 filename,
-                                mod_cache_dir,
                                 &mut final_mods,
                                 "profile CurseForge",
                                 mod_name,
@@ -495,8 +501,8 @@ pub async fn resolve_target_mods(
             ModSource::Url { .. } | ModSource::Maven { .. } => {
                 // Common logic for sources that can override pack mods
                 if let Some(canonical_key) = get_canonical_key_profile(&mod_info.source) {
-                    match profile_state::get_profile_mod_filename(&mod_info.source) {
-                        Ok(filename) => {
+                    match profile_mod_files(&mod_info.source, mod_cache_dir) {
+                        Ok((filename, cache_path)) => {
                             let mod_type_str = match &mod_info.source {
                                 ModSource::Url { .. } => "profile URL",
                                 ModSource::Maven { .. } => "profile Maven",
@@ -506,8 +512,8 @@ pub async fn resolve_target_mods(
                             let mod_name = mod_info.display_name.as_deref().unwrap_or(&mod_id_string);
                             try_add_mod_to_final_list(
                                 canonical_key,
+                                cache_path,
                                 filename,
-                                mod_cache_dir,
                                 &mut final_mods,
                                 mod_type_str,
                                 mod_name,
@@ -1,19 +1,19 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
+use crate::minecraft::downloads::ExpectedFile;
 use crate::minecraft::dto::neo_forge_install_profile::NeoForgeInstallProfile;
 use crate::minecraft::dto::neo_forge_meta::NeoForgeVersion;
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
-use futures::stream::{iter, StreamExt};
+use crate::state::download_state::DownloadScheduler;
+use futures::stream::{self, StreamExt};
 use log::info;
 use std::path::PathBuf;
 use tokio::fs;
 
 const LIBRARIES_DIR: &str = "libraries";
-const DEFAULT_CONCURRENT_DOWNLOADS: usize = 10;
 
 pub struct NeoForgeLibrariesDownload {
     base_path: PathBuf,
-    concurrent_downloads: usize,
 }
 
 impl NeoForgeLibrariesDownload {
@@ -21,14 +21,9 @@ impl NeoForgeLibrariesDownload {
         let base_path = LAUNCHER_DIRECTORY.meta_dir().join(LIBRARIES_DIR);
         Self {
             base_path,
-            concurrent_downloads: DEFAULT_CONCURRENT_DOWNLOADS,
         }
     }
 
-    pub fn set_concurrent_downloads(&mut self, count: usize) {
-        self.concurrent_downloads = count;
-    }
-
     pub async fn download_libraries(&self, forge_version: &NeoForgeVersion) -> Result<()> {
         let mut downloads = Vec::new();
 
@@ -45,14 +40,12 @@ impl NeoForgeLibrariesDownload {
         }
 
         info!("Found {} files to download", downloads.len());
-        info!(
-            "Downloading with {} concurrent downloads",
-            self.concurrent_downloads
-        );
+
+        let fan_out = DownloadScheduler::global_fan_out().await;
 
         // Execute downloads concurrently
-        let results: Vec<Result<()>> = iter(downloads)
-            .buffer_unordered(self.concurrent_downloads)
+        let results: Vec<Result<()>> = stream::iter(downloads)
+            .buffer_unordered(fan_out)
             .collect()
             .await;
 
@@ -189,9 +182,11 @@ impl NeoForgeLibrariesDownload {
 
         info!("Found {} installer libraries to download", downloads.len());
 
+        let fan_out = DownloadScheduler::global_fan_out().await;
+
         // Execute downloads concurrently
-        let results: Vec<Result<()>> = iter(downloads)
-            .buffer_unordered(self.concurrent_downloads)
+        let results: Vec<Result<()>> = stream::iter(downloads)
+            .buffer_unordered(fan_out)
             .collect()
             .await;
 
@@ -290,11 +285,12 @@ impl NeoForgeLibrariesDownload {
         info!("  - Already exists: {}", skipped);
         info!("  - Invalid format: {}", invalid);
         info!("  - To download: {}", downloads.len());
-        info!("  - Concurrent downloads: {}", self.concurrent_downloads);
+
+        let fan_out = DownloadScheduler::global_fan_out().await;
 
         // Führe Downloads parallel aus
-        let results: Vec<Result<()>> = iter(downloads)
-            .buffer_unordered(self.concurrent_downloads)
+        let results: Vec<Result<()>> = stream::iter(downloads)
+            .buffer_unordered(fan_out)
             .collect()
             .await;
 
@@ -314,4 +310,23 @@ impl NeoForgeLibrariesDownload {
         info!("\n✨ All legacy libraries processed successfully!");
         Ok(())
     }
+
+    /// Library artifacts the version JSON lists with a download URL, with their SHA1 and size.
+    /// Artifacts without a URL are produced by the installer and only checked for existence.
+    pub fn expected_library_files(&self, version: &NeoForgeVersion) -> Vec<ExpectedFile> {
+        version
+            .libraries
+            .iter()
+            .filter_map(|library| library.downloads.as_ref())
+            .flat_map(|downloads| downloads.artifact.iter().chain(downloads.classifiers.values()))
+            .filter(|artifact| !artifact.url.is_empty())
+            .map(|artifact| ExpectedFile {
+                name: format!("NeoForge library {}", artifact.path),
+                path: self.get_library_path(artifact),
+                sha1: artifact.sha1.clone(),
+                size: u64::try_from(artifact.size).ok().filter(|size| *size > 0),
+                url: Some(artifact.url.clone()),
+            })
+            .collect()
+    }
 }
@@ -4,10 +4,12 @@ use crate::minecraft::api::NoriskApi;
 use crate::minecraft::auth::minecraft_auth::Credentials;
 use crate::minecraft::dto::norisk_meta::GEGAssets;
 use crate::minecraft::dto::piston_meta::AssetObject;
+use crate::minecraft::offline::OfflineCheck;
+use crate::state::download_state::{DownloadPriority, DownloadScheduler};
 use crate::state::event_state::{EventPayload, EventType};
 use crate::state::profile_state::Profile;
 use crate::state::State;
-use futures::stream::{iter, StreamExt};
+use futures::stream::{self, StreamExt};
 use log::{debug, error, info, trace, warn};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -19,11 +21,12 @@ use uuid::Uuid;
 
 const ASSETS_DIR: &str = "assets";
 const GEG_ASSETS_DIR: &str = "GEG";
-const DEFAULT_CONCURRENT_DOWNLOADS: usize = 12;
+// Asset list of a group, stored next to its objects for offline launches
+const ASSET_INDEX_FILE: &str = "index.json";
+const MAX_LISTED_MISSING_ASSETS: usize = 10;
 
 pub struct NoriskAssetsDownloadService {
     base_path: PathBuf,
-    concurrent_downloads: usize,
 }
 
 impl NoriskAssetsDownloadService {
@@ -35,16 +38,9 @@ impl NoriskAssetsDownloadService {
         );
         Self {
             base_path,
-            concurrent_downloads: DEFAULT_CONCURRENT_DOWNLOADS,
         }
     }
 
-    /// Sets the number of concurrent downloads to use
-    pub fn with_concurrent_downloads(mut self, concurrent_downloads: usize) -> Self {
-        self.concurrent_downloads = concurrent_downloads;
-        self
-    }
-
     /// Downloads GEG client assets for a specific profile, processing the main pack
     /// and any additional asset groups defined in the pack configuration.
     pub async fn download_nrc_assets_for_profile(
@@ -86,6 +82,10 @@ impl NoriskAssetsDownloadService {
         };
 
         let creds = match credentials {
+            Some(c) if c.is_offline() => {
+                info!("[NRC Assets Download] Offline account in use, skipping asset download");
+                return Ok(());
+            }
             Some(c) => c,
             None => {
                 warn!("[NRC Assets Download] No credentials provided, skipping asset download");
@@ -313,6 +313,12 @@ impl NoriskAssetsDownloadService {
                         asset_id,
                         fetched_assets.objects.len()
                     );
+                    if let Err(e) = self.save_asset_index(asset_id, &fetched_assets).await {
+                        warn!(
+                            "[NRC Assets Group '{}'] Could not store asset list: {}",
+                            asset_id, e
+                        );
+                    }
                     if fetched_assets.objects.is_empty() {
                         warn!(
                             "[NRC Assets Group '{}'] No assets found. Skipping download/copy.",
@@ -518,6 +524,7 @@ impl NoriskAssetsDownloadService {
         };
         // Clone state before the closure so the original `state` remains available after the loop
         let state_clone_for_inspect = state.clone();
+        let scheduler = DownloadScheduler::global().await;
 
         for (name, asset) in assets_list {
             let hash = asset.hash.clone();
@@ -533,6 +540,7 @@ impl NoriskAssetsDownloadService {
             let completed_counter_clone = Arc::clone(&completed_counter);
             let total_to_download_clone = Arc::clone(&total_to_download);
             let asset_id_clone = asset_id.to_string();
+            let scheduler_clone = scheduler.clone();
             let GEG_token_clone = GEG_token.to_string();
 
             // Hash-based check - if hash file exists, content is guaranteed correct
@@ -558,6 +566,12 @@ impl NoriskAssetsDownloadService {
                     "https://cdn.GEG.gg/assets", asset_id_clone, name_clone
                 );
 
+                // Counts against the launcher-wide connection limit like every other download
+                let permit = match &scheduler_clone {
+                    Some(scheduler) => Some(scheduler.acquire(DownloadPriority::LaunchBlocking).await),
+                    None => None,
+                };
+
                 let mut request = HTTP_CLIENT.get(&url);
                 request = request.header("Authorization", format!("Bearer {}", GEG_token_clone));
 
@@ -585,6 +599,12 @@ impl NoriskAssetsDownloadService {
                     }
                 };
 
+                if let Some(scheduler) = &scheduler_clone {
+                    scheduler.consume(bytes.len() as u64).await;
+                }
+                // Writing the file does not need the connection anymore
+                drop(permit);
+
                 if let Some(parent) = target_path.parent() {
                     if let Err(e) = fs::create_dir_all(parent).await {
                         error!("[NRC Assets Download '{}' Task {}] Error creating dir for {}: {}", asset_id_clone, task_id, name_clone, e);
@@ -638,15 +658,16 @@ impl NoriskAssetsDownloadService {
         }
 
         info!(
-            "[NRC Assets Download '{}'] Processing tasks with {} concurrent downloads...",
-            asset_id, self.concurrent_downloads
+            "[NRC Assets Download '{}'] Processing tasks through the download scheduler...",
+            asset_id
         );
 
         let completed_ref = Arc::clone(&completed_counter);
         let asset_id_clone = asset_id.to_string();
 
-        let results: Vec<Result<()>> = iter(downloads)
-            .buffer_unordered(self.concurrent_downloads)
+        let fan_out = DownloadScheduler::global_fan_out().await;
+        let results: Vec<Result<()>> = stream::iter(downloads)
+            .buffer_unordered(fan_out)
             .inspect({
                 let asset_id_inspect = asset_id_clone.clone();
                  // Use the cloned state inside the closure
@@ -837,15 +858,8 @@ impl NoriskAssetsDownloadService {
                 let hash_prefix = &hash[0..2];
                 let source_path = source_dir.join("objects").join(hash_prefix).join(hash);
 
-                // Special handling for override assets
-                let (target_path, is_override) = if name.starts_with("overrides/") {
-                    // For overrides, copy to Minecraft directory and strip the "overrides/" prefix
-                    let relative_path = name.strip_prefix("overrides/").unwrap_or(name);
-                    (minecraft_dir.join(relative_path), true)
-                } else {
-                    // Normal assets go to the target base directory
-                    (target_base_dir.join(&name), false)
-                };
+                let (target_path, is_override) =
+                    Self::asset_target_path(name, target_base_dir, minecraft_dir);
 
                 if !fs::try_exists(&source_path).await? {
                     warn!(
@@ -969,6 +983,114 @@ impl NoriskAssetsDownloadService {
         Ok(())
     }
 
+    /// Where an asset is copied to. Returns the path and whether it is an override.
+    fn asset_target_path(
+        name: &str,
+        target_base_dir: &Path,
+        minecraft_dir: &Path,
+    ) -> (PathBuf, bool) {
+        match name.strip_prefix("overrides/") {
+            // Overrides go into the Minecraft directory without the "overrides/" prefix
+            Some(relative_path) => (minecraft_dir.join(relative_path), true),
+            // Normal assets go to the target base directory
+            None => (target_base_dir.join(name), false),
+        }
+    }
+
+    fn asset_index_path(&self, asset_id: &str) -> PathBuf {
+        self.base_path
+            .join(GEG_ASSETS_DIR)
+            .join(asset_id)
+            .join(ASSET_INDEX_FILE)
+    }
+
+    async fn save_asset_index(&self, asset_id: &str, assets: &GEGAssets) -> Result<()> {
+        let index_path = self.asset_index_path(asset_id);
+        if let Some(parent) = index_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&index_path, serde_json::to_vec(assets)?).await?;
+        Ok(())
+    }
+
+    async fn read_asset_index(&self, asset_id: &str) -> Result<GEGAssets> {
+        let content = fs::read(self.asset_index_path(asset_id)).await?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Checks without network access that an earlier online launch copied the GEG assets
+    /// of the profile's pack into the game directory.
+    pub async fn verify_assets_for_profile(
+        &self,
+        state: &State,
+        profile: &Profile,
+        game_directory: &Path,
+        check: &mut OfflineCheck,
+    ) {
+        let Some(pack_id) = profile
+            .selected_GEG_pack_id
+            .as_ref()
+            .filter(|id| !id.is_empty())
+        else {
+            return;
+        };
+
+        let config = state.GEG_pack_manager.get_config().await;
+        let pack_definition = match config.get_resolved_pack_definition(pack_id) {
+            Ok(definition) => definition,
+            Err(e) => {
+                check.add_missing(format!("GEG pack definition '{}': {}", pack_id, e));
+                return;
+            }
+        };
+
+        let target_base_dir = game_directory.join("GEG").join("assets");
+        let mut seen = HashSet::new();
+        for asset_id in pack_definition.assets.iter().filter(|id| seen.insert(*id)) {
+            let assets = match self.read_asset_index(asset_id).await {
+                Ok(assets) => assets,
+                Err(e) => {
+                    check.add_missing(format!(
+                        "GEG assets '{}' (never downloaded online: {})",
+                        asset_id, e
+                    ));
+                    continue;
+                }
+            };
+
+            let mut missing: Vec<&String> = assets
+                .objects
+                .keys()
+                .filter(|name| {
+                    !Self::asset_target_path(name, &target_base_dir, game_directory)
+                        .0
+                        .is_file()
+                })
+                .collect();
+            if missing.is_empty() {
+                continue;
+            }
+            missing.sort();
+            if missing.len() <= MAX_LISTED_MISSING_ASSETS {
+                for name in missing {
+                    check.add_missing(format!("GEG asset {} (group {})", name, asset_id));
+                }
+            } else {
+                check.add_missing(format!(
+                    "{} of {} GEG assets from group {} (e.g. {})",
+                    missing.len(),
+                    assets.objects.len(),
+                    asset_id,
+                    missing[..MAX_LISTED_MISSING_ASSETS]
+                        .iter()
+                        .map(|name| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+    }
+
     /// Helper method for emitting copy progress events
     async fn emit_copy_event(
         &self,
@@ -1,32 +1,24 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
 use crate::integrations::norisk_packs::{self, GEGModSourceDefinition, GEGModpacksConfig};
+use crate::minecraft::offline::OfflineCheck;
+use crate::utils::content_store::ContentStore;
 use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
-use futures::stream::{iter, StreamExt};
+use crate::state::download_state::DownloadScheduler;
+use futures::stream::{self, StreamExt};
 use log::{error, info, warn};
 use std::path::PathBuf;
 use tokio::fs;
 
-const DEFAULT_CONCURRENT_MOD_DOWNLOADS: usize = 4;
 const MOD_CACHE_DIR_NAME: &str = "mod_cache"; // Reuse the same cache directory
 const MODRINTH_MAVEN_URL: &str = "https://api.modrinth.com/maven"; // Modrinth Maven repo
 
 #[derive(Clone)]
-pub struct NoriskPackDownloadService {
-    concurrent_downloads: usize,
-}
+pub struct NoriskPackDownloadService;
 
 impl NoriskPackDownloadService {
     pub fn new() -> Self {
-        Self {
-            concurrent_downloads: DEFAULT_CONCURRENT_MOD_DOWNLOADS,
-        }
-    }
-
-    pub fn with_concurrency(concurrent_downloads: usize) -> Self {
-        Self {
-            concurrent_downloads,
-        }
+        Self
     }
 
     /// Downloads mods specified in a GEG pack definition to the central mod cache.
@@ -39,8 +31,8 @@ impl NoriskPackDownloadService {
         loader: &str,
     ) -> Result<()> {
         info!(
-            "Checking/Downloading GEG Pack mods to cache. Pack ID: '{}', MC: {}, Loader: {} (Concurrency: {})",
-            pack_id, minecraft_version, loader, self.concurrent_downloads
+            "Checking/Downloading GEG Pack mods to cache. Pack ID: '{}', MC: {}, Loader: {}",
+            pack_id, minecraft_version, loader
         );
 
         let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
@@ -189,8 +181,9 @@ impl NoriskPackDownloadService {
             download_futures.len(),
             pack_id
         );
-        let results: Vec<Result<()>> = iter(download_futures)
-            .buffer_unordered(self.concurrent_downloads)
+        let fan_out = DownloadScheduler::global_fan_out().await;
+        let results: Vec<Result<()>> = stream::iter(download_futures)
+            .buffer_unordered(fan_out)
             .collect()
             .await;
 
@@ -216,6 +209,52 @@ impl NoriskPackDownloadService {
         }
     }
 
+    /// Offline counterpart of `download_pack_mods_to_cache`: verifies that every pack mod that is
+    /// compatible with the given version and loader is already in the cache.
+    pub async fn verify_pack_mods_in_cache(
+        &self,
+        config: &GEGModpacksConfig,
+        pack_id: &str,
+        minecraft_version: &str,
+        loader: &str,
+        check: &mut OfflineCheck,
+    ) {
+        let pack_definition = match config.get_resolved_pack_definition(pack_id) {
+            Ok(definition) => definition,
+            Err(e) => {
+                check.add_missing(format!("GEG pack definition '{}': {}", pack_id, e));
+                return;
+            }
+        };
+
+        let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+
+        for mod_entry in &pack_definition.mods {
+            let Some(target) = mod_entry
+                .compatibility
+                .get(minecraft_version)
+                .and_then(|loader_map| loader_map.get(loader))
+            else {
+                continue;
+            };
+
+            let display_name = mod_entry.display_name.as_deref().unwrap_or(&mod_entry.id);
+            let effective_source = target.source.as_ref().unwrap_or(&mod_entry.source);
+            match norisk_packs::get_GEG_pack_mod_filename(effective_source, target, &mod_entry.id) {
+                Ok(filename) => {
+                    check
+                        .check_file(
+                            &format!("GEG pack mod {}", display_name),
+                            &mod_cache_dir.join(&filename),
+                            None,
+                        )
+                        .await;
+                }
+                Err(e) => check.add_missing(format!("GEG pack mod {}: {}", display_name, e)),
+            }
+        }
+    }
+
     /// Helper function to download a mod from a Maven repository.
     async fn download_maven_mod(
         repo_url: String,
@@ -254,7 +293,16 @@ impl NoriskPackDownloadService {
             config = config.with_sha1(hash.to_string());
         }
 
-        DownloadUtils::download_file(url, target_path, config).await
+        DownloadUtils::download_file(url, target_path, config).await?;
+
+        // Deduplicate through the content store, the cache file becomes a link of the blob
+        if let Err(e) = ContentStore::new()
+            .adopt_if_new(target_path, expected_sha1)
+            .await
+        {
+            warn!("Failed to add {:?} to the content store: {}", target_path, e);
+        }
+        Ok(())
     }
 
 
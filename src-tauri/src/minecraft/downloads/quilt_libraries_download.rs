@@ -1,8 +1,10 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::Result;
+use crate::minecraft::downloads::ExpectedFile;
 use crate::minecraft::dto::quilt_meta::{QuiltLibrary, QuiltVersionInfo};
 use crate::utils::download_utils::{DownloadUtils, DownloadConfig};
-use futures::stream::StreamExt;
+use crate::state::download_state::DownloadScheduler;
+use futures::stream::{self, StreamExt};
 use log::info;
 use std::path::PathBuf;
 use tokio::fs;
@@ -10,7 +12,6 @@ use tokio::fs;
 pub struct QuiltLibrariesDownloadService {
     base_path: PathBuf,
     libraries_path: PathBuf,
-    concurrent_downloads: usize,
 }
 
 impl QuiltLibrariesDownloadService {
@@ -18,14 +19,9 @@ impl QuiltLibrariesDownloadService {
         Self {
             base_path: LAUNCHER_DIRECTORY.meta_dir().join("quilt"),
             libraries_path: LAUNCHER_DIRECTORY.meta_dir().join("libraries"),
-            concurrent_downloads: 10,
         }
     }
 
-    pub fn set_concurrent_downloads(&mut self, count: usize) {
-        self.concurrent_downloads = count;
-    }
-
     pub fn print_version_info(&self, version: &QuiltVersionInfo) {
         info!("\n=== Quilt Version ===");
         info!("Loader:");
@@ -159,9 +155,9 @@ impl QuiltLibrariesDownloadService {
         }
 
         info!("Found {} components to download", all_libraries.len());
-        info!("Downloading with {} concurrent downloads", self.concurrent_downloads);
 
-        let downloads = futures::stream::iter(all_libraries.into_iter())
+        let fan_out = DownloadScheduler::global_fan_out().await;
+        let downloads = stream::iter(all_libraries)
             .map(|library| {
                 let self_clone = self.clone();
                 async move {
@@ -173,7 +169,7 @@ impl QuiltLibrariesDownloadService {
                     result
                 }
             })
-            .buffer_unordered(self.concurrent_downloads);
+            .buffer_unordered(fan_out);
 
         let results: Vec<Result<()>> = downloads.collect().await;
         let errors: Vec<_> = results.into_iter().filter_map(|r| r.err()).collect();
@@ -305,6 +301,44 @@ impl QuiltLibrariesDownloadService {
             )))
         }
     }
+
+    /// Libraries for which the launcher meta lists a SHA1 or size, so integrity checks can verify them
+    pub fn expected_library_files(&self, version: &QuiltVersionInfo) -> Vec<ExpectedFile> {
+        version
+            .launcher_meta
+            .libraries
+            .common
+            .iter()
+            .chain(version.launcher_meta.libraries.client.iter())
+            .filter(|library| library.sha1.is_some() || library.size.is_some())
+            .filter_map(|library| {
+                let parts: Vec<&str> = library.name.split(':').collect();
+                if parts.len() != 3 {
+                    return None;
+                }
+                let (group, artifact, version) = (parts[0], parts[1], parts[2]);
+                let relative_path = format!(
+                    "{}/{}/{}/{}-{}.jar",
+                    group.replace('.', "/"),
+                    artifact,
+                    version,
+                    artifact,
+                    version
+                );
+                let base_url = library
+                    .url
+                    .as_deref()
+                    .unwrap_or("https://repo1.maven.org/maven2/");
+                Some(ExpectedFile {
+                    name: format!("Quilt library {}", library.name),
+                    path: self.libraries_path.join(&relative_path),
+                    sha1: library.sha1.clone(),
+                    size: library.size,
+                    url: Some(format!("{}{}", base_url, relative_path)),
+                })
+            })
+            .collect()
+    }
 }
 
 impl Clone for QuiltLibrariesDownloadService {
@@ -312,7 +346,6 @@ impl Clone for QuiltLibrariesDownloadService {
         Self {
             base_path: self.base_path.clone(),
             libraries_path: self.libraries_path.clone(),
-            concurrent_downloads: self.concurrent_downloads,
         }
     }
 }
@@ -9,8 +9,12 @@ use crate::minecraft::downloads::mc_libraries_download::MinecraftLibrariesDownlo
 use crate::minecraft::downloads::mc_natives_download::MinecraftNativesDownloadService;
 use crate::minecraft::downloads::NoriskPackDownloadService;
 use crate::minecraft::downloads::{ModDownloadService, NoriskAssetsDownloadService};
+use crate::minecraft::dto::piston_meta::PistonMeta;
 use crate::minecraft::dto::JavaDistribution;
-use crate::minecraft::{MinecraftLaunchParameters, MinecraftLauncher};
+use crate::minecraft::launch::launch_context::{self, LaunchContext};
+use crate::minecraft::modloader::ModloaderInstallResult;
+use crate::minecraft::offline::OfflineCheck;
+use crate::minecraft::{LaunchDryRun, MinecraftLaunchParameters, MinecraftLauncher};
 use crate::state::event_state::{EventPayload, EventType};
 use crate::state::profile_state::{ModLoader, Profile};
 use crate::state::state_manager::State;
@@ -21,7 +25,11 @@ use uuid::Uuid;
 use super::minecraft_auth::Credentials;
 use super::modloader::ModloaderFactory;
 use crate::minecraft::downloads::MinecraftLoggingDownloadService;
+use crate::integrations::mod_dependencies;
+use crate::utils::java_detector;
 use crate::utils::mc_utils;
+use crate::utils::options_utils;
+use crate::utils::world_backup_utils::{self, WorldBackupTrigger};
 use tokio::fs as async_fs;
 
 async fn emit_progress_event(
@@ -46,6 +54,18 @@ async fn emit_progress_event(
     Ok(event_id)
 }
 
+/// Controls how far `install_minecraft_version` goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchMode {
+    /// Install everything that is missing and start the game.
+    Launch,
+    /// Install everything that is missing, but stop before hooks run and the game is spawned.
+    InstallOnly,
+    /// Install everything that is missing and resolve the launch command without running hooks
+    /// or spawning the game. Optionally writes a `launch.sh` into the game directory.
+    DryRun { write_launch_script: bool },
+}
+
 pub async fn install_minecraft_version(
     version_id: &str,
     modloader_str: &str,
@@ -54,7 +74,9 @@ pub async fn install_minecraft_version(
     quick_play_singleplayer: Option<String>,
     quick_play_multiplayer: Option<String>,
     migration_info: Option<crate::utils::profile_utils::MigrationInfo>,
-) -> Result<()> {
+    launch_mode: LaunchMode,
+    offline: bool,
+) -> Result<Option<LaunchDryRun>> {
     // Convert string modloader to ModLoader enum
     let modloader_enum = match modloader_str {
         "vanilla" => ModLoader::Vanilla,
@@ -89,6 +111,9 @@ pub async fn install_minecraft_version(
         "[Launch] Using concurrent downloads: {}",
         launcher_config.concurrent_downloads
     );
+    if offline {
+        info!("[Launch] Offline mode: only cached files are used, nothing is downloaded.");
+    }
 
     // <--- HARDCODED TEST ERROR (50% CHANCE) --- >
     let should_throw_error = {
@@ -105,7 +130,13 @@ pub async fn install_minecraft_version(
     // <--- END HARDCODED TEST ERROR --- >
 
     // Execute migration if provided
-    if let Some(migration) = &migration_info {
+    if let (true, Some(migration)) = (offline, &migration_info) {
+        // Migrations only run online, check_for_group_migration reports it again on the next launch
+        warn!(
+            "[Launch] Offline mode: skipping pending group migration {:?}",
+            migration
+        );
+    } else if let Some(migration) = &migration_info {
         info!("[Launch] Executing migration before installation: {:?}", migration);
 
         // Execute the migration (detailed progress events are sent from within execute_group_migration)
@@ -149,7 +180,11 @@ pub async fn install_minecraft_version(
     }
 
     let api_service = MinecraftApiService::new();
-    let manifest = api_service.get_version_manifest().await?;
+    let manifest = if offline {
+        api_service.get_cached_version_manifest().await?
+    } else {
+        api_service.get_version_manifest().await?
+    };
     let version = manifest
         .versions
         .iter()
@@ -157,7 +192,11 @@ pub async fn install_minecraft_version(
         .ok_or_else(|| AppError::VersionNotFound(format!("Version {} not found", version_id)))?;
 
     // Get version metadata
-    let piston_meta = api_service.get_piston_meta(&version.url).await?;
+    let piston_meta = if offline {
+        api_service.get_cached_piston_meta(&version.url).await?
+    } else {
+        api_service.get_piston_meta(&version.url).await?
+    };
     piston_meta.display_info();
 
     // Get Java version from Minecraft version manifest
@@ -175,8 +214,12 @@ pub async fn install_minecraft_version(
     )
     .await?;
 
+    // Collects everything missing for an offline launch, so it can fail with one complete list
+    let mut offline_check = OfflineCheck::new();
+
     // Check if profile uses a custom Java path
     let mut custom_java_valid = false;
+    let mut custom_java_major_version: Option<u32> = None;
     let java_path = if profile.settings.use_custom_java_path && profile.settings.java_path.is_some()
     {
         // Try to use the custom Java path
@@ -187,7 +230,6 @@ pub async fn install_minecraft_version(
         let path = std::path::PathBuf::from(custom_path);
         if path.exists() {
             // Check if it's a valid Java installation
-            use crate::utils::java_detector;
             match java_detector::get_java_info(&path).await {
                 Ok(java_info) => {
                     info!(
@@ -202,6 +244,7 @@ pub async fn install_minecraft_version(
                             java_info.major_version, java_version
                         );
                         custom_java_valid = true;
+                        custom_java_major_version = Some(java_info.major_version);
                         path
                     } else {
                         info!(
@@ -252,6 +295,33 @@ pub async fn install_minecraft_version(
         .await?;
 
         java_path
+    } else if offline {
+        // Offline only an already installed Java can be used
+        let java_service = JavaDownloadService::new();
+        let force_x86_64 =
+            java_service.needs_x86_64_java(Some(&piston_meta.java_version.component));
+        match find_installed_java(&java_service, java_version, force_x86_64).await {
+            Some(installed_path) => {
+                info!("Using installed Java: {:?}", installed_path);
+                emit_progress_event(
+                    &state,
+                    EventType::InstallingJava,
+                    profile.id,
+                    &format!("Using installed Java {}!", java_version),
+                    1.0,
+                    None,
+                )
+                .await?;
+                installed_path
+            }
+            None => {
+                offline_check.add_missing(format!(
+                    "Java {} runtime (no installed runtime found)",
+                    java_version
+                ));
+                std::path::PathBuf::new()
+            }
+        }
     } else {
         // Download Java since custom path is not valid or not set
         info!("Downloading Java {}...", java_version);
@@ -280,6 +350,27 @@ pub async fn install_minecraft_version(
         downloaded_path
     };
 
+    // Major version of the Java that will actually run the game (used for JVM flag presets)
+    let java_major_version = custom_java_major_version.unwrap_or(java_version);
+
+    // Profile with the loader version that will actually be installed
+    let install_profile = resolve_install_profile(&state, profile, version_id, &modloader_enum).await;
+
+    // Offline: verify everything up front and fail before anything is touched
+    let mut cached_modloader_result = None;
+    if offline {
+        cached_modloader_result = verify_offline_resources(
+            profile,
+            &install_profile,
+            &piston_meta,
+            version_id,
+            &modloader_enum,
+            credentials.as_ref(),
+            offline_check,
+        )
+        .await?;
+    }
+
     // Create game directory
     let game_directory = state
         .profile_manager
@@ -316,35 +407,36 @@ pub async fn install_minecraft_version(
     }
     info!("User data import check complete.");
 
-    // Emit libraries download event
-    let libraries_event_id = emit_progress_event(
-        &state,
-        EventType::DownloadingLibraries,
-        profile.id,
-        "Downloading libraries...",
-        0.0,
-        None,
-    )
-    .await?;
-
-    // Download all required files
-    info!("\nDownloading libraries...");
-    let libraries_service = MinecraftLibrariesDownloadService::new()
-        .with_concurrent_downloads(launcher_config.concurrent_downloads);
-    libraries_service
-        .download_libraries(&piston_meta.libraries)
+    if !offline {
+        // Emit libraries download event
+        let libraries_event_id = emit_progress_event(
+            &state,
+            EventType::DownloadingLibraries,
+            profile.id,
+            "Downloading libraries...",
+            0.0,
+            None,
+        )
         .await?;
-    info!("Library download completed!");
 
-    emit_progress_event(
-        &state,
-        EventType::DownloadingLibraries,
-        profile.id,
-        "Libraries download completed!",
-        1.0,
-        None,
-    )
-    .await?;
+        // Download all required files
+        info!("\nDownloading libraries...");
+        let libraries_service = MinecraftLibrariesDownloadService::new();
+        libraries_service
+            .download_libraries(&piston_meta.libraries)
+            .await?;
+        info!("Library download completed!");
+
+        emit_progress_event(
+            &state,
+            EventType::DownloadingLibraries,
+            profile.id,
+            "Libraries download completed!",
+            1.0,
+            None,
+        )
+        .await?;
+    }
 
     // Emit natives extraction event
     let natives_event_id = emit_progress_event(
@@ -374,54 +466,56 @@ pub async fn install_minecraft_version(
     )
     .await?;
 
-    info!("\nDownloading assets...");
-    let assets_service = MinecraftAssetsDownloadService::new()
-        .with_concurrent_downloads(launcher_config.concurrent_downloads);
-    assets_service
-        .download_assets_with_progress(&piston_meta.asset_index, profile.id)
-        .await?;
-    info!("Asset download completed!");
-
-    // Download GEG assets if profile has a selected pack
-    info!("\nDownloading GEG assets...");
+    if offline {
+        info!("\nOffline mode: skipping asset, GEG asset and client downloads.");
+    } else {
+        info!("\nDownloading assets...");
+        let assets_service = MinecraftAssetsDownloadService::new();
+        assets_service
+            .download_assets_with_progress(&piston_meta.asset_index, profile.id)
+            .await?;
+        info!("Asset download completed!");
 
-    let GEG_assets_service = NoriskAssetsDownloadService::new()
-        .with_concurrent_downloads(launcher_config.concurrent_downloads);
+        // Download GEG assets if profile has a selected pack
+        info!("\nDownloading GEG assets...");
 
-    // Download assets for this profile - progress events are now handled internally
-    GEG_assets_service
-        .download_nrc_assets_for_profile(&profile, credentials.as_ref(), is_experimental_mode)
-        .await?;
+        let GEG_assets_service = NoriskAssetsDownloadService::new();
 
-    info!("GEG Asset download completed!");
+        // Download assets for this profile - progress events are now handled internally
+        GEG_assets_service
+            .download_nrc_assets_for_profile(&profile, credentials.as_ref(), is_experimental_mode)
+            .await?;
 
-    // Emit client download event
-    let client_event_id = emit_progress_event(
-        &state,
-        EventType::DownloadingClient,
-        profile.id,
-        "Downloading Minecraft client...",
-        0.0,
-        None,
-    )
-    .await?;
+        info!("GEG Asset download completed!");
 
-    info!("\nDownloading Minecraft client...");
-    let client_service = MinecraftClientDownloadService::new();
-    client_service
-        .download_client(&piston_meta.downloads.client, &piston_meta.id)
+        // Emit client download event
+        let client_event_id = emit_progress_event(
+            &state,
+            EventType::DownloadingClient,
+            profile.id,
+            "Downloading Minecraft client...",
+            0.0,
+            None,
+        )
         .await?;
-    info!("Client download completed!");
 
-    emit_progress_event(
-        &state,
-        EventType::DownloadingClient,
-        profile.id,
-        "Minecraft client download completed!",
-        1.0,
-        None,
-    )
-    .await?;
+        info!("\nDownloading Minecraft client...");
+        let client_service = MinecraftClientDownloadService::new();
+        client_service
+            .download_client(&piston_meta.downloads.client, &piston_meta.id)
+            .await?;
+        info!("Client download completed!");
+
+        emit_progress_event(
+            &state,
+            EventType::DownloadingClient,
+            profile.id,
+            "Minecraft client download completed!",
+            1.0,
+            None,
+        )
+        .await?;
+    }
 
     // Create and use Minecraft launcher
     let launcher = MinecraftLauncher::new(
@@ -444,7 +538,9 @@ pub async fn install_minecraft_version(
     let mut launch_params = MinecraftLaunchParameters::new(profile.id, memory_max)
         .with_old_minecraft_arguments(piston_meta.minecraft_arguments.clone())
         .with_resolution(profile.settings.resolution.clone())
-        .with_experimental_mode(is_experimental_mode);
+        .with_experimental_mode(is_experimental_mode)
+        .with_java_major_version(java_major_version)
+        .with_jvm_flags_preset(profile.settings.jvm_flags_preset);
 
     // Add Quick Play parameters if provided
     if let Some(world_name) = quick_play_singleplayer {
@@ -455,39 +551,32 @@ pub async fn install_minecraft_version(
 
     // Install modloader using the factory
     if modloader_enum != ModLoader::Vanilla {
-        // Resolve loader version using the new modloader factory method
-        let mut install_profile = profile.clone();
-        let config_now: GEGModpacksConfig = state.GEG_pack_manager.get_config().await;
-        let resolved_loader = crate::minecraft::modloader::ModloaderFactory::resolve_loader_version(
-            profile,
-            version_id,
-            Some(&config_now),
-        ).await;
-
-        if let Some(version) = resolved_loader.version {
-            let reason_str = match resolved_loader.reason {
-                crate::minecraft::modloader::LoaderVersionReason::GEGPack => "GEG pack policy",
-                crate::minecraft::modloader::LoaderVersionReason::UserOverwrite => "user overwrite",
-                crate::minecraft::modloader::LoaderVersionReason::ProfileDefault => "profile default",
-                crate::minecraft::modloader::LoaderVersionReason::NotResolved => "not resolved",
-            };
-            
-            info!(
-                "Applying loader version '{}' from {} for MC {} ({:?})",
-                version,
-                reason_str,
-                version_id,
-                modloader_enum
-            );
-            install_profile.loader_version = Some(version);
-        }
+        let modloader_result = if offline {
+            // Verified by verify_offline_resources
+            cached_modloader_result.take().ok_or_else(|| {
+                AppError::OfflineResourcesMissing(vec![format!(
+                    "{:?} install for Minecraft {}",
+                    modloader_enum, version_id
+                )])
+            })?
+        } else {
+            let modloader_installer =
+                ModloaderFactory::create_installer(&modloader_enum, java_path.clone());
+            let result = modloader_installer.install(version_id, &install_profile).await?;
 
-        let modloader_installer = ModloaderFactory::create_installer_with_config(
-            &modloader_enum,
-            java_path.clone(),
-            launcher_config.concurrent_downloads,
-        );
-        let modloader_result = modloader_installer.install(version_id, &install_profile).await?;
+            // Remember the result so the profile can be launched offline later
+            if let Err(e) = ModloaderFactory::cache_install_result(
+                &modloader_enum,
+                version_id,
+                install_profile.loader_version.as_deref(),
+                &result,
+            )
+            .await
+            {
+                warn!("Failed to cache {:?} install result: {}", modloader_enum, e);
+            }
+            result
+        };
 
         // Apply modloader specific parameters to launch parameters
         if let Some(main_class) = modloader_result.main_class {
@@ -552,7 +641,17 @@ pub async fn install_minecraft_version(
             "Fetching GEG config because pack '{}' is selected. Attempting to refresh first.",
             pack_id
         );
-        if let Some(creds) = credentials.as_ref() {
+        if offline {
+            info!(
+                "Offline mode, using cached configuration for GEG pack '{}'.",
+                pack_id
+            );
+        } else if credentials.as_ref().is_some_and(|c| c.is_offline()) {
+            info!(
+                "Offline account in use, using cached configuration for GEG pack '{}'.",
+                pack_id
+            );
+        } else if let Some(creds) = credentials.as_ref() {
             match creds
                 .GEG_credentials
                 .get_token_for_mode(is_experimental_mode)
@@ -611,15 +710,34 @@ pub async fn install_minecraft_version(
         profile.name
     );
     let mod_downloader_service =
-        ModDownloadService::with_concurrency(launcher_config.concurrent_downloads);
-    mod_downloader_service
-        .download_mods_to_cache(&profile)
-        .await?;
+        ModDownloadService::new();
+    if offline {
+        info!("Offline mode, profile mods were already verified in the cache.");
+    } else {
+        mod_downloader_service
+            .download_mods_to_cache(&profile)
+            .await?;
+    }
     info!(
         "Profile mod cache check/download completed successfully for profile '{}'",
         profile.name
     );
 
+    // URL and Maven mods only get a name and version once their jar is in the cache
+    let jar_metadata = mod_downloader_service.cached_jar_metadata(&profile).await;
+    if !jar_metadata.is_empty() {
+        if let Err(e) = state
+            .profile_manager
+            .apply_jar_metadata(profile.id, &jar_metadata)
+            .await
+        {
+            warn!(
+                "Failed to store jar metadata of profile '{}': {}",
+                profile.name, e
+            );
+        }
+    }
+
     emit_progress_event(
         &state,
         EventType::DownloadingMods,
@@ -631,7 +749,9 @@ pub async fn install_minecraft_version(
     .await?;
 
     // --- Step: Download mods from selected GEG Pack (if any) ---
-    if let Some(selected_pack_id) = &profile.selected_GEG_pack_id {
+    if offline {
+        info!("Offline mode, GEG pack mods were already verified in the cache.");
+    } else if let Some(selected_pack_id) = &profile.selected_GEG_pack_id {
         // Use the already loaded config
         if let Some(config) = loaded_GEG_config.as_ref() {
             let GEG_mods_event_id = emit_progress_event(
@@ -653,7 +773,7 @@ pub async fn install_minecraft_version(
             );
 
             let GEG_downloader_service =
-                NoriskPackDownloadService::with_concurrency(launcher_config.concurrent_downloads);
+                NoriskPackDownloadService::new();
             let loader_str = modloader_enum.as_str();
 
             match GEG_downloader_service
@@ -757,6 +877,29 @@ pub async fn install_minecraft_version(
     )
     .await?;
 
+    // --- Pre-launch dependency check: report, but don't block the launch ---
+    if !offline && modloader_enum != ModLoader::Vanilla {
+        let (provided, jars) = mod_dependencies::target_mod_inputs(profile, &target_mods);
+        match mod_dependencies::check_dependencies_before_launch(profile, provided, jars).await {
+            Ok(report) if !report.is_satisfied() => {
+                warn!(
+                    "Profile '{}' has unsatisfied dependencies: {} missing, {} conflicts, {} duplicates, {} missing mod IDs, {} for another loader",
+                    profile.name,
+                    report.missing.len(),
+                    report.conflicts.len(),
+                    report.duplicates.len(),
+                    report.missing_mod_ids.len(),
+                    report.wrong_loader.len()
+                );
+                if let Err(e) = mod_dependencies::emit_dependency_issues(&report).await {
+                    warn!("Failed to emit dependency issues: {}", e);
+                }
+            }
+            Ok(_) => info!("All mod dependencies of profile '{}' are satisfied", profile.name),
+            Err(e) => warn!("Dependency check for profile '{}' failed: {}", profile.name, e),
+        }
+    }
+
     // --- Prototype: Provide managed mods via Fabric addMods meta file (Fabric only) ---
     if modloader_enum == ModLoader::Fabric {
         let add_mods_arg = crate::minecraft::downloads::mod_resolver::build_fabric_add_mods_arg(
@@ -820,9 +963,13 @@ pub async fn install_minecraft_version(
     if let Some(logging) = &piston_meta.logging {
         info!("\nDownloading log4j configuration...");
         let logging_service = MinecraftLoggingDownloadService::new();
-        let config_path = logging_service
-            .download_logging_config(&logging.client)
-            .await?;
+        let config_path = if offline {
+            logging_service.get_logging_config_path(&logging.client)
+        } else {
+            logging_service
+                .download_logging_config(&logging.client)
+                .await?
+        };
         log4j_arg = Some(logging_service.get_jvm_argument(&config_path));
         info!("Log4j configuration download completed!");
     }
@@ -835,9 +982,48 @@ pub async fn install_minecraft_version(
         launch_params = launch_params.with_additional_jvm_args(jvm_args);
     }
 
+    match launch_mode {
+        LaunchMode::Launch => {}
+        LaunchMode::InstallOnly => {
+            info!(
+                "Install-only mode: profile '{}' is installed, skipping hooks and launch.",
+                profile.name
+            );
+            return Ok(None);
+        }
+        LaunchMode::DryRun {
+            write_launch_script,
+        } => {
+            info!(
+                "Dry-run mode: resolving launch command for profile '{}' without starting it.",
+                profile.name
+            );
+            let dry_run = launcher
+                .dry_run(
+                    &piston_meta,
+                    launch_params,
+                    Some(profile.clone()),
+                    write_launch_script,
+                )
+                .await?;
+            return Ok(Some(dry_run));
+        }
+    }
+
+    // --- Default options preset and options sync ---
+    if let Err(e) = options_utils::prepare_options_before_launch(profile, &game_directory).await {
+        warn!("Failed to prepare options.txt (non-critical error): {}", e);
+    }
+
+    // --- Snapshot worlds before launch ---
+    if profile.settings.world_backups.before_launch {
+        world_backup_utils::backup_profile_worlds(profile, WorldBackupTrigger::BeforeLaunch).await;
+    }
+
     // --- Execute pre-launch hooks ---
     let launcher_config = state.config_manager.get_config().await;
-    if let Some(hook) = &launcher_config.hooks.pre_launch {
+    let hooks = launch_context::resolve_hooks(&launcher_config.hooks, Some(profile));
+    if let Some(hook) = &hooks.pre_launch {
         info!("Executing pre-launch hook: {}", hook);
         let hook_event_id = emit_progress_event(
             &state,
@@ -849,11 +1035,9 @@ pub async fn install_minecraft_version(
         )
         .await?;
 
-        let mut cmd = hook.split(' ');
-        if let Some(command) = cmd.next() {
-            let result = std::process::Command::new(command)
-                .args(cmd.collect::<Vec<&str>>())
-                .current_dir(&game_directory)
+        let context = LaunchContext::new(profile, &game_directory);
+        if let Some(mut command) = launch_context::hook_command(hook, &game_directory, &context) {
+            let result = command
                 .spawn()
                 .map_err(|e| AppError::Io(e))?
                 .wait()
@@ -897,5 +1081,175 @@ pub async fn install_minecraft_version(
     )
     .await?;
 
-    Ok(())
+    Ok(None)
+}
+
+/// Returns a copy of the profile with the loader version that should be installed
+/// (GEG pack policy, user overwrite or profile default).
+pub(crate) async fn resolve_install_profile(
+    state: &State,
+    profile: &Profile,
+    version_id: &str,
+    modloader: &ModLoader,
+) -> Profile {
+    let mut install_profile = profile.clone();
+    if *modloader == ModLoader::Vanilla {
+        return install_profile;
+    }
+
+    let config_now: GEGModpacksConfig = state.GEG_pack_manager.get_config().await;
+    let resolved_loader =
+        ModloaderFactory::resolve_loader_version(profile, version_id, Some(&config_now)).await;
+
+    if let Some(version) = resolved_loader.version {
+        let reason_str = match resolved_loader.reason {
+            crate::minecraft::modloader::LoaderVersionReason::GEGPack => "GEG pack policy",
+            crate::minecraft::modloader::LoaderVersionReason::UserOverwrite => "user overwrite",
+            crate::minecraft::modloader::LoaderVersionReason::ProfileDefault => "profile default",
+            crate::minecraft::modloader::LoaderVersionReason::NotResolved => "not resolved",
+        };
+
+        info!(
+            "Applying loader version '{}' from {} for MC {} ({:?})",
+            version, reason_str, version_id, modloader
+        );
+        install_profile.loader_version = Some(version);
+    }
+    install_profile
+}
+
+/// Finds an installed runtime with the required Java version without downloading anything:
+/// any distribution the launcher downloaded before, then the runtimes installed on the system.
+async fn find_installed_java(
+    java_service: &JavaDownloadService,
+    java_version: u32,
+    force_x86_64: bool,
+) -> Option<std::path::PathBuf> {
+    for distribution in [
+        JavaDistribution::Zulu,
+        JavaDistribution::Temurin,
+        JavaDistribution::GraalVM,
+    ] {
+        let Ok(path) = java_service
+            .find_java_binary(&distribution, &java_version, force_x86_64)
+            .await
+        else {
+            continue;
+        };
+        match java_detector::get_java_info(&path).await {
+            Ok(java_info) if java_info.major_version == java_version => return Some(path),
+            Ok(java_info) => warn!(
+                "Cached {} runtime {:?} reports Java {}, expected {}",
+                distribution.get_name(),
+                path,
+                java_info.major_version,
+                java_version
+            ),
+            Err(e) => warn!(
+                "Cached {} runtime {:?} is not usable: {}",
+                distribution.get_name(),
+                path,
+                e
+            ),
+        }
+    }
+
+    match java_detector::detect_java_installations().await {
+        Ok(installations) => installations
+            .into_iter()
+            .find(|java| java.major_version == java_version && java.is_64bit)
+            .map(|java| java.path),
+        Err(e) => {
+            warn!("Could not detect installed Java runtimes: {}", e);
+            None
+        }
+    }
+}
+
+/// Verifies (presence and hashes) everything an offline launch needs without touching the network
+/// and returns the cached modloader install result. Fails with the complete list of missing files.
+async fn verify_offline_resources(
+    profile: &Profile,
+    install_profile: &Profile,
+    piston_meta: &PistonMeta,
+    version_id: &str,
+    modloader: &ModLoader,
+    credentials: Option<&Credentials>,
+    mut check: OfflineCheck,
+) -> Result<Option<ModloaderInstallResult>> {
+    info!("\nVerifying cached files for offline launch...");
+    let state = State::get().await?;
+
+    MinecraftLibrariesDownloadService::new()
+        .verify_libraries(&piston_meta.libraries, &mut check)
+        .await;
+    MinecraftClientDownloadService::new()
+        .verify_client(&piston_meta.downloads.client, &piston_meta.id, &mut check)
+        .await;
+    MinecraftAssetsDownloadService::new()
+        .verify_assets(&piston_meta.asset_index, &mut check)
+        .await;
+    if let Some(logging) = &piston_meta.logging {
+        MinecraftLoggingDownloadService::new()
+            .verify_logging_config(&logging.client, &mut check)
+            .await;
+    }
+
+    let mut modloader_result = None;
+    if *modloader != ModLoader::Vanilla {
+        let loader_version = install_profile.loader_version.as_deref();
+        match ModloaderFactory::load_cached_install_result(modloader, version_id, loader_version)
+            .await
+        {
+            Ok(Some(result)) => {
+                for library in &result.libraries {
+                    check
+                        .check_file(&format!("{:?} library", modloader), library, None)
+                        .await;
+                }
+                if let Some(client_path) = &result.custom_client_path {
+                    check
+                        .check_file(&format!("{:?} client jar", modloader), client_path, None)
+                        .await;
+                }
+                modloader_result = Some(result);
+            }
+            Ok(None) => check.add_missing(format!(
+                "{:?} {} for Minecraft {} (never installed online)",
+                modloader,
+                loader_version.unwrap_or("default version"),
+                version_id
+            )),
+            Err(e) => check.add_missing(format!("{:?} install cache: {}", modloader, e)),
+        }
+    }
+
+    ModDownloadService::new()
+        .verify_mods_in_cache(profile, &mut check)
+        .await;
+    if let Some(pack_id) = &profile.selected_GEG_pack_id {
+        let config = state.GEG_pack_manager.get_config().await;
+        NoriskPackDownloadService::new()
+            .verify_pack_mods_in_cache(
+                &config,
+                pack_id,
+                version_id,
+                modloader.as_str(),
+                &mut check,
+            )
+            .await;
+    }
+    // Online launches only download GEG assets for GEG (non-offline) accounts
+    if credentials.is_some_and(|c| !c.is_offline()) {
+        let game_directory = state
+            .profile_manager
+            .calculate_instance_path_for_profile(profile)?;
+        NoriskAssetsDownloadService::new()
+            .verify_assets_for_profile(&state, profile, &game_directory, &mut check)
+            .await;
+    }
+
+    check.into_result()?;
+    info!("All files for the offline launch are present.");
+    Ok(modloader_result)
 }
@@ -3,6 +3,7 @@ use crate::minecraft::dto::piston_meta::Library;
 use crate::minecraft::launch::version::compare_versions;
 use crate::minecraft::rules::RuleProcessor;
 use log::info;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -12,6 +13,31 @@ struct LibraryInfo {
     priority: u32, // Höhere Zahl = höhere Priorität
 }
 
+/// Where a classpath entry came from
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClasspathEntrySource {
+    /// Library that won the version/priority resolution
+    Library,
+    /// Manually added entry
+    Extra,
+    /// Custom client jar (e.g. patched by a mod loader)
+    CustomClientJar,
+    /// Vanilla client jar
+    ClientJar,
+}
+
+/// A single resolved classpath entry
+#[derive(Debug, Clone, Serialize)]
+pub struct ClasspathEntry {
+    pub path: String,
+    pub source: ClasspathEntrySource,
+    /// Library priority (higher wins), only set for libraries
+    pub priority: Option<u32>,
+    /// Library version the entry was resolved to, only set for libraries
+    pub version: Option<String>,
+}
+
 pub struct ClasspathBuilder {
     entries: Vec<String>,
     libraries: HashMap<String, LibraryInfo>,
@@ -172,35 +198,63 @@ impl ClasspathBuilder {
         self
     }
 
-    pub fn build(&self, force_include_minecraft_jar: bool) -> String {
+    /// Resolves all entries in classpath order: libraries (highest priority first), extra entries, client jar.
+    pub fn build_entries(&self, force_include_minecraft_jar: bool) -> Vec<ClasspathEntry> {
         use std::collections::HashSet;
 
-        let mut unique_entries = HashSet::new();
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        let mut push = |path: String, source, priority, version| {
+            if seen.insert(path.clone()) {
+                entries.push(ClasspathEntry {
+                    path,
+                    source,
+                    priority,
+                    version,
+                });
+            }
+        };
 
-        for lib_info in self.libraries.values() {
-            let path_str = lib_info
-                .path
-                .to_string_lossy()
-                .to_string()
-                .replace("\\", "/");
-            unique_entries.insert(path_str);
+        let mut libraries: Vec<&LibraryInfo> = self.libraries.values().collect();
+        libraries.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        for lib_info in libraries {
+            push(
+                lib_info.path.to_string_lossy().replace("\\", "/"),
+                ClasspathEntrySource::Library,
+                Some(lib_info.priority),
+                Some(lib_info.version.clone()),
+            );
         }
 
         for entry in &self.entries {
-            unique_entries.insert(entry.replace("\\", "/"));
+            push(
+                entry.replace("\\", "/"),
+                ClasspathEntrySource::Extra,
+                None,
+                None,
+            );
         }
 
         if let Some(custom_client_jar) = &self.custom_client_jar_path {
             info!("Using custom client jar: {}", custom_client_jar.display());
-            unique_entries.insert(
-                custom_client_jar
-                    .to_string_lossy()
-                    .to_string()
-                    .replace("\\", "/"),
+            push(
+                custom_client_jar.to_string_lossy().replace("\\", "/"),
+                ClasspathEntrySource::CustomClientJar,
+                None,
+                None,
             );
         } else if let Some(vanilla_jar) = &self.vanilla_client_jar {
             info!("Using vanilla client jar: {}", vanilla_jar.display());
-            unique_entries.insert(vanilla_jar.to_string_lossy().to_string().replace("\\", "/"));
+            push(
+                vanilla_jar.to_string_lossy().replace("\\", "/"),
+                ClasspathEntrySource::ClientJar,
+                None,
+                None,
+            );
         } else {
             info!("⚠️ Warning: No client jar found! This might cause issues.");
         }
@@ -211,16 +265,26 @@ impl ClasspathBuilder {
                     "Force including vanilla client jar: {}",
                     vanilla_jar.display()
                 );
-                unique_entries.insert(vanilla_jar.to_string_lossy().to_string().replace("\\", "/"));
+                push(
+                    vanilla_jar.to_string_lossy().replace("\\", "/"),
+                    ClasspathEntrySource::ClientJar,
+                    None,
+                    None,
+                );
             }
         }
 
-        let all_entries: Vec<String> = unique_entries.into_iter().collect();
-        info!(
-            "Final classpath contains {} unique entries",
-            all_entries.len()
-        );
-        all_entries.join(if cfg!(windows) { ";" } else { ":" })
+        info!("Final classpath contains {} unique entries", entries.len());
+        entries
+    }
+
+    /// Joins entries into a classpath string with the platform separator.
+    pub fn join_entries(entries: &[ClasspathEntry]) -> String {
+        entries
+            .iter()
+            .map(|e| e.path.as_str())
+            .collect::<Vec<_>>()
+            .join(if cfg!(windows) { ";" } else { ":" })
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::minecraft::dto::piston_meta::{ArgumentValue, ComplexArgument, GameArgument};
-use crate::minecraft::minecraft_auth::Credentials;
+use crate::minecraft::minecraft_auth::{Credentials, OFFLINE_ACCESS_TOKEN};
 use std::path::PathBuf;
 
 pub struct GameArguments {
@@ -68,6 +68,14 @@ impl GameArguments {
     }
 
     pub fn replace_variables(&self, arg: &str) -> String {
+        // Offline accounts must never hand a real token to the game
+        let is_offline = self.credentials.as_ref().is_some_and(|c| c.is_offline());
+        let access_token = match &self.credentials {
+            Some(c) if !is_offline => c.access_token.clone(),
+            _ => OFFLINE_ACCESS_TOKEN.to_string(),
+        };
+        let user_type = if is_offline { "legacy" } else { "msa" };
+
         arg.replace(
             "${auth_player_name}",
             &self
@@ -101,17 +109,10 @@ impl GameArguments {
                 .map(|c| c.id.to_string())
                 .unwrap_or_else(|| "00000000-0000-0000-0000-000000000000".to_string()),
         )
-        .replace(
-            "${auth_access_token}",
-            &self
-                .credentials
-                .as_ref()
-                .map(|c| c.access_token.clone())
-                .unwrap_or_else(|| "0".to_string()),
-        )
+        .replace("${auth_access_token}", &access_token)
         .replace("${clientid}", "c4502edb-87c6-40cb-b595-64a280cf8906")
         .replace("${auth_xuid}", "0")
-        .replace("${user_type}", "msa")
+        .replace("${user_type}", user_type)
         .replace("${version_type}", &self.version_type)
         .replace("${user_properties}", "{}")
     }
@@ -0,0 +1,234 @@
+use crate::state::profile_state::JvmFlagsPreset;
+use log::warn;
+use std::collections::HashSet;
+
+/// Flags that select a garbage collector. Two of them on one command line make the JVM refuse to start.
+const GC_SELECTION_FLAGS: &[&str] = &[
+    "-XX:+UseG1GC",
+    "-XX:+UseZGC",
+    "-XX:+UseShenandoahGC",
+    "-XX:+UseParallelGC",
+    "-XX:+UseSerialGC",
+    "-XX:+UseConcMarkSweepGC",
+    "-XX:+UseEpsilonGC",
+];
+
+/// Tuning flags that only apply to one collector: (flag selecting it, flag key prefix)
+const COLLECTOR_TUNING_PREFIXES: &[(&str, &str)] = &[
+    ("-XX:+UseG1GC", "XX:G1"),
+    ("-XX:+UseZGC", "XX:Z"),
+    ("-XX:+UseShenandoahGC", "XX:Shenandoah"),
+];
+
+const VANILLA_G1_FLAGS: &[&str] = &[
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+UseG1GC",
+    "-XX:G1NewSizePercent=20",
+    "-XX:G1ReservePercent=20",
+    "-XX:MaxGCPauseMillis=50",
+    "-XX:G1HeapRegionSize=32M",
+];
+
+const AIKAR_FLAGS: &[&str] = &[
+    "-XX:+UseG1GC",
+    "-XX:+ParallelRefProcEnabled",
+    "-XX:MaxGCPauseMillis=200",
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:G1NewSizePercent=30",
+    "-XX:G1MaxNewSizePercent=40",
+    "-XX:G1HeapRegionSize=8M",
+    "-XX:G1ReservePercent=20",
+    "-XX:G1HeapWastePercent=5",
+    "-XX:G1MixedGCCountTarget=4",
+    "-XX:InitiatingHeapOccupancyPercent=15",
+    "-XX:G1MixedGCLiveThresholdPercent=90",
+    "-XX:SurvivorRatio=32",
+    "-XX:+PerfDisableSharedMem",
+    "-XX:MaxTenuringThreshold=1",
+];
+
+/// Returns the preset that can actually run on the given Java version,
+/// downgrading to vanilla G1 when the requested collector is not available.
+pub fn resolve_preset(preset: JvmFlagsPreset, java_major_version: Option<u32>) -> JvmFlagsPreset {
+    let Some(major) = java_major_version else {
+        return preset;
+    };
+
+    let required = match preset {
+        JvmFlagsPreset::ZgcGenerational => 21,
+        JvmFlagsPreset::Shenandoah => 12,
+        _ => return preset,
+    };
+
+    if major < required {
+        warn!(
+            "JVM flags preset {:?} requires Java {}+, but Java {} is used. Falling back to {:?}.",
+            preset,
+            required,
+            major,
+            JvmFlagsPreset::VanillaG1
+        );
+        return JvmFlagsPreset::VanillaG1;
+    }
+    preset
+}
+
+/// Builds the flags of a preset for the given Java version. Call `resolve_preset` first.
+pub fn preset_flags(preset: JvmFlagsPreset, java_major_version: Option<u32>) -> Vec<String> {
+    let flags: Vec<&str> = match preset {
+        JvmFlagsPreset::None => Vec::new(),
+        JvmFlagsPreset::VanillaG1 => VANILLA_G1_FLAGS.to_vec(),
+        JvmFlagsPreset::Aikar => AIKAR_FLAGS.to_vec(),
+        JvmFlagsPreset::ZgcGenerational => {
+            // Generational mode is the default (and the flag deprecated) since Java 23
+            if java_major_version.is_some_and(|major| major >= 23) {
+                vec!["-XX:+UseZGC"]
+            } else {
+                vec!["-XX:+UseZGC", "-XX:+ZGenerational"]
+            }
+        }
+        JvmFlagsPreset::Shenandoah => vec!["-XX:+UseShenandoahGC"],
+    };
+    flags.into_iter().map(String::from).collect()
+}
+
+/// Removes preset flags that the user (or the mod loader) already sets in `user_args`.
+///
+/// If `user_args` select a garbage collector themselves, the preset's collector selection is
+/// dropped as well, and so are its tuning flags for a different collector. All other flags stay.
+pub fn dedupe_against(preset_flags: Vec<String>, user_args: &[String]) -> Vec<String> {
+    let user_keys: HashSet<String> = user_args.iter().filter_map(|a| flag_key(a)).collect();
+    let is_gc_selection = |arg: &String| GC_SELECTION_FLAGS.contains(&arg.as_str());
+    let user_gc = user_args.iter().find(|a| is_gc_selection(a));
+    let preset_gc = preset_flags.iter().find(|f| is_gc_selection(f)).cloned();
+
+    let foreign_tuning_prefix = match (user_gc, preset_gc) {
+        (Some(user_gc), Some(preset_gc)) if *user_gc != preset_gc => {
+            warn!(
+                "Custom JVM arguments select {}, skipping the preset's flags for {}",
+                user_gc, preset_gc
+            );
+            COLLECTOR_TUNING_PREFIXES
+                .iter()
+                .find(|(gc, _)| *gc == preset_gc)
+                .map(|(_, prefix)| *prefix)
+        }
+        _ => None,
+    };
+
+    preset_flags
+        .into_iter()
+        .filter(|flag| !(user_gc.is_some() && is_gc_selection(flag)))
+        .filter(|flag| match flag_key(flag) {
+            Some(key) => {
+                !user_keys.contains(&key)
+                    && !foreign_tuning_prefix.is_some_and(|prefix| key.starts_with(prefix))
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// Identity of a JVM flag regardless of its value, e.g. `-XX:+Foo`, `-XX:-Foo` and `-XX:Foo=1` are all `XX:Foo`.
+fn flag_key(arg: &str) -> Option<String> {
+    if let Some(option) = arg.strip_prefix("-XX:") {
+        let name = option
+            .trim_start_matches(['+', '-'])
+            .split('=')
+            .next()
+            .unwrap_or_default();
+        return Some(format!("XX:{}", name));
+    }
+    if let Some(property) = arg.strip_prefix("-D") {
+        return Some(format!(
+            "D:{}",
+            property.split('=').next().unwrap_or_default()
+        ));
+    }
+    ["-Xmx", "-Xms", "-Xss", "-Xmn"]
+        .iter()
+        .find(|prefix| arg.starts_with(*prefix))
+        .map(|prefix| prefix.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn falls_back_to_g1_when_java_is_too_old() {
+        assert_eq!(
+            resolve_preset(JvmFlagsPreset::ZgcGenerational, Some(17)),
+            JvmFlagsPreset::VanillaG1
+        );
+        assert_eq!(
+            resolve_preset(JvmFlagsPreset::ZgcGenerational, Some(21)),
+            JvmFlagsPreset::ZgcGenerational
+        );
+        assert_eq!(
+            resolve_preset(JvmFlagsPreset::Shenandoah, Some(8)),
+            JvmFlagsPreset::VanillaG1
+        );
+        // Unknown Java version: keep what the user picked
+        assert_eq!(
+            resolve_preset(JvmFlagsPreset::Shenandoah, None),
+            JvmFlagsPreset::Shenandoah
+        );
+
+        assert_eq!(
+            preset_flags(JvmFlagsPreset::ZgcGenerational, Some(21)),
+            args(&["-XX:+UseZGC", "-XX:+ZGenerational"])
+        );
+        assert_eq!(
+            preset_flags(JvmFlagsPreset::ZgcGenerational, Some(23)),
+            args(&["-XX:+UseZGC"])
+        );
+    }
+
+    #[test]
+    fn drops_preset_flags_the_user_already_sets() {
+        let flags = preset_flags(JvmFlagsPreset::VanillaG1, Some(21));
+
+        let deduped = dedupe_against(
+            flags.clone(),
+            &args(&[
+                "-XX:MaxGCPauseMillis=100",
+                "-XX:-UnlockExperimentalVMOptions",
+            ]),
+        );
+        assert_eq!(
+            deduped,
+            args(&[
+                "-XX:+UseG1GC",
+                "-XX:G1NewSizePercent=20",
+                "-XX:G1ReservePercent=20",
+                "-XX:G1HeapRegionSize=32M",
+            ])
+        );
+
+        // A different collector in the user's args drops only the G1 selection and tuning
+        assert_eq!(
+            dedupe_against(flags.clone(), &args(&["-XX:+UseZGC"])),
+            args(&[
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:MaxGCPauseMillis=50",
+            ])
+        );
+        // The same collector keeps the preset's tuning flags
+        assert_eq!(
+            dedupe_against(flags.clone(), &args(&["-XX:+UseG1GC"])),
+            flags[..1]
+                .iter()
+                .chain(&flags[2..])
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(dedupe_against(flags.clone(), &[]), flags);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::state::config_state::Hooks;
+use crate::state::profile_state::Profile;
+use log::warn;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use uuid::Uuid;
+
+/// Information about a launch that is handed to hooks and the game process
+/// through `GEG_*` environment variables.
+#[derive(Debug, Clone)]
+pub struct LaunchContext {
+    pub profile_id: Uuid,
+    pub profile_name: String,
+    pub instance_path: PathBuf,
+    pub minecraft_version: String,
+    pub loader: String,
+    pub loader_version: Option<String>,
+    /// PID of the game process, only known once it has been started (post-exit hook)
+    pub pid: Option<u32>,
+}
+
+impl LaunchContext {
+    pub fn new(profile: &Profile, instance_path: &Path) -> Self {
+        Self {
+            profile_id: profile.id,
+            profile_name: profile.name.clone(),
+            instance_path: instance_path.to_path_buf(),
+            minecraft_version: profile.game_version.clone(),
+            loader: profile.loader.as_str().to_string(),
+            loader_version: profile.loader_version.clone(),
+            pid: None,
+        }
+    }
+
+    pub fn with_pid(mut self, pid: u32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            ("GEG_PROFILE_ID", self.profile_id.to_string()),
+            ("GEG_PROFILE_NAME", self.profile_name.clone()),
+            (
+                "GEG_INSTANCE_PATH",
+                self.instance_path.to_string_lossy().into_owned(),
+            ),
+            ("GEG_MC_VERSION", self.minecraft_version.clone()),
+            ("GEG_LOADER", self.loader.clone()),
+            (
+                "GEG_LOADER_VERSION",
+                self.loader_version.clone().unwrap_or_default(),
+            ),
+        ];
+        if let Some(pid) = self.pid {
+            vars.push(("GEG_PID", pid.to_string()));
+        }
+        vars
+    }
+
+    pub fn apply_to(&self, command: &mut Command) {
+        command.envs(self.env_vars());
+    }
+}
+
+/// Resolves the hooks for a profile: profile overrides on top of the global hooks.
+pub fn resolve_hooks(global: &Hooks, profile: Option<&Profile>) -> Hooks {
+    match profile {
+        Some(p) => global.with_overrides(&p.settings.hooks),
+        None => global.clone(),
+    }
+}
+
+/// Splits a hook or wrapper string into program and arguments like a shell would,
+/// so quoted paths with spaces stay one word.
+pub fn split_command(command: &str) -> Vec<String> {
+    shell_words::split(command).unwrap_or_else(|e| {
+        warn!("Could not split command '{}': {}", command, e);
+        command.split_whitespace().map(String::from).collect()
+    })
+}
+
+/// Builds the command that starts `program`, run through the wrapper words if there are any.
+pub fn wrapped_command(wrapper: &[String], program: &Path) -> Command {
+    match wrapper.split_first() {
+        Some((wrapper_program, wrapper_args)) => {
+            let mut command = Command::new(wrapper_program);
+            command.args(wrapper_args).arg(program);
+            command
+        }
+        None => Command::new(program),
+    }
+}
+
+/// Builds a command for a hook string (program followed by its arguments).
+pub fn hook_command(hook: &str, working_dir: &Path, context: &LaunchContext) -> Option<Command> {
+    let words = split_command(hook);
+    let (program, args) = words.split_first()?;
+    let mut command = Command::new(program);
+    command.args(args).current_dir(working_dir);
+    context.apply_to(&mut command);
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn args(command: &Command) -> Vec<&OsStr> {
+        command.get_args().collect()
+    }
+
+    #[test]
+    fn multi_word_wrapper_runs_program_with_arguments() {
+        let java = Path::new("/opt/java/bin/java");
+        let wrapper = split_command("gamemoderun mangohud --dlsym");
+        let command = wrapped_command(&wrapper, java);
+        assert_eq!(command.get_program(), "gamemoderun");
+        assert_eq!(
+            args(&command),
+            vec!["mangohud", "--dlsym", "/opt/java/bin/java"]
+        );
+
+        let unwrapped = wrapped_command(&[], java);
+        assert_eq!(unwrapped.get_program(), java.as_os_str());
+        assert!(args(&unwrapped).is_empty());
+    }
+
+    #[test]
+    fn hooks_are_split_like_wrappers() {
+        let context = LaunchContext {
+            profile_id: Uuid::nil(),
+            profile_name: "My Pack".to_string(),
+            instance_path: PathBuf::from("/games/My Pack"),
+            minecraft_version: "1.21.1".to_string(),
+            loader: "fabric".to_string(),
+            loader_version: None,
+            pid: None,
+        };
+        let command = hook_command(
+            "'/opt/my scripts/sync.sh' --profile \"My Pack\"",
+            Path::new("/games/My Pack"),
+            &context,
+        )
+        .unwrap();
+        assert_eq!(command.get_program(), "/opt/my scripts/sync.sh");
+        assert_eq!(args(&command), vec!["--profile", "My Pack"]);
+        assert!(hook_command("  ", Path::new("/"), &context).is_none());
+    }
+}
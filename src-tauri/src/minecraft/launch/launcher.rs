@@ -1,15 +1,20 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::Result;
 use crate::minecraft::dto::piston_meta::PistonMeta;
+use crate::minecraft::launch::classpath::ClasspathEntry;
+use crate::minecraft::launch::jvm_flags;
+use crate::minecraft::launch::launch_context::{self, LaunchContext};
 use crate::minecraft::minecraft_auth::Credentials;
 use crate::minecraft::ClasspathBuilder;
 use crate::minecraft::GameArguments;
 use crate::minecraft::JvmArguments;
-use crate::state::profile_state::{Profile, WindowSize};
+use crate::state::config_state::Hooks;
+use crate::state::profile_state::{JvmFlagsPreset, Profile, WindowSize};
 use crate::state::state_manager::State;
 use log::{debug, error, info, warn};
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::Instant;
@@ -30,6 +35,8 @@ pub struct MinecraftLaunchParameters {
     pub resolution: Option<WindowSize>,
     pub quick_play_singleplayer: Option<String>,
     pub quick_play_multiplayer: Option<String>,
+    pub java_major_version: Option<u32>,
+    pub jvm_flags_preset: JvmFlagsPreset,
 }
 
 impl MinecraftLaunchParameters {
@@ -48,6 +55,8 @@ impl MinecraftLaunchParameters {
             resolution: None,
             quick_play_singleplayer: None,
             quick_play_multiplayer: None,
+            java_major_version: None,
+            jvm_flags_preset: JvmFlagsPreset::default(),
         }
     }
 
@@ -110,6 +119,16 @@ impl MinecraftLaunchParameters {
         self.quick_play_multiplayer = Some(server_address);
         self
     }
+
+    pub fn with_java_major_version(mut self, major_version: u32) -> Self {
+        self.java_major_version = Some(major_version);
+        self
+    }
+
+    pub fn with_jvm_flags_preset(mut self, preset: JvmFlagsPreset) -> Self {
+        self.jvm_flags_preset = preset;
+        self
+    }
 }
 
 pub struct MinecraftLauncher {
@@ -177,41 +196,34 @@ impl MinecraftLauncher {
             parts.push(program_str.into_owned());
         }
 
-        // Arguments
-        let mut args_iter = command.get_args().peekable();
-        while let Some(arg_os_str) = args_iter.next() {
-            let mut arg_str = arg_os_str.to_string_lossy().into_owned();
-
-            if arg_str.starts_with("-DGEG.token=") {
-                parts.push("-DGEG.token=*****".to_string());
-            } else if arg_str == "--accessToken" {
-                parts.push(arg_str); // Push "--accessToken"
-                if args_iter.peek().is_some() {
-                    args_iter.next(); // Consume the actual token value
-                    parts.push("*****".to_string()); // Push the redacted placeholder
-                }
+        // Arguments (secrets redacted)
+        let args = redact_args(
+            command
+                .get_args()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect(),
+        );
+        for arg_str in args {
+            // Quote if contains space, is empty, or contains a double quote itself.
+            // The check for double quote in arg_str itself is important to ensure it gets quoted.
+            if arg_str.contains(' ') || arg_str.is_empty() || arg_str.contains('\"') {
+                parts.push(format!("\"{}\"", arg_str.replace('\"', "\\\"")));
+            // Escape inner quotes
             } else {
-                // Quote if contains space, is empty, or contains a double quote itself.
-                // The check for double quote in arg_str itself is important to ensure it gets quoted.
-                if arg_str.contains(' ') || arg_str.is_empty() || arg_str.contains('\"') {
-                    parts.push(format!("\"{}\"", arg_str.replace('\"', "\\\"")));
-                // Escape inner quotes
-                } else {
-                    parts.push(arg_str);
-                }
+                parts.push(arg_str);
             }
         }
         parts.join(" ")
     }
 
-    pub async fn launch(
+    /// Builds the complete game command (wrapper, JVM args, classpath, game args, env) without spawning it.
+    async fn prepare_command(
         &self,
         piston_meta: &PistonMeta,
         params: MinecraftLaunchParameters,
-        profile: Option<Profile>,
-    ) -> Result<()> {
+        profile: Option<&Profile>,
+    ) -> Result<PreparedCommand> {
         let state = State::get().await?;
-        let process_manager = &state.process_manager;
 
         // Remove fetching the profile just for RAM
         // let profile = state.profile_manager.get_profile(params.profile_id).await?;
@@ -219,20 +231,33 @@ impl MinecraftLauncher {
 
         // 2. Java-Befehl initialisieren (mit wrapper support)
         let launcher_config = state.config_manager.get_config().await;
-        let mut command = match launcher_config.hooks.wrapper {
-            Some(wrapper) => {
-                info!("Using wrapper command: {}", wrapper);
-                // Exactly like Modrinth: use the whole wrapper string as command and add java path as arg
-                {
-                    let mut it = Command::new(wrapper);
-                    it.arg(&self.java_path);
-                    it
-                }
-            }
-            None => Command::new(&self.java_path),
-        };
+        let hooks = launch_context::resolve_hooks(&launcher_config.hooks, profile);
+        // A wrapper like `gamemoderun mangohud` is a program with arguments, not one word.
+        // The same words are used for the launch, the dry-run and the launch script.
+        let wrapper = hooks
+            .wrapper
+            .as_deref()
+            .map(launch_context::split_command)
+            .unwrap_or_default();
+        if !wrapper.is_empty() {
+            info!("Using wrapper command: {:?}", wrapper);
+        }
+        let mut command = launch_context::wrapped_command(&wrapper, &self.java_path);
         command.current_dir(&self.game_directory);
 
+        // Launch context for the wrapper/game plus the profile's own environment variables
+        if let Some(p) = &profile {
+            LaunchContext::new(p, &self.game_directory).apply_to(&mut command);
+            if !p.settings.env_vars.is_empty() {
+                info!(
+                    "Setting {} profile environment variable(s): {:?}",
+                    p.settings.env_vars.len(),
+                    p.settings.env_vars.keys().collect::<Vec<_>>()
+                );
+                command.envs(&p.settings.env_vars);
+            }
+        }
+
         // Define paths
         let natives_path = LAUNCHER_DIRECTORY
             .meta_dir()
@@ -240,18 +265,19 @@ impl MinecraftLauncher {
             .join(&piston_meta.id);
 
         // Build classpath first as it's needed for JVM arguments
-        let classpath = if let Some(client_jar) = params.custom_client_jar {
+        let classpath_entries = if let Some(client_jar) = params.custom_client_jar {
             ClasspathBuilder::new(&piston_meta.id)
                 .add_additional_libraries(&params.additional_libraries, 1)
                 .add_piston_libraries(&piston_meta.libraries)
                 .set_custom_client_jar(client_jar)
-                .build(params.force_include_minecraft_jar)
+                .build_entries(params.force_include_minecraft_jar)
         } else {
             ClasspathBuilder::new(&piston_meta.id)
                 .add_additional_libraries(&params.additional_libraries, 1)
                 .add_piston_libraries(&piston_meta.libraries)
-                .build(params.force_include_minecraft_jar)
+                .build_entries(params.force_include_minecraft_jar)
         };
+        let classpath = ClasspathBuilder::join_entries(&classpath_entries);
 
         // Create JVM arguments processor
         let jvm_args = JvmArguments::new(
@@ -282,14 +308,17 @@ impl MinecraftLauncher {
         info!("Adding RAM JVM argument: -Xmx{}M", params.memory_max_mb);
         command.arg(format!("-Xmx{}M", params.memory_max_mb));
 
-        // Add recommended GC flags
-        command.arg("-XX:+UnlockExperimentalVMOptions");
-        command.arg("-XX:+UseG1GC");
-        // Add additional G1GC optimization flags like vanilla launcher
-        command.arg("-XX:G1NewSizePercent=20");
-        command.arg("-XX:G1ReservePercent=20");
-        command.arg("-XX:MaxGCPauseMillis=50");
-        command.arg("-XX:G1HeapRegionSize=32M");
+        // Add GC flags from the profile's preset, without the ones the user/loader already sets
+        let preset = jvm_flags::resolve_preset(params.jvm_flags_preset, params.java_major_version);
+        let preset_flags = jvm_flags::dedupe_against(
+            jvm_flags::preset_flags(preset, params.java_major_version),
+            &params.additional_jvm_args,
+        );
+        info!(
+            "Using JVM flags preset {:?} (Java {:?}): {:?}",
+            preset, params.java_major_version, preset_flags
+        );
+        command.args(preset_flags);
 
         // Add GEG client specific parameters
         // Only add token if we have credentials AND a GEG pack is selected in the profile
@@ -303,7 +332,12 @@ impl MinecraftLauncher {
             command.arg(format!("-DGEG.profile.name={}", p.name));
         }
 
-        if let Some(creds) = &self.credentials {
+        if let Some(creds) = self.credentials.as_ref().filter(|c| c.is_offline()) {
+            info!(
+                "[JS Launcher] Offline account '{}', skipping JS parameters",
+                creds.username
+            );
+        } else if let Some(creds) = &self.credentials {
             if has_GEG_pack {
                 // Get the appropriate GEG token based on experimental mode setting
                 if let Some(GEG_token) = if params.is_experimental_mode {
@@ -382,6 +416,7 @@ impl MinecraftLauncher {
         }
 
         // Add main class
+        let main_class_index = command.get_args().len();
         command.arg(&params.main_class);
 
         // Create game arguments processor
@@ -441,6 +476,30 @@ impl MinecraftLauncher {
             command.arg(arg);
         }
 
+        Ok(PreparedCommand {
+            command,
+            classpath: classpath_entries,
+            main_class: params.main_class,
+            main_class_index,
+            wrapper,
+            hooks,
+        })
+    }
+
+    pub async fn launch(
+        &self,
+        piston_meta: &PistonMeta,
+        params: MinecraftLaunchParameters,
+        profile: Option<Profile>,
+    ) -> Result<()> {
+        let state = State::get().await?;
+        let process_manager = &state.process_manager;
+        let profile_id = params.profile_id;
+
+        let PreparedCommand { command, hooks, .. } = self
+            .prepare_command(piston_meta, params, profile.as_ref())
+            .await?;
+
         // Log the command before execution, with sensitive information redacted.
         let loggable_command_view = Self::create_loggable_command_string(&command);
         info!("Executing command: {}", loggable_command_view);
@@ -464,14 +523,13 @@ impl MinecraftLauncher {
             None => (None, None, None, None),
         };
 
-        // Get post-exit hook from config at launch time (not at exit time)
-        let launcher_config = state.config_manager.get_config().await;
-        let post_exit_hook = launcher_config.hooks.post_exit.clone();
+        // Post-exit hook is resolved at launch time (not at exit time)
+        let post_exit_hook = hooks.post_exit.clone();
 
         // Start the process using ProcessManager with additional metadata
         process_manager
             .start_process(
-                params.profile_id,
+                profile_id,
                 command,
                 account_uuid,
                 account_name,
@@ -486,4 +544,215 @@ impl MinecraftLauncher {
 
         Ok(())
     }
+
+    /// Resolves the full launch command like `launch` does, but returns it instead of starting the game.
+    /// Secrets (access token, GEG token) are redacted. Optionally writes a `launch.sh` into the game directory.
+    pub async fn dry_run(
+        &self,
+        piston_meta: &PistonMeta,
+        params: MinecraftLaunchParameters,
+        profile: Option<Profile>,
+        write_launch_script: bool,
+    ) -> Result<LaunchDryRun> {
+        let prepared = self
+            .prepare_command(piston_meta, params, profile.as_ref())
+            .await?;
+        let command = &prepared.command;
+
+        let args = redact_args(
+            command
+                .get_args()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect(),
+        );
+        // With a wrapper the java path follows the wrapper's own arguments
+        let jvm_args_start = prepared.wrapper.len();
+        let jvm_args = args[jvm_args_start..prepared.main_class_index].to_vec();
+        let game_args = args[prepared.main_class_index + 1..].to_vec();
+
+        let env: BTreeMap<String, String> = command
+            .get_envs()
+            .filter_map(|(key, value)| {
+                let key = key.to_string_lossy().into_owned();
+                let value = value?.to_string_lossy().into_owned();
+                Some((key, value))
+            })
+            .map(|(key, value)| {
+                let upper = key.to_uppercase();
+                if ["TOKEN", "SECRET", "PASSWORD"].iter().any(|s| upper.contains(s)) {
+                    (key, REDACTED.to_string())
+                } else {
+                    (key, value)
+                }
+            })
+            .collect();
+
+        let mut dry_run = LaunchDryRun {
+            java_path: self.java_path.clone(),
+            wrapper: prepared.wrapper,
+            jvm_args,
+            main_class: prepared.main_class,
+            classpath: prepared.classpath,
+            game_args,
+            working_dir: self.game_directory.clone(),
+            env,
+            launch_script: None,
+        };
+
+        if write_launch_script {
+            let script_path = self.game_directory.join("launch.sh");
+            tokio::fs::write(&script_path, dry_run.to_shell_script()).await?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                tokio::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755))
+                    .await?;
+            }
+            info!("Wrote launch script to {}", script_path.display());
+            dry_run.launch_script = Some(script_path);
+        }
+
+        Ok(dry_run)
+    }
+}
+
+const REDACTED: &str = "*****";
+
+/// Output of `install_minecraft_version` in `LaunchMode::DryRun`: the launch command split into its parts.
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchDryRun {
+    pub java_path: PathBuf,
+    /// Wrapper program followed by its arguments, empty without a wrapper
+    pub wrapper: Vec<String>,
+    pub jvm_args: Vec<String>,
+    pub main_class: String,
+    pub classpath: Vec<ClasspathEntry>,
+    pub game_args: Vec<String>,
+    pub working_dir: PathBuf,
+    /// Environment variables set on top of the launcher's own environment
+    pub env: BTreeMap<String, String>,
+    pub launch_script: Option<PathBuf>,
+}
+
+impl LaunchDryRun {
+    /// Renders a standalone POSIX shell script that reproduces the launch.
+    pub fn to_shell_script(&self) -> String {
+        fn quote(value: &str) -> String {
+            format!("'{}'", value.replace('\'', "'\"'\"'"))
+        }
+
+        let mut script = String::from("#!/bin/sh\n# Generated by the launcher dry-run, secrets are redacted\n");
+        script.push_str(&format!(
+            "cd {} || exit 1\n",
+            quote(&self.working_dir.to_string_lossy())
+        ));
+        for (key, value) in &self.env {
+            script.push_str(&format!("export {}={}\n", key, quote(value)));
+        }
+
+        let mut parts: Vec<String> = Vec::new();
+        parts.extend(self.wrapper.iter().map(|w| quote(w)));
+        parts.push(quote(&self.java_path.to_string_lossy()));
+        parts.extend(self.jvm_args.iter().map(|a| quote(a)));
+        parts.push(quote(&self.main_class));
+        parts.extend(self.game_args.iter().map(|a| quote(a)));
+
+        script.push_str("exec ");
+        script.push_str(&parts.join(" \\\n  "));
+        script.push('\n');
+        script
+    }
+}
+
+/// Result of `MinecraftLauncher::prepare_command`
+struct PreparedCommand {
+    command: Command,
+    classpath: Vec<ClasspathEntry>,
+    main_class: String,
+    /// Index of the main class within the command's arguments
+    main_class_index: usize,
+    /// Wrapper program and its arguments, split once for the launch and the dry-run
+    wrapper: Vec<String>,
+    hooks: Hooks,
+}
+
+/// Replaces secret values (GEG token, access token) in a list of command arguments.
+fn redact_args(args: Vec<String>) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut args_iter = args.into_iter();
+    while let Some(arg) = args_iter.next() {
+        if arg.starts_with("-DGEG.token=") {
+            redacted.push(format!("-DGEG.token={}", REDACTED));
+        } else if arg == "--accessToken" {
+            redacted.push(arg);
+            if args_iter.next().is_some() {
+                redacted.push(REDACTED.to_string());
+            }
+        } else {
+            redacted.push(arg);
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn redacts_tokens_in_arguments() {
+        let args = strings(&[
+            "-Xmx4096M",
+            "-DGEG.token=secret-jwt",
+            "net.minecraft.client.main.Main",
+            "--username",
+            "Steve",
+            "--accessToken",
+            "secret-access-token",
+            "--version",
+            "1.21.1",
+        ]);
+        assert_eq!(
+            redact_args(args),
+            strings(&[
+                "-Xmx4096M",
+                "-DGEG.token=*****",
+                "net.minecraft.client.main.Main",
+                "--username",
+                "Steve",
+                "--accessToken",
+                "*****",
+                "--version",
+                "1.21.1",
+            ])
+        );
+    }
+
+    #[test]
+    fn launch_script_quotes_each_word() {
+        let dry_run = LaunchDryRun {
+            java_path: PathBuf::from("/opt/java/bin/java"),
+            wrapper: strings(&["gamemoderun", "mangohud", "--dlsym"]),
+            jvm_args: strings(&["-Xmx4096M", "-Dlog='x'"]),
+            main_class: "net.minecraft.client.main.Main".to_string(),
+            classpath: Vec::new(),
+            game_args: strings(&["--username", "Steve", "--gameDir", "/games/My Pack"]),
+            working_dir: PathBuf::from("/games/My Pack"),
+            env: BTreeMap::from([("GEG_PROFILE_NAME".to_string(), "My Pack".to_string())]),
+            launch_script: None,
+        };
+
+        let script = dry_run.to_shell_script();
+        assert!(script.contains("cd '/games/My Pack' || exit 1\n"));
+        assert!(script.contains("export GEG_PROFILE_NAME='My Pack'\n"));
+        assert!(script.contains(
+            "exec 'gamemoderun' \\\n  'mangohud' \\\n  '--dlsym' \\\n  '/opt/java/bin/java' \\\n"
+        ));
+        assert!(script.contains("'-Dlog='\"'\"'x'\"'\"''"));
+        assert!(script.ends_with("'--gameDir' \\\n  '/games/My Pack'\n"));
+    }
 }
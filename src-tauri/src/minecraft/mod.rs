@@ -1,10 +1,12 @@
 pub mod api;
 pub mod auth;
+pub mod crash_analyzer;
 pub mod downloads;
 pub mod dto;
 pub mod installer;
 pub mod launch;
 pub mod modloader;
+pub mod offline;
 
 pub use api::*;
 pub use auth::*;
@@ -9,20 +9,11 @@ use log::info;
 use std::path::PathBuf;
 use uuid::Uuid;
 
-pub struct FabricInstaller {
-    concurrent_downloads: usize,
-}
+pub struct FabricInstaller;
 
 impl FabricInstaller {
     pub fn new() -> Self {
-        Self {
-            concurrent_downloads: 10, // Default value
-        }
-    }
-
-    pub fn set_concurrent_downloads(&mut self, count: usize) -> &mut Self {
-        self.concurrent_downloads = count;
-        self
+        Self
     }
 
     pub async fn install(&self, version_id: &str, profile: &Profile) -> Result<Vec<PathBuf>> {
@@ -42,10 +33,7 @@ impl FabricInstaller {
 
         info!("\nInstalling Fabric...");
         let fabric_api = FabricApi::new();
-        let mut fabric_libraries_download = FabricLibrariesDownloadService::new();
-
-        // Setze die Anzahl der konkurrenten Downloads
-        fabric_libraries_download.set_concurrent_downloads(self.concurrent_downloads);
+        let fabric_libraries_download = FabricLibrariesDownloadService::new();
 
         // --- Determine Fabric Version ---
         let fabric_version = match &profile.loader_version {
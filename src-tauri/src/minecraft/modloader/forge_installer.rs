@@ -1,7 +1,9 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
 use crate::minecraft::api::forge_api::ForgeApi;
-use crate::minecraft::downloads::{ForgeInstallerDownloadService, ForgeLibrariesDownload};
+use crate::minecraft::downloads::{
+    ExpectedFile, ForgeInstallerDownloadService, ForgeLibrariesDownload,
+};
 use crate::minecraft::launch::forge_arguments::ForgeArguments;
 use crate::minecraft::ForgePatcher;
 use crate::state::event_state::{EventPayload, EventType};
@@ -13,22 +15,15 @@ use uuid::Uuid;
 
 pub struct ForgeInstaller {
     java_path: PathBuf,
-    concurrent_downloads: usize,
 }
 
 impl ForgeInstaller {
     pub fn new(java_path: PathBuf) -> Self {
         Self {
             java_path,
-            concurrent_downloads: 10, // Default value
         }
     }
 
-    pub fn set_concurrent_downloads(&mut self, count: usize) -> &mut Self {
-        self.concurrent_downloads = count;
-        self
-    }
-
     pub async fn install(&self, version_id: &str, profile: &Profile) -> Result<ForgeInstallResult> {
         // Emit Forge installation event
         let forge_event_id = Uuid::new_v4();
@@ -48,13 +43,9 @@ impl ForgeInstaller {
 
         // Initialize services
         let forge_api = ForgeApi::new();
-        let mut forge_libraries_download = ForgeLibrariesDownload::new();
+        let forge_libraries_download = ForgeLibrariesDownload::new();
         let forge_installer_download = ForgeInstallerDownloadService::new();
 
-        // Setze die Anzahl der konkurrenten Downloads
-        forge_libraries_download.set_concurrent_downloads(self.concurrent_downloads);
-        // Forge installer hat möglicherweise keine set_concurrent_downloads Methode
-
         // Get all Forge versions metadata
         let forge_metadata = forge_api.get_all_versions().await?;
         // Get versions compatible with the current Minecraft version
@@ -174,6 +165,7 @@ impl ForgeInstaller {
         let libraries = forge_libraries_download
             .get_library_paths(&forge_version, profile_json.is_none())
             .await?;
+        let library_files = forge_libraries_download.expected_library_files(&forge_version);
 
         info!("Forge Libraries: {:?}", libraries);
 
@@ -321,6 +313,7 @@ impl ForgeInstaller {
 
         let result = ForgeInstallResult {
             libraries,
+            library_files,
             main_class: forge_version.main_class.clone(),
             jvm_args: ForgeArguments::get_jvm_arguments(
                 &forge_version,
@@ -343,6 +336,7 @@ impl ForgeInstaller {
 
 pub struct ForgeInstallResult {
     pub libraries: Vec<PathBuf>,
+    pub library_files: Vec<ExpectedFile>,
     pub main_class: String,
     pub jvm_args: Vec<String>,
     pub game_args: Vec<String>,
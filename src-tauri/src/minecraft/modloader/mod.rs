@@ -5,6 +5,9 @@ pub mod quilt_installer;
 
 use crate::config::ProjectDirsExt;
 use crate::error::Result;
+use crate::minecraft::downloads::fabric_libraries_download::FabricLibrariesDownloadService;
+use crate::minecraft::downloads::quilt_libraries_download::QuiltLibrariesDownloadService;
+use crate::minecraft::downloads::ExpectedFile;
 use crate::state::profile_state::{ModLoader, Profile};
 use crate::integrations::norisk_packs::GEGModpacksConfig;
 use async_trait::async_trait;
@@ -148,33 +151,49 @@ impl ModloaderFactory {
         }
     }
 
-    pub fn create_installer_with_config(
+    fn install_result_cache_path(
         modloader: &ModLoader,
-        java_path: PathBuf,
-        concurrent_downloads: usize,
-    ) -> Box<dyn ModloaderInstaller> {
-        match modloader {
-            ModLoader::Fabric => {
-                let mut installer = FabricInstaller::new();
-                installer.set_concurrent_downloads(concurrent_downloads);
-                Box::new(installer)
-            }
-            ModLoader::Quilt => {
-                let mut installer = QuiltInstaller::new();
-                installer.set_concurrent_downloads(concurrent_downloads);
-                Box::new(installer)
-            }
-            ModLoader::Forge => {
-                let mut installer = ForgeInstaller::new(java_path);
-                installer.set_concurrent_downloads(concurrent_downloads);
-                Box::new(installer)
-            }
-            ModLoader::NeoForge => {
-                let mut installer = NeoForgeInstaller::new(java_path);
-                installer.set_concurrent_downloads(concurrent_downloads);
-                Box::new(installer)
-            }
-            ModLoader::Vanilla => Box::new(VanillaInstaller),
+        minecraft_version: &str,
+        loader_version: Option<&str>,
+    ) -> PathBuf {
+        crate::config::LAUNCHER_DIRECTORY
+            .meta_dir()
+            .join("minecraft_cache")
+            .join("modloader_results")
+            .join(format!(
+                "{}_{}_{}.json",
+                modloader.as_str(),
+                minecraft_version,
+                loader_version.filter(|v| !v.is_empty()).unwrap_or("default")
+            ))
+    }
+
+    /// Remembers the result of a successful online install so an offline launch can reuse it.
+    pub async fn cache_install_result(
+        modloader: &ModLoader,
+        minecraft_version: &str,
+        loader_version: Option<&str>,
+        result: &ModloaderInstallResult,
+    ) -> Result<()> {
+        let path = Self::install_result_cache_path(modloader, minecraft_version, loader_version);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, serde_json::to_vec_pretty(result)?).await?;
+        Ok(())
+    }
+
+    /// Loads the result of a previous online install, `None` if this loader version was never installed.
+    pub async fn load_cached_install_result(
+        modloader: &ModLoader,
+        minecraft_version: &str,
+        loader_version: Option<&str>,
+    ) -> Result<Option<ModloaderInstallResult>> {
+        let path = Self::install_result_cache_path(modloader, minecraft_version, loader_version);
+        match tokio::fs::read(&path).await {
+            Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
         }
     }
 }
@@ -207,6 +226,8 @@ impl ModloaderInstaller for FabricInstaller {
 
         Ok(ModloaderInstallResult {
             libraries,
+            library_files: FabricLibrariesDownloadService::new()
+                .expected_library_files(&fabric_version),
             main_class: Some(self.get_main_class(&fabric_version)),
             jvm_args: None,
             game_args: None,
@@ -240,6 +261,8 @@ impl ModloaderInstaller for QuiltInstaller {
 
         Ok(ModloaderInstallResult {
             libraries,
+            library_files: QuiltLibrariesDownloadService::new()
+                .expected_library_files(&quilt_version),
             main_class: Some(self.get_main_class(&quilt_version)),
             jvm_args: None,
             game_args: None,
@@ -257,6 +280,7 @@ impl ModloaderInstaller for ForgeInstaller {
 
         Ok(ModloaderInstallResult {
             libraries: result.libraries,
+            library_files: result.library_files,
             main_class: Some(result.main_class),
             jvm_args: Some(result.jvm_args),
             game_args: Some(result.game_args),
@@ -306,6 +330,7 @@ impl ModloaderInstaller for NeoForgeInstaller {
 
         Ok(ModloaderInstallResult {
             libraries: result.libraries,
+            library_files: result.library_files,
             main_class: Some(result.main_class),
             jvm_args: alt_jvm_args.or(Some(result.jvm_args)),
             game_args: Some(result.game_args),
@@ -332,6 +357,7 @@ impl ModloaderInstaller for VanillaInstaller {
         // Nothing to install for vanilla
         Ok(ModloaderInstallResult {
             libraries: Vec::new(),
+            library_files: Vec::new(),
             main_class: None, // Will use the vanilla main class from the version JSON
             jvm_args: None,
             game_args: None,
@@ -342,8 +368,12 @@ impl ModloaderInstaller for VanillaInstaller {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModloaderInstallResult {
     pub libraries: Vec<PathBuf>,
+    /// Libraries with a SHA1/size from the loader's version JSON, used by integrity checks
+    #[serde(default)]
+    pub library_files: Vec<ExpectedFile>,
     pub main_class: Option<String>,
     pub jvm_args: Option<Vec<String>>,
     pub game_args: Option<Vec<String>>,
@@ -1,6 +1,8 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
-use crate::minecraft::downloads::{NeoForgeInstallerDownloadService, NeoForgeLibrariesDownload};
+use crate::minecraft::downloads::{
+    ExpectedFile, NeoForgeInstallerDownloadService, NeoForgeLibrariesDownload,
+};
 use crate::minecraft::launch::neo_forge_arguments::NeoForgeArguments;
 use crate::minecraft::launch::version::compare_versions;
 use crate::minecraft::{NeoForgeApi, NeoForgePatcher};
@@ -13,22 +15,15 @@ use uuid::Uuid;
 
 pub struct NeoForgeInstaller {
     java_path: PathBuf,
-    concurrent_downloads: usize,
 }
 
 impl NeoForgeInstaller {
     pub fn new(java_path: PathBuf) -> Self {
         Self {
             java_path,
-            concurrent_downloads: 10, // Default value
         }
     }
 
-    pub fn set_concurrent_downloads(&mut self, count: usize) -> &mut Self {
-        self.concurrent_downloads = count;
-        self
-    }
-
     pub async fn install(
         &self,
         version_id: &str,
@@ -52,12 +47,9 @@ impl NeoForgeInstaller {
 
         // Initialize services
         let neoforge_api = NeoForgeApi::new();
-        let mut neoforge_libraries_download = NeoForgeLibrariesDownload::new();
+        let neoforge_libraries_download = NeoForgeLibrariesDownload::new();
         let neoforge_installer_download = NeoForgeInstallerDownloadService::new();
 
-        // Setze die Anzahl der konkurrenten Downloads
-        neoforge_libraries_download.set_concurrent_downloads(self.concurrent_downloads);
-
         // Get all NeoForge versions metadata
         let neoforge_metadata = neoforge_api.get_all_versions().await?;
         // Get versions compatible with the current Minecraft version
@@ -180,6 +172,7 @@ impl NeoForgeInstaller {
         let libraries = neoforge_libraries_download
             .get_library_paths(&neoforge_version, profile_json.is_none())
             .await?;
+        let library_files = neoforge_libraries_download.expected_library_files(&neoforge_version);
 
         info!("NeoForge Libraries: {:?}", libraries);
         let neo_forge_game_arguments = NeoForgeArguments::get_game_arguments(&neoforge_version);
@@ -334,6 +327,7 @@ impl NeoForgeInstaller {
 
         let result = NeoForgeInstallResult {
             libraries,
+            library_files,
             main_class: neoforge_version.main_class.clone(),
             jvm_args: NeoForgeArguments::get_jvm_arguments(
                 &neoforge_version,
@@ -352,6 +346,7 @@ impl NeoForgeInstaller {
 
 pub struct NeoForgeInstallResult {
     pub libraries: Vec<PathBuf>,
+    pub library_files: Vec<ExpectedFile>,
     pub main_class: String,
     pub jvm_args: Vec<String>,
     pub game_args: Vec<String>,
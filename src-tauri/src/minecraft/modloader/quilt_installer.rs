@@ -9,20 +9,11 @@ use log::info;
 use std::path::PathBuf;
 use uuid::Uuid;
 
-pub struct QuiltInstaller {
-    concurrent_downloads: usize,
-}
+pub struct QuiltInstaller;
 
 impl QuiltInstaller {
     pub fn new() -> Self {
-        Self {
-            concurrent_downloads: 10, // Default value
-        }
-    }
-
-    pub fn set_concurrent_downloads(&mut self, count: usize) -> &mut Self {
-        self.concurrent_downloads = count;
-        self
+        Self
     }
 
     pub async fn install(&self, version_id: &str, profile: &Profile) -> Result<Vec<PathBuf>> {
@@ -42,10 +33,7 @@ impl QuiltInstaller {
 
         info!("\nInstalling Quilt...");
         let quilt_api = QuiltApi::new();
-        let mut quilt_libraries_download = QuiltLibrariesDownloadService::new();
-
-        // Setze die Anzahl der konkurrenten Downloads
-        quilt_libraries_download.set_concurrent_downloads(self.concurrent_downloads);
+        let quilt_libraries_download = QuiltLibrariesDownloadService::new();
 
         // --- Determine Quilt Version ---
         let quilt_version = match &profile.loader_version {
@@ -0,0 +1,118 @@
+use crate::error::{AppError, Result};
+use crate::minecraft::downloads::ExpectedFile;
+use crate::utils::hash_utils;
+use log::{debug, warn};
+use std::path::Path;
+
+/// Collects everything an offline launch would need but cannot find (or verify) on disk,
+/// so the launch can fail once with the complete list instead of on the first missing file.
+#[derive(Debug, Default)]
+pub struct OfflineCheck {
+    missing: Vec<String>,
+}
+
+impl OfflineCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_missing(&mut self, what: impl Into<String>) {
+        let what = what.into();
+        warn!("[Offline] Missing: {}", what);
+        self.missing.push(what);
+    }
+
+    /// Checks that `path` exists and, if `sha1` is given, that its content matches the hash.
+    /// Returns whether the file is usable.
+    pub async fn check_file(&mut self, what: &str, path: &Path, sha1: Option<&str>) -> bool {
+        if !path.is_file() {
+            self.add_missing(format!("{} ({})", what, path.display()));
+            return false;
+        }
+
+        let Some(expected) = sha1 else {
+            debug!("[Offline] Present: {}", what);
+            return true;
+        };
+
+        match hash_utils::calculate_sha1_from_file(path).await {
+            Ok(actual) if actual.eq_ignore_ascii_case(expected) => {
+                debug!("[Offline] Verified: {}", what);
+                return true;
+            }
+            Ok(actual) => self.add_missing(format!(
+                "{} ({}): SHA1 mismatch, expected {} but found {}",
+                what,
+                path.display(),
+                expected,
+                actual
+            )),
+            Err(e) => self.add_missing(format!(
+                "{} ({}): could not be read: {}",
+                what,
+                path.display(),
+                e
+            )),
+        }
+        false
+    }
+
+    pub async fn check_expected(&mut self, file: &ExpectedFile) -> bool {
+        self.check_file(&file.name, &file.path, file.sha1.as_deref())
+            .await
+    }
+
+    /// Fails with `AppError::OfflineResourcesMissing` if anything was reported missing.
+    pub fn into_result(self) -> Result<()> {
+        if self.missing.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::OfflineResourcesMissing(self.missing))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn collects_every_missing_or_corrupt_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = dir.path().join("client.jar");
+        tokio::fs::write(&client, b"hello world").await.unwrap();
+        let hello_sha1 = "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed";
+
+        let mut check = OfflineCheck::new();
+        assert!(
+            check
+                .check_file("Client jar", &client, Some(hello_sha1))
+                .await
+        );
+        assert!(check.check_file("Client jar", &client, None).await);
+        assert!(check.into_result().is_ok());
+
+        let mut check = OfflineCheck::new();
+        assert!(
+            !check
+                .check_file("Asset index", &dir.path().join("17.json"), None)
+                .await
+        );
+        assert!(
+            !check
+                .check_file("Client jar", &client, Some(&"0".repeat(40)))
+                .await
+        );
+        check.add_missing("Java 21 runtime");
+
+        match check.into_result() {
+            Err(AppError::OfflineResourcesMissing(missing)) => {
+                assert_eq!(missing.len(), 3);
+                assert!(missing[0].starts_with("Asset index"));
+                assert!(missing[1].contains("SHA1 mismatch"));
+                assert_eq!(missing[2], "Java 21 runtime");
+            }
+            other => panic!("expected missing resources, got {:?}", other.err()),
+        }
+    }
+}
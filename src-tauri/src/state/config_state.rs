@@ -1,4 +1,4 @@
-use crate::config::{update_custom_game_dir, ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::config::{update_custom_game_dir, update_url_rewrites, ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::Result;
 use crate::state::post_init::PostInitializationHandler;
 use crate::state::profile_state::MemorySettings;
@@ -22,6 +22,104 @@ pub struct Hooks {
     pub post_exit: Option<String>,
 }
 
+impl Hooks {
+    /// Merges profile-level hook overrides on top of these (global) hooks.
+    ///
+    /// A hook set on the profile wins over the global one, an empty string on the
+    /// profile disables the global hook for that profile.
+    pub fn with_overrides(&self, overrides: &Hooks) -> Hooks {
+        fn pick(override_hook: &Option<String>, global_hook: &Option<String>) -> Option<String> {
+            match override_hook {
+                Some(hook) if hook.trim().is_empty() => None,
+                Some(hook) => Some(hook.clone()),
+                None => global_hook.clone(),
+            }
+        }
+
+        Hooks {
+            pre_launch: pick(&overrides.pre_launch, &self.pre_launch),
+            wrapper: pick(&overrides.wrapper, &self.wrapper),
+            post_exit: pick(&overrides.post_exit, &self.post_exit),
+        }
+    }
+}
+
+/// Replaces the start of a URL, e.g. to point Mojang or Maven downloads at a local mirror
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UrlRewriteRule {
+    /// e.g. `https://libraries.minecraft.net/`
+    pub prefix: String,
+    /// e.g. `http://mirror.lan:8080/libraries/`
+    pub replacement: String,
+    /// Matching rules are tried in ascending order, rules with equal order in list order
+    #[serde(default)]
+    pub order: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UrlRewrites {
+    #[serde(default)]
+    pub rules: Vec<UrlRewriteRule>,
+    /// Try the original URL after every matching mirror failed
+    #[serde(default = "default_fallback_to_original")]
+    pub fallback_to_original: bool,
+}
+
+impl Default for UrlRewrites {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            fallback_to_original: default_fallback_to_original(),
+        }
+    }
+}
+
+impl UrlRewrites {
+    /// All URLs to try for `url`, in order. Without a matching rule this is just `url`.
+    pub fn candidates(&self, url: &str) -> Vec<String> {
+        let mut matching: Vec<(usize, &UrlRewriteRule)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| !rule.prefix.is_empty() && url.starts_with(&rule.prefix))
+            .collect();
+        matching.sort_by_key(|(index, rule)| (rule.order, *index));
+
+        let mut candidates: Vec<String> = Vec::new();
+        for (_, rule) in &matching {
+            let rewritten = format!("{}{}", rule.replacement, &url[rule.prefix.len()..]);
+            if !candidates.contains(&rewritten) {
+                candidates.push(rewritten);
+            }
+        }
+
+        if (candidates.is_empty() || self.fallback_to_original)
+            && !candidates.iter().any(|candidate| candidate == url)
+        {
+            candidates.push(url.to_string());
+        }
+        candidates
+    }
+}
+
+fn default_fallback_to_original() -> bool {
+    true
+}
+
+/// options.txt handling shared by all profiles
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GameOptionsSettings {
+    /// Preset written into profiles that don't have an options.txt yet
+    #[serde(default)]
+    pub default_preset_id: Option<Uuid>,
+    /// Copy `sync_keys` from the most recently changed options.txt before every launch
+    #[serde(default)]
+    pub sync_enabled: bool,
+    /// Option keys to sync, a trailing `*` matches a prefix (e.g. `key_*` for all key binds)
+    #[serde(default)]
+    pub sync_keys: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LauncherConfig {
     #[serde(default = "default_config_version")]
@@ -31,8 +129,12 @@ pub struct LauncherConfig {
     // Add more configuration options as needed:
     #[serde(default)]
     pub auto_check_updates: bool,
+    /// Simultaneous download connections, shared by all running installs
     #[serde(default = "default_concurrent_downloads")]
     pub concurrent_downloads: usize,
+    /// Download bandwidth cap in bytes per second, `None` = unlimited
+    #[serde(default)]
+    pub download_speed_limit: Option<u64>,
     #[serde(default = "default_discord_presence")]
     pub enable_discord_presence: bool,
     #[serde(default)]
@@ -55,6 +157,10 @@ pub struct LauncherConfig {
     pub custom_game_directory: Option<PathBuf>,
     #[serde(default)] // Add default modpack installed tracking
     pub default_modpack_installed: bool, // New field for tracking default modpack installation
+    #[serde(default)]
+    pub url_rewrites: UrlRewrites,
+    #[serde(default)]
+    pub game_options: GameOptionsSettings,
 }
 
 fn default_config_version() -> u32 {
@@ -104,6 +210,7 @@ impl Default for LauncherConfig {
             is_experimental: false,
             auto_check_updates: true,
             concurrent_downloads: default_concurrent_downloads(),
+            download_speed_limit: None,
             enable_discord_presence: default_discord_presence(),
             check_beta_channel: false,
             profile_grouping_criterion: default_profile_grouping_criterion(),
@@ -115,6 +222,8 @@ impl Default for LauncherConfig {
             global_memory_settings: default_global_memory_settings(),
             custom_game_directory: None,
             default_modpack_installed: default_default_modpack_installed(), // Initialize to false by default
+            url_rewrites: UrlRewrites::default(),
+            game_options: GameOptionsSettings::default(),
         }
     }
 }
@@ -164,6 +273,7 @@ impl ConfigManager {
                 *config = loaded_config.clone();
 
                 // Update cache
+                update_url_rewrites(loaded_config.url_rewrites);
                 update_custom_game_dir(loaded_config.custom_game_directory);
             }
             Err(e) => {
@@ -229,6 +339,13 @@ impl ConfigManager {
                                     migrated_config.concurrent_downloads = downloads as usize;
                                 }
                             }
+                            if let Some(limit) =
+                                obj.get("download_speed_limit").and_then(|v| v.as_u64())
+                            {
+                                if limit > 0 {
+                                    migrated_config.download_speed_limit = Some(limit);
+                                }
+                            }
                             if let Some(io_limit) =
                                 obj.get("concurrent_io_limit").and_then(|v| v.as_u64())
                             {
@@ -279,6 +396,14 @@ impl ConfigManager {
                                 migrated_config.custom_game_directory =
                                     Some(PathBuf::from(custom_dir_str));
                             }
+
+                            // Migrate URL rewrites
+                            if let Some(rewrites) = obj
+                                .get("url_rewrites")
+                                .and_then(|v| serde_json::from_value::<UrlRewrites>(v.clone()).ok())
+                            {
+                                migrated_config.url_rewrites = rewrites;
+                            }
                         }
 
                         info!("Migration completed, saving migrated configuration");
@@ -290,6 +415,7 @@ impl ConfigManager {
                         self.save_config().await?;
 
                         // Update cache
+                        update_url_rewrites(migrated_config.url_rewrites);
                         update_custom_game_dir(migrated_config.custom_game_directory);
                     }
                     Err(json_err) => {
@@ -356,6 +482,7 @@ impl ConfigManager {
             if current.is_experimental == new_config.is_experimental
                 && current.auto_check_updates == new_config.auto_check_updates
                 && current.concurrent_downloads == new_config.concurrent_downloads
+                && current.download_speed_limit == new_config.download_speed_limit
                 && current.enable_discord_presence == new_config.enable_discord_presence
                 && current.check_beta_channel == new_config.check_beta_channel
                 && current.profile_grouping_criterion == new_config.profile_grouping_criterion
@@ -367,6 +494,8 @@ impl ConfigManager {
                 && current.global_memory_settings.min == new_config.global_memory_settings.min
                 && current.global_memory_settings.max == new_config.global_memory_settings.max
                 && current.custom_game_directory == new_config.custom_game_directory
+                && current.url_rewrites == new_config.url_rewrites
+                && current.game_options == new_config.game_options
             {
                 debug!("No config changes detected, skipping save");
                 false
@@ -393,6 +522,12 @@ impl ConfigManager {
                         current.concurrent_downloads, new_config.concurrent_downloads
                     );
                 }
+                if current.download_speed_limit != new_config.download_speed_limit {
+                    info!(
+                        "Changing download speed limit: {:?} -> {:?}",
+                        current.download_speed_limit, new_config.download_speed_limit
+                    );
+                }
                 if current.enable_discord_presence != new_config.enable_discord_presence {
                     info!(
                         "Changing Discord Rich Presence: {} -> {}",
@@ -458,6 +593,18 @@ impl ConfigManager {
                         current.custom_game_directory, new_config.custom_game_directory
                     );
                 }
+                if current.url_rewrites != new_config.url_rewrites {
+                    info!(
+                        "Changing URL rewrites: {:?} -> {:?}",
+                        current.url_rewrites, new_config.url_rewrites
+                    );
+                }
+                if current.game_options != new_config.game_options {
+                    info!(
+                        "Changing game options settings: {:?} -> {:?}",
+                        current.game_options, new_config.game_options
+                    );
+                }
 
                 // Update config while preserving version
                 *config = LauncherConfig {
@@ -465,6 +612,7 @@ impl ConfigManager {
                     is_experimental: new_config.is_experimental,
                     auto_check_updates: new_config.auto_check_updates,
                     concurrent_downloads: new_config.concurrent_downloads,
+                    download_speed_limit: new_config.download_speed_limit,
                     enable_discord_presence: new_config.enable_discord_presence,
                     check_beta_channel: new_config.check_beta_channel,
                     profile_grouping_criterion: new_config.profile_grouping_criterion.clone(),
@@ -476,6 +624,8 @@ impl ConfigManager {
                     global_memory_settings: new_config.global_memory_settings,
                     custom_game_directory: new_config.custom_game_directory.clone(),
                     default_modpack_installed: new_config.default_modpack_installed,
+                    url_rewrites: new_config.url_rewrites.clone(),
+                    game_options: new_config.game_options.clone(),
                 };
 
                 true
@@ -487,6 +637,7 @@ impl ConfigManager {
             self.save_config().await?;
 
             // Update cache
+            update_url_rewrites(new_config.url_rewrites.clone());
             update_custom_game_dir(new_config.custom_game_directory.clone());
 
             // Update Discord status if it changed
@@ -499,6 +650,14 @@ impl ConfigManager {
                         e
                     );
                 }
+
+                // Limits apply to downloads that are already running
+                state
+                    .download_scheduler
+                    .set_max_connections(new_config.concurrent_downloads);
+                state
+                    .download_scheduler
+                    .set_speed_limit(new_config.download_speed_limit);
             }
         }
 
@@ -508,7 +667,7 @@ impl ConfigManager {
 
 #[async_trait]
 impl PostInitializationHandler for ConfigManager {
-    async fn on_state_ready(&self, _app_handle: Arc<tauri::AppHandle>) -> Result<()> {
+    async fn on_state_ready(&self, _app_handle: Option<Arc<tauri::AppHandle>>) -> Result<()> {
         info!("ConfigManager: on_state_ready called. Loading configuration...");
         self.load_config_internal().await?;
         info!("ConfigManager: Successfully loaded configuration in on_state_ready.");
@@ -519,3 +678,70 @@ impl PostInitializationHandler for ConfigManager {
 pub fn default_config_path() -> PathBuf {
     LAUNCHER_DIRECTORY.root_dir().join(CONFIG_FILENAME)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(prefix: &str, replacement: &str, order: i32) -> UrlRewriteRule {
+        UrlRewriteRule {
+            prefix: prefix.to_string(),
+            replacement: replacement.to_string(),
+            order,
+        }
+    }
+
+    #[test]
+    fn test_profile_hooks_override_or_disable_global_hooks() {
+        let global = Hooks {
+            pre_launch: Some("./sync.sh".to_string()),
+            wrapper: Some("gamemoderun".to_string()),
+            post_exit: Some("./upload-logs.sh".to_string()),
+        };
+        let overrides = Hooks {
+            pre_launch: Some("./profile-sync.sh".to_string()),
+            wrapper: Some("  ".to_string()),
+            post_exit: None,
+        };
+
+        let merged = global.with_overrides(&overrides);
+        assert_eq!(merged.pre_launch.as_deref(), Some("./profile-sync.sh"));
+        // An empty override switches the global hook off for this profile
+        assert_eq!(merged.wrapper, None);
+        assert_eq!(merged.post_exit.as_deref(), Some("./upload-logs.sh"));
+
+        let unchanged = global.with_overrides(&Hooks::default());
+        assert_eq!(unchanged.wrapper.as_deref(), Some("gamemoderun"));
+    }
+
+    #[test]
+    fn test_url_rewrite_candidates_follow_order_and_fallback() {
+        let url = "https://libraries.minecraft.net/com/mojang/brigadier.jar";
+        let mut rewrites = UrlRewrites {
+            rules: vec![
+                rule("https://libraries.minecraft.net/", "http://second/", 1),
+                rule("https://libraries.minecraft.net/", "http://first/", 0),
+                rule("https://maven.fabricmc.net/", "http://fabric/", 0),
+            ],
+            fallback_to_original: true,
+        };
+
+        assert_eq!(
+            rewrites.candidates(url),
+            vec![
+                "http://first/com/mojang/brigadier.jar".to_string(),
+                "http://second/com/mojang/brigadier.jar".to_string(),
+                url.to_string(),
+            ]
+        );
+
+        rewrites.fallback_to_original = false;
+        assert_eq!(rewrites.candidates(url).len(), 2);
+
+        // Without a matching rule the original URL is always used
+        assert_eq!(
+            rewrites.candidates("https://meta.quiltmc.org/v3"),
+            vec!["https://meta.quiltmc.org/v3".to_string()]
+        );
+    }
+}
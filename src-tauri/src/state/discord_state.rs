@@ -1,22 +1,180 @@
 use crate::error::{AppError, Result};
 use crate::state; // Need this for State and ProcessState access
+use crate::state::event_state::{EventPayload, EventType};
+use crate::state::post_init::PostInitializationHandler;
+use crate::state::process_state::ProcessMetadata;
+use async_trait::async_trait;
 use discord_rich_presence::{activity, DiscordIpc, DiscordIpcClient};
 use log::{debug, error, info, warn};
-use std::sync::atomic::AtomicBool;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::Manager; // Keep for app_handle.state()
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
 
 // Discord application ID for GEG
 const DISCORD_APP_ID: &str = "1422293493455388783"; // Replace with actual Discord application ID
 
+// Installations without a new progress event for this long are considered finished or aborted
+const INSTALL_STALE_AFTER_SECS: i64 = 60;
+const DEFAULT_SERVER_PORT: &str = "25565";
+
 // Different states for Discord Rich Presence
 #[derive(Debug, Clone, PartialEq)]
 pub enum DiscordState {
     Idle,
-    // TODO: Add other states like InGame(profile_name), Editing(profile_name) etc.
+    Browsing { profile_name: Option<String> },
+    Installing(InstallActivity),
+    InGame(GameActivity),
+}
+
+/// Where the player currently is inside a running game, read from latest.log
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldActivity {
+    Menu,
+    Singleplayer,
+    Multiplayer { server: Option<String> },
+}
+
+/// A running game as shown in the presence, one per process
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameActivity {
+    pub process_id: Uuid,
+    pub profile_name: Option<String>,
+    pub minecraft_version: Option<String>,
+    pub modloader: Option<String>,
+    pub pack: Option<String>,
+    pub start_timestamp: i64,
+    pub world: WorldActivity,
+}
+
+impl GameActivity {
+    pub fn from_process(metadata: &ProcessMetadata) -> Self {
+        Self {
+            process_id: metadata.id,
+            profile_name: metadata.profile_name.clone(),
+            minecraft_version: metadata.minecraft_version.clone(),
+            modloader: metadata.modloader.clone(),
+            pack: metadata.GEG_pack.clone(),
+            start_timestamp: metadata.start_time.timestamp(),
+            world: WorldActivity::Menu,
+        }
+    }
+}
+
+/// A profile that is being installed before launch
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstallActivity {
+    pub profile_name: Option<String>,
+    pub step: &'static str,
+    /// Rounded down to 10% steps so Discord is not flooded with updates
+    pub progress_percent: Option<u8>,
+}
+
+// Owned texts of an activity, `activity::Activity` only borrows them
+struct PresenceContent {
+    details: Option<String>,
+    state: String,
+    large_text: String,
+    start_timestamp: i64,
+}
+
+impl PresenceContent {
+    fn to_activity(&self) -> activity::Activity<'_> {
+        let icon = "icon_512px"; // Use a consistent icon name
+
+        // TODO: Resolve button issue
+        let download_button =
+            activity::Button::new("Download", "https://grueneeule.de/gaming/launcher");
+
+        let mut activity = activity::Activity::new()
+            .state(&self.state)
+            .assets(
+                activity::Assets::new()
+                    .large_image(icon)
+                    .large_text(&self.large_text),
+            )
+            .timestamps(activity::Timestamps::new().start(self.start_timestamp))
+            .buttons(vec![download_button]);
+        if let Some(details) = &self.details {
+            activity = activity.details(details);
+        }
+        activity
+    }
+}
+
+/// Label for the installation steps that are shown in the presence
+fn install_step_label(event_type: &EventType) -> Option<&'static str> {
+    Some(match event_type {
+        EventType::InstallingJava => "Installing Java",
+        EventType::DownloadingLibraries => "Downloading libraries",
+        EventType::ExtractingNatives => "Extracting natives",
+        EventType::DownloadingAssets | EventType::ReusingMinecraftAssets => "Downloading assets",
+        EventType::CopyingInitialData => "Copying game data",
+        EventType::CopyingGEGAssets | EventType::DownloadingGEGAssets => "Downloading GEG assets",
+        EventType::DownloadingClient => "Downloading Minecraft",
+        EventType::InstallingFabric => "Installing Fabric",
+        EventType::InstallingQuilt => "Installing Quilt",
+        EventType::InstallingForge => "Installing Forge",
+        EventType::InstallingNeoForge => "Installing NeoForge",
+        EventType::PatchingForge => "Patching Forge",
+        EventType::DownloadingMods => "Downloading mods",
+        EventType::SyncingMods => "Syncing mods",
+        EventType::LaunchingMinecraft => "Launching",
+        _ => return None,
+    })
+}
+
+/// Detects joining and leaving worlds in a line of the game's latest.log
+pub fn parse_world_activity(line: &str) -> Option<WorldActivity> {
+    if line.contains("Starting integrated minecraft server version") {
+        return Some(WorldActivity::Singleplayer);
+    }
+    if line.contains("]: Stopping server") {
+        return Some(WorldActivity::Menu);
+    }
+    // "[Render thread/INFO]: Connecting to play.example.net, 25565"
+    let target = line.split_once("]: Connecting to ")?.1.trim();
+    let server = match target.rsplit_once(", ") {
+        Some((host, DEFAULT_SERVER_PORT)) => host.to_string(),
+        Some((host, port)) => format!("{}:{}", host, port),
+        None => target.to_string(),
+    };
+    Some(WorldActivity::Multiplayer {
+        server: (!server.is_empty()).then_some(server),
+    })
+}
+
+/// Picks what to show: the most recently started game, then the most recently
+/// updated installation, then whatever the launcher UI reported
+fn resolve_state(
+    launcher_state: &DiscordState,
+    games: &HashMap<Uuid, GameActivity>,
+    installs: &HashMap<Uuid, (i64, InstallActivity)>,
+    now: i64,
+) -> DiscordState {
+    if let Some(game) = games
+        .values()
+        .max_by_key(|game| (game.start_timestamp, game.process_id))
+    {
+        return DiscordState::InGame(game.clone());
+    }
+    if let Some((_, install)) = installs
+        .values()
+        .filter(|(updated_at, _)| now - updated_at < INSTALL_STALE_AFTER_SECS)
+        .max_by_key(|(updated_at, _)| *updated_at)
+    {
+        return DiscordState::Installing(install.clone());
+    }
+    launcher_state.clone()
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0) // Fallback if time is before epoch
 }
 
 pub struct DiscordManager {
@@ -24,6 +182,12 @@ pub struct DiscordManager {
     current_state: Arc<RwLock<DiscordState>>,
     enabled: Arc<RwLock<bool>>,
     idle_start_timestamp: Arc<RwLock<Option<i64>>>,
+    // State of the launcher UI (idle/browsing), shown when nothing is running
+    launcher_state: Arc<RwLock<DiscordState>>,
+    // Running games by process ID
+    games: Arc<RwLock<HashMap<Uuid, GameActivity>>>,
+    // Running installations by profile ID with the time of their last event
+    installs: Arc<RwLock<HashMap<Uuid, (i64, InstallActivity)>>>,
 }
 
 impl DiscordManager {
@@ -44,6 +208,9 @@ impl DiscordManager {
             current_state: Arc::new(RwLock::new(DiscordState::Idle)),
             enabled: Arc::new(RwLock::new(enabled)),
             idle_start_timestamp: Arc::new(RwLock::new(initial_timestamp)),
+            launcher_state: Arc::new(RwLock::new(DiscordState::Idle)),
+            games: Arc::new(RwLock::new(HashMap::new())),
+            installs: Arc::new(RwLock::new(HashMap::new())),
         };
 
         // Initialize Discord presence if enabled
@@ -131,23 +298,30 @@ impl DiscordManager {
         Ok(())
     }
 
-    /* TODO
-    lass mal brainstormen wie wir das handhaben wollen also imagine wir starten ein profil
-    und dann editieren wir was im mods oder so... dann würden wir ja das spiel quasi überschreiben check?
-     */
+    /// Sets what the launcher UI is doing (idle or browsing a profile).
+    /// Running games and installations take precedence, so editing a profile while
+    /// playing does not overwrite the in-game presence.
     // Public method that catches errors to prevent application crashes
     pub async fn set_state(&self, state: DiscordState, force: bool) -> Result<()> {
-        debug!("Setting Discord state to: {:?}", state);
-        match self.set_state_internal(state, force).await {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                error!(
-                    "Error setting Discord state: {}. Continuing without Discord presence.",
-                    e
-                );
-                // Return Ok to prevent application errors
-                Ok(())
-            }
+        debug!("Setting Discord launcher state to: {:?}", state);
+        *self.launcher_state.write().await = state;
+        self.refresh(force).await;
+        Ok(())
+    }
+
+    // Shows whatever has priority right now; errors are only logged
+    async fn refresh(&self, force: bool) {
+        let state = {
+            let launcher_state = self.launcher_state.read().await;
+            let games = self.games.read().await;
+            let installs = self.installs.read().await;
+            resolve_state(&launcher_state, &games, &installs, unix_now())
+        };
+        if let Err(e) = self.set_state_internal(state, force).await {
+            error!(
+                "Error setting Discord state: {}. Continuing without Discord presence.",
+                e
+            );
         }
     }
 
@@ -188,11 +362,11 @@ impl DiscordManager {
 
         if let Some(client_ref) = client_lock.as_mut() {
             // Create activity for current state (pass self to access timestamp)
-            let activity = self.create_activity_for_state(&state).await; // Make async
+            let presence = self.presence_for_state(&state).await;
 
             debug!("Sending activity to Discord...");
             match client_ref
-                .set_activity(activity)
+                .set_activity(presence.to_activity())
                 .map_err(|e| AppError::DiscordError(format!("Discord activity error: {}", e)))
             {
                 Ok(_) => {
@@ -211,13 +385,16 @@ impl DiscordManager {
 
                     debug!("Reconnection successful, trying to set activity again...");
                     // Try setting activity again after reconnect with a new activity
-                    let new_activity = self.create_activity_for_state(&state).await;
-                    if let Err(retry_e) = client_ref.set_activity(new_activity).map_err(|e| {
-                        AppError::DiscordError(format!(
-                            "Discord activity error after reconnect: {}",
-                            e
-                        ))
-                    }) {
+                    if let Err(retry_e) =
+                        client_ref
+                            .set_activity(presence.to_activity())
+                            .map_err(|e| {
+                                AppError::DiscordError(format!(
+                                    "Discord activity error after reconnect: {}",
+                                    e
+                                ))
+                            })
+                    {
                         error!(
                             "Failed to update Discord Rich Presence after reconnect: {}",
                             retry_e
@@ -236,33 +413,78 @@ impl DiscordManager {
     }
 
     // Make async to allow reading the timestamp lock
-    async fn create_activity_for_state(&self, state: &DiscordState) -> activity::Activity {
-        let icon = "icon_512px"; // Use a consistent icon name
-
-        // TODO: Resolve button issue
-        let download_button =
-            activity::Button::new("Download", "https://grueneeule.de/gaming/launcher");
-        let buttons = vec![download_button];
-
+    async fn presence_for_state(&self, state: &DiscordState) -> PresenceContent {
         debug!("Creating activity for Discord state: {:?}", state);
         match state {
-            DiscordState::Idle => {
+            DiscordState::Idle | DiscordState::Browsing { .. } => {
                 // Read the idle start timestamp
                 let idle_timestamp = *self.idle_start_timestamp.read().await;
 
-                let start_time = idle_timestamp.unwrap_or_else(|| {
+                let start_timestamp = idle_timestamp.unwrap_or_else(|| {
                     warn!("Idle state detected but no idle timestamp found. Using current time.");
-                    SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .map(|d| d.as_secs() as i64)
-                        .unwrap_or(0) // Fallback if time is before epoch
+                    unix_now()
                 });
 
-                activity::Activity::new()
-                    .state("Idling...")
-                    .assets(activity::Assets::new().large_image(icon).large_text("GEG"))
-                    .timestamps(activity::Timestamps::new().start(start_time))
-                    .buttons(buttons) // Include buttons here
+                let text = match state {
+                    DiscordState::Browsing {
+                        profile_name: Some(profile_name),
+                    } => format!("Browsing {}", profile_name),
+                    DiscordState::Browsing { profile_name: None } => {
+                        "Browsing profiles".to_string()
+                    }
+                    _ => "Idling...".to_string(),
+                };
+                PresenceContent {
+                    details: None,
+                    state: text,
+                    large_text: "GEG".to_string(),
+                    start_timestamp,
+                }
+            }
+            DiscordState::Installing(install) => PresenceContent {
+                details: Some(match &install.profile_name {
+                    Some(profile_name) => format!("Preparing {}", profile_name),
+                    None => "Preparing Minecraft".to_string(),
+                }),
+                state: match install.progress_percent {
+                    Some(percent) => format!("{} ({}%)", install.step, percent),
+                    None => install.step.to_string(),
+                },
+                large_text: "GEG".to_string(),
+                start_timestamp: self
+                    .idle_start_timestamp
+                    .read()
+                    .await
+                    .unwrap_or_else(unix_now),
+            },
+            DiscordState::InGame(game) => {
+                let version = match (&game.minecraft_version, &game.modloader) {
+                    (Some(version), Some(loader)) if loader != "vanilla" => {
+                        format!("Minecraft {} ({})", version, loader)
+                    }
+                    (Some(version), _) => format!("Minecraft {}", version),
+                    (None, _) => "Minecraft".to_string(),
+                };
+                let world = match &game.world {
+                    WorldActivity::Menu => "In the menus".to_string(),
+                    WorldActivity::Singleplayer => "Playing singleplayer".to_string(),
+                    WorldActivity::Multiplayer {
+                        server: Some(server),
+                    } => format!("Playing on {}", server),
+                    WorldActivity::Multiplayer { server: None } => {
+                        "Playing multiplayer".to_string()
+                    }
+                };
+
+                PresenceContent {
+                    details: Some(game.profile_name.clone().unwrap_or_else(|| version.clone())),
+                    state: world,
+                    large_text: match &game.pack {
+                        Some(pack) => format!("{} - {}", version, pack),
+                        None => version,
+                    },
+                    start_timestamp: game.start_timestamp,
+                }
             }
         }
     }
@@ -286,12 +508,8 @@ impl DiscordManager {
                 return Ok(());
             }
 
-            // Set initial state and catch errors
-            if let Err(e) = self.set_state_internal(DiscordState::Idle, true).await {
-                error!("Failed to set initial Discord state: {}", e);
-                // Continue without error return
-                return Ok(());
-            }
+            // Set initial state, a game may already be running
+            self.refresh(true).await;
         } else if was_enabled && !enabled {
             // Was enabled, now disabled - disconnect
             debug!("Discord was enabled, now disabled - disconnecting...");
@@ -344,44 +562,140 @@ impl DiscordManager {
             return Ok(());
         }
 
-        // Get the global state and check processes
-        let is_game_running = match state::State::get().await {
+        // Get the global state and sync the running games with the process list
+        match state::State::get().await {
             Ok(state) => {
-                // Access process manager via the successfully retrieved state
                 let processes = state.process_manager.list_processes().await;
-                processes
+                let mut games = self.games.write().await;
+                games.retain(|process_id, _| processes.iter().any(|p| p.id == *process_id));
+                // Games that were already running when the launcher was started
+                for process in processes
                     .iter()
-                    .any(|p| p.state == state::process_state::ProcessState::Running)
+                    .filter(|p| p.state == state::process_state::ProcessState::Running)
+                {
+                    games
+                        .entry(process.id)
+                        .or_insert_with(|| GameActivity::from_process(process));
+                }
             }
             Err(e) => {
-                error!("Focus handling: Failed to get global state using State::get(): {}. Assuming game might be running.", e);
-                // Safety measure: Assume a game *might* be running if we can't get state.
-                true
+                error!("Focus handling: Failed to get global state using State::get(): {}. Keeping known games.", e);
             }
         };
 
-        if !is_game_running {
-            debug!(
-                "Focus handling: DRP enabled, no game running. Ensuring idle timestamp and state."
-            );
+        if self.games.read().await.is_empty() {
+            debug!("Focus handling: DRP enabled, no game running. Ensuring idle timestamp.");
             self.ensure_idle_timestamp_set().await; // Ensure timestamp is set
-                                                    // Force update to Idle state (will use the timestamp we just potentially set)
-            self.set_state_internal(DiscordState::Idle, true).await?;
-        } else {
-            debug!("Focus handling: Game is running, yielding DRP control.");
         }
+        self.refresh(true).await;
 
         Ok(())
     }
 
     /// Notifies the Discord manager that a game process has started.
-    /// This will clear the idle timestamp if Discord is enabled.
-    pub async fn notify_game_start(&self, process_id: Uuid) {
+    /// The game is shown until it exits, the idle timestamp is cleared.
+    pub async fn notify_game_start(&self, metadata: &ProcessMetadata) {
         debug!(
             "Received game start notification for process {}, clearing idle timestamp.",
-            process_id
+            metadata.id
         );
         self.clear_idle_timestamp().await;
+        self.installs.write().await.remove(&metadata.profile_id);
+        self.games
+            .write()
+            .await
+            .insert(metadata.id, GameActivity::from_process(metadata));
+        self.refresh(false).await;
+    }
+
+    /// Notifies the Discord manager that a game process has exited.
+    /// Falls back to another running game, or to the launcher state if none is left.
+    pub async fn notify_game_exit(&self, process_id: Uuid) {
+        if self.games.write().await.remove(&process_id).is_none() {
+            return;
+        }
+        debug!(
+            "Game process {} exited, updating Discord state.",
+            process_id
+        );
+        if self.games.read().await.is_empty() {
+            self.ensure_idle_timestamp_set().await;
+        }
+        self.refresh(false).await;
+    }
+
+    /// Updates where the player is in a running game, detected by the log tailer.
+    pub async fn notify_world_activity(&self, process_id: Uuid, world: WorldActivity) {
+        {
+            let mut games = self.games.write().await;
+            match games.get_mut(&process_id) {
+                Some(game) if game.world != world => {
+                    debug!(
+                        "Process {} world activity changed to {:?}",
+                        process_id, world
+                    );
+                    game.world = world;
+                }
+                _ => return,
+            }
+        }
+        self.refresh(false).await;
+    }
+
+    /// Tracks installation progress and the end of launches from the event stream.
+    pub async fn handle_event(&self, payload: &EventPayload) {
+        let Some(profile_id) = payload.target_id else {
+            return;
+        };
+
+        if matches!(
+            payload.event_type,
+            EventType::LaunchSuccessful | EventType::Error
+        ) {
+            if self.installs.write().await.remove(&profile_id).is_some() {
+                self.refresh(false).await;
+            }
+            return;
+        }
+
+        let Some(step) = install_step_label(&payload.event_type) else {
+            return;
+        };
+        if !self.is_enabled().await {
+            return;
+        }
+
+        let known_name = self
+            .installs
+            .read()
+            .await
+            .get(&profile_id)
+            .map(|(_, install)| install.profile_name.clone());
+        let profile_name = match known_name {
+            Some(name) => name,
+            None => match state::State::get().await {
+                Ok(state) => state
+                    .profile_manager
+                    .get_profile(profile_id)
+                    .await
+                    .ok()
+                    .map(|profile| profile.name),
+                Err(_) => None,
+            },
+        };
+
+        let install = InstallActivity {
+            profile_name,
+            step,
+            progress_percent: payload
+                .progress
+                .map(|progress| ((progress.clamp(0.0, 1.0) * 10.0).floor() as u8) * 10),
+        };
+        self.installs
+            .write()
+            .await
+            .insert(profile_id, (unix_now(), install));
+        self.refresh(false).await;
     }
 
     /// Ensures the idle_start_timestamp is set to the current time if it is None.
@@ -409,3 +723,122 @@ impl DiscordManager {
         }
     }
 }
+
+#[async_trait]
+impl PostInitializationHandler for DiscordManager {
+    async fn on_state_ready(&self, _app_handle: Option<Arc<tauri::AppHandle>>) -> Result<()> {
+        let mut events = state::State::get().await?.event_state.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                // Without events for a while, let stale installations fall back
+                let received = tokio::time::timeout(
+                    Duration::from_secs(INSTALL_STALE_AFTER_SECS as u64),
+                    events.recv(),
+                )
+                .await;
+                let Ok(state) = state::State::get().await else {
+                    continue;
+                };
+                match received {
+                    Ok(Ok(payload)) => state.discord_manager.handle_event(&payload).await,
+                    Ok(Err(RecvError::Lagged(skipped))) => {
+                        debug!("Discord event listener skipped {} events", skipped);
+                    }
+                    Ok(Err(RecvError::Closed)) => break,
+                    Err(_) => state.discord_manager.refresh(false).await,
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_world_activity_from_log_lines() {
+        assert_eq!(
+            parse_world_activity("[12:00:01] [Server thread/INFO]: Starting integrated minecraft server version 1.21.1"),
+            Some(WorldActivity::Singleplayer)
+        );
+        assert_eq!(
+            parse_world_activity("[12:10:00] [Server thread/INFO]: Stopping server"),
+            Some(WorldActivity::Menu)
+        );
+        assert_eq!(
+            parse_world_activity(
+                "[12:00:01] [Render thread/INFO]: Connecting to play.example.net, 25565"
+            ),
+            Some(WorldActivity::Multiplayer {
+                server: Some("play.example.net".to_string())
+            })
+        );
+        assert_eq!(
+            parse_world_activity("[12:00:01] [Render thread/INFO]: Connecting to 10.0.0.5, 25570"),
+            Some(WorldActivity::Multiplayer {
+                server: Some("10.0.0.5:25570".to_string())
+            })
+        );
+        assert_eq!(
+            parse_world_activity("[12:00:01] [Render thread/INFO]: Loaded 1287 recipes"),
+            None
+        );
+    }
+
+    #[test]
+    fn prefers_latest_game_then_fresh_install_then_launcher_state() {
+        let game = |start_timestamp: i64| GameActivity {
+            process_id: Uuid::new_v4(),
+            profile_name: Some(format!("Game {}", start_timestamp)),
+            minecraft_version: Some("1.21.1".to_string()),
+            modloader: Some("fabric".to_string()),
+            pack: None,
+            start_timestamp,
+            world: WorldActivity::Menu,
+        };
+        let install = InstallActivity {
+            profile_name: None,
+            step: "Downloading mods",
+            progress_percent: Some(40),
+        };
+        let launcher_state = DiscordState::Browsing { profile_name: None };
+        let now = 10_000;
+
+        let mut games = HashMap::new();
+        let mut installs = HashMap::new();
+        installs.insert(Uuid::new_v4(), (now - 5, install.clone()));
+        let (older, newer) = (game(100), game(200));
+        games.insert(older.process_id, older.clone());
+        games.insert(newer.process_id, newer.clone());
+
+        assert_eq!(
+            resolve_state(&launcher_state, &games, &installs, now),
+            DiscordState::InGame(newer.clone())
+        );
+
+        // The newer game exits, the other one is still running
+        games.remove(&newer.process_id);
+        assert_eq!(
+            resolve_state(&launcher_state, &games, &installs, now),
+            DiscordState::InGame(older.clone())
+        );
+
+        games.clear();
+        assert_eq!(
+            resolve_state(&launcher_state, &games, &installs, now),
+            DiscordState::Installing(install)
+        );
+        assert_eq!(
+            resolve_state(
+                &launcher_state,
+                &games,
+                &installs,
+                now + INSTALL_STALE_AFTER_SECS
+            ),
+            launcher_state
+        );
+    }
+}
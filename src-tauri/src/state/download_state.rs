@@ -0,0 +1,417 @@
+use crate::error::Result;
+use crate::state::config_state::LauncherConfig;
+use crate::state::event_state::{EventPayload, EventType};
+use crate::state::post_init::PostInitializationHandler;
+use crate::state::State;
+use crate::utils::disk_space_utils;
+use async_trait::async_trait;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+const DEFAULT_MAX_CONNECTIONS: usize = 5;
+/// Downloads a downloader keeps in flight per connection, so existence checks, hashing and
+/// disk space checks of the next files overlap with transfers without running all at once
+const FAN_OUT_PER_CONNECTION: usize = 4;
+const THROUGHPUT_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Order in which waiting downloads get a free connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadPriority {
+    /// Needed before a game can start (libraries, assets, client, mods of a launch)
+    #[default]
+    LaunchBlocking,
+    /// Nobody is waiting on it to play, e.g. content installs and prefetching
+    Background,
+}
+
+/// Snapshot of the scheduler, e.g. for the throughput event
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadStats {
+    pub active_connections: usize,
+    pub max_connections: usize,
+    pub queued_launch_blocking: usize,
+    pub queued_background: usize,
+    pub bytes_per_second: u64,
+    pub speed_limit: Option<u64>,
+    pub total_bytes: u64,
+}
+
+struct ConnectionQueue {
+    active: usize,
+    max: usize,
+    launch_blocking: VecDeque<oneshot::Sender<DownloadPermit>>,
+    background: VecDeque<oneshot::Sender<DownloadPermit>>,
+}
+
+impl ConnectionQueue {
+    fn pop_waiter(&mut self) -> Option<oneshot::Sender<DownloadPermit>> {
+        self.launch_blocking
+            .pop_front()
+            .or_else(|| self.background.pop_front())
+    }
+}
+
+/// Token bucket with one second of burst
+struct Bandwidth {
+    limit: Option<u64>,
+    available: f64,
+    last_refill: Instant,
+}
+
+struct SchedulerInner {
+    // Std mutexes: they are never held across an await
+    queue: Mutex<ConnectionQueue>,
+    bandwidth: Mutex<Bandwidth>,
+    total_bytes: AtomicU64,
+    window_bytes: AtomicU64,
+    last_rate: AtomicU64,
+}
+
+impl SchedulerInner {
+    /// Gives a free connection to the next waiter that is still interested.
+    /// Returns false if nobody is waiting.
+    fn hand_off(self: &Arc<Self>, queue: &mut ConnectionQueue) -> bool {
+        while let Some(waiter) = queue.pop_waiter() {
+            let permit = DownloadPermit {
+                scheduler: Some(self.clone()),
+            };
+            match waiter.send(permit) {
+                Ok(()) => return true,
+                // The waiting download was cancelled; the permit must not release again
+                Err(mut permit) => permit.scheduler = None,
+            }
+        }
+        false
+    }
+
+    fn release(self: &Arc<Self>) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.active > queue.max || !self.hand_off(&mut queue) {
+            queue.active -= 1;
+        }
+    }
+}
+
+/// A download connection slot, released on drop
+pub struct DownloadPermit {
+    scheduler: Option<Arc<SchedulerInner>>,
+}
+
+impl Drop for DownloadPermit {
+    fn drop(&mut self) {
+        if let Some(scheduler) = self.scheduler.take() {
+            scheduler.release();
+        }
+    }
+}
+
+/// Launcher-wide download scheduler.
+/// All downloads share its connection limit and bandwidth cap, no matter how many
+/// profiles are installing at the same time. Launch-blocking work is served first.
+#[derive(Clone)]
+pub struct DownloadScheduler {
+    inner: Arc<SchedulerInner>,
+}
+
+impl Default for DownloadScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DownloadScheduler {
+    pub fn new() -> Self {
+        info!("Initializing DownloadScheduler...");
+        Self {
+            inner: Arc::new(SchedulerInner {
+                queue: Mutex::new(ConnectionQueue {
+                    active: 0,
+                    max: DEFAULT_MAX_CONNECTIONS,
+                    launch_blocking: VecDeque::new(),
+                    background: VecDeque::new(),
+                }),
+                bandwidth: Mutex::new(Bandwidth {
+                    limit: None,
+                    available: 0.0,
+                    last_refill: Instant::now(),
+                }),
+                total_bytes: AtomicU64::new(0),
+                window_bytes: AtomicU64::new(0),
+                last_rate: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Scheduler of the running launcher, `None` before the state is initialized (tests, early startup)
+    pub async fn global() -> Option<Self> {
+        if !State::initialized() {
+            return None;
+        }
+        State::get()
+            .await
+            .ok()
+            .map(|state| state.download_scheduler.clone())
+    }
+
+    /// How many downloads one batch should have in flight at once
+    pub fn fan_out(&self) -> usize {
+        self.inner.queue.lock().unwrap().max * FAN_OUT_PER_CONNECTION
+    }
+
+    /// `fan_out` of the global scheduler, based on the default limit if there is none
+    pub async fn global_fan_out() -> usize {
+        match Self::global().await {
+            Some(scheduler) => scheduler.fan_out(),
+            None => DEFAULT_MAX_CONNECTIONS * FAN_OUT_PER_CONNECTION,
+        }
+    }
+
+    pub fn apply_config(&self, config: &LauncherConfig) {
+        self.set_max_connections(config.concurrent_downloads);
+        self.set_speed_limit(config.download_speed_limit);
+    }
+
+    /// Changes the connection limit. Running downloads keep their connection,
+    /// a lower limit takes effect as they finish.
+    pub fn set_max_connections(&self, max_connections: usize) {
+        let max_connections = max_connections.max(1);
+        let mut queue = self.inner.queue.lock().unwrap();
+        if queue.max != max_connections {
+            info!(
+                "[Downloads] Connection limit {} -> {}",
+                queue.max, max_connections
+            );
+        }
+        queue.max = max_connections;
+        while queue.active < queue.max && self.inner.hand_off(&mut queue) {
+            queue.active += 1;
+        }
+    }
+
+    /// Changes the bandwidth cap in bytes per second, `None` or 0 removes it
+    pub fn set_speed_limit(&self, bytes_per_second: Option<u64>) {
+        let limit = bytes_per_second.filter(|limit| *limit > 0);
+        let mut bandwidth = self.inner.bandwidth.lock().unwrap();
+        if bandwidth.limit != limit {
+            info!(
+                "[Downloads] Speed limit {:?} -> {:?} bytes/s",
+                bandwidth.limit, limit
+            );
+        }
+        bandwidth.limit = limit;
+        bandwidth.available = limit.unwrap_or(0) as f64;
+        bandwidth.last_refill = Instant::now();
+    }
+
+    /// Waits for a free connection. Launch-blocking requests overtake queued background ones.
+    pub async fn acquire(&self, priority: DownloadPriority) -> DownloadPermit {
+        loop {
+            let receiver = {
+                let mut queue = self.inner.queue.lock().unwrap();
+                let nobody_ahead = match priority {
+                    DownloadPriority::LaunchBlocking => queue.launch_blocking.is_empty(),
+                    DownloadPriority::Background => {
+                        queue.launch_blocking.is_empty() && queue.background.is_empty()
+                    }
+                };
+                if queue.active < queue.max && nobody_ahead {
+                    queue.active += 1;
+                    return DownloadPermit {
+                        scheduler: Some(self.inner.clone()),
+                    };
+                }
+
+                let (sender, receiver) = oneshot::channel();
+                match priority {
+                    DownloadPriority::LaunchBlocking => queue.launch_blocking.push_back(sender),
+                    DownloadPriority::Background => queue.background.push_back(sender),
+                }
+                receiver
+            };
+
+            if let Ok(permit) = receiver.await {
+                return permit;
+            }
+        }
+    }
+
+    /// Accounts `bytes` that were just received and waits as long as the speed limit requires
+    pub async fn consume(&self, bytes: u64) {
+        self.inner.total_bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.inner.window_bytes.fetch_add(bytes, Ordering::Relaxed);
+
+        let wait = {
+            let mut bandwidth = self.inner.bandwidth.lock().unwrap();
+            let Some(limit) = bandwidth.limit else {
+                return;
+            };
+            let now = Instant::now();
+            let refill = now.duration_since(bandwidth.last_refill).as_secs_f64() * limit as f64;
+            bandwidth.available = (bandwidth.available + refill).min(limit as f64);
+            bandwidth.last_refill = now;
+            bandwidth.available -= bytes as f64;
+            if bandwidth.available < 0.0 {
+                Duration::from_secs_f64(-bandwidth.available / limit as f64)
+            } else {
+                Duration::ZERO
+            }
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    pub fn stats(&self) -> DownloadStats {
+        let queue = self.inner.queue.lock().unwrap();
+        DownloadStats {
+            active_connections: queue.active,
+            max_connections: queue.max,
+            queued_launch_blocking: queue.launch_blocking.len(),
+            queued_background: queue.background.len(),
+            bytes_per_second: self.inner.last_rate.load(Ordering::Relaxed),
+            speed_limit: self.inner.bandwidth.lock().unwrap().limit,
+            total_bytes: self.inner.total_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Emits the aggregated throughput of all downloads once per interval while anything is downloading
+    async fn report_throughput(self) {
+        let event_id = Uuid::new_v4();
+        let mut reporting = false;
+        let mut interval = tokio::time::interval(THROUGHPUT_REPORT_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let bytes = self.inner.window_bytes.swap(0, Ordering::Relaxed);
+            let rate = (bytes as f64 / THROUGHPUT_REPORT_INTERVAL.as_secs_f64()) as u64;
+            self.inner.last_rate.store(rate, Ordering::Relaxed);
+            let stats = self.stats();
+            let busy = bytes > 0 || stats.active_connections > 0;
+
+            if !busy && !reporting {
+                continue;
+            }
+
+            let Ok(state) = State::get().await else {
+                continue;
+            };
+
+            let message = if busy {
+                format!(
+                    "Downloading at {}/s ({} active, {} queued)",
+                    disk_space_utils::format_bytes(stats.bytes_per_second),
+                    stats.active_connections,
+                    stats.queued_launch_blocking + stats.queued_background
+                )
+            } else {
+                "Downloads idle".to_string()
+            };
+
+            if let Err(e) = state
+                .emit_event(EventPayload {
+                    event_id,
+                    event_type: EventType::DownloadThroughput,
+                    target_id: None,
+                    message,
+                    progress: None,
+                    error: None,
+                })
+                .await
+            {
+                warn!("[Downloads] Failed to emit throughput event: {}", e);
+            }
+
+            if !busy {
+                let _ = state.event_state.complete_event(event_id).await;
+            }
+            reporting = busy;
+        }
+    }
+}
+
+#[async_trait]
+impl PostInitializationHandler for DownloadScheduler {
+    async fn on_state_ready(&self, _app_handle: Option<Arc<tauri::AppHandle>>) -> Result<()> {
+        let state = State::get().await?;
+        let config = state.config_manager.get_config().await;
+        self.apply_config(&config);
+        debug!("DownloadScheduler: applied config limits");
+
+        tokio::spawn(self.clone().report_throughput());
+        info!("DownloadScheduler: Spawned throughput reporter.");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn test_launch_blocking_overtakes_background() {
+        let scheduler = DownloadScheduler::new();
+        scheduler.set_max_connections(1);
+        let running = scheduler.acquire(DownloadPriority::Background).await;
+
+        let mut background = Box::pin(scheduler.acquire(DownloadPriority::Background));
+        let mut launch = Box::pin(scheduler.acquire(DownloadPriority::LaunchBlocking));
+        assert!(background.as_mut().now_or_never().is_none());
+        assert!(launch.as_mut().now_or_never().is_none());
+
+        drop(running);
+        let launch_permit = launch.await;
+        assert!(background.as_mut().now_or_never().is_none());
+
+        drop(launch_permit);
+        let _background_permit = background.await;
+        assert_eq!(scheduler.stats().active_connections, 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit_changes_at_runtime() {
+        let scheduler = DownloadScheduler::new();
+        scheduler.set_max_connections(1);
+        let first = scheduler.acquire(DownloadPriority::LaunchBlocking).await;
+        let mut second = Box::pin(scheduler.acquire(DownloadPriority::LaunchBlocking));
+        assert!(second.as_mut().now_or_never().is_none());
+
+        // Raising the limit wakes the waiting download right away
+        scheduler.set_max_connections(2);
+        let second = second.await;
+        assert_eq!(scheduler.stats().active_connections, 2);
+        assert_eq!(scheduler.fan_out(), 2 * FAN_OUT_PER_CONNECTION);
+
+        // Lowering it lets running downloads finish without handing their slot on
+        scheduler.set_max_connections(1);
+        let mut third = Box::pin(scheduler.acquire(DownloadPriority::LaunchBlocking));
+        drop(first);
+        assert!(third.as_mut().now_or_never().is_none());
+        drop(second);
+        let _third = third.await;
+        assert_eq!(scheduler.stats().active_connections, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_leak_connection() {
+        let scheduler = DownloadScheduler::new();
+        scheduler.set_max_connections(1);
+        let running = scheduler.acquire(DownloadPriority::LaunchBlocking).await;
+        let mut cancelled = Box::pin(scheduler.acquire(DownloadPriority::LaunchBlocking));
+        assert!(cancelled.as_mut().now_or_never().is_none());
+        drop(cancelled);
+
+        drop(running);
+        assert_eq!(scheduler.stats().active_connections, 0);
+        let _permit = scheduler.acquire(DownloadPriority::Background).await;
+    }
+}
@@ -1,12 +1,16 @@
 use crate::error::Result;
+use crate::minecraft::crash_analyzer::CrashDiagnosis;
 use crate::state::process_state::ProcessMetadata;
 use dashmap::DashMap;
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::Emitter;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
+const LOCAL_EVENT_CHANNEL_CAPACITY: usize = 256;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum EventType {
@@ -41,9 +45,11 @@ pub enum EventType {
     MigrationStarted,
     MigrationCompleted,
     MigrationFailed,
+    DownloadThroughput,
+    DependencyIssues,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Debug)]
 pub struct EventPayload {
     pub event_id: Uuid,
     pub event_type: EventType,
@@ -61,6 +67,7 @@ pub struct MinecraftProcessExitedPayload {
     pub success: bool,
     pub process_metadata: Option<ProcessMetadata>,
     pub crash_report_content: Option<String>,
+    pub crash_diagnosis: Option<CrashDiagnosis>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -80,14 +87,18 @@ struct EventInfo {
 pub struct EventState {
     app: Option<Arc<tauri::AppHandle>>,
     active_events: DashMap<Uuid, EventInfo>,
+    /// Local fan-out of every emitted event, used by consumers without a webview (e.g. the CLI).
+    local_sender: broadcast::Sender<EventPayload>,
 }
 
 impl EventState {
     pub fn new(app: Option<Arc<tauri::AppHandle>>) -> Self {
         info!("Initializing EventState...");
+        let (local_sender, _) = broadcast::channel(LOCAL_EVENT_CHANNEL_CAPACITY);
         let state = Self {
             app,
             active_events: DashMap::new(),
+            local_sender,
         };
         info!("Successfully initialized EventState.");
         state
@@ -106,6 +117,9 @@ impl EventState {
             );
         }
 
+        // Forward to local subscribers; an error only means nobody is listening
+        let _ = self.local_sender.send(payload.clone());
+
         // Emit the event to the frontend
         if let Some(app) = &self.app {
             app.emit("state_event", payload)
@@ -115,6 +129,12 @@ impl EventState {
         Ok(())
     }
 
+    /// Subscribes to all events emitted after this call.
+    /// Slow receivers lag and skip events instead of blocking emitters.
+    pub fn subscribe(&self) -> broadcast::Receiver<EventPayload> {
+        self.local_sender.subscribe()
+    }
+
     /// Specific helper to emit a TriggerProfileUpdate event.
     pub async fn trigger_profile_update(&self, profile_id: Uuid) -> Result<()> {
         let payload = EventPayload {
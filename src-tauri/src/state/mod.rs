@@ -1,5 +1,6 @@
 pub mod config_state;
 pub mod discord_state;
+pub mod download_state;
 pub mod event_state;
 pub mod norisk_packs_state;
 pub mod norisk_versions_state;
@@ -4,5 +4,6 @@ use std::sync::Arc;
 
 #[async_trait]
 pub trait PostInitializationHandler {
-    async fn on_state_ready(&self, app_handle: Arc<tauri::AppHandle>) -> Result<()>;
+    /// `app_handle` is `None` when the state runs headless (e.g. the CLI binary).
+    async fn on_state_ready(&self, app_handle: Option<Arc<tauri::AppHandle>>) -> Result<()>;
 }
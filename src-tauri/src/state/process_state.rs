@@ -1,9 +1,13 @@
 use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
+use crate::minecraft::launch::launch_context::{self, LaunchContext};
 use crate::state::event_state::{
     EventPayload, EventState, EventType, MinecraftProcessExitedPayload,
 };
+use crate::state::discord_state::{self, DiscordManager, WorldActivity};
 use crate::state::{self, post_init::PostInitializationHandler, State};
+use crate::utils::session_history_utils::{self, SessionRecord};
+use crate::utils::world_backup_utils::{self, WorldBackupTrigger};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
@@ -35,7 +39,7 @@ const PROCESSES_FILENAME: &str = "processes.json";
 const NOTIFY_EVENT_CHANNEL_BUFFER: usize = 100;
 
 pub struct ProcessManager {
-    app_handle: Arc<tauri::AppHandle>,
+    app_handle: Option<Arc<tauri::AppHandle>>,
     processes: Arc<RwLock<HashMap<Uuid, Process>>>,
     processes_file_path: PathBuf,
     save_lock: Mutex<()>,
@@ -88,7 +92,7 @@ struct CrashReportNotification {
 impl ProcessManager {
     pub async fn new(
         processes_file_path: PathBuf,
-        app_handle: Arc<tauri::AppHandle>,
+        app_handle: Option<Arc<tauri::AppHandle>>,
     ) -> Result<Self> {
         log::info!(
             "Initializing ProcessManager with state file: {:?}",
@@ -106,7 +110,7 @@ impl ProcessManager {
             mpsc::channel::<CrashReportNotification>(NOTIFY_EVENT_CHANNEL_BUFFER);
 
         Ok(Self {
-            app_handle: Arc::clone(&app_handle),
+            app_handle,
             processes,
             processes_file_path,
             save_lock,
@@ -633,7 +637,7 @@ impl ProcessManager {
                     "Notifying Discord manager about game process {} start.",
                     process_id
                 );
-                state.discord_manager.notify_game_start(process_id).await;
+                state.discord_manager.notify_game_start(&metadata).await;
             }
             Err(e) => {
                 log::error!("Failed to get global state to update Discord timestamp for process {}: {}. Discord state might be incorrect.", process_id, e);
@@ -655,7 +659,11 @@ impl ProcessManager {
             let launcher_config = global_state.config_manager.get_config().await;
             if launcher_config.hide_on_process_start {
                 log::info!("Hiding main window as configured (hide_on_process_start = true)");
-                if let Some(main_window) = self.app_handle.get_webview_window("main") {
+                if let Some(main_window) = self
+                    .app_handle
+                    .as_ref()
+                    .and_then(|app| app.get_webview_window("main"))
+                {
                     if let Err(e) = main_window.hide() {
                         log::error!("Failed to hide main window: {}", e);
                     } else {
@@ -688,7 +696,7 @@ impl ProcessManager {
         // Alternative: Der Monitor-Task entfernt nur aus processes, und periodic_process_check räumt Watcher auf.
         // ODER: Der Monitor Task sendet eine "ProcessEnded" Nachricht, auf die der PM reagiert.
 
-        let app_handle_clone_for_monitor = Arc::clone(&self.app_handle);
+        let app_handle_clone_for_monitor = self.app_handle.clone();
 
         tokio::spawn(async move {
             // State holen, um Zugriff auf den ProcessManager für das Stoppen des Watchers zu haben.
@@ -725,6 +733,7 @@ impl ProcessManager {
                 }
             };
 
+            let end_time = Utc::now();
             let exit_code: Option<i32> = exit_status.and_then(|s| s.code());
             let mut success: bool = exit_code == Some(0);
 
@@ -766,6 +775,28 @@ impl ProcessManager {
                 }
             };
 
+            // Known failure signatures for the crash dialog, only for unexpected exits
+            let crash_diagnosis = if success {
+                None
+            } else {
+                match crate::minecraft::crash_analyzer::analyze_profile_crash(
+                    profile_id,
+                    crash_content_for_payload.as_deref(),
+                )
+                .await
+                {
+                    Ok(diagnosis) => Some(diagnosis),
+                    Err(e) => {
+                        log::warn!(
+                            "Crash analysis for process {} failed: {}",
+                            process_id,
+                            e
+                        );
+                        None
+                    }
+                }
+            };
+
             // Event an UI senden
             if let Ok(state) = &state_for_monitor_res {
                 // Re-access state for this block, or ensure it's still valid
@@ -776,6 +807,7 @@ impl ProcessManager {
                     success,
                     process_metadata: exiting_process_metadata_clone,
                     crash_report_content: crash_content_for_payload,
+                    crash_diagnosis,
                 };
                 let specific_payload_json = serde_json::to_string(&specific_payload)
                     .unwrap_or_else(|e| {
@@ -834,6 +866,22 @@ impl ProcessManager {
                 );
             }
 
+            if let Some(process) = &removed_process_metadata {
+                let record = SessionRecord::from_process(
+                    &process.metadata,
+                    end_time,
+                    exit_code,
+                    !success,
+                );
+                if let Err(e) = session_history_utils::append_session(&record).await {
+                    log::error!(
+                        "Failed to record session history for process {}: {}",
+                        process_id,
+                        e
+                    );
+                }
+            }
+
             // Watcher stoppen NACHDEM der Prozess aus der Hauptmap entfernt wurde.
             // periodic_process_check wird den Watcher sonst nicht als verwaist erkennen.
             if let Ok(state) = state_for_monitor_res {
@@ -841,6 +889,7 @@ impl ProcessManager {
                     .process_manager
                     .stop_crash_report_watcher(process_id)
                     .await;
+                state.discord_manager.notify_game_exit(process_id).await;
                 if let Err(e) = state.process_manager.save_processes().await {
                     log::error!("Monitor task for process {} failed to save processes state after removal: {}. In-memory map updated, but persistence failed.", process_id, e);
                 } else {
@@ -858,6 +907,9 @@ impl ProcessManager {
                     &removed_process_metadata,
                 )
                 .await;
+
+                Self::backup_worlds_after_exit_if_enabled(&state, &removed_process_metadata)
+                    .await;
             } else {
                 log::error!("Monitor task for process {} could not get state to stop watcher or save processes.", process_id);
             }
@@ -958,7 +1010,7 @@ impl ProcessManager {
     }
 
     async fn periodic_process_check(
-        app_handle: Arc<tauri::AppHandle>,
+        app_handle: Option<Arc<tauri::AppHandle>>,
         processes_arc: Arc<RwLock<HashMap<Uuid, Process>>>,
         active_watchers_arc: Arc<RwLock<HashMap<Uuid, RecommendedWatcher>>>,
         notify_tx: mpsc::Sender<CrashReportNotification>,
@@ -1051,10 +1103,30 @@ impl ProcessManager {
                     dead_process_ids_from_map
                 );
                 let mut processes_map_writer = processes_arc.write().await;
+                let removed_processes: Vec<Process> = dead_process_ids_from_map
+                    .iter()
+                    .filter_map(|id| processes_map_writer.remove(id))
+                    .collect();
+                drop(processes_map_writer);
                 for id in &dead_process_ids_from_map {
-                    processes_map_writer.remove(id);
+                    global_state.discord_manager.notify_game_exit(*id).await;
+                }
+                // Processes the monitor task did not see exit (killed, crashed, or still running
+                // from before a launcher restart) only end up here, so record their sessions too
+                for process in &removed_processes {
+                    let end_time =
+                        Self::estimated_exit_time(&global_state, &process.metadata).await;
+                    // No exit code was observed, a normal exit can't be told from a crash
+                    let record =
+                        SessionRecord::from_unobserved_process(&process.metadata, end_time);
+                    if let Err(e) = session_history_utils::append_session(&record).await {
+                        log::error!(
+                            "Periodic check: Failed to record session history for process {}: {}",
+                            process.metadata.id,
+                            e
+                        );
+                    }
                 }
-                drop(processes_map_writer);
                 // Speichere Änderungen an der Prozessliste
                 if let Err(e) = global_state.process_manager.save_processes().await {
                     log::error!(
@@ -1094,6 +1166,33 @@ impl ProcessManager {
         }
     }
 
+    /// Exit time of a process that was found dead: the last write to its `latest.log`,
+    /// or now if that is unknown. The launcher may have been closed while the game ran.
+    async fn estimated_exit_time(state: &State, metadata: &ProcessMetadata) -> DateTime<Utc> {
+        let now = Utc::now();
+        let Ok(instance_path) = state
+            .profile_manager
+            .get_profile_instance_path(metadata.profile_id)
+            .await
+        else {
+            return now;
+        };
+        match async_fs::metadata(instance_path.join("logs").join("latest.log"))
+            .await
+            .and_then(|log_metadata| log_metadata.modified())
+        {
+            Ok(modified) => {
+                let modified: DateTime<Utc> = modified.into();
+                if modified > metadata.start_time && modified < now {
+                    modified
+                } else {
+                    now
+                }
+            }
+            Err(_) => now,
+        }
+    }
+
     async fn periodic_log_tailer(processes_arc: Arc<RwLock<HashMap<Uuid, Process>>>) {
         let mut interval = interval(Duration::from_secs(1)); // Log-Tailing kann weiterhin häufig sein
         log::info!("Starting periodic log tailing task (crash reports handled by notify).");
@@ -1164,6 +1263,7 @@ impl ProcessManager {
                         process_id,
                         &last_pos_mutex,
                         &app_state.event_state, // Verwende app_state Variable
+                        &app_state.discord_manager,
                     )
                     .await
                     {
@@ -1185,6 +1285,7 @@ impl ProcessManager {
         process_id: Uuid,
         last_pos_mutex: &Arc<Mutex<u64>>,
         event_state: &EventState,
+        discord_manager: &DiscordManager,
     ) -> Result<()> {
         let current_metadata = tokio::fs::metadata(log_path).await.map_err(AppError::Io)?;
         let current_size = current_metadata.len();
@@ -1213,6 +1314,8 @@ impl ProcessManager {
         }
 
         let mut bytes_actually_read: u64 = 0;
+        // Last world join/leave seen in this batch, for the Discord presence
+        let mut world_activity: Option<WorldActivity> = None;
 
         if current_size > read_from_pos {
             log::trace!(
@@ -1248,6 +1351,10 @@ impl ProcessManager {
                         let trimmed_line = line_string.trim_end();
 
                         if !trimmed_line.is_empty() {
+                            if let Some(activity) = discord_state::parse_world_activity(trimmed_line) {
+                                world_activity = Some(activity);
+                            }
+
                             // Mask sensitive information before sending to UI
                             let safe_line = crate::utils::security_utils::mask_sensitive_data(trimmed_line);
 
@@ -1319,6 +1426,13 @@ impl ProcessManager {
             process_id,
             *last_pos_guard
         );
+        drop(last_pos_guard);
+
+        if let Some(activity) = world_activity {
+            discord_manager
+                .notify_world_activity(process_id, activity)
+                .await;
+        }
 
         Ok(())
     }
@@ -1413,6 +1527,22 @@ impl ProcessManager {
         self.launching_processes.contains_key(&profile_id)
     }
 
+    /// Checks if a profile is launching or has a game process that has not exited yet
+    pub async fn is_profile_running(&self, profile_id: Uuid) -> bool {
+        if self.has_launching_process(profile_id) {
+            return true;
+        }
+        self.get_process_metadata_by_profile(profile_id)
+            .await
+            .iter()
+            .any(|metadata| {
+                matches!(
+                    metadata.state,
+                    ProcessState::Starting | ProcessState::Running | ProcessState::Stopping
+                )
+            })
+    }
+
     // Helper function to execute post-exit hook with flatter structure
     async fn execute_post_exit_hook_if_needed(
         success: bool,
@@ -1481,21 +1611,17 @@ impl ProcessManager {
             }
         };
 
-        // Execute hook without waiting for completion (fire and forget)
-        let hook_command = hook.clone();
-        let game_dir_clone = game_directory.clone();
-        tokio::spawn(async move {
-            let mut cmd = hook_command.split(' ');
-            let command = match cmd.next() {
+        let context = LaunchContext::new(&profile, &game_directory)
+            .with_pid(removed_process.metadata.pid);
+        let mut command =
+            match launch_context::hook_command(hook, &game_directory, &context) {
                 Some(c) => c,
                 None => return,
             };
 
-            match std::process::Command::new(command)
-                .args(cmd.collect::<Vec<&str>>())
-                .current_dir(&game_dir_clone)
-                .spawn()
-            {
+        // Execute hook without waiting for completion (fire and forget)
+        tokio::spawn(async move {
+            match command.spawn() {
                 Ok(_) => {
                     log::info!(
                         "Post-exit hook spawned successfully for process {}",
@@ -1513,9 +1639,40 @@ impl ProcessManager {
         });
     }
 
+    // Snapshots the profile's worlds after the game exited (also after crashes)
+    async fn backup_worlds_after_exit_if_enabled(
+        state: &State,
+        removed_process_metadata: &Option<Process>,
+    ) {
+        let Some(process) = removed_process_metadata else {
+            return;
+        };
+        let profile = match state
+            .profile_manager
+            .get_profile(process.metadata.profile_id)
+            .await
+        {
+            Ok(profile) => profile,
+            Err(_) => return,
+        };
+        if !profile.settings.world_backups.after_exit {
+            return;
+        }
+
+        // Archiving large worlds takes a while, don't hold up the monitor task
+        tokio::spawn(async move {
+            world_backup_utils::backup_profile_worlds(&profile, WorldBackupTrigger::AfterExit)
+                .await;
+        });
+    }
+
     // Private helper to schedule the auto-opening of the log window
     fn schedule_auto_open_log_window(&self, process_id: Uuid) {
-        let app_handle_clone = Arc::clone(&self.app_handle);
+        // Without an app handle (headless mode) there is no window to open
+        let app_handle_clone = match &self.app_handle {
+            Some(app_handle) => Arc::clone(app_handle),
+            None => return,
+        };
 
         tokio::spawn(async move {
             match crate::state::State::get().await {
@@ -1560,7 +1717,7 @@ impl ProcessManager {
 
 #[async_trait]
 impl PostInitializationHandler for ProcessManager {
-    async fn on_state_ready(&self, app_handle: Arc<tauri::AppHandle>) -> Result<()> {
+    async fn on_state_ready(&self, app_handle: Option<Arc<tauri::AppHandle>>) -> Result<()> {
         log::info!("ProcessManager: on_state_ready called. Performing post-initialization tasks.");
 
         // For process_crash_report_events: The task requires the receive end of an mpsc channel.
@@ -1575,7 +1732,7 @@ impl PostInitializationHandler for ProcessManager {
 
         let manager_clone_periodic_check_processes = Arc::clone(&self.processes);
         let manager_clone_periodic_check_watchers = Arc::clone(&self.active_watchers);
-        let app_handle_for_periodic_check = Arc::clone(&app_handle);
+        let app_handle_for_periodic_check = app_handle.clone();
         let notify_tx_for_periodic_check = self.notify_event_tx.clone();
 
         tokio::spawn(Self::periodic_process_check(
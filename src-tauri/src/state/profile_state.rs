@@ -2,9 +2,11 @@ use crate::config::LAUNCHER_DIRECTORY;
 use crate::error::AppError;
 use crate::error::Result;
 use crate::integrations::modrinth::{self, ModrinthDependencyType, ModrinthVersion};
+use crate::state::config_state::Hooks;
 use crate::state::post_init::PostInitializationHandler;
 use crate::utils::backup_utils::{self, BackupConfig, safe_write_with_backup};
 use crate::utils::hash_utils;
+use crate::utils::jar_metadata::JarModMetadata;
 use crate::utils::mc_utils;
 use crate::utils::path_utils;
 use async_trait::async_trait;
@@ -31,6 +33,9 @@ pub enum ModSource {
     Url {
         url: String,
         file_name: Option<String>,
+        /// Optional SHA1 the download has to match (hash pinning)
+        #[serde(default)]
+        file_hash_sha1: Option<String>,
     },
     Maven {
         coordinates: String,
@@ -62,8 +67,8 @@ pub struct Mod {
     pub id: Uuid,
     pub source: ModSource,
     pub enabled: bool,
-    pub display_name: Option<String>, // Optional: Could be inferred from mod metadata later
-    pub version: Option<String>,      // Optional: Could be inferred
+    pub display_name: Option<String>, // Platform name; URL/Maven mods: read from the cached jar
+    pub version: Option<String>,      // Platform version; URL/Maven mods: read from the cached jar
     pub game_versions: Option<Vec<String>>, // Changed: List of supported Minecraft versions
     pub file_name_override: Option<String>, // Optional: To store the actual filename on disk if needed
     pub associated_loader: Option<ModLoader>, // Optional: Tracks the loader this mod was originally intended for
@@ -76,6 +81,30 @@ pub struct Mod {
     pub updates_enabled: bool,
 }
 
+impl Mod {
+    /// Takes name and version from the jar's metadata, returns whether anything changed.
+    /// Unresolved placeholders like `${version}` are ignored.
+    pub fn apply_jar_metadata(&mut self, jar_mod: &JarModMetadata) -> bool {
+        let mut changed = false;
+        if self.display_name.is_none() {
+            self.display_name = Some(
+                jar_mod
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| jar_mod.mod_id.clone()),
+            );
+            changed = true;
+        }
+        if let Some(version) = jar_mod.version.as_ref().filter(|v| !v.contains("${")) {
+            if self.version.as_ref() != Some(version) {
+                self.version = Some(version.clone());
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
 // New struct to uniquely identify a GEG Pack mod within a specific context
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct GEGModIdentifier {
@@ -257,6 +286,102 @@ pub struct ProfileSettings {
     pub custom_jvm_args: Option<String>, // Zusätzliche JVM-Argumente als String
     #[serde(default)]
     pub quick_play_path: Option<String>, // Quick Play Pfad für direkten Welt-/Server-Start
+    #[serde(default)]
+    pub hooks: Hooks, // Hook-Overrides, fallen auf die globalen Hooks zurück
+    #[serde(default)]
+    pub env_vars: HashMap<String, String>, // Zusätzliche Umgebungsvariablen für den Spielprozess
+    #[serde(default)]
+    pub jvm_flags_preset: JvmFlagsPreset, // GC-Flags, die vor den benutzerdefinierten JVM-Args gesetzt werden
+    #[serde(default)]
+    pub world_backups: WorldBackupSettings, // Automatische Welt-Backups und deren Aufbewahrung
+    #[serde(default)]
+    pub snapshots: ProfileSnapshotSettings, // Snapshots der Mod-Liste vor Updates und Modpack-Wechseln
+}
+
+/// When worlds of a profile are snapshotted automatically and how many snapshots are kept.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WorldBackupSettings {
+    #[serde(default)]
+    pub before_launch: bool,
+    #[serde(default)]
+    pub after_exit: bool,
+    #[serde(default = "default_true")]
+    pub before_modpack_switch: bool,
+    /// Automatic snapshots kept per world, manual snapshots are never pruned
+    #[serde(default = "default_world_backup_retention")]
+    pub max_backups_per_world: usize,
+    #[serde(default)]
+    pub format: WorldBackupFormat,
+}
+
+fn default_world_backup_retention() -> usize {
+    5
+}
+
+impl Default for WorldBackupSettings {
+    fn default() -> Self {
+        Self {
+            before_launch: false,
+            after_exit: false,
+            before_modpack_switch: true,
+            max_backups_per_world: default_world_backup_retention(),
+            format: WorldBackupFormat::default(),
+        }
+    }
+}
+
+/// What profile snapshots contain besides the mod list and how many automatic ones are kept.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProfileSnapshotSettings {
+    /// Instance-relative files and folders copied into each snapshot
+    #[serde(default = "default_snapshot_config_paths")]
+    pub config_paths: Vec<String>,
+    /// Automatic snapshots kept per profile, manual snapshots are never pruned
+    #[serde(default = "default_snapshot_retention")]
+    pub max_automatic: usize,
+}
+
+fn default_snapshot_config_paths() -> Vec<String> {
+    vec!["config".to_string(), "defaultconfigs".to_string()]
+}
+
+fn default_snapshot_retention() -> usize {
+    10
+}
+
+impl Default for ProfileSnapshotSettings {
+    fn default() -> Self {
+        Self {
+            config_paths: default_snapshot_config_paths(),
+            max_automatic: default_snapshot_retention(),
+        }
+    }
+}
+
+/// Archive format of world snapshots
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldBackupFormat {
+    #[default]
+    Zip,
+    TarZst,
+}
+
+/// Garbage collector flag sets the launcher can add in front of the user's JVM arguments.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JvmFlagsPreset {
+    /// No GC flags, the JVM defaults apply
+    None,
+    /// G1 tuning used by the vanilla launcher
+    #[default]
+    VanillaG1,
+    /// Aikar's G1 flags
+    Aikar,
+    /// Generational ZGC, requires Java 21+
+    ZgcGenerational,
+    /// Shenandoah, requires Java 12+ (OpenJDK builds)
+    Shenandoah,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -294,6 +419,7 @@ pub struct CustomModInfo {
     pub filename: String, // Base filename (e.g., OptiFine.jar)
     pub is_enabled: bool, // True if the file doesn't end with .disabled
     pub path: PathBuf,    // Full path to the file in custom_mods directory
+    pub metadata: Option<crate::utils::jar_metadata::JarMetadata>, // Only filled by the get_custom_mods command
 }
 
 impl Profile {
@@ -1423,6 +1549,36 @@ impl ProfileManager {
     }
 
     // Set the enabled status of a specific mod within a profile
+    /// Fills in name and version of mods without platform metadata from their jars.
+    /// A name that is already set is kept, the version always follows the jar.
+    pub async fn apply_jar_metadata(
+        &self,
+        profile_id: Uuid,
+        metadata: &[(Uuid, JarModMetadata)],
+    ) -> Result<()> {
+        let mut profiles = self.profiles.write().await;
+        let profile = profiles
+            .get_mut(&profile_id)
+            .ok_or(AppError::ProfileNotFound(profile_id))?;
+
+        let mut changed = false;
+        for (mod_id, jar_mod) in metadata {
+            if let Some(mod_info) = profile.mods.iter_mut().find(|m| m.id == *mod_id) {
+                changed |= mod_info.apply_jar_metadata(jar_mod);
+            }
+        }
+        drop(profiles);
+
+        if changed {
+            info!(
+                "Updated mod names and versions of profile {} from jar metadata",
+                profile_id
+            );
+            self.save_profiles().await?;
+        }
+        Ok(())
+    }
+
     pub async fn set_mod_enabled(
         &self,
         profile_id: Uuid,
@@ -2319,6 +2475,7 @@ impl ProfileManager {
                             filename: base_filename,
                             is_enabled,
                             path: path.clone(),
+                            metadata: None,
                         });
                     } else {
                         log::trace!(
@@ -3139,7 +3296,7 @@ impl ProfileManager {
 
 #[async_trait]
 impl PostInitializationHandler for ProfileManager {
-    async fn on_state_ready(&self, _app_handle: Arc<tauri::AppHandle>) -> Result<()> {
+    async fn on_state_ready(&self, _app_handle: Option<Arc<tauri::AppHandle>>) -> Result<()> {
         info!("ProfileManager: on_state_ready called. Loading profiles...");
         // PRIORITY 0: Create backup BEFORE ANYTHING else (including loading)
         info!("ProfileManager: Creating pre-load backup of profiles.json...");
@@ -3203,13 +3360,28 @@ pub fn get_profile_mod_filename(source: &ModSource) -> crate::error::Result<Stri
         ModSource::Modrinth { file_name, .. } => Ok(file_name.clone()),
         ModSource::CurseForge { file_name, .. } => Ok(file_name.clone()),
         ModSource::Local { file_name } => Ok(file_name.clone()),
-        ModSource::Url { file_name, url } => file_name.clone().ok_or_else(|| {
-            crate::error::AppError::Other(format!("Filename missing for URL mod source: {}", url))
-        }),
-        ModSource::Maven { coordinates, .. } => Err(crate::error::AppError::Other(format!(
-            "Cannot determine filename for profile Maven mod source: {}",
-            coordinates
-        ))),
+        ModSource::Url { file_name, url, .. } => file_name
+            .clone()
+            .or_else(|| {
+                // Fall back to the last path segment of the URL
+                url::Url::parse(url).ok().and_then(|parsed| {
+                    parsed
+                        .path_segments()
+                        .and_then(|mut segments| segments.next_back())
+                        .filter(|segment| !segment.is_empty())
+                        .map(str::to_string)
+                })
+            })
+            .ok_or_else(|| {
+                crate::error::AppError::Other(format!(
+                    "Filename missing for URL mod source: {}",
+                    url
+                ))
+            }),
+        ModSource::Maven { coordinates, .. } => {
+            crate::minecraft::downloads::maven_artifact::MavenCoordinates::parse(coordinates)
+                .map(|coordinates| coordinates.file_name())
+        }
         ModSource::Embedded { name } => Err(crate::error::AppError::Other(format!(
             "Cannot get filename for embedded mod source: {}",
             name
@@ -3217,6 +3389,27 @@ pub fn get_profile_mod_filename(source: &ModSource) -> crate::error::Result<Stri
     }
 }
 
+/// Name of a mod's file in the shared mod cache. Same as the profile file name, except where
+/// that name alone is not unique: Maven artifacts get their group as prefix, URL mods a hash
+/// of their URL.
+pub fn get_mod_cache_filename(source: &ModSource) -> crate::error::Result<String> {
+    match source {
+        ModSource::Maven { coordinates, .. } => {
+            crate::minecraft::downloads::maven_artifact::MavenCoordinates::parse(coordinates)
+                .map(|coordinates| coordinates.cache_file_name())
+        }
+        ModSource::Url { url, .. } => {
+            let url_hash = crate::utils::hash_utils::calculate_sha1_from_bytes(url.as_bytes());
+            Ok(format!(
+                "{}-{}",
+                &url_hash[..12],
+                get_profile_mod_filename(source)?
+            ))
+        }
+        _ => get_profile_mod_filename(source),
+    }
+}
+
 pub fn default_profile_path() -> PathBuf {
     // Check cache first (same system as meta_dir)
     if let Ok(guard) = crate::config::CUSTOM_GAME_DIR_CACHE.read() {
@@ -3384,6 +3577,11 @@ impl Default for ProfileSettings {
             extra_game_args: Vec::new(),
             custom_jvm_args: None, // Standardmäßig keine benutzerdefinierten JVM-Args
             quick_play_path: None,
+            hooks: Hooks::default(),
+            env_vars: HashMap::new(),
+            jvm_flags_preset: JvmFlagsPreset::default(),
+            world_backups: WorldBackupSettings::default(),
+            snapshots: ProfileSnapshotSettings::default(),
         }
     }
 }
@@ -3405,3 +3603,54 @@ impl Default for WindowSize {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_name_and_version_from_jar_metadata() {
+        let mut mod_info = Mod {
+            id: Uuid::new_v4(),
+            source: ModSource::Maven {
+                coordinates: "com.example:example-mod:1.2.0".to_string(),
+                repository_url: None,
+            },
+            enabled: true,
+            display_name: None,
+            version: None,
+            game_versions: None,
+            file_name_override: None,
+            associated_loader: None,
+            modpack_origin: None,
+            updates_enabled: true,
+        };
+        let mut jar_mod = JarModMetadata {
+            mod_id: "example".to_string(),
+            name: Some("Example Mod".to_string()),
+            version: Some("1.2.0".to_string()),
+            description: None,
+            loader: ModLoader::Fabric,
+            minecraft_versions: None,
+            dependencies: Vec::new(),
+            provides: Vec::new(),
+            icon: None,
+        };
+
+        assert!(mod_info.apply_jar_metadata(&jar_mod));
+        assert_eq!(mod_info.display_name.as_deref(), Some("Example Mod"));
+        assert_eq!(mod_info.version.as_deref(), Some("1.2.0"));
+        assert!(!mod_info.apply_jar_metadata(&jar_mod));
+
+        // A name set by the user stays, the version follows the jar
+        mod_info.display_name = Some("My Mod".to_string());
+        jar_mod.version = Some("1.3.0".to_string());
+        assert!(mod_info.apply_jar_metadata(&jar_mod));
+        assert_eq!(mod_info.display_name.as_deref(), Some("My Mod"));
+        assert_eq!(mod_info.version.as_deref(), Some("1.3.0"));
+
+        jar_mod.version = Some("${version}".to_string());
+        assert!(!mod_info.apply_jar_metadata(&jar_mod));
+        assert_eq!(mod_info.version.as_deref(), Some("1.3.0"));
+    }
+}
@@ -3,6 +3,7 @@ use crate::error::{AppError, Result};
 use crate::minecraft::minecraft_auth::MinecraftAuthStore;
 use crate::state::config_state::ConfigManager;
 use crate::state::discord_state::DiscordManager;
+use crate::state::download_state::DownloadScheduler;
 use crate::state::event_state::{EventPayload, EventState};
 use crate::state::norisk_packs_state::{default_norisk_packs_path, NoriskPackManager};
 use crate::state::norisk_versions_state::{default_norisk_versions_path, NoriskVersionManager};
@@ -28,19 +29,32 @@ pub struct State {
     pub config_manager: ConfigManager,
     pub skin_manager: SkinManager,
     pub discord_manager: DiscordManager,
+    pub download_scheduler: DownloadScheduler,
     pub io_semaphore: Arc<Semaphore>,
 }
 
 impl State {
     // Initialize the global state
     pub async fn init(app: Arc<tauri::AppHandle>) -> Result<()> {
+        Self::init_internal(Some(app)).await
+    }
+
+    /// Initializes the global state without a Tauri app handle.
+    /// Events are only delivered to local subscribers (see `EventState::subscribe`),
+    /// and window related behaviour (hiding, log windows) is skipped.
+    pub async fn init_headless() -> Result<()> {
+        Self::init_internal(None).await
+    }
+
+    async fn init_internal(app: Option<Arc<tauri::AppHandle>>) -> Result<()> {
         let initial_state_arc = LAUNCHER_STATE
             .get_or_try_init(|| async {
                 log::info!("State::init - Starting primary initialization of managers (Phase 1 - Lightweight Instantiation)...");
                 let config_manager = ConfigManager::new()?;
                 let discord_manager = DiscordManager::new(false).await?;
                 let io_semaphore = Arc::new(Semaphore::new(10));
-                let event_state = EventState::new(Some(app.clone()));
+                let download_scheduler = DownloadScheduler::new();
+                let event_state = EventState::new(app.clone());
                 let minecraft_account_manager_v2 = MinecraftAuthStore::new().await?;
                 let GEG_pack_manager = NoriskPackManager::new(default_norisk_packs_path())?;
                 let GEG_version_manager = NoriskVersionManager::new(default_norisk_versions_path())?;
@@ -60,6 +74,7 @@ impl State {
                     config_manager,
                     skin_manager,
                     discord_manager,
+                    download_scheduler,
                     io_semaphore,
                 }))
             })
@@ -73,6 +88,12 @@ impl State {
             .await?;
         log::info!("State::init - ConfigManager post-initialization complete.");
 
+        initial_state_arc
+            .download_scheduler
+            .on_state_ready(app.clone())
+            .await?;
+        log::info!("State::init - DownloadScheduler post-initialization complete.");
+
         let loaded_config = initial_state_arc.config_manager.get_config().await;
 
         if initial_state_arc.io_semaphore.available_permits() != loaded_config.concurrent_io_limit {
@@ -92,6 +113,12 @@ impl State {
             loaded_config.enable_discord_presence
         );
 
+        initial_state_arc
+            .discord_manager
+            .on_state_ready(app.clone())
+            .await?;
+        log::info!("State::init - DiscordManager post-initialization complete.");
+
         initial_state_arc
             .GEG_version_manager
             .on_state_ready(app.clone())
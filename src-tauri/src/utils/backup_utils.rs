@@ -34,12 +34,18 @@ pub fn get_backup_root() -> PathBuf {
     LAUNCHER_DIRECTORY.meta_dir().join("backups")
 }
 
-/// Ensure backup root (and optional category) exists
-async fn ensure_backup_dir(category: Option<&str>) -> Result<PathBuf> {
+/// Backup directory of a category: <backup_root>/<category>
+fn backup_dir(category: Option<&str>) -> PathBuf {
     let mut base = get_backup_root();
     if let Some(cat) = category {
         base = base.join(cat);
     }
+    base
+}
+
+/// Ensure backup root (and optional category) exists
+async fn ensure_backup_dir(category: Option<&str>) -> Result<PathBuf> {
+    let base = backup_dir(category);
     fs::create_dir_all(&base).await.map_err(AppError::Io)?;
     Ok(base)
 }
@@ -50,6 +56,15 @@ pub async fn create_backup<P: AsRef<Path>>(
     source_path: P,
     category: Option<&str>,
     config: &BackupConfig,
+) -> Result<PathBuf> {
+    create_backup_in(&backup_dir(category), source_path, config).await
+}
+
+/// Like `create_backup`, but into an explicit backup directory instead of a category
+pub async fn create_backup_in<P: AsRef<Path>>(
+    backup_base: &Path,
+    source_path: P,
+    config: &BackupConfig,
 ) -> Result<PathBuf> {
     let source_path = source_path.as_ref();
 
@@ -60,7 +75,7 @@ pub async fn create_backup<P: AsRef<Path>>(
         )));
     }
 
-    let backup_base = ensure_backup_dir(category).await?;
+    fs::create_dir_all(backup_base).await.map_err(AppError::Io)?;
 
     // Generate backup filename with Unix timestamp and UUID
     let timestamp: DateTime<Utc> = Utc::now();
@@ -83,7 +98,7 @@ pub async fn create_backup<P: AsRef<Path>>(
     let backup_path = backup_base.join(backup_filename);
 
     // Check if we should skip backup due to minimum interval
-    if let Some(last_backup) = get_last_backup_time(source_path, category).await {
+    if let Some(last_backup) = get_last_backup_time(source_path, backup_base).await {
         let elapsed = timestamp.signed_duration_since(last_backup).num_seconds();
         if elapsed < config.min_backup_interval_seconds as i64 {
             info!(
@@ -120,15 +135,13 @@ pub async fn create_backup<P: AsRef<Path>>(
     fs::write(&metadata_path, metadata.as_bytes()).await.map_err(AppError::Io)?;
 
     // Cleanup old backups
-    cleanup_old_backups(source_path, category, config).await?;
+    cleanup_old_backups_in(backup_base, source_path, config).await?;
 
     Ok(backup_path)
 }
 
 /// Gets the timestamp of the last backup for a file
-async fn get_last_backup_time(source_path: &Path, category: Option<&str>) -> Option<DateTime<Utc>> {
-    let backup_base = ensure_backup_dir(category).await.ok()?;
-
+async fn get_last_backup_time(source_path: &Path, backup_base: &Path) -> Option<DateTime<Utc>> {
     let original_name = source_path
         .file_name()
         .and_then(OsStr::to_str)
@@ -136,7 +149,7 @@ async fn get_last_backup_time(source_path: &Path, category: Option<&str>) -> Opt
 
     let mut latest_time: Option<DateTime<Utc>> = None;
 
-    if let Ok(mut entries) = fs::read_dir(&backup_base).await {
+    if let Ok(mut entries) = fs::read_dir(backup_base).await {
         while let Ok(Some(entry)) = entries.next_entry().await {
             let path = entry.path();
             if let Some(filename) = path.file_name().and_then(OsStr::to_str) {
@@ -177,6 +190,15 @@ pub async fn cleanup_old_backups(
     config: &BackupConfig,
 ) -> Result<()> {
     let backup_base = ensure_backup_dir(category).await?;
+    cleanup_old_backups_in(&backup_base, source_path, config).await
+}
+
+/// Like `cleanup_old_backups`, but for an explicit backup directory
+async fn cleanup_old_backups_in(
+    backup_base: &Path,
+    source_path: &Path,
+    config: &BackupConfig,
+) -> Result<()> {
     let now = Utc::now();
 
     let original_name = source_path
@@ -187,7 +209,7 @@ pub async fn cleanup_old_backups(
     // Collect all backup files for this source with their metadata
     let mut backup_files = Vec::new();
 
-    if let Ok(mut entries) = fs::read_dir(&backup_base).await {
+    if let Ok(mut entries) = fs::read_dir(backup_base).await {
         while let Ok(Some(entry)) = entries.next_entry().await {
             let path = entry.path();
             if let Some(filename) = path.file_name().and_then(OsStr::to_str) {
@@ -313,8 +335,16 @@ pub async fn list_backups<P: AsRef<Path>>(
     source_path: P,
     category: Option<&str>,
 ) -> Result<Vec<(PathBuf, DateTime<Utc>)>> {
-    let source_path = source_path.as_ref();
     let backup_base = ensure_backup_dir(category).await?;
+    list_backups_in(&backup_base, source_path).await
+}
+
+/// Like `list_backups`, but for an explicit backup directory
+pub async fn list_backups_in<P: AsRef<Path>>(
+    backup_base: &Path,
+    source_path: P,
+) -> Result<Vec<(PathBuf, DateTime<Utc>)>> {
+    let source_path = source_path.as_ref();
 
     let original_name = source_path
         .file_name()
@@ -323,7 +353,7 @@ pub async fn list_backups<P: AsRef<Path>>(
 
     let mut backups = Vec::new();
 
-    if let Ok(mut entries) = fs::read_dir(&backup_base).await {
+    if let Ok(mut entries) = fs::read_dir(backup_base).await {
         while let Ok(Some(entry)) = entries.next_entry().await {
             let path = entry.path();
             if let Some(filename) = path.file_name().and_then(OsStr::to_str) {
@@ -406,12 +436,22 @@ pub async fn safe_write_with_backup<P: AsRef<Path>, C: AsRef<[u8]>>(
     contents: C,
     category: Option<&str>,
     config: &BackupConfig,
+) -> Result<()> {
+    safe_write_with_backup_in(&backup_dir(category), file_path, contents, config).await
+}
+
+/// Like `safe_write_with_backup`, but backs up into an explicit backup directory
+pub async fn safe_write_with_backup_in<P: AsRef<Path>, C: AsRef<[u8]>>(
+    backup_base: &Path,
+    file_path: P,
+    contents: C,
+    config: &BackupConfig,
 ) -> Result<()> {
     let file_path = file_path.as_ref();
 
     // Create backup if file exists
     if file_path.exists() {
-        create_backup(file_path, category, config).await?;
+        create_backup_in(backup_base, file_path, config).await?;
     }
 
     // Write new content (atomic operation)
@@ -0,0 +1,621 @@
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::{AppError, Result};
+use crate::minecraft::downloads::mod_downloader::MOD_CACHE_DIR_NAME;
+use crate::minecraft::downloads::mod_resolver;
+use crate::state::download_state::DownloadPriority;
+use crate::state::profile_state::ModSource;
+use crate::state::state_manager::State;
+use crate::utils::download_utils::{DownloadConfig, DownloadUtils};
+use crate::utils::hash_utils;
+use crate::utils::jar_metadata;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::fs;
+use uuid::Uuid;
+
+const STORE_DIR: &str = "content_store";
+const TMP_DIR: &str = "tmp";
+
+/// Folders of an instance that can hold files placed from the store
+const PROFILE_CONTENT_DIRS: &[&str] = &[
+    "mods",
+    "custom_mods",
+    "resourcepacks",
+    "shaderpacks",
+    "datapacks",
+];
+
+/// Blobs younger than this are never collected, they may belong to an install in progress.
+pub const DEFAULT_GC_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// How a file from the store ended up at its target location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMethod {
+    Hardlink,
+    Reflink,
+    Copy,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct GarbageCollectionReport {
+    pub removed_blobs: usize,
+    pub freed_bytes: u64,
+    pub kept_blobs: usize,
+}
+
+/// Global SHA1-keyed file store under `<meta_dir>/content_store/<first two hex chars>/<sha1>`.
+///
+/// Holds the content that used to be copied into every profile: mods (the mod cache, GEG pack
+/// mods and custom mods) and the resource packs, shader packs and datapacks installed through
+/// `download_content`. Libraries, assets and Java are shared per version in `meta_dir` already
+/// and stay outside. Profiles get hardlinks (or reflinks, falling back to copies) of the blobs,
+/// so a mod used by many profiles only exists once on disk.
+/// Blobs must never be modified in place, replacing a linked file always means removing it first.
+pub struct ContentStore {
+    root: PathBuf,
+    /// Folders whose files are links the store owns (the mod cache), removed with their blob
+    owned_dirs: Vec<PathBuf>,
+}
+
+impl Default for ContentStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContentStore {
+    pub fn new() -> Self {
+        Self::with_paths(
+            LAUNCHER_DIRECTORY.meta_dir().join(STORE_DIR),
+            vec![LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME)],
+        )
+    }
+
+    pub fn with_paths(root: PathBuf, owned_dirs: Vec<PathBuf>) -> Self {
+        Self { root, owned_dirs }
+    }
+
+    pub fn blob_path(&self, sha1: &str) -> PathBuf {
+        let sha1 = sha1.to_ascii_lowercase();
+        let shard = sha1.get(..2).unwrap_or("00").to_string();
+        self.root.join(shard).join(sha1)
+    }
+
+    pub fn contains(&self, sha1: &str) -> bool {
+        self.blob_path(sha1).is_file()
+    }
+
+    /// Takes a file the launcher owns (e.g. a freshly downloaded mod in `mod_cache`) into the store.
+    /// The file stays where it is and becomes a hardlink of the blob where possible.
+    /// `known_sha1` skips hashing when the caller has already verified the content.
+    pub async fn adopt_file(&self, path: &Path, known_sha1: Option<&str>) -> Result<String> {
+        let sha1 = match known_sha1 {
+            Some(sha1) => sha1.to_ascii_lowercase(),
+            None => hash_utils::calculate_sha1_from_file(path).await?,
+        };
+        let blob = self.blob_path(&sha1);
+
+        if blob.is_file() {
+            // Replace a separate copy with a link to the existing blob
+            if !is_same_file(path, &blob).await {
+                self.link_blob(&sha1, path).await?;
+            }
+            return Ok(sha1);
+        }
+
+        if let Some(parent) = blob.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        if fs::hard_link(path, &blob).await.is_err() {
+            // Different filesystem or no hardlink support, keep an independent copy
+            self.copy_into_store(path, &blob).await?;
+        }
+        debug!("Added {:?} to content store as {}", path, sha1);
+        Ok(sha1)
+    }
+
+    /// Like `adopt_file`, but skips files that are already hardlinked (i.e. adopted before),
+    /// so downloaders can call it on every cache check without hashing again.
+    pub async fn adopt_if_new(&self, path: &Path, known_sha1: Option<&str>) -> Result<()> {
+        if link_count(&fs::metadata(path).await?).is_some_and(|links| links > 1) {
+            return Ok(());
+        }
+        self.adopt_file(path, known_sha1).await.map(|_| ())
+    }
+
+    /// Copies a file the launcher does not own (e.g. a user's jar) into the store.
+    /// The source is never linked, so later edits to it cannot change the blob.
+    pub async fn import_file(&self, source: &Path) -> Result<String> {
+        let sha1 = hash_utils::calculate_sha1_from_file(source).await?;
+        let blob = self.blob_path(&sha1);
+        if !blob.is_file() {
+            if let Some(parent) = blob.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            self.copy_into_store(source, &blob).await?;
+        }
+        Ok(sha1)
+    }
+
+    /// Downloads `url` into the store, skipping the download if the blob is already present.
+    pub async fn download(&self, url: &str, expected_sha1: Option<&str>) -> Result<String> {
+        if let Some(sha1) = expected_sha1 {
+            if self.contains(sha1) {
+                debug!("Content store hit for {} ({})", url, sha1);
+                return Ok(sha1.to_ascii_lowercase());
+            }
+        }
+
+        let tmp_path = self.tmp_path();
+        // Content installs; a launch waiting for its files goes first
+        let mut config = DownloadConfig::new()
+            .with_streaming(true)
+            .with_retries(3)
+            .with_priority(DownloadPriority::Background);
+        if let Some(sha1) = expected_sha1 {
+            config = config.with_sha1(sha1);
+        }
+        DownloadUtils::download_file(url, &tmp_path, config).await?;
+
+        let sha1 = match expected_sha1 {
+            Some(sha1) => sha1.to_ascii_lowercase(),
+            None => hash_utils::calculate_sha1_from_file(&tmp_path).await?,
+        };
+        let blob = self.blob_path(&sha1);
+        if blob.is_file() {
+            fs::remove_file(&tmp_path).await?;
+        } else {
+            if let Some(parent) = blob.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::rename(&tmp_path, &blob).await?;
+        }
+        Ok(sha1)
+    }
+
+    /// Places the blob `sha1` at `target`, replacing whatever is there.
+    pub async fn link_blob(&self, sha1: &str, target: &Path) -> Result<LinkMethod> {
+        let blob = self.blob_path(sha1);
+        if !blob.is_file() {
+            return Err(AppError::FileNotFound(blob));
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        if target.exists() {
+            if is_same_file(target, &blob).await {
+                return Ok(LinkMethod::Hardlink);
+            }
+            // Never write through an existing file, it may itself be a link to another blob
+            fs::remove_file(target).await?;
+        }
+
+        if fs::hard_link(&blob, target).await.is_ok() {
+            return Ok(LinkMethod::Hardlink);
+        }
+
+        let (reflink_source, reflink_target) = (blob.clone(), target.to_path_buf());
+        let reflinked =
+            tokio::task::spawn_blocking(move || reflink(&reflink_source, &reflink_target))
+                .await
+                .unwrap_or(false);
+        if reflinked {
+            return Ok(LinkMethod::Reflink);
+        }
+
+        fs::copy(&blob, target).await?;
+        Ok(LinkMethod::Copy)
+    }
+
+    /// Puts `source` (launcher-owned) into the store and places it at `target`.
+    pub async fn link_file(&self, source: &Path, target: &Path) -> Result<LinkMethod> {
+        let sha1 = self.adopt_file(source, None).await?;
+        self.link_blob(&sha1, target).await
+    }
+
+    /// Removes blobs that are not in `referenced`, together with their links in the mod cache.
+    /// Blobs and temp files younger than `min_age` are kept, they may belong to a running install.
+    /// `referenced` must come from `collect_profile_references`; it alone decides, since the
+    /// mod cache links every mod blob and link counts never drop to one.
+    /// Links in profile folders are left alone, removing the blob only drops the store's name.
+    pub async fn collect_garbage(
+        &self,
+        referenced: &HashSet<String>,
+        min_age: Duration,
+    ) -> Result<GarbageCollectionReport> {
+        let mut report = GarbageCollectionReport::default();
+        if !self.root.exists() {
+            return Ok(report);
+        }
+
+        let blob_sizes = self.blob_sizes().await?;
+        let mut owned_links = HashMap::new();
+        for dir in &self.owned_dirs {
+            hash_matching_files(dir, &blob_sizes, &mut owned_links).await;
+        }
+
+        let mut shards = fs::read_dir(&self.root).await?;
+        while let Some(shard) = shards.next_entry().await? {
+            if !shard.file_type().await?.is_dir() {
+                continue;
+            }
+            let is_tmp = shard.file_name() == TMP_DIR;
+
+            let mut blobs = fs::read_dir(shard.path()).await?;
+            while let Some(blob) = blobs.next_entry().await? {
+                let metadata = blob.metadata().await?;
+                let name = blob.file_name().to_string_lossy().to_ascii_lowercase();
+                let too_young = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|m| SystemTime::now().duration_since(m).ok())
+                    .is_some_and(|age| age < min_age);
+
+                let remove = is_unused(is_tmp, too_young, referenced.contains(&name));
+                if !remove {
+                    if !is_tmp {
+                        report.kept_blobs += 1;
+                    }
+                    continue;
+                }
+
+                match fs::remove_file(blob.path()).await {
+                    Ok(()) => {
+                        report.removed_blobs += 1;
+                        report.freed_bytes += metadata.len();
+                    }
+                    Err(e) => {
+                        warn!("Failed to remove unused blob {:?}: {}", blob.path(), e);
+                        continue;
+                    }
+                }
+                for link in owned_links.get(&name).into_iter().flatten() {
+                    match fs::remove_file(link).await {
+                        Ok(()) => debug!("Removed {:?} with unused blob {}", link, name),
+                        Err(e) => warn!("Failed to remove {:?} of unused blob: {}", link, e),
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Content store GC removed {} blobs ({} bytes), kept {}",
+            report.removed_blobs, report.freed_bytes, report.kept_blobs
+        );
+        Ok(report)
+    }
+
+    /// Sizes of all blobs, files of other sizes cannot be one of them and need no hashing
+    async fn blob_sizes(&self) -> Result<HashSet<u64>> {
+        let mut sizes = HashSet::new();
+        if !self.root.exists() {
+            return Ok(sizes);
+        }
+        let mut shards = fs::read_dir(&self.root).await?;
+        while let Some(shard) = shards.next_entry().await? {
+            if !shard.file_type().await?.is_dir() || shard.file_name() == TMP_DIR {
+                continue;
+            }
+            let mut blobs = fs::read_dir(shard.path()).await?;
+            while let Some(blob) = blobs.next_entry().await? {
+                sizes.insert(blob.metadata().await?.len());
+            }
+        }
+        Ok(sizes)
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        self.root.join(TMP_DIR).join(Uuid::new_v4().to_string())
+    }
+
+    /// Copies via a temp file and a rename, so a blob is never visible half-written.
+    async fn copy_into_store(&self, source: &Path, blob: &Path) -> Result<()> {
+        let tmp_path = self.tmp_path();
+        if let Some(parent) = tmp_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let (reflink_source, reflink_target) = (source.to_path_buf(), tmp_path.clone());
+        let reflinked =
+            tokio::task::spawn_blocking(move || reflink(&reflink_source, &reflink_target))
+                .await
+                .unwrap_or(false);
+        if !reflinked {
+            fs::copy(source, &tmp_path).await?;
+        }
+        fs::rename(&tmp_path, blob).await?;
+        Ok(())
+    }
+}
+
+/// Decides whether GC removes a store entry. Blobs stay while the reference scan finds them,
+/// leftover temp files go once they are old enough.
+fn is_unused(is_tmp: bool, too_young: bool, referenced: bool) -> bool {
+    !too_young && (is_tmp || !referenced)
+}
+
+/// SHA1s declared by the mods of the given profiles, enabled or not.
+fn declared_mod_hashes<'a>(mods: impl IntoIterator<Item = &'a ModSource>) -> HashSet<String> {
+    mods.into_iter()
+        .filter_map(|source| match source {
+            ModSource::Modrinth { file_hash_sha1, .. }
+            | ModSource::CurseForge { file_hash_sha1, .. }
+            | ModSource::Url { file_hash_sha1, .. } => file_hash_sha1.as_deref(),
+            _ => None,
+        })
+        .map(|sha1| sha1.to_ascii_lowercase())
+        .collect()
+}
+
+/// Files below `dir` whose size matches a blob, by SHA1.
+async fn hash_matching_files(
+    dir: &Path,
+    blob_sizes: &HashSet<u64>,
+    files: &mut HashMap<String, Vec<PathBuf>>,
+) {
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(mut entries) = fs::read_dir(&current).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            let path = entry.path();
+            if metadata.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !blob_sizes.contains(&metadata.len()) {
+                continue;
+            }
+            match hash_utils::calculate_sha1_from_file(&path).await {
+                Ok(sha1) => files.entry(sha1).or_default().push(path),
+                Err(e) => warn!("Failed to hash {:?} for content store GC: {}", path, e),
+            }
+        }
+    }
+}
+
+/// SHA1s of everything profiles (including standard versions) use: hashes their mods declare,
+/// the mod cache files of the mods they would load (GEG pack mods included) and the content
+/// of their mods, custom mods, resource pack, shader pack and datapack folders.
+/// The mod cache itself belongs to the store and keeps nothing alive.
+/// Fails instead of returning a partial set, GC would delete whatever is missing.
+pub async fn collect_profile_references(store: &ContentStore) -> Result<HashSet<String>> {
+    let state = State::get().await?;
+    let mut profiles = state.profile_manager.list_profiles().await?;
+    profiles.extend(state.GEG_version_manager.get_config().await.profiles);
+
+    let mut referenced = declared_mod_hashes(
+        profiles
+            .iter()
+            .flat_map(|p| p.mods.iter().map(|m| &m.source)),
+    );
+
+    let blob_sizes = store.blob_sizes().await?;
+    if blob_sizes.is_empty() {
+        return Ok(referenced);
+    }
+
+    let pack_config = state.GEG_pack_manager.get_config().await;
+    let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+    for profile in &profiles {
+        // Custom mods are covered by the folder scan below
+        let target_mods = mod_resolver::resolve_target_mods(
+            profile,
+            profile.selected_GEG_pack_id.as_ref().map(|_| &pack_config),
+            None,
+            &profile.game_version,
+            profile.loader.as_str(),
+            &mod_cache_dir,
+        )
+        .await?;
+        for target in target_mods {
+            // Not downloaded yet, nothing to keep
+            if let Ok(sha1) = jar_metadata::cached_sha1(&target.cache_path).await {
+                referenced.insert(sha1);
+            }
+        }
+    }
+
+    let mut content_dirs = Vec::new();
+    let mut instance_paths = HashSet::new();
+    for profile in &profiles {
+        match state
+            .profile_manager
+            .calculate_instance_path_for_profile(profile)
+        {
+            // Profiles in a group share one instance folder
+            Ok(path) if instance_paths.insert(path.clone()) => {
+                content_dirs.extend(PROFILE_CONTENT_DIRS.iter().map(|dir| path.join(dir)))
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Failed to resolve instance path of profile {} for content store GC: {}",
+                profile.id, e
+            ),
+        }
+    }
+
+    let mut placed = HashMap::new();
+    for dir in content_dirs {
+        hash_matching_files(&dir, &blob_sizes, &mut placed).await;
+    }
+    referenced.extend(placed.into_keys());
+    debug!(
+        "Content store GC found {} referenced hashes",
+        referenced.len()
+    );
+    Ok(referenced)
+}
+
+async fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (fs::metadata(a).await, fs::metadata(b).await) {
+        (Ok(a), Ok(b)) => same_inode(&a, &b),
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+fn same_inode(a: &std::fs::Metadata, b: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_inode(_a: &std::fs::Metadata, _b: &std::fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.nlink())
+}
+
+// Link counts are not available on stable Rust here
+#[cfg(not(unix))]
+fn link_count(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// Copy-on-write clone of `source` to a new file `target`. Returns false if unsupported.
+#[cfg(target_os = "linux")]
+fn reflink(source: &Path, target: &Path) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    let Ok(src) = std::fs::File::open(source) else {
+        return false;
+    };
+    let Ok(dst) = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(target)
+    else {
+        return false;
+    };
+    // SAFETY: both descriptors are valid for the duration of the call
+    let result = unsafe { libc::ioctl(dst.as_raw_fd(), libc::FICLONE, src.as_raw_fd()) };
+    if result != 0 {
+        drop(dst);
+        let _ = std::fs::remove_file(target);
+        return false;
+    }
+    true
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(source: &Path, target: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let (Ok(src), Ok(dst)) = (
+        CString::new(source.as_os_str().as_bytes()),
+        CString::new(target.as_os_str().as_bytes()),
+    ) else {
+        return false;
+    };
+    // SAFETY: both arguments are valid NUL-terminated paths
+    unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) == 0 }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_source: &Path, _target: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_blobs_that_are_referenced_or_young() {
+        assert!(is_unused(false, false, false));
+        assert!(!is_unused(false, false, true));
+        assert!(!is_unused(false, true, false));
+        // Leftover temp files go once they are old enough
+        assert!(is_unused(true, false, false));
+        assert!(!is_unused(true, true, false));
+    }
+
+    #[tokio::test]
+    async fn gc_removes_unreferenced_blobs_with_their_mod_cache_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let mod_cache = dir.path().join("mod_cache");
+        let instance_mods = dir.path().join("instance").join("mods");
+        fs::create_dir_all(&mod_cache).await.unwrap();
+        let store = ContentStore::with_paths(dir.path().join("store"), vec![mod_cache.clone()]);
+
+        let unused_cache_file = mod_cache.join("unused.jar");
+        fs::write(&unused_cache_file, b"unused mod").await.unwrap();
+        let unused = store.adopt_file(&unused_cache_file, None).await.unwrap();
+        store
+            .link_blob(&unused, &instance_mods.join("unused.jar"))
+            .await
+            .unwrap();
+
+        let used_cache_file = mod_cache.join("used.jar");
+        fs::write(&used_cache_file, b"used mod").await.unwrap();
+        let used = store.adopt_file(&used_cache_file, None).await.unwrap();
+
+        let report = store
+            .collect_garbage(&HashSet::from([used.clone()]), Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(report.removed_blobs, 1);
+        assert_eq!(report.kept_blobs, 1);
+        assert!(!store.contains(&unused));
+        assert!(!unused_cache_file.exists());
+        // Files placed in profiles stay, only the store's own names go
+        assert!(instance_mods.join("unused.jar").exists());
+        assert!(store.contains(&used));
+        assert!(used_cache_file.exists());
+    }
+
+    #[tokio::test]
+    async fn references_include_declared_hashes_and_placed_content() {
+        let sources = [
+            ModSource::Modrinth {
+                project_id: "AANobbMI".to_string(),
+                version_id: "tFw0iWAk".to_string(),
+                file_name: "sodium.jar".to_string(),
+                download_url: String::new(),
+                file_hash_sha1: Some("ABCDEF".to_string()),
+            },
+            ModSource::Url {
+                url: "https://example.com/mod.jar".to_string(),
+                file_name: None,
+                file_hash_sha1: Some("123456".to_string()),
+            },
+            ModSource::Local {
+                file_name: "local.jar".to_string(),
+            },
+        ];
+        let mut referenced = declared_mod_hashes(&sources);
+        assert_eq!(
+            referenced,
+            HashSet::from(["abcdef".to_string(), "123456".to_string()])
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let shaders = dir.path().join("shaderpacks").join("nested");
+        fs::create_dir_all(&shaders).await.unwrap();
+        fs::write(shaders.join("pack.zip"), b"hello world")
+            .await
+            .unwrap();
+        fs::write(dir.path().join("other.zip"), b"no blob has this size")
+            .await
+            .unwrap();
+
+        let mut placed = HashMap::new();
+        hash_matching_files(dir.path(), &HashSet::from([11]), &mut placed).await;
+        referenced.extend(placed.into_keys());
+        assert_eq!(referenced.len(), 3);
+        assert!(referenced.contains("2aae6c35c94fcfb415dbe95f408b9ce91ee846ed"));
+    }
+}
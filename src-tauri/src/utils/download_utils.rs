@@ -1,10 +1,13 @@
-use crate::config::HTTP_CLIENT;
+use crate::config::{url_candidates, HTTP_CLIENT};
 use crate::error::{AppError, Result};
+use crate::state::download_state::{DownloadPermit, DownloadPriority, DownloadScheduler};
 use crate::utils::hash_utils;
 use crate::utils::disk_space_utils::DiskSpaceUtils;
 use futures::stream::StreamExt;
 use log::{debug, error, info, warn};
-use reqwest::Response;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
@@ -31,6 +34,8 @@ pub struct DownloadConfig {
     pub check_disk_space: bool,
     /// Buffer percentage for disk space check (default: 0.25 = 25%)
     pub disk_space_buffer: f64,
+    /// Queue position for a connection of the global download scheduler
+    pub priority: DownloadPriority,
 }
 
 impl std::fmt::Debug for DownloadConfig {
@@ -46,6 +51,7 @@ impl std::fmt::Debug for DownloadConfig {
             .field("progress_callback", &"<callback function>")
             .field("check_disk_space", &self.check_disk_space)
             .field("disk_space_buffer", &self.disk_space_buffer)
+            .field("priority", &self.priority)
             .finish()
     }
 }
@@ -65,6 +71,7 @@ impl Clone for DownloadConfig {
             progress_callback: None,
             check_disk_space: self.check_disk_space,
             disk_space_buffer: self.disk_space_buffer,
+            priority: self.priority,
         }
     }
 }
@@ -85,6 +92,7 @@ impl Default for DownloadConfig {
             progress_callback: None,
             check_disk_space: true,
             disk_space_buffer: 0.25, // 25% buffer by default
+            priority: DownloadPriority::LaunchBlocking,
         }
     }
 }
@@ -146,6 +154,46 @@ impl DownloadConfig {
         self.disk_space_buffer = buffer_percentage;
         self
     }
+
+    pub fn with_priority(mut self, priority: DownloadPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// Sidecar stored next to a `.part` file, describing the file the partial data belongs to.
+/// A part file is only resumed if the current download expects exactly the same file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PartialDownloadInfo {
+    url: String,
+    expected_size: Option<u64>,
+    expected_sha1: Option<String>,
+    expected_sha256: Option<String>,
+}
+
+impl PartialDownloadInfo {
+    fn new(url: &str, config: &DownloadConfig) -> Self {
+        Self {
+            url: url.to_string(),
+            expected_size: config.expected_size,
+            expected_sha1: config.expected_sha1.as_ref().map(|h| h.to_lowercase()),
+            expected_sha256: config.expected_sha256.as_ref().map(|h| h.to_lowercase()),
+        }
+    }
+
+    /// Without size or hash a resumed file could not be checked for consistency
+    fn is_verifiable(&self) -> bool {
+        self.expected_size.is_some() || self.expected_sha1.is_some() || self.expected_sha256.is_some()
+    }
+
+    /// Same expected content; the URL only has to match when no hash identifies the file
+    fn matches(&self, other: &Self) -> bool {
+        let has_hash = self.expected_sha1.is_some() || self.expected_sha256.is_some();
+        self.expected_size == other.expected_size
+            && self.expected_sha1 == other.expected_sha1
+            && self.expected_sha256 == other.expected_sha256
+            && (has_hash || self.url == other.url)
+    }
 }
 
 /// Central download utility for robust file downloads
@@ -161,6 +209,9 @@ impl DownloadUtils {
         let target_path = target_path.as_ref();
         debug!("Starting download: {} -> {:?}", url, target_path);
 
+        // Connections of the launcher-wide limit are only taken while data is transferred
+        let scheduler = DownloadScheduler::global().await;
+
         // Check if file already exists and is valid
         if !config.force_overwrite && Self::verify_existing_file(target_path, &config).await? {
             info!("File already exists and passes verification: {:?}", target_path);
@@ -182,6 +233,32 @@ impl DownloadUtils {
             }
         }
 
+        let candidates = url_candidates(url);
+        let mut last_error = None;
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            if index > 0 {
+                warn!("Falling back to {} for: {}", candidate, url);
+            }
+
+            match Self::download_with_retries(candidate, target_path, &config, scheduler.as_ref()).await {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            AppError::Download(format!("No download URL left for {}", url))
+        }))
+    }
+
+    /// Downloads from exactly `url`, retrying up to `config.max_retries` times
+    async fn download_with_retries(
+        url: &str,
+        target_path: &Path,
+        config: &DownloadConfig,
+        scheduler: Option<&DownloadScheduler>,
+    ) -> Result<()> {
         let mut attempt = 0;
         let mut last_error = None;
 
@@ -190,7 +267,7 @@ impl DownloadUtils {
                 warn!("Retry attempt {}/{} for: {}", attempt, config.max_retries, url);
             }
 
-            match Self::download_attempt(url, target_path, &config).await {
+            match Self::download_attempt(url, target_path, config, scheduler).await {
                 Ok(()) => {
                     info!("Successfully downloaded: {} -> {:?}", url, target_path);
                     return Ok(());
@@ -201,8 +278,9 @@ impl DownloadUtils {
                     attempt += 1;
                     
                     if attempt <= config.max_retries {
-                        // Clean up partially downloaded file
-                        if target_path.exists() {
+                        // Streaming downloads keep their .part file so the next attempt can
+                        // resume it; only in-memory downloads write the target directly.
+                        if !config.use_streaming && target_path.exists() {
                             debug!("Cleaning up partially downloaded file: {:?}", target_path);
                             if let Err(cleanup_err) = fs::remove_file(target_path).await {
                                 warn!("Failed to clean up partial file {:?}: {}", target_path, cleanup_err);
@@ -254,11 +332,44 @@ impl DownloadUtils {
         Self::download_file(url, target_path, config).await
     }
 
+    /// GET `url` through the configured mirrors, e.g. for API requests that are not file downloads.
+    /// Mirrors answering with an error status are skipped; the last candidate's response is returned as-is.
+    pub async fn get_with_mirrors(url: &str) -> std::result::Result<Response, reqwest::Error> {
+        Self::send_with_mirrors(url, |candidate| HTTP_CLIENT.get(candidate)).await
+    }
+
+    /// Like [`Self::get_with_mirrors`], with a custom request per candidate URL (headers, client, ...)
+    pub async fn send_with_mirrors<F>(
+        url: &str,
+        build_request: F,
+    ) -> std::result::Result<Response, reqwest::Error>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let candidates = url_candidates(url);
+        let last_index = candidates.len().saturating_sub(1);
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            let result = build_request(candidate).send().await;
+            if index == last_index {
+                return result;
+            }
+            match result.and_then(|response| response.error_for_status()) {
+                Ok(response) => return Ok(response),
+                Err(e) => warn!("Mirror {} failed for {}: {}", candidate, url, e),
+            }
+        }
+
+        // url_candidates never returns an empty list
+        build_request(url).send().await
+    }
+
     /// Single download attempt
     async fn download_attempt(
         url: &str,
         target_path: &Path,
         config: &DownloadConfig,
+        scheduler: Option<&DownloadScheduler>,
     ) -> Result<()> {
         // Create parent directories
         if let Some(parent) = target_path.parent() {
@@ -270,79 +381,196 @@ impl DownloadUtils {
             })?;
         }
 
-        // Make HTTP request
+        if config.use_streaming {
+            return Self::download_streaming(url, target_path, config, scheduler).await;
+        }
+
+        // Never write through an existing file: it may be a hardlink into the content store
+        // that other profiles share, so it is replaced instead of truncated.
+        Self::remove_existing_target(target_path).await?;
+
+        let permit = Self::acquire_connection(scheduler, config).await;
+        let response = Self::send_request(url, config, None).await?;
+        let response = Self::ensure_success(url, response).await?;
+        Self::download_in_memory(response, permit, target_path, config, url, scheduler).await
+    }
+
+    /// One connection of the launcher-wide limit. Held only for the transfer itself,
+    /// hashing and moving the file happen after it is released.
+    async fn acquire_connection(
+        scheduler: Option<&DownloadScheduler>,
+        config: &DownloadConfig,
+    ) -> Option<DownloadPermit> {
+        match scheduler {
+            Some(scheduler) => Some(scheduler.acquire(config.priority).await),
+            None => None,
+        }
+    }
+
+    /// Sends the GET request, optionally asking for everything from `range_start` on
+    async fn send_request(
+        url: &str,
+        config: &DownloadConfig,
+        range_start: Option<u64>,
+    ) -> Result<Response> {
         let mut request = HTTP_CLIENT.get(url);
-        
+
         if let Some(user_agent) = &config.user_agent {
             request = request.header("User-Agent", user_agent);
         }
 
-        let response = request.send().await.map_err(|e| {
+        if let Some(start) = range_start {
+            request = request.header(RANGE, format!("bytes={}-", start));
+        }
+
+        request.send().await.map_err(|e| {
             let error_msg = format!("HTTP request failed for {}: {}", url, e);
             error!("{}", error_msg);
             AppError::Download(error_msg)
-        })?;
+        })
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_msg = format!(
-                "Download failed: {} returned status {} ({})",
-                url, status.as_u16(), status.canonical_reason().unwrap_or("Unknown")
-            );
-            error!("{}", error_msg);
-            
-            // Try to get response body for more details (but limit to avoid hanging)
-            if let Ok(body) = response.text().await {
-                let truncated_body = if body.len() > 200 {
-                    format!("{}...", &body[..200])
-                } else {
-                    body
-                };
-                error!("Response body: {}", truncated_body);
-            }
-            
-            return Err(AppError::Download(error_msg));
+    /// Turns a non-success response into a download error
+    async fn ensure_success(url: &str, response: Response) -> Result<Response> {
+        if response.status().is_success() {
+            return Ok(response);
         }
 
-        // Get content length for progress tracking
-        let content_length = response.content_length();
+        let status = response.status();
+        let error_msg = format!(
+            "Download failed: {} returned status {} ({})",
+            url, status.as_u16(), status.canonical_reason().unwrap_or("Unknown")
+        );
+        error!("{}", error_msg);
+
+        // Try to get response body for more details (but limit to avoid hanging)
+        if let Ok(body) = response.text().await {
+            let truncated_body = if body.len() > 200 {
+                format!("{}...", &body[..200])
+            } else {
+                body
+            };
+            error!("Response body: {}", truncated_body);
+        }
 
-        if config.use_streaming {
-            Self::download_streaming(response, target_path, config, content_length).await
-        } else {
-            Self::download_in_memory(response, target_path, config, url).await
+        Err(AppError::Download(error_msg))
+    }
+
+    async fn remove_existing_target(target_path: &Path) -> Result<()> {
+        if target_path.exists() {
+            fs::remove_file(target_path).await.map_err(|e| {
+                AppError::Download(format!(
+                    "Failed to remove outdated file {:?}: {}",
+                    target_path, e
+                ))
+            })?;
         }
+        Ok(())
     }
 
-    /// Download using streaming (recommended for large files)
+    /// Download using streaming (recommended for large files).
+    /// Data goes into `<target>.part` next to a sidecar describing the expected file, so an
+    /// interrupted download is continued with a `Range` request instead of starting over.
     async fn download_streaming(
-        response: Response,
+        url: &str,
         target_path: &Path,
         config: &DownloadConfig,
-        content_length: Option<u64>,
+        scheduler: Option<&DownloadScheduler>,
     ) -> Result<()> {
-        debug!("Creating file for streaming download: {:?}", target_path);
-        let mut file = fs::File::create(target_path).await.map_err(|e| {
-            let error_msg = format!("Failed to create file {:?}: {}", target_path, e);
+        let part_path = Self::part_path(target_path);
+        let info_path = Self::part_info_path(target_path);
+        let info = PartialDownloadInfo::new(url, config);
+
+        let resume_from = Self::resumable_length(&part_path, &info_path, &info).await;
+        if resume_from == 0 {
+            Self::write_part_info(&info_path, &info).await?;
+        }
+
+        // A previous attempt may have received everything but failed before the rename
+        if resume_from > 0 && Some(resume_from) == info.expected_size {
+            debug!("Partial download {:?} is already complete", part_path);
+            return Self::finish_part_file(&part_path, &info_path, target_path, config).await;
+        }
+
+        let permit = Self::acquire_connection(scheduler, config).await;
+        let mut response = Self::send_request(
+            url,
+            config,
+            if resume_from > 0 { Some(resume_from) } else { None },
+        )
+        .await?;
+
+        let mut offset = 0u64;
+        let mut total_size = response.content_length();
+        if resume_from > 0 {
+            if response.status() == StatusCode::PARTIAL_CONTENT {
+                match Self::parse_content_range(&response) {
+                    Some((start, total))
+                        if start == resume_from
+                            && (info.expected_size.is_none() || total == info.expected_size) =>
+                    {
+                        info!("Resuming download of {} at byte {}", url, resume_from);
+                        offset = resume_from;
+                        total_size = total.or(total_size.map(|len| len + resume_from));
+                    }
+                    content_range => {
+                        warn!(
+                            "Server answered Range request for {} with unexpected Content-Range {:?}, restarting download",
+                            url, content_range
+                        );
+                        drop(response);
+                        response = Self::send_request(url, config, None).await?;
+                        total_size = response.content_length();
+                    }
+                }
+            } else if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+                warn!("Server rejected resume of {} at byte {}, restarting download", url, resume_from);
+                drop(response);
+                response = Self::send_request(url, config, None).await?;
+                total_size = response.content_length();
+            } else if response.status().is_success() {
+                info!("Server ignored Range request for {}, restarting download", url);
+            }
+        }
+        let response = Self::ensure_success(url, response).await?;
+
+        let mut file = if offset > 0 {
+            fs::OpenOptions::new().append(true).open(&part_path).await
+        } else {
+            fs::File::create(&part_path).await
+        }
+        .map_err(|e| {
+            let error_msg = format!("Failed to open file {:?}: {}", part_path, e);
             error!("{}", error_msg);
             AppError::Download(error_msg)
         })?;
 
         let mut stream = response.bytes_stream();
-        let mut downloaded = 0u64;
+        let mut downloaded = offset;
         let mut chunk_count = 0u64;
 
-        debug!("Starting streaming download (content_length: {:?})", content_length);
-        
+        debug!(
+            "Starting streaming download (offset: {}, total size: {:?})",
+            offset, total_size
+        );
+
         while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| {
-                let error_msg = format!("Stream error during download: {}", e);
-                error!("{}", error_msg);
-                AppError::Download(error_msg)
-            })?;
+            let chunk = match chunk_result {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    // Keep what arrived so far for the next attempt
+                    let _ = file.sync_all().await;
+                    let error_msg = format!(
+                        "Stream error during download after {} bytes: {}",
+                        downloaded, e
+                    );
+                    error!("{}", error_msg);
+                    return Err(AppError::Download(error_msg));
+                }
+            };
 
             file.write_all(&chunk).await.map_err(|e| {
-                let error_msg = format!("Write error for {:?}: {}", target_path, e);
+                let error_msg = format!("Write error for {:?}: {}", part_path, e);
                 error!("{}", error_msg);
                 AppError::Download(error_msg)
             })?;
@@ -350,6 +578,11 @@ impl DownloadUtils {
             downloaded += chunk.len() as u64;
             chunk_count += 1;
 
+            // Throughput accounting and speed limit
+            if let Some(scheduler) = scheduler {
+                scheduler.consume(chunk.len() as u64).await;
+            }
+
             // Log progress every 1000 chunks or every 10MB for large downloads
             if chunk_count % 1000 == 0 || downloaded % (10 * 1024 * 1024) == 0 {
                 debug!("Downloaded {} bytes in {} chunks", downloaded, chunk_count);
@@ -357,7 +590,7 @@ impl DownloadUtils {
 
             // Call progress callback if provided
             if let Some(callback) = &config.progress_callback {
-                callback(downloaded, content_length);
+                callback(downloaded, total_size);
             }
         }
 
@@ -365,27 +598,144 @@ impl DownloadUtils {
 
         // Ensure file is fully written to disk - CRITICAL for preventing corruption
         file.sync_all().await.map_err(|e| {
-            AppError::Download(format!("Failed to sync file {:?}: {}", target_path, e))
+            AppError::Download(format!("Failed to sync file {:?}: {}", part_path, e))
         })?;
 
         // Explicitly close the file handle
         drop(file);
+        drop(permit);
+
+        Self::finish_part_file(&part_path, &info_path, target_path, config).await
+    }
+
+    /// Verifies a completed `.part` file and moves it onto the target.
+    /// A part file that fails verification is discarded so the next attempt starts fresh.
+    async fn finish_part_file(
+        part_path: &Path,
+        info_path: &Path,
+        target_path: &Path,
+        config: &DownloadConfig,
+    ) -> Result<()> {
+        if let Err(e) = Self::verify_downloaded_file(part_path, target_path, config).await {
+            Self::discard_part_file(part_path, info_path).await;
+            return Err(e);
+        }
+
+        // Replace instead of truncate, the old file may be a shared hardlink
+        Self::remove_existing_target(target_path).await?;
+        fs::rename(part_path, target_path).await.map_err(|e| {
+            AppError::Download(format!(
+                "Failed to move {:?} to {:?}: {}",
+                part_path, target_path, e
+            ))
+        })?;
+
+        if let Err(e) = fs::remove_file(info_path).await {
+            warn!("Failed to remove download info {:?}: {}", info_path, e);
+        }
+        Ok(())
+    }
+
+    /// Returns how many bytes of an existing `.part` file can be reused, discarding the
+    /// part file if it belongs to a different download or cannot be verified afterwards.
+    async fn resumable_length(part_path: &Path, info_path: &Path, info: &PartialDownloadInfo) -> u64 {
+        let part_len = match fs::metadata(part_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+
+        let stored_info = match fs::read(info_path).await {
+            Ok(bytes) => serde_json::from_slice::<PartialDownloadInfo>(&bytes).ok(),
+            Err(_) => None,
+        };
+
+        let reusable = part_len > 0
+            && info.is_verifiable()
+            && stored_info.as_ref().is_some_and(|stored| stored.matches(info))
+            && info.expected_size.is_none_or(|size| part_len <= size);
+
+        if reusable {
+            debug!("Found resumable partial download {:?} ({} bytes)", part_path, part_len);
+            return part_len;
+        }
+
+        if part_len > 0 || stored_info.is_some() {
+            debug!("Discarding stale partial download {:?}", part_path);
+            Self::discard_part_file(part_path, info_path).await;
+        }
+        0
+    }
+
+    async fn write_part_info(info_path: &Path, info: &PartialDownloadInfo) -> Result<()> {
+        let json = serde_json::to_vec_pretty(info)?;
+        fs::write(info_path, json).await.map_err(|e| {
+            AppError::Download(format!(
+                "Failed to write download info {:?}: {}",
+                info_path, e
+            ))
+        })
+    }
+
+    async fn discard_part_file(part_path: &Path, info_path: &Path) {
+        for path in [part_path, info_path] {
+            if path.exists() {
+                if let Err(e) = fs::remove_file(path).await {
+                    warn!("Failed to remove partial download file {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    /// Parses `Content-Range: bytes <start>-<end>/<total>` into start and total (`*` = unknown)
+    fn parse_content_range(response: &Response) -> Option<(u64, Option<u64>)> {
+        let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+        let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+        let (start, end) = range.split_once('-')?;
+        let start = start.trim().parse::<u64>().ok()?;
+        let end = end.trim().parse::<u64>().ok()?;
+        if end < start {
+            return None;
+        }
+        let total = match total.trim() {
+            "*" => None,
+            total => Some(total.parse::<u64>().ok()?),
+        };
+        Some((start, total))
+    }
+
+    fn part_path(target_path: &Path) -> PathBuf {
+        Self::with_appended_extension(target_path, "part")
+    }
 
-        // Verify the downloaded file
-        Self::verify_downloaded_file(target_path, config).await
+    fn part_info_path(target_path: &Path) -> PathBuf {
+        Self::with_appended_extension(target_path, "part.json")
+    }
+
+    fn with_appended_extension(path: &Path, extension: &str) -> PathBuf {
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".");
+        file_name.push(extension);
+        path.with_file_name(file_name)
     }
 
     /// Download by loading entire response into memory (for small files)
     async fn download_in_memory(
         response: Response,
+        permit: Option<DownloadPermit>,
         target_path: &Path,
         config: &DownloadConfig,
         url: &str,
+        scheduler: Option<&DownloadScheduler>,
     ) -> Result<()> {
         let bytes = response.bytes().await.map_err(|e| {
             AppError::Download(format!("Failed to read response bytes: {}", e))
         })?;
 
+        if let Some(scheduler) = scheduler {
+            scheduler.consume(bytes.len() as u64).await;
+        }
+        drop(permit);
+
         // Call progress callback if provided
         if let Some(callback) = &config.progress_callback {
             callback(bytes.len() as u64, Some(bytes.len() as u64));
@@ -512,8 +862,13 @@ impl DownloadUtils {
         Ok(true)
     }
 
-    /// Verify downloaded file after writing
-    async fn verify_downloaded_file(target_path: &Path, config: &DownloadConfig) -> Result<()> {
+    /// Verify downloaded file after writing.
+    /// `final_path` is where the file will end up and decides the file type checks.
+    async fn verify_downloaded_file(
+        target_path: &Path,
+        final_path: &Path,
+        config: &DownloadConfig,
+    ) -> Result<()> {
         debug!("Verifying downloaded file: {:?}", target_path);
 
         // Check size
@@ -561,7 +916,7 @@ impl DownloadUtils {
         }
 
         // Additional ZIP integrity check for JAR files to detect incomplete downloads
-        if let Some(extension) = target_path.extension() {
+        if let Some(extension) = final_path.extension() {
             if extension == "jar" && !Self::is_zip_file_complete(target_path).await {
                 let error_msg = format!(
                     "Downloaded JAR file failed ZIP integrity check (incomplete/corrupt): {:?}",
@@ -646,3 +1001,225 @@ impl DownloadUtils {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    /// How the stand-in server answers requests after the first one
+    #[derive(Clone, Copy)]
+    enum RangeSupport {
+        /// Proper 206 responses for `Range` requests
+        Honour,
+        /// Always the full body with 200
+        Ignore,
+        /// 206 but with a `Content-Range` that does not match the request
+        Mismatch,
+    }
+
+    struct TestServer {
+        url: String,
+        /// `Range` header of every request received, in order
+        ranges: Arc<Mutex<Vec<Option<String>>>>,
+    }
+
+    /// Minimal HTTP/1.1 server that serves `body`. The first response is cut off after
+    /// `drop_first_after` bytes by closing the connection, like a flaky CDN would.
+    async fn spawn_server(
+        body: Vec<u8>,
+        drop_first_after: Option<usize>,
+        range_support: RangeSupport,
+    ) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/file.bin", listener.local_addr().unwrap());
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = ranges.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                let range = request.lines().find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("range")
+                        .then(|| value.trim().to_string())
+                });
+                let request_index = {
+                    let mut seen = seen.lock().unwrap();
+                    seen.push(range.clone());
+                    seen.len() - 1
+                };
+
+                let range_start = range
+                    .as_deref()
+                    .and_then(|r| r.strip_prefix("bytes="))
+                    .and_then(|r| r.strip_suffix('-'))
+                    .and_then(|r| r.parse::<usize>().ok());
+                let len = body.len();
+
+                let (head, payload) = match (range_start, range_support) {
+                    (Some(start), RangeSupport::Honour) if request_index > 0 => (
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\n",
+                            len - start,
+                            start,
+                            len - 1,
+                            len
+                        ),
+                        &body[start..],
+                    ),
+                    (Some(_), RangeSupport::Mismatch) if request_index > 0 => (
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes 0-{}/{}\r\n",
+                            len,
+                            len - 1,
+                            len
+                        ),
+                        &body[..],
+                    ),
+                    _ => (
+                        format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n", len),
+                        &body[..],
+                    ),
+                };
+
+                let _ = socket
+                    .write_all(format!("{}Connection: close\r\n\r\n", head).as_bytes())
+                    .await;
+                match drop_first_after {
+                    Some(cut) if request_index == 0 => {
+                        let _ = socket.write_all(&payload[..cut]).await;
+                    }
+                    _ => {
+                        let _ = socket.write_all(payload).await;
+                    }
+                }
+                let _ = socket.flush().await;
+                // Dropping the socket closes the connection, mid-body for the first request
+            }
+        });
+
+        TestServer { url, ranges }
+    }
+
+    fn test_body() -> Vec<u8> {
+        (0..256 * 1024).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn config_for(body: &[u8]) -> DownloadConfig {
+        DownloadConfig::new()
+            .with_sha1(hash_utils::calculate_sha1_from_bytes(body))
+            .with_size(body.len() as u64)
+            .with_retries(2)
+            .with_disk_space_check(false)
+    }
+
+    fn range_start(range: &Option<String>) -> Option<u64> {
+        range
+            .as_deref()?
+            .strip_prefix("bytes=")?
+            .strip_suffix('-')?
+            .parse()
+            .ok()
+    }
+
+    #[tokio::test]
+    async fn test_resumes_after_dropped_connection() {
+        let body = test_body();
+        let server = spawn_server(body.clone(), Some(100 * 1024), RangeSupport::Honour).await;
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("file.bin");
+
+        DownloadUtils::download_file(&server.url, &target, config_for(&body))
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read(&target).await.unwrap(), body);
+        let ranges = server.ranges.lock().unwrap().clone();
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0], None);
+        let resumed_at = range_start(&ranges[1]).expect("retry should send a Range header");
+        assert!(resumed_at > 0 && resumed_at <= 100 * 1024);
+        assert!(!DownloadUtils::part_path(&target).exists());
+        assert!(!DownloadUtils::part_info_path(&target).exists());
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_full_download_when_range_ignored() {
+        let body = test_body();
+        let server = spawn_server(body.clone(), Some(100 * 1024), RangeSupport::Ignore).await;
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("file.bin");
+
+        DownloadUtils::download_file(&server.url, &target, config_for(&body))
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read(&target).await.unwrap(), body);
+        let ranges = server.ranges.lock().unwrap().clone();
+        assert_eq!(ranges.len(), 2);
+        assert!(range_start(&ranges[1]).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_restarts_on_mismatched_content_range() {
+        let body = test_body();
+        let server = spawn_server(body.clone(), Some(100 * 1024), RangeSupport::Mismatch).await;
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("file.bin");
+
+        DownloadUtils::download_file(&server.url, &target, config_for(&body))
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read(&target).await.unwrap(), body);
+        let ranges = server.ranges.lock().unwrap().clone();
+        // Dropped first request, rejected resume, then a plain full request
+        assert_eq!(ranges.len(), 3);
+        assert!(range_start(&ranges[1]).is_some());
+        assert_eq!(ranges[2], None);
+    }
+
+    #[tokio::test]
+    async fn test_discards_part_file_of_other_download() {
+        let body = test_body();
+        let server = spawn_server(body.clone(), None, RangeSupport::Honour).await;
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("file.bin");
+
+        // Leftover from an older version of the file with a different hash
+        let stale_info = PartialDownloadInfo {
+            url: server.url.clone(),
+            expected_size: Some(body.len() as u64),
+            expected_sha1: Some("0000000000000000000000000000000000000000".to_string()),
+            expected_sha256: None,
+        };
+        fs::write(DownloadUtils::part_path(&target), vec![0xAA; 1000])
+            .await
+            .unwrap();
+        DownloadUtils::write_part_info(&DownloadUtils::part_info_path(&target), &stale_info)
+            .await
+            .unwrap();
+
+        DownloadUtils::download_file(&server.url, &target, config_for(&body))
+            .await
+            .unwrap();
+
+        assert_eq!(fs::read(&target).await.unwrap(), body);
+        assert_eq!(server.ranges.lock().unwrap().clone(), vec![None]);
+    }
+}
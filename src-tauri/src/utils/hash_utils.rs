@@ -1,5 +1,5 @@
 use sha1::{Digest, Sha1};
-use sha2::Sha256;
+use sha2::{Sha256, Sha512};
 use std::io;
 use std::path::{Path, PathBuf};
 use tokio::fs::File;
@@ -61,3 +61,73 @@ pub fn calculate_sha256_from_bytes(bytes: &[u8]) -> String {
     let hash_bytes = hasher.finalize();
     format!("{:x}", hash_bytes) // Format as hex string
 }
+
+/// Asynchronously calculates the SHA512 hash of a file (required by `.mrpack` indexes).
+pub async fn calculate_sha512_from_file<P: AsRef<Path>>(path: P) -> Result<String, io::Error> {
+    let mut file = File::open(path).await?;
+    let mut hasher = Sha512::new();
+    let mut buffer = [0; 8192];
+
+    loop {
+        let n = file.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// CurseForge file fingerprint: MurmurHash2 (seed 1) over the file bytes with all
+/// whitespace bytes (tab, LF, CR, space) removed.
+pub fn calculate_curseforge_fingerprint(bytes: &[u8]) -> u32 {
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let data: Vec<u8> = bytes
+        .iter()
+        .copied()
+        .filter(|b| !matches!(b, 9 | 10 | 13 | 32))
+        .collect();
+    let mut h: u32 = 1 ^ data.len() as u32;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, byte) in tail.iter().enumerate() {
+            h ^= (*byte as u32) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curseforge_fingerprint_ignores_whitespace() {
+        assert_eq!(calculate_curseforge_fingerprint(b"hello world"), 2824650221);
+        assert_eq!(
+            calculate_curseforge_fingerprint(b"hello\tworld\r\n"),
+            calculate_curseforge_fingerprint(b"helloworld")
+        );
+        assert_eq!(calculate_curseforge_fingerprint(b""), 1540447798);
+    }
+}
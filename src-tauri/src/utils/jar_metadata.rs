@@ -0,0 +1,919 @@
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::{AppError, Result};
+use crate::state::profile_state::ModLoader;
+use crate::utils::hash_utils;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+use toml::Value as TomlValue;
+
+const CACHE_DIR: &str = "jar_metadata";
+/// Per-file digests inside `CACHE_DIR`, keyed by the SHA1 of the file's path
+const DIGESTS_DIR: &str = "files";
+/// Bumped when `JarMetadata` gains information, older cache entries are read again
+const CACHE_VERSION: u32 = 2;
+const FABRIC_MOD_JSON: &str = "fabric.mod.json";
+const QUILT_MOD_JSON: &str = "quilt.mod.json";
+const FORGE_MODS_TOML: &str = "META-INF/mods.toml";
+const NEOFORGE_MODS_TOML: &str = "META-INF/neoforge.mods.toml";
+const MANIFEST: &str = "META-INF/MANIFEST.MF";
+const JARJAR_METADATA: &str = "META-INF/jarjar/metadata.json";
+/// Jar-in-jar levels followed when reading bundled mods
+const MAX_NESTING_DEPTH: usize = 3;
+
+/// Mod IDs of the game, the loaders and Java; never reported as missing dependencies
+pub const PLATFORM_MOD_IDS: &[&str] = &[
+    "minecraft",
+    "java",
+    "fabricloader",
+    "fabric-loader",
+    "quilt_loader",
+    "forge",
+    "neoforge",
+    "fml",
+    "javafml",
+    "lowcodefml",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JarDependencyKind {
+    Required,
+    Optional,
+    Incompatible,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JarDependency {
+    pub mod_id: String,
+    /// Version range as written in the metadata (`>=0.5.0`, `[47,)`), `None` for any version
+    pub version_range: Option<String>,
+    pub kind: JarDependencyKind,
+}
+
+/// One mod declared in a jar. Forge jars may declare several.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JarModMetadata {
+    pub mod_id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// Loader whose metadata format declared this mod
+    pub loader: ModLoader,
+    /// Range of supported Minecraft versions from the `minecraft` dependency
+    pub minecraft_versions: Option<String>,
+    pub dependencies: Vec<JarDependency>,
+    /// Additional mod IDs this mod provides (Fabric `provides`, Quilt `provides`)
+    #[serde(default)]
+    pub provides: Vec<String>,
+    /// Path of the icon inside the jar
+    pub icon: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JarMetadata {
+    pub sha1: String,
+    /// Empty for jars without loader metadata (libraries, OptiFine, ...)
+    pub mods: Vec<JarModMetadata>,
+    /// Mods bundled as jar-in-jar, loaded alongside the jar's own mods
+    #[serde(default)]
+    pub nested_mods: Vec<JarModMetadata>,
+}
+
+impl JarMetadata {
+    /// All loaders the jar has metadata for
+    pub fn loaders(&self) -> Vec<ModLoader> {
+        let mut loaders: Vec<ModLoader> = Vec::new();
+        for m in &self.mods {
+            if !loaders.contains(&m.loader) {
+                loaders.push(m.loader);
+            }
+        }
+        loaders
+    }
+
+    /// False if the jar only has metadata for loaders that can't load it in this profile.
+    /// Jars without metadata are assumed to be compatible.
+    pub fn is_loadable_by(&self, loader: ModLoader, game_version: &str) -> bool {
+        let loaders = self.loaders();
+        loaders.is_empty()
+            || loaders
+                .into_iter()
+                .any(|jar_loader| loader_can_load(loader, jar_loader, game_version))
+    }
+
+    /// The main mod of the jar
+    pub fn primary(&self) -> Option<&JarModMetadata> {
+        self.mods.first()
+    }
+}
+
+/// Whether a profile using `loader` can load mods written for `jar_loader`
+pub fn loader_can_load(loader: ModLoader, jar_loader: ModLoader, game_version: &str) -> bool {
+    match (loader, jar_loader) {
+        (a, b) if a == b => true,
+        // Quilt loads Fabric mods, NeoForge for 1.20.1 still loads Forge mods
+        (ModLoader::Quilt, ModLoader::Fabric) => true,
+        (ModLoader::NeoForge, ModLoader::Forge) => game_version == "1.20.1",
+        _ => false,
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Fabric version predicates are either a string or a list of alternatives
+fn json_version_range(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(range) if range != "*" => non_empty(Some(range)),
+        JsonValue::Array(ranges) => {
+            let ranges: Vec<&str> = ranges.iter().filter_map(JsonValue::as_str).collect();
+            (!ranges.is_empty() && !ranges.contains(&"*")).then(|| ranges.join(" || "))
+        }
+        _ => None,
+    }
+}
+
+/// `icon` is a path or a map of sizes to paths, the largest icon wins
+fn json_icon(value: Option<&JsonValue>) -> Option<String> {
+    match value? {
+        JsonValue::String(path) => non_empty(Some(path)),
+        JsonValue::Object(sizes) => sizes
+            .iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+            .and_then(|(_, path)| non_empty(path.as_str())),
+        _ => None,
+    }
+}
+
+/// fabric.mod.json files often contain raw line breaks inside strings, which strict JSON rejects
+fn parse_lenient_json(content: &str) -> Result<JsonValue> {
+    let cleaned: String = content
+        .trim_start_matches('\u{feff}')
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    Ok(serde_json::from_str(&cleaned)?)
+}
+
+fn parse_fabric_mod_json(content: &str) -> Result<JarModMetadata> {
+    let json = parse_lenient_json(content)?;
+    let mod_id = non_empty(json.get("id").and_then(JsonValue::as_str))
+        .ok_or_else(|| AppError::Other("fabric.mod.json has no id".to_string()))?;
+
+    let mut dependencies = Vec::new();
+    let mut minecraft_versions = None;
+    for (key, kind) in [
+        ("depends", JarDependencyKind::Required),
+        ("recommends", JarDependencyKind::Optional),
+        ("suggests", JarDependencyKind::Optional),
+        ("breaks", JarDependencyKind::Incompatible),
+        ("conflicts", JarDependencyKind::Incompatible),
+    ] {
+        let Some(entries) = json.get(key).and_then(JsonValue::as_object) else {
+            continue;
+        };
+        for (dependency_id, range) in entries {
+            let version_range = json_version_range(range);
+            if dependency_id == "minecraft" && kind == JarDependencyKind::Required {
+                minecraft_versions = version_range.clone();
+            }
+            dependencies.push(JarDependency {
+                mod_id: dependency_id.clone(),
+                version_range,
+                kind,
+            });
+        }
+    }
+
+    Ok(JarModMetadata {
+        mod_id,
+        name: non_empty(json.get("name").and_then(JsonValue::as_str)),
+        version: non_empty(json.get("version").and_then(JsonValue::as_str)),
+        description: non_empty(json.get("description").and_then(JsonValue::as_str)),
+        loader: ModLoader::Fabric,
+        minecraft_versions,
+        dependencies,
+        provides: json
+            .get("provides")
+            .and_then(JsonValue::as_array)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| id.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        icon: json_icon(json.get("icon")),
+    })
+}
+
+/// Quilt dependency entries are either a mod ID or an object with `id`, `versions`, `optional`
+fn quilt_dependency(entry: &JsonValue, kind: JarDependencyKind) -> Option<JarDependency> {
+    match entry {
+        JsonValue::String(id) => Some(JarDependency {
+            mod_id: id.clone(),
+            version_range: None,
+            kind,
+        }),
+        JsonValue::Object(object) => {
+            let optional = object
+                .get("optional")
+                .and_then(JsonValue::as_bool)
+                .unwrap_or(false);
+            Some(JarDependency {
+                mod_id: non_empty(object.get("id").and_then(JsonValue::as_str))?,
+                version_range: object.get("versions").and_then(json_version_range),
+                kind: if optional && kind == JarDependencyKind::Required {
+                    JarDependencyKind::Optional
+                } else {
+                    kind
+                },
+            })
+        }
+        _ => None,
+    }
+}
+
+fn parse_quilt_mod_json(content: &str) -> Result<JarModMetadata> {
+    let json = parse_lenient_json(content)?;
+    let loader = json
+        .get("quilt_loader")
+        .ok_or_else(|| AppError::Other("quilt.mod.json has no quilt_loader".to_string()))?;
+    let mod_id = non_empty(loader.get("id").and_then(JsonValue::as_str))
+        .ok_or_else(|| AppError::Other("quilt.mod.json has no id".to_string()))?;
+    let metadata = loader.get("metadata");
+
+    let mut dependencies = Vec::new();
+    for (key, kind) in [
+        ("depends", JarDependencyKind::Required),
+        ("breaks", JarDependencyKind::Incompatible),
+    ] {
+        if let Some(entries) = loader.get(key).and_then(JsonValue::as_array) {
+            dependencies.extend(entries.iter().filter_map(|e| quilt_dependency(e, kind)));
+        }
+    }
+    let minecraft_versions = dependencies
+        .iter()
+        .find(|d| d.mod_id == "minecraft")
+        .and_then(|d| d.version_range.clone());
+
+    Ok(JarModMetadata {
+        mod_id,
+        name: non_empty(
+            metadata
+                .and_then(|m| m.get("name"))
+                .and_then(JsonValue::as_str),
+        ),
+        version: non_empty(loader.get("version").and_then(JsonValue::as_str)),
+        description: non_empty(
+            metadata
+                .and_then(|m| m.get("description"))
+                .and_then(JsonValue::as_str),
+        ),
+        loader: ModLoader::Quilt,
+        minecraft_versions,
+        dependencies,
+        provides: loader
+            .get("provides")
+            .and_then(JsonValue::as_array)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|e| quilt_dependency(e, JarDependencyKind::Required))
+                    .map(|d| d.mod_id)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        icon: json_icon(metadata.and_then(|m| m.get("icon"))),
+    })
+}
+
+/// `Implementation-Version` from the jar manifest, used for `${file.jarVersion}`
+fn manifest_version(manifest: Option<&str>) -> Option<String> {
+    manifest?.lines().find_map(|line| {
+        line.strip_prefix("Implementation-Version:")
+            .and_then(|v| non_empty(Some(v)))
+    })
+}
+
+fn toml_str<'a>(table: &'a TomlValue, key: &str) -> Option<&'a str> {
+    table.get(key).and_then(TomlValue::as_str)
+}
+
+/// Parses `mods.toml` (Forge) and `neoforge.mods.toml` (NeoForge)
+fn parse_mods_toml(
+    content: &str,
+    loader: ModLoader,
+    manifest: Option<&str>,
+) -> Result<Vec<JarModMetadata>> {
+    let root: TomlValue = toml::from_str(content)
+        .map_err(|e| AppError::Other(format!("Invalid mods.toml: {}", e)))?;
+    let jar_version = manifest_version(manifest);
+    let global_logo = non_empty(toml_str(&root, "logoFile"));
+
+    let mut result = Vec::new();
+    for entry in root
+        .get("mods")
+        .and_then(TomlValue::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(mod_id) = non_empty(toml_str(entry, "modId")) else {
+            continue;
+        };
+
+        let version = non_empty(toml_str(entry, "version")).and_then(|v| {
+            if v.contains("${file.jarVersion}") {
+                jar_version.clone()
+            } else {
+                Some(v)
+            }
+        });
+
+        let mut dependencies = Vec::new();
+        let declared = root
+            .get("dependencies")
+            .and_then(|d| d.get(&mod_id))
+            .and_then(TomlValue::as_array);
+        for dependency in declared.into_iter().flatten() {
+            let Some(dependency_id) = non_empty(toml_str(dependency, "modId")) else {
+                continue;
+            };
+            // Forge uses `mandatory`, NeoForge uses `type`
+            let kind = match toml_str(dependency, "type").map(str::to_ascii_lowercase) {
+                Some(t) if t == "required" => JarDependencyKind::Required,
+                Some(t) if t == "incompatible" || t == "discouraged" => {
+                    JarDependencyKind::Incompatible
+                }
+                Some(_) => JarDependencyKind::Optional,
+                None => match dependency.get("mandatory").and_then(TomlValue::as_bool) {
+                    Some(false) => JarDependencyKind::Optional,
+                    _ => JarDependencyKind::Required,
+                },
+            };
+            let version_range = non_empty(toml_str(dependency, "versionRange"))
+                .filter(|range| range != "*" && range != "[0,)");
+            dependencies.push(JarDependency {
+                mod_id: dependency_id,
+                version_range,
+                kind,
+            });
+        }
+        let minecraft_versions = dependencies
+            .iter()
+            .find(|d| d.mod_id == "minecraft")
+            .and_then(|d| d.version_range.clone());
+
+        result.push(JarModMetadata {
+            mod_id,
+            name: non_empty(toml_str(entry, "displayName")),
+            version,
+            description: non_empty(toml_str(entry, "description")),
+            loader,
+            minecraft_versions,
+            dependencies,
+            provides: Vec::new(),
+            icon: non_empty(toml_str(entry, "logoFile")).or_else(|| global_logo.clone()),
+        });
+    }
+    Ok(result)
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, name: &str) -> Option<String> {
+    let mut file = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    match file.read_to_string(&mut content) {
+        Ok(_) => Some(content),
+        Err(e) => {
+            warn!("Failed to read {} from jar: {}", name, e);
+            None
+        }
+    }
+}
+
+/// Reads the metadata of every loader the jar declares, followed by the mods it bundles as
+/// jar-in-jar (Fabric/Quilt `jars`, Forge/NeoForge `META-INF/jarjar`) up to `MAX_NESTING_DEPTH`.
+/// Broken metadata files are skipped with a warning, the remaining ones are still used.
+pub fn read_jar_mods<R: Read + Seek>(
+    reader: R,
+) -> Result<(Vec<JarModMetadata>, Vec<JarModMetadata>)> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    Ok((archive_mods(&mut archive), nested_mods(&mut archive, 1)))
+}
+
+fn archive_mods<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Vec<JarModMetadata> {
+    let mut mods = Vec::new();
+
+    if let Some(content) = read_entry(archive, QUILT_MOD_JSON) {
+        match parse_quilt_mod_json(&content) {
+            Ok(m) => mods.push(m),
+            Err(e) => warn!("Ignoring invalid quilt.mod.json: {}", e),
+        }
+    }
+    if let Some(content) = read_entry(archive, FABRIC_MOD_JSON) {
+        match parse_fabric_mod_json(&content) {
+            Ok(m) => mods.push(m),
+            Err(e) => warn!("Ignoring invalid fabric.mod.json: {}", e),
+        }
+    }
+    let manifest = read_entry(archive, MANIFEST);
+    for (name, loader) in [
+        (NEOFORGE_MODS_TOML, ModLoader::NeoForge),
+        (FORGE_MODS_TOML, ModLoader::Forge),
+    ] {
+        if let Some(content) = read_entry(archive, name) {
+            match parse_mods_toml(&content, loader, manifest.as_deref()) {
+                Ok(parsed) => mods.extend(parsed),
+                Err(e) => warn!("Ignoring invalid {}: {}", name, e),
+            }
+        }
+    }
+
+    mods
+}
+
+/// Paths of the jars bundled inside the archive, as listed by the loader metadata
+fn nested_jar_paths<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Vec<String> {
+    let json_entry = |archive: &mut zip::ZipArchive<R>, name: &str| {
+        read_entry(archive, name).and_then(|content| parse_lenient_json(&content).ok())
+    };
+    let mut paths: Vec<String> = Vec::new();
+
+    // Fabric and Forge/NeoForge: `"jars": [{ "file" | "path": "..." }]`
+    for (name, key) in [(FABRIC_MOD_JSON, "file"), (JARJAR_METADATA, "path")] {
+        if let Some(json) = json_entry(archive, name) {
+            paths.extend(
+                json.get("jars")
+                    .and_then(JsonValue::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|jar| jar.get(key).and_then(JsonValue::as_str))
+                    .map(str::to_string),
+            );
+        }
+    }
+    // Quilt: `"quilt_loader": { "jars": ["..."] }`
+    if let Some(json) = json_entry(archive, QUILT_MOD_JSON) {
+        paths.extend(
+            json.pointer("/quilt_loader/jars")
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(JsonValue::as_str)
+                .map(str::to_string),
+        );
+    }
+
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn nested_mods<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    depth: usize,
+) -> Vec<JarModMetadata> {
+    let mut mods = Vec::new();
+    if depth > MAX_NESTING_DEPTH {
+        return mods;
+    }
+
+    for path in nested_jar_paths(archive) {
+        let mut bytes = Vec::new();
+        match archive.by_name(&path) {
+            Ok(mut file) => {
+                if let Err(e) = file.read_to_end(&mut bytes) {
+                    warn!("Failed to read nested jar {}: {}", path, e);
+                    continue;
+                }
+            }
+            Err(_) => {
+                debug!("Nested jar {} is listed but not in the jar", path);
+                continue;
+            }
+        }
+        let mut nested = match zip::ZipArchive::new(Cursor::new(bytes)) {
+            Ok(nested) => nested,
+            Err(e) => {
+                warn!("Ignoring invalid nested jar {}: {}", path, e);
+                continue;
+            }
+        };
+        mods.extend(archive_mods(&mut nested));
+        mods.extend(nested_mods(&mut nested, depth + 1));
+    }
+    mods
+}
+
+fn cache_path(sha1: &str) -> std::path::PathBuf {
+    LAUNCHER_DIRECTORY
+        .meta_dir()
+        .join(CACHE_DIR)
+        .join(format!("{}-v{}.json", sha1, CACHE_VERSION))
+}
+
+/// Hashes of a file on disk, valid while its size and modification time are unchanged
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct FileDigests {
+    size: u64,
+    modified_nanos: u128,
+    sha1: String,
+    /// Filled in the first time it is asked for
+    curseforge_fingerprint: Option<u32>,
+}
+
+/// Size and modification time identifying the current contents of a file
+async fn file_stamp(path: &Path) -> Result<(u64, u128)> {
+    let metadata = fs::metadata(path).await?;
+    let modified_nanos = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    Ok((metadata.len(), modified_nanos))
+}
+
+fn digests_path(digests_dir: &Path, path: &Path) -> PathBuf {
+    let key = hash_utils::calculate_sha1_from_bytes(path.to_string_lossy().as_bytes());
+    digests_dir.join(format!("{}.json", key))
+}
+
+/// Cached digests of `path` in `digests_dir`; the file is hashed again only if it changed
+/// since then. The CurseForge fingerprint is computed as well if `with_fingerprint` is set.
+async fn file_digests_in(
+    digests_dir: &Path,
+    path: &Path,
+    with_fingerprint: bool,
+) -> Result<FileDigests> {
+    let (size, modified_nanos) = file_stamp(path).await?;
+    let digests_file = digests_path(digests_dir, path);
+    let cached = fs::read(&digests_file)
+        .await
+        .ok()
+        .and_then(|raw| serde_json::from_slice::<FileDigests>(&raw).ok())
+        .filter(|d| d.size == size && d.modified_nanos == modified_nanos);
+    if let Some(digests) = &cached {
+        if !with_fingerprint || digests.curseforge_fingerprint.is_some() {
+            return Ok(digests.clone());
+        }
+    }
+
+    let digests = if with_fingerprint {
+        let bytes = fs::read(path).await?;
+        FileDigests {
+            size,
+            modified_nanos,
+            sha1: hash_utils::calculate_sha1_from_bytes(&bytes),
+            curseforge_fingerprint: Some(hash_utils::calculate_curseforge_fingerprint(&bytes)),
+        }
+    } else {
+        FileDigests {
+            size,
+            modified_nanos,
+            sha1: hash_utils::calculate_sha1_from_file(path).await?,
+            curseforge_fingerprint: None,
+        }
+    };
+
+    fs::create_dir_all(digests_dir).await?;
+    if let Err(e) = fs::write(&digests_file, serde_json::to_vec(&digests)?).await {
+        warn!("Failed to cache digests of {:?}: {}", path, e);
+    }
+    Ok(digests)
+}
+
+fn digests_dir() -> PathBuf {
+    LAUNCHER_DIRECTORY
+        .meta_dir()
+        .join(CACHE_DIR)
+        .join(DIGESTS_DIR)
+}
+
+/// SHA1 of a file, only hashed again if its size or modification time changed
+pub async fn cached_sha1(path: &Path) -> Result<String> {
+    Ok(file_digests_in(&digests_dir(), path, false).await?.sha1)
+}
+
+/// CurseForge fingerprint of a file, only computed again if its size or modification time changed
+pub async fn cached_curseforge_fingerprint(path: &Path) -> Result<u32> {
+    file_digests_in(&digests_dir(), path, true)
+        .await?
+        .curseforge_fingerprint
+        .ok_or_else(|| AppError::Other(format!("No fingerprint computed for {:?}", path)))
+}
+
+/// Inspects a jar whose SHA1 is already known, using the cache where possible
+pub async fn inspect_jar_with_sha1(path: &Path, sha1: &str) -> Result<JarMetadata> {
+    let cache_file = cache_path(sha1);
+    if let Ok(cached) = fs::read(&cache_file).await {
+        match serde_json::from_slice::<JarMetadata>(&cached) {
+            Ok(metadata) => return Ok(metadata),
+            Err(e) => warn!(
+                "Ignoring corrupt jar metadata cache {:?}: {}",
+                cache_file, e
+            ),
+        }
+    }
+
+    let jar_path = path.to_path_buf();
+    let (mods, nested_mods) = tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&jar_path)?;
+        read_jar_mods(std::io::BufReader::new(file))
+    })
+    .await
+    .map_err(|e| AppError::Other(format!("Jar inspection task failed: {}", e)))??;
+
+    let metadata = JarMetadata {
+        sha1: sha1.to_string(),
+        mods,
+        nested_mods,
+    };
+    debug!(
+        "Read metadata of {:?}: {:?}",
+        path,
+        metadata.mods.iter().map(|m| &m.mod_id).collect::<Vec<_>>()
+    );
+
+    if let Some(parent) = cache_file.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    if let Err(e) = fs::write(&cache_file, serde_json::to_vec(&metadata)?).await {
+        warn!("Failed to cache jar metadata at {:?}: {}", cache_file, e);
+    }
+    Ok(metadata)
+}
+
+/// Inspects a jar, the result is cached by SHA1. Unchanged files are not hashed again.
+pub async fn inspect_jar(path: &Path) -> Result<JarMetadata> {
+    let sha1 = cached_sha1(path).await?;
+    inspect_jar_with_sha1(path, &sha1).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn jar(entries: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let mut cursor = writer.finish().unwrap();
+        cursor.set_position(0);
+        cursor
+    }
+
+    #[test]
+    fn test_reads_fabric_and_quilt_metadata() {
+        let fabric = r#"{
+            "schemaVersion": 1,
+            "id": "sodium-extra",
+            "version": "0.5.1",
+            "name": "Sodium Extra",
+            "description": "Features that
+ Sodium does not have",
+            "icon": { "16": "assets/icon16.png", "128": "assets/icon.png" },
+            "depends": { "minecraft": ["1.20", "1.20.1"], "sodium": ">=0.5.0", "fabricloader": "*" },
+            "breaks": { "optifabric": "*" },
+            "provides": ["sodium_extra"]
+        }"#;
+        let (mods, _) = read_jar_mods(jar(&[(FABRIC_MOD_JSON, fabric)])).unwrap();
+        let m = &mods[0];
+        assert_eq!(m.mod_id, "sodium-extra");
+        assert_eq!(m.loader, ModLoader::Fabric);
+        assert_eq!(m.icon.as_deref(), Some("assets/icon.png"));
+        assert_eq!(m.minecraft_versions.as_deref(), Some("1.20 || 1.20.1"));
+        assert_eq!(m.provides, vec!["sodium_extra".to_string()]);
+        assert!(m.dependencies.contains(&JarDependency {
+            mod_id: "sodium".into(),
+            version_range: Some(">=0.5.0".into()),
+            kind: JarDependencyKind::Required,
+        }));
+        assert!(m.dependencies.contains(&JarDependency {
+            mod_id: "fabricloader".into(),
+            version_range: None,
+            kind: JarDependencyKind::Required,
+        }));
+        assert!(m.dependencies.contains(&JarDependency {
+            mod_id: "optifabric".into(),
+            version_range: None,
+            kind: JarDependencyKind::Incompatible,
+        }));
+
+        let quilt = r#"{
+            "schema_version": 1,
+            "quilt_loader": {
+                "id": "qsl_example",
+                "version": "1.0.0",
+                "metadata": { "name": "Example", "icon": "icon.png" },
+                "depends": [
+                    "quilt_loader",
+                    { "id": "minecraft", "versions": ">=1.20" },
+                    { "id": "modmenu", "optional": true }
+                ]
+            }
+        }"#;
+        let (mods, _) = read_jar_mods(jar(&[(QUILT_MOD_JSON, quilt)])).unwrap();
+        assert_eq!(mods[0].loader, ModLoader::Quilt);
+        assert_eq!(mods[0].minecraft_versions.as_deref(), Some(">=1.20"));
+        assert_eq!(mods[0].dependencies[2].kind, JarDependencyKind::Optional);
+    }
+
+    #[test]
+    fn test_reads_forge_and_neoforge_mods_toml() {
+        let forge = r#"
+modLoader = "javafml"
+loaderVersion = "[47,)"
+logoFile = "logo.png"
+
+[[mods]]
+modId = "create"
+version = "${file.jarVersion}"
+displayName = "Create"
+
+[[dependencies.create]]
+modId = "forge"
+mandatory = true
+versionRange = "[47.1.3,)"
+
+[[dependencies.create]]
+modId = "minecraft"
+mandatory = true
+versionRange = "[1.20.1,1.20.2)"
+
+[[dependencies.create]]
+modId = "jei"
+mandatory = false
+versionRange = "*"
+"#;
+        let neoforge = r#"
+[[mods]]
+modId = "create"
+version = "6.0.0"
+
+[[dependencies.create]]
+modId = "flywheel"
+type = "required"
+versionRange = "[1.0,)"
+
+[[dependencies.create]]
+modId = "optifine"
+type = "incompatible"
+"#;
+        let data = jar(&[
+            (FORGE_MODS_TOML, forge),
+            (NEOFORGE_MODS_TOML, neoforge),
+            (
+                MANIFEST,
+                "Manifest-Version: 1.0\r\nImplementation-Version: 0.5.1.f\r\n",
+            ),
+        ]);
+        let metadata = JarMetadata {
+            sha1: String::new(),
+            mods: read_jar_mods(data).unwrap().0,
+            nested_mods: Vec::new(),
+        };
+
+        let neo = &metadata.mods[0];
+        assert_eq!(neo.loader, ModLoader::NeoForge);
+        assert_eq!(neo.dependencies[0].kind, JarDependencyKind::Required);
+        assert_eq!(neo.dependencies[1].kind, JarDependencyKind::Incompatible);
+
+        let forge = &metadata.mods[1];
+        assert_eq!(forge.loader, ModLoader::Forge);
+        assert_eq!(forge.version.as_deref(), Some("0.5.1.f"));
+        assert_eq!(forge.icon.as_deref(), Some("logo.png"));
+        assert_eq!(forge.minecraft_versions.as_deref(), Some("[1.20.1,1.20.2)"));
+        assert_eq!(forge.dependencies[2].kind, JarDependencyKind::Optional);
+        assert_eq!(forge.dependencies[2].version_range, None);
+
+        assert!(metadata.is_loadable_by(ModLoader::Forge, "1.20.1"));
+        assert!(!metadata.is_loadable_by(ModLoader::Fabric, "1.20.1"));
+        // Jars without any metadata are not flagged
+        let library = JarMetadata {
+            sha1: String::new(),
+            mods: read_jar_mods(jar(&[(MANIFEST, "Manifest-Version: 1.0\n")]))
+                .unwrap()
+                .0,
+            nested_mods: Vec::new(),
+        };
+        assert!(library.is_loadable_by(ModLoader::Fabric, "1.20.1"));
+    }
+
+    #[test]
+    fn test_reads_nested_jar_in_jar_mods() {
+        let zip_bytes = |entries: &[(&str, &[u8])]| {
+            let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+            for (name, content) in entries {
+                writer
+                    .start_file(*name, zip::write::SimpleFileOptions::default())
+                    .unwrap();
+                writer.write_all(content).unwrap();
+            }
+            writer.finish().unwrap().into_inner()
+        };
+
+        let innermost = zip_bytes(&[(
+            FABRIC_MOD_JSON,
+            br#"{ "id": "fabric-api-base", "provides": ["fabric-api-base-common"] }"#,
+        )]);
+        let module = zip_bytes(&[
+            (
+                FABRIC_MOD_JSON,
+                br#"{ "id": "fabric-networking-api-v1", "jars": [{ "file": "META-INF/jars/base.jar" }] }"#,
+            ),
+            ("META-INF/jars/base.jar", &innermost),
+        ]);
+        let forge_lib = zip_bytes(&[(
+            FORGE_MODS_TOML,
+            b"[[mods]]\nmodId = \"mixinextras\"\nversion = \"0.3.5\"\n",
+        )]);
+        let outer = zip_bytes(&[
+            (
+                FABRIC_MOD_JSON,
+                br#"{ "id": "outer", "jars": [{ "file": "META-INF/jars/networking.jar" }, { "file": "META-INF/jars/missing.jar" }] }"#,
+            ),
+            ("META-INF/jars/networking.jar", &module),
+            (
+                JARJAR_METADATA,
+                br#"{ "jars": [{ "path": "META-INF/jarjar/mixinextras.jar" }] }"#,
+            ),
+            ("META-INF/jarjar/mixinextras.jar", &forge_lib),
+        ]);
+
+        let (own, nested) = read_jar_mods(Cursor::new(outer)).unwrap();
+        let ids: Vec<&str> = nested.iter().map(|m| m.mod_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["mixinextras", "fabric-networking-api-v1", "fabric-api-base"]
+        );
+        assert_eq!(nested[0].loader, ModLoader::Forge);
+        assert_eq!(
+            nested[2].provides,
+            vec!["fabric-api-base-common".to_string()]
+        );
+        // The jar's own mods don't include bundled ones
+        assert_eq!(own.len(), 1);
+        assert_eq!(own[0].mod_id, "outer");
+    }
+
+    #[tokio::test]
+    async fn test_file_digests_reused_until_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let digests_dir = dir.path().join("digests");
+        let jar_path = dir.path().join("mod.jar");
+        std::fs::write(&jar_path, b"first").unwrap();
+
+        let first = file_digests_in(&digests_dir, &jar_path, false)
+            .await
+            .unwrap();
+        assert_eq!(first.sha1, hash_utils::calculate_sha1_from_bytes(b"first"));
+        assert_eq!(first.curseforge_fingerprint, None);
+
+        // A stale entry with matching size and mtime is trusted without hashing the file
+        let stale = FileDigests {
+            sha1: "cached".to_string(),
+            ..first.clone()
+        };
+        std::fs::write(
+            digests_path(&digests_dir, &jar_path),
+            serde_json::to_vec(&stale).unwrap(),
+        )
+        .unwrap();
+        let cached = file_digests_in(&digests_dir, &jar_path, false)
+            .await
+            .unwrap();
+        assert_eq!(cached.sha1, "cached");
+
+        // Asking for the fingerprint reads the file once more
+        let with_fingerprint = file_digests_in(&digests_dir, &jar_path, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            with_fingerprint.curseforge_fingerprint,
+            Some(hash_utils::calculate_curseforge_fingerprint(b"first"))
+        );
+
+        // Changing the size invalidates the entry
+        std::fs::write(&jar_path, b"second file").unwrap();
+        let changed = file_digests_in(&digests_dir, &jar_path, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            changed.sha1,
+            hash_utils::calculate_sha1_from_bytes(b"second file")
+        );
+    }
+}
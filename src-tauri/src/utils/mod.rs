@@ -7,18 +7,25 @@ pub mod hash_utils;
 pub mod java_detector; // Java detector to find Java installations
 pub mod mc_utils; // Utilities for Minecraft-related operations
 pub mod migration_utils; // Migration utilities for profile and config updates
+pub mod options_utils; // options.txt lesen/schreiben, Presets und Sync ausgewählter Optionen vor dem Start
 pub mod path_utils; // Deklariert das neue Modul und macht seinen Inhalt (wenn `pub`) nutzbar
 pub mod profile_utils; // Utility-Funktionen für Profilinhalte wie das Installieren von Modrinth-Content
 pub mod repair_utils; // Repair utilities for fixing profile issues
 pub mod resourcepack_utils; // ResourcePack-Utils für das Scannen und Verwalten von ResourcePacks
 pub mod security_utils; // Security utilities for masking sensitive data
+pub mod server_list_utils; // Bearbeiten der servers.dat (hinzufügen, ändern, sortieren, an Gruppen verteilen)
 pub mod server_ping; // Server-Ping-Modul für die Kommunikation mit Minecraft-Servern
 pub mod shaderpack_utils; // ShaderPack-Utils für das Scannen und Verwalten von ShaderPacks
 pub mod system_info; // <-- Hinzufügen
 pub mod updater_utils;
 pub mod world_utils; // <-- Hinzugefügt
+pub mod world_backup_utils; // Welt-Snapshots (zip / tar.zst) mit Aufbewahrung und Wiederherstellung
+pub mod session_history_utils; // Spielzeit: abgeschlossene Sessions pro Profil/Account und Statistiken daraus
+pub mod profile_snapshot_utils; // Snapshots von Mod-Liste, Loader und Configs eines Profils mit Rollback
 pub mod trash_utils; // <-- New trash module
 pub mod backup_utils; // <-- New backup module for critical files
+pub mod content_store; // SHA1-keyed file store, profiles get hardlinks instead of copies
+pub mod jar_metadata; // Reads fabric.mod.json, quilt.mod.json and (neoforge.)mods.toml from mod jars, cached by SHA1
 
 // Füge hier ggf. andere Util-Module hinzu
 // pub mod network_utils;
@@ -0,0 +1,739 @@
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::{AppError, Result};
+use crate::state::profile_state::Profile;
+use crate::state::State;
+use crate::utils::mc_utils::is_legacy_minecraft_version;
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::fs;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+const OPTIONS_FILE: &str = "options.txt";
+const PRESETS_FILENAME: &str = "options_presets.json";
+const KEY_BINDING_PREFIX: &str = "key_";
+/// Data version of the file, the game upgrades the other options based on it.
+/// Never copied between files, not even by presets without keys or a `*` sync pattern.
+const DATA_VERSION_KEY: &str = "version";
+
+// Presets are read, changed and written back as a whole
+static PRESETS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// Renamed key binds: (old name, new name, minor version of the 1.x release that renamed it)
+const RENAMED_KEY_BINDINGS: &[(&str, &str, u32)] =
+    &[("key_key.swapHands", "key_key.swapOffhand", 16)];
+
+// Up to 1.12 key binds are stored as LWJGL 2 key codes, since 1.13 as key names
+const LEGACY_KEY_CODES: &[(i32, &str)] = &[
+    (0, "key.keyboard.unknown"),
+    (-100, "key.mouse.left"),
+    (-99, "key.mouse.right"),
+    (-98, "key.mouse.middle"),
+    (-97, "key.mouse.4"),
+    (-96, "key.mouse.5"),
+    (1, "key.keyboard.escape"),
+    (2, "key.keyboard.1"),
+    (3, "key.keyboard.2"),
+    (4, "key.keyboard.3"),
+    (5, "key.keyboard.4"),
+    (6, "key.keyboard.5"),
+    (7, "key.keyboard.6"),
+    (8, "key.keyboard.7"),
+    (9, "key.keyboard.8"),
+    (10, "key.keyboard.9"),
+    (11, "key.keyboard.0"),
+    (12, "key.keyboard.minus"),
+    (13, "key.keyboard.equal"),
+    (14, "key.keyboard.backspace"),
+    (15, "key.keyboard.tab"),
+    (16, "key.keyboard.q"),
+    (17, "key.keyboard.w"),
+    (18, "key.keyboard.e"),
+    (19, "key.keyboard.r"),
+    (20, "key.keyboard.t"),
+    (21, "key.keyboard.y"),
+    (22, "key.keyboard.u"),
+    (23, "key.keyboard.i"),
+    (24, "key.keyboard.o"),
+    (25, "key.keyboard.p"),
+    (26, "key.keyboard.left.bracket"),
+    (27, "key.keyboard.right.bracket"),
+    (28, "key.keyboard.enter"),
+    (29, "key.keyboard.left.control"),
+    (30, "key.keyboard.a"),
+    (31, "key.keyboard.s"),
+    (32, "key.keyboard.d"),
+    (33, "key.keyboard.f"),
+    (34, "key.keyboard.g"),
+    (35, "key.keyboard.h"),
+    (36, "key.keyboard.j"),
+    (37, "key.keyboard.k"),
+    (38, "key.keyboard.l"),
+    (39, "key.keyboard.semicolon"),
+    (40, "key.keyboard.apostrophe"),
+    (41, "key.keyboard.grave.accent"),
+    (42, "key.keyboard.left.shift"),
+    (43, "key.keyboard.backslash"),
+    (44, "key.keyboard.z"),
+    (45, "key.keyboard.x"),
+    (46, "key.keyboard.c"),
+    (47, "key.keyboard.v"),
+    (48, "key.keyboard.b"),
+    (49, "key.keyboard.n"),
+    (50, "key.keyboard.m"),
+    (51, "key.keyboard.comma"),
+    (52, "key.keyboard.period"),
+    (53, "key.keyboard.slash"),
+    (54, "key.keyboard.right.shift"),
+    (55, "key.keyboard.keypad.multiply"),
+    (56, "key.keyboard.left.alt"),
+    (57, "key.keyboard.space"),
+    (58, "key.keyboard.caps.lock"),
+    (59, "key.keyboard.f1"),
+    (60, "key.keyboard.f2"),
+    (61, "key.keyboard.f3"),
+    (62, "key.keyboard.f4"),
+    (63, "key.keyboard.f5"),
+    (64, "key.keyboard.f6"),
+    (65, "key.keyboard.f7"),
+    (66, "key.keyboard.f8"),
+    (67, "key.keyboard.f9"),
+    (68, "key.keyboard.f10"),
+    (71, "key.keyboard.keypad.7"),
+    (72, "key.keyboard.keypad.8"),
+    (73, "key.keyboard.keypad.9"),
+    (74, "key.keyboard.keypad.subtract"),
+    (75, "key.keyboard.keypad.4"),
+    (76, "key.keyboard.keypad.5"),
+    (77, "key.keyboard.keypad.6"),
+    (78, "key.keyboard.keypad.add"),
+    (79, "key.keyboard.keypad.1"),
+    (80, "key.keyboard.keypad.2"),
+    (81, "key.keyboard.keypad.3"),
+    (82, "key.keyboard.keypad.0"),
+    (83, "key.keyboard.keypad.decimal"),
+    (87, "key.keyboard.f11"),
+    (88, "key.keyboard.f12"),
+    (156, "key.keyboard.keypad.enter"),
+    (157, "key.keyboard.right.control"),
+    (181, "key.keyboard.keypad.divide"),
+    (184, "key.keyboard.right.alt"),
+    (199, "key.keyboard.home"),
+    (200, "key.keyboard.up"),
+    (201, "key.keyboard.page.up"),
+    (203, "key.keyboard.left"),
+    (205, "key.keyboard.right"),
+    (207, "key.keyboard.end"),
+    (208, "key.keyboard.down"),
+    (209, "key.keyboard.page.down"),
+    (210, "key.keyboard.insert"),
+    (211, "key.keyboard.delete"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum OptionsLine {
+    Option { key: String, value: String },
+    // Empty or malformed lines, written back unchanged
+    Other(String),
+}
+
+/// options.txt as a list of lines, so unknown keys and their order survive a rewrite
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionsFile {
+    lines: Vec<OptionsLine>,
+    line_ending: &'static str,
+}
+
+impl Default for OptionsFile {
+    fn default() -> Self {
+        Self {
+            lines: Vec::new(),
+            line_ending: "\n",
+        }
+    }
+}
+
+impl OptionsFile {
+    pub fn parse(content: &str) -> Self {
+        let lines = content
+            .lines()
+            .map(|line| match line.split_once(':') {
+                Some((key, value)) if !key.is_empty() => OptionsLine::Option {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                },
+                _ => OptionsLine::Other(line.to_string()),
+            })
+            .collect();
+        Self {
+            lines,
+            // The game writes CRLF on Windows
+            line_ending: if content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            },
+        }
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut content = String::new();
+        for line in &self.lines {
+            match line {
+                OptionsLine::Option { key, value } => {
+                    content.push_str(key);
+                    content.push(':');
+                    content.push_str(value);
+                }
+                OptionsLine::Other(text) => content.push_str(text),
+            }
+            content.push_str(self.line_ending);
+        }
+        content
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options()
+            .find(|(option_key, _)| *option_key == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Replaces the value in place, new keys are appended
+    pub fn set(&mut self, key: &str, value: &str) {
+        for line in &mut self.lines {
+            if let OptionsLine::Option {
+                key: option_key,
+                value: option_value,
+            } = line
+            {
+                if option_key == key {
+                    *option_value = value.to_string();
+                    return;
+                }
+            }
+        }
+        self.lines.push(OptionsLine::Option {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    pub fn options(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| match line {
+            OptionsLine::Option { key, value } => Some((key.as_str(), value.as_str())),
+            OptionsLine::Other(_) => None,
+        })
+    }
+}
+
+/// One option in the 1.13+ format (key names instead of LWJGL 2 key codes)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OptionValue {
+    pub key: String,
+    pub value: String,
+}
+
+/// Named set of options that can be written into any profile
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OptionsPreset {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub options: Vec<OptionValue>,
+}
+
+/// Outcome of applying a preset to one profile
+#[derive(Serialize, Debug, Clone)]
+pub struct OptionsApplyResult {
+    pub profile_id: Uuid,
+    #[serde(flatten)]
+    pub status: OptionsApplyStatus,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OptionsApplyStatus {
+    Applied {
+        options: usize,
+    },
+    /// The game rewrites options.txt when it exits, changes would be lost
+    SkippedRunning,
+    Failed {
+        error: String,
+    },
+}
+
+/// `*` at the end of a pattern matches any key with that prefix
+fn matches_key(patterns: &[String], key: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => pattern == key,
+        })
+}
+
+// Key binds may carry a suffix after the key, e.g. Forge's modifier `key.keyboard.g:CONTROL`
+fn convert_key_value(value: &str, to_legacy: bool) -> Option<String> {
+    let (key, suffix) = match value.split_once(':') {
+        Some((key, suffix)) => (key, Some(suffix)),
+        None => (value, None),
+    };
+    let converted = match (to_legacy, key.parse::<i32>()) {
+        (true, Ok(_)) | (false, Err(_)) => key.to_string(),
+        (true, Err(_)) => LEGACY_KEY_CODES
+            .iter()
+            .find(|(_, name)| *name == key)?
+            .0
+            .to_string(),
+        (false, Ok(code)) => LEGACY_KEY_CODES
+            .iter()
+            .find(|(legacy_code, _)| *legacy_code == code)?
+            .1
+            .to_string(),
+    };
+    Some(match suffix {
+        Some(suffix) => format!("{}:{}", converted, suffix),
+        None => converted,
+    })
+}
+
+/// True for `1.x` releases before `1.<minor>`. Snapshots and unknown formats count as newer.
+fn is_release_before(game_version: &str, minor: u32) -> bool {
+    let mut parts = game_version.split('.');
+    match (parts.next(), parts.next().map(str::parse::<u32>)) {
+        (Some("1"), Some(Ok(version_minor))) => version_minor < minor,
+        _ => false,
+    }
+}
+
+/// Converts an option read from a file of `game_version` into the current format:
+/// key names instead of legacy key codes and the current names of renamed key binds.
+/// Key binds without a known counterpart are dropped.
+fn to_modern(key: &str, value: &str, game_version: &str) -> Option<OptionValue> {
+    if !key.starts_with(KEY_BINDING_PREFIX) {
+        return Some(OptionValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+    let key = RENAMED_KEY_BINDINGS
+        .iter()
+        .find(|(old, _, _)| *old == key)
+        .map_or(key, |(_, new, _)| *new);
+    let value = if is_legacy_minecraft_version(game_version) {
+        convert_key_value(value, false)?
+    } else {
+        value.to_string()
+    };
+    Some(OptionValue {
+        key: key.to_string(),
+        value,
+    })
+}
+
+/// Converts an option in the current format for a file of `game_version`. A renamed key bind
+/// keeps whichever name the file already uses, otherwise the name of that version is used.
+fn to_target(
+    option: &OptionValue,
+    target: &OptionsFile,
+    game_version: &str,
+) -> Option<OptionValue> {
+    if !option.key.starts_with(KEY_BINDING_PREFIX) {
+        return Some(option.clone());
+    }
+    let key = match RENAMED_KEY_BINDINGS
+        .iter()
+        .find(|(_, new, _)| *new == option.key)
+    {
+        Some((old, _, _)) if target.get(old).is_some() => old,
+        Some((_, new, _)) if target.get(new).is_some() => new,
+        Some((old, _, renamed_in)) if is_release_before(game_version, *renamed_in) => old,
+        _ => option.key.as_str(),
+    };
+    Some(OptionValue {
+        key: key.to_string(),
+        value: convert_key_value(&option.value, is_legacy_minecraft_version(game_version))?,
+    })
+}
+
+/// Reads all options of a file in the current format, optionally only matching keys
+fn modern_options(
+    file: &OptionsFile,
+    game_version: &str,
+    patterns: Option<&[String]>,
+) -> Vec<OptionValue> {
+    file.options()
+        .filter(|(key, _)| *key != DATA_VERSION_KEY)
+        .filter_map(|(key, value)| to_modern(key, value, game_version))
+        .filter(|option| patterns.is_none_or(|patterns| matches_key(patterns, &option.key)))
+        .collect()
+}
+
+/// Writes options in the current format into a file of the given version,
+/// returns how many were applied
+fn apply_options(file: &mut OptionsFile, options: &[OptionValue], game_version: &str) -> usize {
+    let mut applied = 0;
+    // Presets saved before the data version was excluded may still contain it
+    for option in options.iter().filter(|o| o.key != DATA_VERSION_KEY) {
+        match to_target(option, file, game_version) {
+            Some(target) => {
+                file.set(&target.key, &target.value);
+                applied += 1;
+            }
+            None => debug!(
+                "[Options] Skipping {}:{}, no equivalent for Minecraft {}",
+                option.key, option.value, game_version
+            ),
+        }
+    }
+    applied
+}
+
+async fn read_options_file(path: &Path) -> Result<OptionsFile> {
+    if !fs::try_exists(path).await? {
+        return Ok(OptionsFile::default());
+    }
+    Ok(OptionsFile::parse(&fs::read_to_string(path).await?))
+}
+
+fn presets_path() -> PathBuf {
+    LAUNCHER_DIRECTORY.root_dir().join(PRESETS_FILENAME)
+}
+
+async fn load_presets() -> Result<Vec<OptionsPreset>> {
+    let path = presets_path();
+    if !fs::try_exists(&path).await? {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path).await?)?)
+}
+
+async fn save_presets(presets: &[OptionsPreset]) -> Result<()> {
+    let path = presets_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(presets)?).await?;
+    Ok(())
+}
+
+pub async fn list_options_presets() -> Result<Vec<OptionsPreset>> {
+    load_presets().await
+}
+
+async fn get_options_preset(preset_id: Uuid) -> Result<OptionsPreset> {
+    load_presets()
+        .await?
+        .into_iter()
+        .find(|preset| preset.id == preset_id)
+        .ok_or_else(|| AppError::NotFound(format!("Options preset {}", preset_id)))
+}
+
+/// Saves the options of a profile as a preset. `keys` limits the preset to matching
+/// options (e.g. `["key_*", "fov"]`), without it every option is taken.
+pub async fn create_options_preset_from_profile(
+    profile_id: Uuid,
+    name: String,
+    keys: Option<Vec<String>>,
+) -> Result<OptionsPreset> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Preset name cannot be empty".to_string(),
+        ));
+    }
+    let state = State::get().await?;
+    let profile = state.profile_manager.get_profile(profile_id).await?;
+    let options_path = state
+        .profile_manager
+        .get_profile_instance_path(profile_id)
+        .await?
+        .join(OPTIONS_FILE);
+    if !fs::try_exists(&options_path).await? {
+        return Err(AppError::FileNotFound(options_path));
+    }
+
+    let file = read_options_file(&options_path).await?;
+    let preset = OptionsPreset {
+        id: Uuid::new_v4(),
+        name: name.trim().to_string(),
+        created_at: Utc::now(),
+        options: modern_options(&file, &profile.game_version, keys.as_deref()),
+    };
+
+    let _guard = PRESETS_LOCK.lock().await;
+    let mut presets = load_presets().await?;
+    presets.push(preset.clone());
+    save_presets(&presets).await?;
+
+    info!(
+        "[Options] Created preset '{}' with {} options from profile '{}'",
+        preset.name,
+        preset.options.len(),
+        profile.name
+    );
+    Ok(preset)
+}
+
+pub async fn delete_options_preset(preset_id: Uuid) -> Result<()> {
+    let _guard = PRESETS_LOCK.lock().await;
+    let mut presets = load_presets().await?;
+    let count = presets.len();
+    presets.retain(|preset| preset.id != preset_id);
+    if presets.len() == count {
+        return Err(AppError::NotFound(format!("Options preset {}", preset_id)));
+    }
+    save_presets(&presets).await
+}
+
+async fn apply_preset_to_profile(
+    state: &State,
+    preset: &OptionsPreset,
+    profile_id: Uuid,
+) -> Result<usize> {
+    let profile = state.profile_manager.get_profile(profile_id).await?;
+    let instance_path = state
+        .profile_manager
+        .get_profile_instance_path(profile_id)
+        .await?;
+    let options_path = instance_path.join(OPTIONS_FILE);
+
+    let mut file = read_options_file(&options_path).await?;
+    let applied = apply_options(&mut file, &preset.options, &profile.game_version);
+    fs::create_dir_all(&instance_path).await?;
+    fs::write(&options_path, file.serialize()).await?;
+    Ok(applied)
+}
+
+/// Writes a preset into the options.txt of each profile. Running profiles are skipped.
+pub async fn apply_options_preset(
+    preset_id: Uuid,
+    profile_ids: Vec<Uuid>,
+) -> Result<Vec<OptionsApplyResult>> {
+    let state = State::get().await?;
+    let preset = get_options_preset(preset_id).await?;
+
+    let mut results = Vec::with_capacity(profile_ids.len());
+    for profile_id in profile_ids {
+        let status = if state.process_manager.is_profile_running(profile_id).await {
+            OptionsApplyStatus::SkippedRunning
+        } else {
+            match apply_preset_to_profile(&state, &preset, profile_id).await {
+                Ok(options) => OptionsApplyStatus::Applied { options },
+                Err(e) => {
+                    warn!(
+                        "[Options] Failed to apply preset '{}' to profile {}: {}",
+                        preset.name, profile_id, e
+                    );
+                    OptionsApplyStatus::Failed {
+                        error: e.to_string(),
+                    }
+                }
+            }
+        };
+        results.push(OptionsApplyResult { profile_id, status });
+    }
+    Ok(results)
+}
+
+async fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).await.ok()?.modified().ok()
+}
+
+/// The most recently changed options.txt of all other profiles (user and standard),
+/// if it is newer than `newer_than`, with the game version of its profile
+async fn newest_options_file(
+    state: &State,
+    own_instance_path: &Path,
+    newer_than: Option<SystemTime>,
+) -> Result<Option<(PathBuf, String)>> {
+    let mut profiles = state.profile_manager.list_profiles().await?;
+    profiles.extend(state.GEG_version_manager.get_config().await.profiles);
+
+    let mut seen_paths = HashSet::new();
+    let mut newest: Option<(SystemTime, PathBuf, String)> = None;
+    for profile in profiles {
+        let Ok(instance_path) = state
+            .profile_manager
+            .calculate_instance_path_for_profile(&profile)
+        else {
+            continue;
+        };
+        // Profiles in a group can share one game directory
+        if instance_path == own_instance_path || !seen_paths.insert(instance_path.clone()) {
+            continue;
+        }
+        let options_path = instance_path.join(OPTIONS_FILE);
+        let Some(modified) = modified_time(&options_path).await else {
+            continue;
+        };
+        if newer_than.is_some_and(|own| modified <= own)
+            || newest
+                .as_ref()
+                .is_some_and(|(time, _, _)| modified <= *time)
+        {
+            continue;
+        }
+        newest = Some((modified, options_path, profile.game_version));
+    }
+    Ok(newest.map(|(_, path, game_version)| (path, game_version)))
+}
+
+/// Runs before launch: gives profiles without options.txt the default preset and
+/// copies the synced keys from the most recently changed options.txt of any profile.
+pub async fn prepare_options_before_launch(profile: &Profile, game_directory: &Path) -> Result<()> {
+    let state = State::get().await?;
+    let settings = state.config_manager.get_config().await.game_options;
+    let sync = settings.sync_enabled && !settings.sync_keys.is_empty();
+    if settings.default_preset_id.is_none() && !sync {
+        return Ok(());
+    }
+
+    let options_path = game_directory.join(OPTIONS_FILE);
+    let own_modified = modified_time(&options_path).await;
+    let mut file = read_options_file(&options_path).await?;
+    let mut changed = false;
+
+    if let (None, Some(preset_id)) = (own_modified, settings.default_preset_id) {
+        match get_options_preset(preset_id).await {
+            Ok(preset) => {
+                let applied = apply_options(&mut file, &preset.options, &profile.game_version);
+                info!(
+                    "[Options] Applied default preset '{}' ({} options) to new profile '{}'",
+                    preset.name, applied, profile.name
+                );
+                changed = true;
+            }
+            Err(e) => warn!("[Options] Default options preset is not available: {}", e),
+        }
+    }
+
+    if sync {
+        if let Some((source_path, source_version)) =
+            newest_options_file(&state, game_directory, own_modified).await?
+        {
+            let source = read_options_file(&source_path).await?;
+            let synced = modern_options(&source, &source_version, Some(&settings.sync_keys));
+            let applied = apply_options(&mut file, &synced, &profile.game_version);
+            info!(
+                "[Options] Synced {} options from {} into profile '{}'",
+                applied,
+                source_path.display(),
+                profile.name
+            );
+            changed |= applied > 0;
+        }
+    }
+
+    if changed {
+        fs::write(&options_path, file.serialize()).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_values_in_place_and_keeps_unknown_lines() {
+        let content = "version:3465\r\nfov:0.0\r\nresourcePacks:[\"vanilla\",\"file/a:b.zip\"]\r\nnot an option\r\nmodded_option:true\r\n";
+        let mut file = OptionsFile::parse(content);
+        assert_eq!(file.serialize(), content);
+        assert_eq!(
+            file.get("resourcePacks"),
+            Some("[\"vanilla\",\"file/a:b.zip\"]")
+        );
+
+        file.set("fov", "0.5");
+        file.set("renderDistance", "16");
+        assert_eq!(
+            file.serialize(),
+            "version:3465\r\nfov:0.5\r\nresourcePacks:[\"vanilla\",\"file/a:b.zip\"]\r\nnot an option\r\nmodded_option:true\r\nrenderDistance:16\r\n"
+        );
+    }
+
+    #[test]
+    fn converts_key_binds_between_legacy_and_modern_versions() {
+        let legacy = OptionsFile::parse(
+            "key_key.attack:-100\nkey_key.forward:17\nkey_key.swapHands:33\nkey_key.custom:9999\nfov:0.25\n",
+        );
+        let patterns = vec!["key_*".to_string()];
+        let modern = modern_options(&legacy, "1.8.9", Some(&patterns));
+        assert_eq!(
+            modern,
+            vec![
+                OptionValue {
+                    key: "key_key.attack".to_string(),
+                    value: "key.mouse.left".to_string()
+                },
+                OptionValue {
+                    key: "key_key.forward".to_string(),
+                    value: "key.keyboard.w".to_string()
+                },
+                OptionValue {
+                    key: "key_key.swapOffhand".to_string(),
+                    value: "key.keyboard.f".to_string()
+                },
+            ]
+        );
+
+        let mut target = OptionsFile::parse("key_key.forward:key.keyboard.z:NONE\n");
+        assert_eq!(apply_options(&mut target, &modern, "1.21.1"), 3);
+        assert_eq!(
+            target.serialize(),
+            "key_key.forward:key.keyboard.w\nkey_key.attack:key.mouse.left\nkey_key.swapOffhand:key.keyboard.f\n"
+        );
+
+        let mut legacy_target = OptionsFile::default();
+        apply_options(&mut legacy_target, &modern, "1.12.2");
+        assert_eq!(legacy_target.get("key_key.swapHands"), Some("33"));
+        assert_eq!(legacy_target.get("key_key.attack"), Some("-100"));
+
+        // swapHands was only renamed in 1.16, 1.13 to 1.15 use key names with the old name
+        let pre_rename = OptionsFile::parse("key_key.swapHands:key.keyboard.g\n");
+        let from_pre_rename = modern_options(&pre_rename, "1.15.2", Some(&patterns));
+        assert_eq!(from_pre_rename[0].key, "key_key.swapOffhand");
+        assert_eq!(from_pre_rename[0].value, "key.keyboard.g");
+        let mut pre_rename_target = OptionsFile::default();
+        apply_options(&mut pre_rename_target, &modern, "1.14.4");
+        assert_eq!(
+            pre_rename_target.get("key_key.swapHands"),
+            Some("key.keyboard.f")
+        );
+        let mut renamed_target = OptionsFile::default();
+        apply_options(&mut renamed_target, &modern, "1.16.5");
+        assert_eq!(
+            renamed_target.get("key_key.swapOffhand"),
+            Some("key.keyboard.f")
+        );
+        assert!(matches_key(&patterns, "key_key.jump") && !matches_key(&patterns, "fov"));
+    }
+
+    #[test]
+    fn never_copies_the_data_version() {
+        let legacy = OptionsFile::parse("version:1343\nfov:0.25\nkey_key.forward:17\n");
+        let everything = vec!["*".to_string()];
+        let preset = modern_options(&legacy, "1.12.2", None);
+        assert_eq!(modern_options(&legacy, "1.12.2", Some(&everything)), preset);
+        assert!(preset.iter().all(|o| o.key != DATA_VERSION_KEY));
+
+        // Also when an older preset still has it
+        let mut stale_preset = preset.clone();
+        stale_preset.push(OptionValue {
+            key: DATA_VERSION_KEY.to_string(),
+            value: "1343".to_string(),
+        });
+
+        let mut target = OptionsFile::parse("version:3465\nfov:0.0\n");
+        assert_eq!(apply_options(&mut target, &stale_preset, "1.20.1"), 2);
+        assert_eq!(target.get(DATA_VERSION_KEY), Some("3465"));
+        assert_eq!(target.get("fov"), Some("0.25"));
+        assert_eq!(target.get("key_key.forward"), Some("key.keyboard.w"));
+    }
+}
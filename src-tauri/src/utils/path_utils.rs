@@ -2,6 +2,7 @@ use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result}; // Dein Result- und Fehlertyp
 use crate::integrations::norisk_packs::{get_GEG_pack_mod_filename, NoriskModEntryDefinition};
 use crate::state::State;
+use crate::utils::content_store::ContentStore;
 use crate::utils::download_utils; // Added for DownloadUtils
 use futures::future::try_join_all; // Added for joining futures
 use log::{error, info, warn};
@@ -134,8 +135,13 @@ pub async fn copy_as_custom_mod(
                 return;
             }
 
-            match fs::copy(&src_path_buf, &dest_path).await {
-                // Use fs::copy directly
+            // Imported into the content store, the profile gets a link instead of a full copy
+            let content_store = ContentStore::new();
+            let import_result = match content_store.import_file(src_path_buf).await {
+                Ok(sha1) => content_store.link_blob(&sha1, &dest_path).await,
+                Err(e) => Err(e),
+            };
+            match import_result {
                 Ok(_) => {
                     info!(
                         "Successfully imported '{}' as custom mod to profile {}.",
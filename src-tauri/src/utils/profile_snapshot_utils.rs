@@ -0,0 +1,685 @@
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::{AppError, Result};
+use crate::minecraft::downloads::mod_downloader::{ModDownloadService, MOD_CACHE_DIR_NAME};
+use crate::state::profile_state::{self, Mod, ModLoader, ModPackInfo, ModSource, Profile};
+use crate::state::State;
+use crate::utils::backup_utils::get_backup_root;
+use crate::utils::path_utils;
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const CONFIG_DIR: &str = "config";
+const COPY_CONCURRENCY: usize = 8;
+
+/// What caused a profile snapshot to be taken
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotTrigger {
+    Manual,
+    BeforeBulkUpdate,
+    BeforeModpackSwitch,
+    BeforeRollback,
+}
+
+impl SnapshotTrigger {
+    fn default_name(&self) -> &'static str {
+        match self {
+            SnapshotTrigger::Manual => "Manual snapshot",
+            SnapshotTrigger::BeforeBulkUpdate => "Before updating all mods",
+            SnapshotTrigger::BeforeModpackSwitch => "Before modpack switch",
+            SnapshotTrigger::BeforeRollback => "Before rollback",
+        }
+    }
+}
+
+/// The mod set of a profile at one point in time, stored as
+/// <backup_root>/profiles/<profile_id>/<snapshot_id>/snapshot.json
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileSnapshot {
+    pub id: Uuid,
+    pub profile_id: Uuid,
+    pub name: String,
+    pub trigger: SnapshotTrigger,
+    pub created_at: DateTime<Utc>,
+    pub game_version: String,
+    pub loader: ModLoader,
+    pub loader_version: Option<String>,
+    pub modpack_info: Option<ModPackInfo>,
+    pub mods: Vec<Mod>,
+    /// Instance-relative config paths copied into the snapshot
+    pub config_paths: Vec<String>,
+}
+
+/// Outcome of rolling a profile back to a snapshot
+#[derive(Serialize, Debug, Clone)]
+pub struct SnapshotRollbackResult {
+    /// Snapshot of the state that was replaced, so the rollback itself can be undone
+    pub undo_snapshot_id: Uuid,
+    pub restored_mods: usize,
+    pub restored_config_paths: Vec<String>,
+    /// Mod files that were already in the mod cache
+    pub cached_files: Vec<String>,
+    /// Mod files that could not be fetched now and will be downloaded on the next launch
+    pub missing_files: Vec<String>,
+}
+
+/// Directory holding the snapshots of one profile: <backup_root>/profiles/<profile_id>
+fn profile_snapshot_dir(profile_id: Uuid) -> PathBuf {
+    get_backup_root()
+        .join("profiles")
+        .join(profile_id.to_string())
+}
+
+/// Only plain relative paths inside the instance may be snapshotted
+fn validate_config_path(relative: &str) -> Result<()> {
+    let path = Path::new(relative);
+    let plain = !relative.is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !plain {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid snapshot config path: '{}'",
+            relative
+        )));
+    }
+    Ok(())
+}
+
+async fn copy_path(src: &Path, dst: &Path) -> Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst).await?;
+        path_utils::copy_dir_recursively(src, dst, Arc::new(Semaphore::new(COPY_CONCURRENCY))).await
+    } else {
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::copy(src, dst).await?;
+        Ok(())
+    }
+}
+
+async fn remove_path(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path).await?;
+    } else if path.exists() {
+        fs::remove_file(path).await?;
+    }
+    Ok(())
+}
+
+/// Takes a snapshot of the profile's mods, loader and configured config paths.
+pub async fn create_profile_snapshot(
+    profile: &Profile,
+    name: Option<String>,
+    trigger: SnapshotTrigger,
+) -> Result<ProfileSnapshot> {
+    let state = State::get().await?;
+    let instance_path = state
+        .profile_manager
+        .get_profile_instance_path(profile.id)
+        .await?;
+
+    let snapshots_dir = profile_snapshot_dir(profile.id);
+    let snapshot = take_snapshot(&snapshots_dir, &instance_path, profile, name, trigger).await?;
+    if trigger != SnapshotTrigger::Manual {
+        prune_snapshots(
+            &snapshots_dir,
+            profile.settings.snapshots.max_automatic,
+            &[snapshot.id],
+        )
+        .await?;
+    }
+    Ok(snapshot)
+}
+
+/// Writes a new snapshot into `snapshots_dir` without pruning older ones.
+async fn take_snapshot(
+    snapshots_dir: &Path,
+    instance_path: &Path,
+    profile: &Profile,
+    name: Option<String>,
+    trigger: SnapshotTrigger,
+) -> Result<ProfileSnapshot> {
+    let id = Uuid::new_v4();
+    let snapshot_dir = snapshots_dir.join(id.to_string());
+    fs::create_dir_all(&snapshot_dir).await?;
+
+    let snapshot = match write_profile_snapshot(
+        profile,
+        instance_path,
+        &snapshot_dir,
+        id,
+        name,
+        trigger,
+    )
+    .await
+    {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            // A half-written snapshot would be listed and restore incomplete configs
+            if let Err(cleanup_error) = fs::remove_dir_all(&snapshot_dir).await {
+                warn!(
+                    "Failed to remove incomplete snapshot {:?}: {}",
+                    snapshot_dir, cleanup_error
+                );
+            }
+            return Err(e);
+        }
+    };
+    info!(
+        "Created profile snapshot '{}' ({}) of profile {} with {} mods",
+        snapshot.name,
+        snapshot.id,
+        profile.id,
+        snapshot.mods.len()
+    );
+    Ok(snapshot)
+}
+
+/// Copies the config paths into `snapshot_dir` and writes the snapshot file last,
+/// so only complete snapshots have one.
+async fn write_profile_snapshot(
+    profile: &Profile,
+    instance_path: &Path,
+    snapshot_dir: &Path,
+    id: Uuid,
+    name: Option<String>,
+    trigger: SnapshotTrigger,
+) -> Result<ProfileSnapshot> {
+    let mut config_paths = Vec::new();
+    for relative in &profile.settings.snapshots.config_paths {
+        if let Err(e) = validate_config_path(relative) {
+            warn!("Skipping snapshot path of profile {}: {}", profile.id, e);
+            continue;
+        }
+        let source = instance_path.join(relative);
+        if !source.exists() {
+            continue;
+        }
+        copy_path(&source, &snapshot_dir.join(CONFIG_DIR).join(relative)).await?;
+        config_paths.push(relative.clone());
+    }
+
+    let snapshot = ProfileSnapshot {
+        id,
+        profile_id: profile.id,
+        name: name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| trigger.default_name().to_string()),
+        trigger,
+        created_at: Utc::now(),
+        game_version: profile.game_version.clone(),
+        loader: profile.loader,
+        loader_version: profile.loader_version.clone(),
+        modpack_info: profile.modpack_info.clone(),
+        mods: profile.mods.clone(),
+        config_paths,
+    };
+    fs::write(
+        snapshot_dir.join(SNAPSHOT_FILE),
+        serde_json::to_vec_pretty(&snapshot)?,
+    )
+    .await?;
+    Ok(snapshot)
+}
+
+/// Takes an automatic snapshot, failures are only logged so the caller can continue.
+pub async fn snapshot_before(profile: &Profile, trigger: SnapshotTrigger) {
+    if let Err(e) = create_profile_snapshot(profile, None, trigger).await {
+        error!(
+            "Automatic snapshot ({:?}) of profile {} failed: {}",
+            trigger, profile.id, e
+        );
+    }
+}
+
+/// Lists the snapshots of a profile, newest first.
+pub async fn list_profile_snapshots(profile_id: Uuid) -> Result<Vec<ProfileSnapshot>> {
+    list_snapshots(&profile_snapshot_dir(profile_id)).await
+}
+
+async fn list_snapshots(dir: &Path) -> Result<Vec<ProfileSnapshot>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let snapshot_file = entry.path().join(SNAPSHOT_FILE);
+        let Ok(content) = fs::read(&snapshot_file).await else {
+            continue;
+        };
+        match serde_json::from_slice::<ProfileSnapshot>(&content) {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(e) => warn!(
+                "Ignoring unreadable profile snapshot {}: {}",
+                snapshot_file.display(),
+                e
+            ),
+        }
+    }
+    sort_newest_first(&mut snapshots);
+    Ok(snapshots)
+}
+
+fn sort_newest_first(snapshots: &mut [ProfileSnapshot]) {
+    snapshots.sort_by_key(|snapshot| Reverse(snapshot.created_at));
+}
+
+/// Automatic snapshots beyond the newest `max_automatic`. Manual snapshots and those in `keep`
+/// are never pruned, kept automatic snapshots take up their slots first.
+fn snapshots_to_prune(
+    snapshots: &[ProfileSnapshot],
+    max_automatic: usize,
+    keep: &[Uuid],
+) -> Vec<Uuid> {
+    let automatic = || {
+        snapshots
+            .iter()
+            .filter(|snapshot| snapshot.trigger != SnapshotTrigger::Manual)
+    };
+    let kept = automatic()
+        .filter(|snapshot| keep.contains(&snapshot.id))
+        .count();
+    automatic()
+        .filter(|snapshot| !keep.contains(&snapshot.id))
+        .skip(max_automatic.saturating_sub(kept))
+        .map(|snapshot| snapshot.id)
+        .collect()
+}
+
+async fn prune_snapshots(snapshots_dir: &Path, max_automatic: usize, keep: &[Uuid]) -> Result<()> {
+    let snapshots = list_snapshots(snapshots_dir).await?;
+    for snapshot_id in snapshots_to_prune(&snapshots, max_automatic, keep) {
+        let path = snapshots_dir.join(snapshot_id.to_string());
+        match fs::remove_dir_all(&path).await {
+            Ok(()) => info!("Removed old profile snapshot {}", path.display()),
+            Err(e) => warn!(
+                "Failed to remove old profile snapshot {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub async fn delete_profile_snapshot(profile_id: Uuid, snapshot_id: Uuid) -> Result<()> {
+    let path = profile_snapshot_dir(profile_id).join(snapshot_id.to_string());
+    if !path.join(SNAPSHOT_FILE).is_file() {
+        return Err(AppError::NotFound(format!(
+            "Profile snapshot {} of profile {}",
+            snapshot_id, profile_id
+        )));
+    }
+    fs::remove_dir_all(&path).await?;
+    info!("Deleted profile snapshot {}", path.display());
+    Ok(())
+}
+
+async fn load_profile_snapshot(profile_id: Uuid, snapshot_id: Uuid) -> Result<ProfileSnapshot> {
+    let snapshot_file = profile_snapshot_dir(profile_id)
+        .join(snapshot_id.to_string())
+        .join(SNAPSHOT_FILE);
+    let content = fs::read(&snapshot_file).await.map_err(|_| {
+        AppError::NotFound(format!(
+            "Profile snapshot {} of profile {}",
+            snapshot_id, profile_id
+        ))
+    })?;
+    Ok(serde_json::from_slice(&content)?)
+}
+
+/// Snapshots the current state, copies the snapshot's config paths back into the instance
+/// and prunes afterwards, keeping both the restored and the undo snapshot.
+/// Returns the undo snapshot id and the restored config paths.
+async fn restore_snapshot(
+    snapshots_dir: &Path,
+    instance_path: &Path,
+    profile: &Profile,
+    snapshot: &ProfileSnapshot,
+) -> Result<(Uuid, Vec<String>)> {
+    // Without an undo snapshot the replaced state would be lost, so nothing is touched
+    let undo_snapshot_id = match take_snapshot(
+        snapshots_dir,
+        instance_path,
+        profile,
+        Some(format!("Before rollback to '{}'", snapshot.name)),
+        SnapshotTrigger::BeforeRollback,
+    )
+    .await
+    {
+        Ok(undo) => undo.id,
+        Err(e) => {
+            error!(
+                "Could not snapshot profile {} before rollback: {}",
+                profile.id, e
+            );
+            return Err(AppError::Other(format!(
+                "Could not create undo snapshot, rollback aborted: {}",
+                e
+            )));
+        }
+    };
+
+    let snapshot_config_dir = snapshots_dir.join(snapshot.id.to_string()).join(CONFIG_DIR);
+    let mut restored_config_paths = Vec::new();
+    for relative in &snapshot.config_paths {
+        validate_config_path(relative)?;
+        let source = snapshot_config_dir.join(relative);
+        if !source.exists() {
+            warn!(
+                "Snapshot {} lists '{}' but has no copy of it",
+                snapshot.id, relative
+            );
+            continue;
+        }
+        let target = instance_path.join(relative);
+        remove_path(&target).await?;
+        copy_path(&source, &target).await?;
+        restored_config_paths.push(relative.clone());
+    }
+
+    // The configs are already restored, a failed cleanup must not abort the rollback
+    if let Err(e) = prune_snapshots(
+        snapshots_dir,
+        profile.settings.snapshots.max_automatic,
+        &[snapshot.id, undo_snapshot_id],
+    )
+    .await
+    {
+        warn!(
+            "Failed to prune snapshots of profile {} after rollback: {}",
+            profile.id, e
+        );
+    }
+    Ok((undo_snapshot_id, restored_config_paths))
+}
+
+/// Cache filenames of the enabled mods that are downloaded, split into (cached, missing)
+fn partition_cached_mods(mods: &[Mod], mod_cache_dir: &Path) -> (Vec<String>, Vec<String>) {
+    mods.iter()
+        .filter(|mod_info| mod_info.enabled)
+        .filter(|mod_info| {
+            !matches!(
+                mod_info.source,
+                ModSource::Local { .. } | ModSource::Embedded { .. }
+            )
+        })
+        .filter_map(|mod_info| profile_state::get_mod_cache_filename(&mod_info.source).ok())
+        .partition(|file_name| mod_cache_dir.join(file_name).is_file())
+}
+
+/// Restores the exact mod versions, loader and config files of a snapshot.
+/// The replaced state is snapshotted first, the rollback is aborted if that fails. Pruning runs only after
+/// the restore and never removes the restored or the undo snapshot. Mod files are served from the mod cache,
+/// anything not cached is downloaded right away or, if that fails, on the next launch.
+pub async fn rollback_profile_snapshot(
+    profile_id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<SnapshotRollbackResult> {
+    let state = State::get().await?;
+    if state.process_manager.is_profile_running(profile_id).await {
+        return Err(AppError::InvalidOperation(
+            "Cannot roll back a profile while it is running".to_string(),
+        ));
+    }
+
+    let snapshot = load_profile_snapshot(profile_id, snapshot_id).await?;
+    let mut profile = state.profile_manager.get_profile(profile_id).await?;
+    let instance_path = state
+        .profile_manager
+        .get_profile_instance_path(profile_id)
+        .await?;
+
+    let (undo_snapshot_id, restored_config_paths) = restore_snapshot(
+        &profile_snapshot_dir(profile_id),
+        &instance_path,
+        &profile,
+        &snapshot,
+    )
+    .await?;
+
+    profile.game_version = snapshot.game_version.clone();
+    profile.loader = snapshot.loader;
+    profile.loader_version = snapshot.loader_version.clone();
+    profile.modpack_info = snapshot.modpack_info.clone();
+    profile.mods = snapshot.mods.clone();
+    state
+        .profile_manager
+        .update_profile(profile_id, profile.clone())
+        .await?;
+    info!(
+        "Rolled back profile {} to snapshot '{}' ({})",
+        profile_id, snapshot.name, snapshot_id
+    );
+
+    let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+    let (cached_files, mut missing_files) = partition_cached_mods(&profile.mods, &mod_cache_dir);
+    if !missing_files.is_empty() {
+        info!(
+            "{} mod files of the snapshot are not cached, downloading them",
+            missing_files.len()
+        );
+        if let Err(e) = ModDownloadService::new()
+            .download_mods_to_cache(&profile)
+            .await
+        {
+            warn!(
+                "Not all mods of the rolled back profile {} could be downloaded: {}",
+                profile_id, e
+            );
+        }
+        missing_files.retain(|file_name| !mod_cache_dir.join(file_name).is_file());
+    }
+
+    Ok(SnapshotRollbackResult {
+        undo_snapshot_id,
+        restored_mods: profile.mods.len(),
+        restored_config_paths,
+        cached_files,
+        missing_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::profile_state::{ProfileSettings, ProfileSnapshotSettings, ProfileState};
+    use std::collections::HashSet;
+
+    fn snapshot(trigger: SnapshotTrigger, minutes_ago: i64) -> ProfileSnapshot {
+        ProfileSnapshot {
+            id: Uuid::new_v4(),
+            profile_id: Uuid::nil(),
+            name: trigger.default_name().to_string(),
+            trigger,
+            created_at: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            game_version: "1.21.1".to_string(),
+            loader: ModLoader::Fabric,
+            loader_version: Some("0.16.5".to_string()),
+            modpack_info: None,
+            mods: Vec::new(),
+            config_paths: vec!["config".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_pruning_keeps_manual_and_newest_automatic_snapshots() {
+        let mut snapshots = vec![
+            snapshot(SnapshotTrigger::BeforeBulkUpdate, 30),
+            snapshot(SnapshotTrigger::Manual, 40),
+            snapshot(SnapshotTrigger::BeforeModpackSwitch, 10),
+            snapshot(SnapshotTrigger::BeforeRollback, 20),
+        ];
+        sort_newest_first(&mut snapshots);
+        assert_eq!(snapshots[0].trigger, SnapshotTrigger::BeforeModpackSwitch);
+        assert_eq!(snapshots[3].trigger, SnapshotTrigger::Manual);
+
+        assert_eq!(
+            snapshots_to_prune(&snapshots, 1, &[]),
+            vec![snapshots[1].id, snapshots[2].id]
+        );
+        assert!(snapshots_to_prune(&snapshots, 0, &[])
+            .iter()
+            .all(|id| *id != snapshots[3].id));
+        assert_eq!(
+            snapshots_to_prune(&snapshots, 0, &[snapshots[0].id, snapshots[2].id]),
+            vec![snapshots[1].id]
+        );
+        assert_eq!(
+            snapshots_to_prune(&snapshots, 2, &[snapshots[2].id]),
+            vec![snapshots[1].id]
+        );
+    }
+
+    fn test_profile(max_automatic: usize) -> Profile {
+        Profile {
+            id: Uuid::new_v4(),
+            name: "Snapshot Test".to_string(),
+            path: "snapshot-test".to_string(),
+            game_version: "1.21.1".to_string(),
+            loader: ModLoader::Fabric,
+            loader_version: Some("0.16.5".to_string()),
+            created: Utc::now(),
+            last_played: None,
+            settings: ProfileSettings {
+                snapshots: ProfileSnapshotSettings {
+                    config_paths: vec!["config".to_string()],
+                    max_automatic,
+                },
+                ..ProfileSettings::default()
+            },
+            state: ProfileState::Installed,
+            mods: Vec::new(),
+            selected_GEG_pack_id: None,
+            disabled_GEG_mods_detailed: HashSet::new(),
+            source_standard_profile_id: None,
+            group: None,
+            is_standard_version: false,
+            use_shared_minecraft_folder: false,
+            description: None,
+            GEG_information: None,
+            banner: None,
+            background: None,
+            modpack_info: None,
+        }
+    }
+
+    async fn write_config(instance_path: &Path, content: &str) {
+        fs::create_dir_all(instance_path.join("config"))
+            .await
+            .unwrap();
+        fs::write(instance_path.join("config/options.json"), content)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rollback_to_oldest_retained_snapshot_keeps_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots_dir = dir.path().join("snapshots");
+        let instance_path = dir.path().join("instance");
+        let profile = test_profile(2);
+
+        write_config(&instance_path, "v1").await;
+        let oldest = take_snapshot(
+            &snapshots_dir,
+            &instance_path,
+            &profile,
+            None,
+            SnapshotTrigger::BeforeBulkUpdate,
+        )
+        .await
+        .unwrap();
+        write_config(&instance_path, "v2").await;
+        let newer = take_snapshot(
+            &snapshots_dir,
+            &instance_path,
+            &profile,
+            None,
+            SnapshotTrigger::BeforeModpackSwitch,
+        )
+        .await
+        .unwrap();
+        write_config(&instance_path, "v3").await;
+
+        let (undo_id, restored) =
+            restore_snapshot(&snapshots_dir, &instance_path, &profile, &oldest)
+                .await
+                .unwrap();
+
+        assert_eq!(restored, vec!["config".to_string()]);
+        assert_eq!(
+            fs::read_to_string(instance_path.join("config/options.json"))
+                .await
+                .unwrap(),
+            "v1"
+        );
+        let remaining: Vec<Uuid> = list_snapshots(&snapshots_dir)
+            .await
+            .unwrap()
+            .iter()
+            .map(|snapshot| snapshot.id)
+            .collect();
+        assert!(remaining.contains(&oldest.id));
+        assert!(remaining.contains(&undo_id));
+        assert!(!remaining.contains(&newer.id));
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_survive_zero_retention() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots_dir = dir.path().join("snapshots");
+        let instance_path = dir.path().join("instance");
+        let profile = test_profile(0);
+        write_config(&instance_path, "v1").await;
+
+        let before_update = take_snapshot(
+            &snapshots_dir,
+            &instance_path,
+            &profile,
+            None,
+            SnapshotTrigger::BeforeBulkUpdate,
+        )
+        .await
+        .unwrap();
+        prune_snapshots(&snapshots_dir, 0, &[before_update.id])
+            .await
+            .unwrap();
+        write_config(&instance_path, "v2").await;
+
+        let (undo_id, _) =
+            restore_snapshot(&snapshots_dir, &instance_path, &profile, &before_update)
+                .await
+                .unwrap();
+        let remaining: Vec<Uuid> = list_snapshots(&snapshots_dir)
+            .await
+            .unwrap()
+            .iter()
+            .map(|snapshot| snapshot.id)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.contains(&before_update.id));
+        assert!(remaining.contains(&undo_id));
+    }
+
+    #[test]
+    fn test_config_paths_must_stay_inside_instance() {
+        assert!(validate_config_path("config").is_ok());
+        assert!(validate_config_path("config/sodium-options.json").is_ok());
+        assert!(validate_config_path("").is_err());
+        assert!(validate_config_path("../other").is_err());
+        assert!(validate_config_path("/etc/passwd").is_err());
+    }
+}
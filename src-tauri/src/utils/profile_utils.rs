@@ -6,8 +6,10 @@ use crate::integrations::unified_mod::ModPlatform;
 use crate::state::profile_state::ModSource;
 use crate::state::profile_state::Profile;
 use crate::state::state_manager::State;
+use crate::utils::content_store::ContentStore;
 use crate::utils::download_utils::DownloadUtils;
 use crate::utils::file_utils;
+use crate::utils::jar_metadata::{self, JarMetadata};
 use crate::utils::{datapack_utils, hash_utils, resourcepack_utils, shaderpack_utils};
 use async_zip::tokio::write::ZipFileWriter;
 use async_zip::{Compression, ZipEntryBuilder};
@@ -154,11 +156,26 @@ async fn download_content(
         file_path.display()
     );
 
-    // Use DownloadUtils for robust downloading
-    if let Some(sha1) = expected_sha1 {
-        DownloadUtils::download_with_sha1(url, file_path, &sha1).await
-    } else {
-        DownloadUtils::download_simple(url, file_path).await
+    // Download into the content store (skipped if another profile already has the file)
+    // and link it into the profile
+    let content_store = ContentStore::new();
+    match content_store.download(url, expected_sha1.as_deref()).await {
+        Ok(sha1) => {
+            let method = content_store.link_blob(&sha1, file_path).await?;
+            debug!("Placed {} via {:?}", file_path.display(), method);
+            Ok(())
+        }
+        Err(e) => {
+            warn!(
+                "Content store download failed for {} ({}), downloading directly",
+                url, e
+            );
+            if let Some(sha1) = expected_sha1 {
+                DownloadUtils::download_with_sha1(url, file_path, &sha1).await
+            } else {
+                DownloadUtils::download_simple(url, file_path).await
+            }
+        }
     }
 }
 
@@ -1267,7 +1284,7 @@ fn sanitize_profile_for_export(profile: &Profile) -> Profile {
 }
 
 /// Collect all files recursively (like Modrinth's add_all_recursive_folder_paths)
-fn collect_all_files_recursive<'a>(
+pub(crate) fn collect_all_files_recursive<'a>(
     dir_path: &'a Path,
     file_list: &'a mut Vec<PathBuf>,
 ) -> BoxFuture<'a, Result<()>> {
@@ -2126,6 +2143,8 @@ pub struct LocalContentItem {
     // Neue Felder für ModPack-Integration
     pub modpack_origin: Option<String>, // "modrinth:project_id" oder "curseforge:project_id:file_id"
     pub updates_enabled: Option<bool>,  // None = Standard (true), Some(true/false) = explizit gesetzt
+    #[serde(default)]
+    pub jar_metadata: Option<JarMetadata>, // Aus der Jar gelesene Loader-Metadaten (nur Mods, braucht Hashes)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)] // Ensure Serialize and Deserialize are here
@@ -2282,6 +2301,7 @@ impl LocalContentLoader {
                                 associated_loader: None,
                                 modpack_origin: None, // GEG mods kommen nicht aus ModPacks
                                 updates_enabled: None, // Default behavior
+                                jar_metadata: None,
                             });
                         }
                     }
@@ -2439,6 +2459,7 @@ impl LocalContentLoader {
                     associated_loader: mod_item.associated_loader.clone(), // Populate associated_loader
                     modpack_origin: mod_item.modpack_origin.clone(), // Übernimm ModPack-Origin
                     updates_enabled: Some(mod_item.updates_enabled), // Übernimm Update-Einstellung
+                    jar_metadata: None,
                 });
             }
         }
@@ -2561,6 +2582,7 @@ impl LocalContentLoader {
                     associated_loader: None,
                     modpack_origin: None, // Lokale Dateien kommen nicht aus ModPacks
                     updates_enabled: None, // Default behavior für lokale Dateien
+                    jar_metadata: None,
                 });
             }
         }
@@ -2666,6 +2688,43 @@ impl LocalContentLoader {
             }
         }
 
+        // Read loader metadata from the mod jars, cached by SHA1
+        if params.content_type == ContentType::Mod {
+            let inspections = final_items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| !item.is_directory)
+                .filter_map(|(index, item)| {
+                    let sha1 = item.sha1_hash.clone().filter(|hash| hash != "0")?;
+                    Some((index, PathBuf::from(&item.path_str), sha1))
+                })
+                .collect::<Vec<_>>();
+            let results = join_all(inspections.into_iter().map(|(index, path, sha1)| async move {
+                (index, jar_metadata::inspect_jar_with_sha1(&path, &sha1).await)
+            }))
+            .await;
+            for (index, result) in results {
+                match result {
+                    Ok(metadata) => {
+                        let item = &mut final_items[index];
+                        if let Some(primary) = metadata.primary() {
+                            if item.fallback_version.is_none() {
+                                item.fallback_version = primary.version.clone();
+                            }
+                            if item.associated_loader.is_none() {
+                                item.associated_loader = Some(primary.loader);
+                            }
+                        }
+                        item.jar_metadata = Some(metadata);
+                    }
+                    Err(e) => debug!(
+                        "Could not read jar metadata of {}: {}",
+                        final_items[index].filename, e
+                    ),
+                }
+            }
+        }
+
         if params.fetch_modrinth_data {
             // Use params.fetch_modrinth_data
             let mut hashes_for_modrinth_lookup: HashMap<String, Vec<usize>> = HashMap::new(); // sha1 -> Vec of indices in final_items
@@ -1,22 +1,163 @@
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
 use crate::error::{AppError, Result};
+use crate::minecraft::api::mc_api::MinecraftApiService;
+use crate::minecraft::downloads::mc_assets_download::MinecraftAssetsDownloadService;
+use crate::minecraft::downloads::mc_client_download::MinecraftClientDownloadService;
+use crate::minecraft::downloads::mc_libraries_download::MinecraftLibrariesDownloadService;
+use crate::minecraft::downloads::mod_downloader::MOD_CACHE_DIR_NAME;
+use crate::minecraft::downloads::mod_resolver;
+use crate::minecraft::downloads::{
+    ExpectedFile, MinecraftLoggingDownloadService, ModDownloadService,
+};
+use crate::minecraft::installer::resolve_install_profile;
+use crate::minecraft::modloader::{ModloaderFactory, ModloaderInstallResult};
+use crate::state::download_state::DownloadScheduler;
+use crate::state::profile_state::ModLoader;
 use crate::state::state_manager::State;
-use crate::state::profile_state::{ModSource, get_profile_mod_filename};
-use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
-use crate::integrations::norisk_packs;
-use crate::utils::path_utils;
+use crate::utils::download_utils::DownloadUtils;
+use crate::utils::hash_utils;
+use futures::stream::{self, StreamExt};
 use log::{debug, info, warn};
-use uuid::Uuid;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use tokio::fs;
+use uuid::Uuid;
+
+/// Files hashed in parallel while verifying
+const VERIFY_CONCURRENCY: usize = 16;
+
+/// What part of the installation a file belongs to
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityCategory {
+    Library,
+    Natives,
+    Client,
+    AssetIndex,
+    Asset,
+    LoggingConfig,
+    LoaderLibrary,
+    Mod,
+}
+
+/// A file that is missing, does not match its expected size/SHA1 or should not be there
+#[derive(Serialize, Debug, Clone)]
+pub struct IntegrityIssue {
+    pub category: IntegrityCategory,
+    pub file: ExpectedFile,
+    /// Only set for corrupt files
+    pub reason: Option<String>,
+}
+
+/// Result of `verify_profile_integrity`, doubles as the repair plan for `repair_profile`
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct IntegrityReport {
+    pub profile_id: Uuid,
+    pub checked_files: usize,
+    pub missing: Vec<IntegrityIssue>,
+    pub corrupt: Vec<IntegrityIssue>,
+    /// Files in the launcher-managed mods folder that the launch would remove anyway
+    pub unexpected: Vec<PathBuf>,
+    /// Known size of everything that can be redownloaded (mods without a known size count as 0)
+    pub bytes_to_redownload: u64,
+}
+
+impl IntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.missing.is_empty() && self.corrupt.is_empty() && self.unexpected.is_empty()
+    }
+
+    fn record(&mut self, category: IntegrityCategory, file: ExpectedFile, state: FileState) {
+        self.checked_files += 1;
+        let reason = match state {
+            FileState::Valid => return,
+            FileState::Missing => None,
+            FileState::Corrupt(reason) => Some(reason),
+        };
+
+        if file.url.is_some() {
+            self.bytes_to_redownload += file.size.unwrap_or(0);
+        }
+        let issue = IntegrityIssue {
+            category,
+            file,
+            reason,
+        };
+        if issue.reason.is_some() {
+            self.corrupt.push(issue);
+        } else {
+            self.missing.push(issue);
+        }
+    }
+
+    async fn check_all(&mut self, category: IntegrityCategory, files: Vec<ExpectedFile>) {
+        let results: Vec<(ExpectedFile, FileState)> = stream::iter(files)
+            .map(|file| async move {
+                let state = inspect_file(&file).await;
+                (file, state)
+            })
+            .buffer_unordered(VERIFY_CONCURRENCY)
+            .collect()
+            .await;
+
+        for (file, state) in results {
+            self.record(category, file, state);
+        }
+    }
+}
+
+/// What `repair_profile` did with an integrity report
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct RepairOutcome {
+    pub report: IntegrityReport,
+    pub redownloaded: usize,
+    pub removed: usize,
+    /// Issues that cannot be fixed by a single download (restored by the next launch instead)
+    pub deferred: Vec<String>,
+    pub failed: Vec<String>,
+}
 
-const MOD_CACHE_DIR_NAME: &str = "mod_cache";
+enum FileState {
+    Valid,
+    Missing,
+    Corrupt(String),
+}
+
+async fn inspect_file(file: &ExpectedFile) -> FileState {
+    let metadata = match fs::metadata(&file.path).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return FileState::Missing,
+    };
+
+    if let Some(size) = file.size {
+        if metadata.len() != size {
+            return FileState::Corrupt(format!(
+                "size mismatch, expected {} bytes but found {}",
+                size,
+                metadata.len()
+            ));
+        }
+    }
+
+    let Some(expected) = &file.sha1 else {
+        return FileState::Valid;
+    };
+    match hash_utils::calculate_sha1_from_file(&file.path).await {
+        Ok(actual) if actual.eq_ignore_ascii_case(expected) => FileState::Valid,
+        Ok(actual) => FileState::Corrupt(format!(
+            "SHA1 mismatch, expected {} but found {}",
+            expected, actual
+        )),
+        Err(e) => FileState::Corrupt(format!("could not be read: {}", e)),
+    }
+}
 
 /// Repairs a profile by checking and fixing common issues
-/// 
-/// This function performs various repair operations on a profile including:
-/// - Verifying and repairing file integrity
-/// - Checking mod compatibility
-/// - Fixing configuration issues
-/// - Redownloading missing or corrupted files
+///
+/// Verifies every file the profile needs (see `verify_profile_integrity`) and then
+/// redownloads only the missing or corrupt files and removes unexpected ones from the
+/// launcher-managed mods folder.
 ///
 /// # Arguments
 ///
@@ -24,166 +165,271 @@ const MOD_CACHE_DIR_NAME: &str = "mod_cache";
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on successful repair, or `Err(AppError)` if repair fails
-pub async fn repair_profile(profile_id: Uuid) -> Result<()> {
+/// Returns what was repaired, or `Err(AppError)` if the profile could not be verified
+pub async fn repair_profile(profile_id: Uuid) -> Result<RepairOutcome> {
     info!("Starting repair process for profile {}", profile_id);
 
-    // Get the profile and state
-    let state = State::get().await?;
-    let profile = state.profile_manager.get_profile(profile_id).await?;
-
-    debug!("Repairing profile: {} ({})", profile.name, profile.id);
-    debug!("Game version: {}, Loader: {}", profile.game_version, profile.loader.as_str());
+    let report = verify_profile_integrity(profile_id).await?;
+    if report.is_healthy() {
+        info!("Profile {} is intact, nothing to repair", profile_id);
+    }
+    let outcome = apply_repair_plan(report).await;
 
-    // Call individual repair functions
-    repair_profile_mods(profile_id).await?;
-    
-    info!("Profile repair completed successfully for profile {}", profile_id);
-    Ok(())
+    info!(
+        "Profile repair for {} finished: {} redownloaded, {} removed, {} deferred, {} failed",
+        profile_id,
+        outcome.redownloaded,
+        outcome.removed,
+        outcome.deferred.len(),
+        outcome.failed.len()
+    );
+    Ok(outcome)
 }
 
 /// Verifies the integrity of profile files
-/// 
-/// # Arguments
 ///
-/// * `profile_id` - The UUID of the profile to verify
+/// Walks the piston-meta libraries and natives, the client jar, the asset index and its
+/// objects, the logging config, the cached loader libraries and every enabled mod with a
+/// known SHA1, and looks for files in the managed mods folder that do not belong there.
 ///
-/// # Returns
-///
-/// Returns `Ok(())` if all files are valid, or `Err(AppError)` if issues are found
-pub async fn verify_profile_integrity(profile_id: Uuid) -> Result<()> {
-    info!("Verifying profile integrity for profile {}", profile_id);
-
-    // TODO: Implement file integrity verification
-    // - Check mod file hashes
-    // - Verify configuration files
-    // - Check for missing dependencies
-    
-    Ok(())
-}
-
-/// Repairs mod-related issues in a profile
-/// 
 /// # Arguments
 ///
-/// * `profile_id` - The UUID of the profile to repair
+/// * `profile_id` - The UUID of the profile to verify
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on successful mod repair, or `Err(AppError)` if repair fails
-pub async fn repair_profile_mods(profile_id: Uuid) -> Result<()> {
-    info!("Repairing mods for profile {}", profile_id);
+/// Returns the integrity report, or `Err(AppError)` if the version metadata is unavailable
+pub async fn verify_profile_integrity(profile_id: Uuid) -> Result<IntegrityReport> {
+    info!("Verifying profile integrity for profile {}", profile_id);
 
     let state = State::get().await?;
     let profile = state.profile_manager.get_profile(profile_id).await?;
-    
-    // 1. Delete the mods folder
-    let mods_dir = state.profile_manager.get_profile_mods_path(&profile)?;
-    if mods_dir.exists() {
-        info!("Deleting mods directory: {:?}", mods_dir);
-        fs::remove_dir_all(&mods_dir).await.map_err(|e| {
-            warn!("Failed to delete mods directory {:?}: {}", mods_dir, e);
-            AppError::Io(e)
-        })?;
-        info!("Successfully deleted mods directory");
+    let version_id = profile.game_version.clone();
+
+    let api_service = MinecraftApiService::new();
+    let manifest = api_service.get_version_manifest().await?;
+    let version = manifest
+        .versions
+        .iter()
+        .find(|v| v.id == version_id)
+        .ok_or_else(|| AppError::VersionNotFound(format!("Version {} not found", version_id)))?;
+    let piston_meta = api_service.get_piston_meta(&version.url).await?;
+
+    let mut report = IntegrityReport {
+        profile_id,
+        ..IntegrityReport::default()
+    };
+
+    let libraries_service = MinecraftLibrariesDownloadService::new();
+    report
+        .check_all(
+            IntegrityCategory::Library,
+            libraries_service.expected_libraries(&piston_meta.libraries),
+        )
+        .await;
+    report
+        .check_all(
+            IntegrityCategory::Natives,
+            libraries_service.expected_natives(&piston_meta.libraries),
+        )
+        .await;
+
+    report
+        .check_all(
+            IntegrityCategory::Client,
+            vec![MinecraftClientDownloadService::new()
+                .expected_client(&piston_meta.downloads.client, &version_id)],
+        )
+        .await;
+
+    let assets_service = MinecraftAssetsDownloadService::new();
+    let index_file = assets_service.expected_asset_index(&piston_meta.asset_index);
+    let index_state = inspect_file(&index_file).await;
+    let index_valid = matches!(index_state, FileState::Valid);
+    report.record(IntegrityCategory::AssetIndex, index_file, index_state);
+    if index_valid {
+        let index = assets_service
+            .read_local_asset_index(&piston_meta.asset_index)
+            .await?;
+        report
+            .check_all(
+                IntegrityCategory::Asset,
+                assets_service.expected_asset_objects(&index),
+            )
+            .await;
     } else {
-        debug!("Mods directory does not exist, skipping deletion: {:?}", mods_dir);
-    }
-
-    // 2. Delete mod files from mod cache (profile mods)
-    let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
-    if mod_cache_dir.exists() {
-        info!("Cleaning mod cache for profile {} mods", profile_id);
-        
-        let mut cache_files_removed = 0;
-        let mut cache_errors = 0;
-        
-        for mod_info in &profile.mods {
-            // Get the filename for this mod
-            match get_profile_mod_filename(&mod_info.source) {
-                Ok(filename) => {
-                    let cache_file_path = mod_cache_dir.join(&filename);
-                    if cache_file_path.exists() {
-                        match fs::remove_file(&cache_file_path).await {
-                            Ok(_) => {
-                                debug!("Removed profile mod cache file: {}", filename);
-                                cache_files_removed += 1;
-                            }
-                            Err(e) => {
-                                warn!("Failed to remove profile mod cache file {}: {}", filename, e);
-                                cache_errors += 1;
-                            }
-                        }
-                    } else {
-                        debug!("Profile mod cache file does not exist: {}", filename);
-                    }
-                }
-                Err(e) => {
-                    warn!("Could not determine filename for profile mod {:?}: {}", 
-                          mod_info.display_name.as_deref().unwrap_or("unknown"), e);
-                    cache_errors += 1;
-                }
+        // Objects can only be listed from a valid index; the repaired index brings them back at launch
+        debug!("Asset index is not valid, skipping asset object verification");
+    }
+
+    if let Some(logging) = &piston_meta.logging {
+        report
+            .check_all(
+                IntegrityCategory::LoggingConfig,
+                vec![
+                    MinecraftLoggingDownloadService::new().expected_logging_config(&logging.client)
+                ],
+            )
+            .await;
+    }
+
+    if profile.loader != ModLoader::Vanilla {
+        let install_profile =
+            resolve_install_profile(&state, &profile, &version_id, &profile.loader).await;
+        match ModloaderFactory::load_cached_install_result(
+            &profile.loader,
+            &version_id,
+            install_profile.loader_version.as_deref(),
+        )
+        .await?
+        {
+            Some(result) => {
+                report
+                    .check_all(
+                        IntegrityCategory::LoaderLibrary,
+                        loader_expected_files(&profile.loader, &result),
+                    )
+                    .await;
             }
+            None => debug!(
+                "No cached {:?} install result, loader libraries are installed on next launch",
+                profile.loader
+            ),
         }
+    }
+
+    report
+        .check_all(
+            IntegrityCategory::Mod,
+            ModDownloadService::new().expected_mods(&profile),
+        )
+        .await;
+
+    // Unexpected files: anything in the managed mods folder the next sync would not place there
+    let mods_dir = state.profile_manager.get_profile_mods_path(&profile)?;
+    if mods_dir.is_dir() {
+        let GEG_config = match &profile.selected_GEG_pack_id {
+            Some(_) => Some(state.GEG_pack_manager.get_config().await),
+            None => None,
+        };
+        let custom_mods = state.profile_manager.list_custom_mods(&profile).await?;
+        let mod_cache_dir = LAUNCHER_DIRECTORY.meta_dir().join(MOD_CACHE_DIR_NAME);
+        let target_mods = mod_resolver::resolve_target_mods(
+            &profile,
+            GEG_config.as_ref(),
+            Some(&custom_mods),
+            &version_id,
+            profile.loader.as_str(),
+            &mod_cache_dir,
+        )
+        .await?;
+        let expected_names: HashSet<String> = target_mods.into_iter().map(|m| m.filename).collect();
 
-        // 3. Delete GEG pack mod files from cache
-        if let Some(pack_id) = &profile.selected_GEG_pack_id {
-            info!("Cleaning GEG pack mod cache for pack: {}", pack_id);
-            
-            let GEG_config = state.GEG_pack_manager.get_config().await;
-            match GEG_config.get_resolved_pack_definition(pack_id) {
-                Ok(resolved_pack) => {
-                    for GEG_mod in &resolved_pack.mods {
-                        // Get the cache path for this GEG mod
-                        match path_utils::get_GEG_mod_cache_path(
-                            GEG_mod,
-                            &profile.game_version,
-                            profile.loader.as_str(),
-                        ) {
-                            Ok(cache_path) => {
-                                if cache_path.exists() {
-                                    match fs::remove_file(&cache_path).await {
-                                        Ok(_) => {
-                                            debug!("Removed GEG pack mod cache file: {:?}", cache_path);
-                                            cache_files_removed += 1;
-                                        }
-                                        Err(e) => {
-                                            warn!("Failed to remove GEG pack mod cache file {:?}: {}", cache_path, e);
-                                            cache_errors += 1;
-                                        }
-                                    }
-                                } else {
-                                    debug!("GEG pack mod cache file does not exist: {:?}", cache_path);
-                                }
-                            }
-                            Err(e) => {
-                                warn!("Could not determine cache path for GEG pack mod {}: {}", 
-                                      GEG_mod.display_name.as_deref().unwrap_or(&GEG_mod.id), e);
-                                cache_errors += 1;
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to get GEG pack definition for {}: {}", pack_id, e);
-                    cache_errors += 1;
-                }
+        let mut entries = fs::read_dir(&mods_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_file() && !expected_names.contains(&name) {
+                report.unexpected.push(path);
             }
-        } else {
-            debug!("Profile has no selected GEG pack, skipping GEG mod cache cleanup");
         }
-        
-        info!("Cache cleanup completed: {} files removed, {} errors", cache_files_removed, cache_errors);
-    } else {
-        debug!("Mod cache directory does not exist: {:?}", mod_cache_dir);
     }
-    
-    info!("Mod repair completed for profile {}", profile_id);
-    Ok(())
+
+    info!(
+        "Integrity check for profile {} done: {} files checked, {} missing, {} corrupt, {} unexpected, {} bytes to redownload",
+        profile_id,
+        report.checked_files,
+        report.missing.len(),
+        report.corrupt.len(),
+        report.unexpected.len(),
+        report.bytes_to_redownload
+    );
+    Ok(report)
+}
+
+/// Files of a cached loader install. Libraries the loader's version JSON lists with a
+/// SHA1/size are verified against it, everything else (e.g. jars the installer patched)
+/// can only be checked for existence.
+fn loader_expected_files(loader: &ModLoader, result: &ModloaderInstallResult) -> Vec<ExpectedFile> {
+    let known: HashMap<&PathBuf, &ExpectedFile> = result
+        .library_files
+        .iter()
+        .map(|file| (&file.path, file))
+        .collect();
+    result
+        .libraries
+        .iter()
+        .chain(result.custom_client_path.iter())
+        .map(|path| match known.get(path) {
+            Some(file) => (*file).clone(),
+            None => ExpectedFile {
+                name: format!("{:?} library {}", loader, path.display()),
+                path: path.clone(),
+                sha1: None,
+                size: None,
+                url: None,
+            },
+        })
+        .collect()
+}
+
+/// Redownloads the missing and corrupt files of `report` and removes the unexpected ones.
+/// Failures are collected instead of aborting, so one bad mirror does not block the rest.
+pub async fn apply_repair_plan(report: IntegrityReport) -> RepairOutcome {
+    let mut outcome = RepairOutcome::default();
+
+    let mut downloads = Vec::new();
+    for issue in report.missing.iter().chain(report.corrupt.iter()) {
+        let Some(url) = issue.file.url.clone() else {
+            outcome.deferred.push(format!(
+                "{} ({}) is restored by the loader installer on the next launch",
+                issue.file.name,
+                issue.file.path.display()
+            ));
+            continue;
+        };
+        let file = &issue.file;
+        downloads.push(async move {
+            debug!("Redownloading {} from {}", file.name, url);
+            let config = file.download_config().with_force_overwrite(true);
+            DownloadUtils::download_file(&url, &file.path, config)
+                .await
+                .map_err(|e| format!("{}: {}", file.name, e))
+        });
+    }
+
+    // Bounded like the verification above, so not every file is checked and hashed at once
+    let fan_out = DownloadScheduler::global_fan_out().await;
+    let mut results = stream::iter(downloads).buffer_unordered(fan_out);
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(()) => outcome.redownloaded += 1,
+            Err(e) => {
+                warn!("Repair download failed: {}", e);
+                outcome.failed.push(e);
+            }
+        }
+    }
+    drop(results);
+
+    for path in &report.unexpected {
+        match fs::remove_file(path).await {
+            Ok(()) => {
+                debug!("Removed unexpected file {:?}", path);
+                outcome.removed += 1;
+            }
+            Err(e) => outcome
+                .failed
+                .push(format!("Could not remove {}: {}", path.display(), e)),
+        }
+    }
+
+    outcome.report = report;
+    outcome
 }
 
 /// Repairs configuration files for a profile
-/// 
+///
 /// # Arguments
 ///
 /// * `profile_id` - The UUID of the profile to repair
@@ -198,6 +444,116 @@ pub async fn repair_profile_config(profile_id: Uuid) -> Result<()> {
     // - Check for corrupted config files
     // - Reset to defaults if necessary
     // - Verify settings validity
-    
+
     Ok(())
-} 
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(path: PathBuf, content: &[u8], url: Option<&str>) -> ExpectedFile {
+        ExpectedFile {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            path,
+            sha1: Some(hash_utils::calculate_sha1_from_bytes(content)),
+            size: Some(content.len() as u64),
+            url: url.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_separates_missing_and_corrupt_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("valid.jar");
+        let corrupt = dir.path().join("corrupt.jar");
+        let same_size = dir.path().join("same_size.jar");
+        fs::write(&valid, b"valid").await.unwrap();
+        fs::write(&corrupt, b"truncated").await.unwrap();
+        fs::write(&same_size, b"abcd").await.unwrap();
+
+        let mut report = IntegrityReport::default();
+        report
+            .check_all(
+                IntegrityCategory::Library,
+                vec![
+                    expected(valid, b"valid", Some("https://example.com/valid.jar")),
+                    expected(
+                        corrupt,
+                        b"truncated content",
+                        Some("https://example.com/c.jar"),
+                    ),
+                    expected(same_size, b"dcba", None),
+                    expected(
+                        dir.path().join("missing.jar"),
+                        b"missing",
+                        Some("https://example.com/m.jar"),
+                    ),
+                ],
+            )
+            .await;
+
+        assert_eq!(report.checked_files, 4);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.corrupt.len(), 2);
+        // Files without a URL cannot be redownloaded and do not count
+        assert_eq!(report.bytes_to_redownload, 17 + 7);
+        assert!(!report.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_repair_reports_deferred_files_and_removes_unexpected() {
+        let dir = tempfile::tempdir().unwrap();
+        let stray = dir.path().join("stray.jar");
+        fs::write(&stray, b"stray").await.unwrap();
+
+        let mut report = IntegrityReport::default();
+        report.record(
+            IntegrityCategory::LoaderLibrary,
+            expected(dir.path().join("patched-client.jar"), b"client", None),
+            FileState::Missing,
+        );
+        report.record(
+            IntegrityCategory::LoaderLibrary,
+            expected(dir.path().join("forge-universal.jar"), b"forge", None),
+            FileState::Corrupt("SHA1 mismatch".to_string()),
+        );
+        report.unexpected.push(stray.clone());
+
+        let outcome = apply_repair_plan(report).await;
+        assert_eq!(outcome.redownloaded, 0);
+        assert_eq!(outcome.removed, 1);
+        assert!(outcome.failed.is_empty());
+        assert_eq!(outcome.deferred.len(), 2);
+        assert!(outcome.deferred[0].starts_with("patched-client.jar"));
+        assert!(outcome.deferred[1].starts_with("forge-universal.jar"));
+        assert!(!stray.exists());
+    }
+
+    #[test]
+    fn test_loader_libraries_use_checksums_from_version_json() {
+        let known = PathBuf::from("/libraries/net/fabricmc/fabric-loader.jar");
+        let patched = PathBuf::from("/libraries/net/minecraftforge/forge-client.jar");
+        let result = ModloaderInstallResult {
+            libraries: vec![known.clone(), patched.clone()],
+            library_files: vec![expected(
+                known.clone(),
+                b"loader",
+                Some("https://maven.fabricmc.net/fabric-loader.jar"),
+            )],
+            main_class: None,
+            jvm_args: None,
+            game_args: None,
+            minecraft_arguments: None,
+            custom_client_path: None,
+            force_include_minecraft_jar: false,
+        };
+
+        let files = loader_expected_files(&ModLoader::Fabric, &result);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, known);
+        assert!(files[0].sha1.is_some() && files[0].url.is_some());
+        assert_eq!(files[1].path, patched);
+        assert!(files[1].sha1.is_none() && files[1].url.is_none());
+    }
+}
@@ -0,0 +1,571 @@
+use crate::error::{AppError, Result};
+use crate::state::State;
+use crate::utils::backup_utils::{self, BackupConfig};
+use crate::utils::mc_utils::{self, ServerInfo};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use fastnbt::Value;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use uuid::Uuid;
+
+const SERVERS_FILE: &str = "servers.dat";
+const SERVER_ICON_SIZE: u32 = 64;
+const DEFAULT_PORT_SUFFIX: &str = ":25565";
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// How the game handles server resource packs (NBT `acceptTextures`)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourcePackPolicy {
+    /// Key missing, the game asks on join
+    #[default]
+    Prompt,
+    Enabled,
+    Disabled,
+}
+
+/// A server entry as edited in the launcher
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServerListEntry {
+    pub name: String,
+    pub address: String,
+    /// Base64 PNG, a `data:image/png;base64,` prefix is accepted. Resized to 64x64 if needed.
+    #[serde(default)]
+    pub icon_base64: Option<String>,
+    #[serde(default)]
+    pub resource_packs: ResourcePackPolicy,
+}
+
+/// Outcome of pushing a server list to one profile of a group
+#[derive(Serialize, Debug, Clone)]
+pub struct ServerListPushResult {
+    pub profile_id: Uuid,
+    pub profile_name: String,
+    #[serde(flatten)]
+    pub status: ServerListPushStatus,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ServerListPushStatus {
+    Updated {
+        servers: usize,
+    },
+    /// Uses the same game directory as a profile that was already updated
+    SharedFolder {
+        with_profile_id: Uuid,
+    },
+    SkippedRunning,
+    Failed {
+        error: String,
+    },
+}
+
+/// servers.dat as read from disk: the root compound and whether it was GZipped
+struct ServerListFile {
+    root: HashMap<String, Value>,
+    gzipped: bool,
+}
+
+impl ServerListFile {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        // servers.dat is normally uncompressed, but keep GZipped files GZipped
+        let gzipped = bytes.starts_with(&GZIP_MAGIC);
+        let root = if gzipped {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(Cursor::new(bytes)).read_to_end(&mut decompressed)?;
+            fastnbt::from_bytes(&decompressed)?
+        } else {
+            fastnbt::from_bytes(bytes)?
+        };
+        Ok(Self { root, gzipped })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let bytes = fastnbt::to_bytes(&self.root)?;
+        if !self.gzipped {
+            return Ok(bytes);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes)?;
+        Ok(encoder.finish()?)
+    }
+
+    fn take_servers(&mut self) -> Vec<Value> {
+        match self.root.remove("servers") {
+            Some(Value::List(servers)) => servers,
+            _ => Vec::new(),
+        }
+    }
+
+    fn set_servers(&mut self, servers: Vec<Value>) {
+        self.root
+            .insert("servers".to_string(), Value::List(servers));
+    }
+}
+
+fn compound_string(compound: &HashMap<String, Value>, key: &str) -> Option<String> {
+    match compound.get(key) {
+        Some(Value::String(value)) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn compound_byte(compound: &HashMap<String, Value>, key: &str) -> Option<u8> {
+    match compound.get(key) {
+        Some(Value::Byte(value)) => Some(*value as u8),
+        _ => None,
+    }
+}
+
+fn to_server_info(entry: &Value) -> Option<ServerInfo> {
+    let Value::Compound(compound) = entry else {
+        return None;
+    };
+    Some(ServerInfo {
+        name: compound_string(compound, "name"),
+        address: compound_string(compound, "ip"),
+        icon_base64: compound_string(compound, "icon"),
+        accepts_textures: compound_byte(compound, "acceptTextures"),
+        previews_chat: compound_byte(compound, "previewsChat"),
+    })
+}
+
+// "Play.Example.net:25565" and "play.example.net" are the same server
+fn normalize_address(address: &str) -> String {
+    let address = address.trim().to_lowercase();
+    match address.strip_suffix(DEFAULT_PORT_SUFFIX) {
+        Some(host) => host.to_string(),
+        None => address,
+    }
+}
+
+/// Validates the entry and returns its icon as the base64 PNG Minecraft expects
+fn prepare_entry(entry: &ServerListEntry) -> Result<Option<String>> {
+    if entry.name.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Server name cannot be empty".to_string(),
+        ));
+    }
+    mc_utils::parse_minecraft_address(entry.address.trim()).map_err(AppError::InvalidInput)?;
+
+    let Some(icon) = &entry.icon_base64 else {
+        return Ok(None);
+    };
+    let icon = icon
+        .trim()
+        .strip_prefix("data:image/png;base64,")
+        .unwrap_or(icon.trim());
+    let bytes = STANDARD
+        .decode(icon)
+        .map_err(|e| AppError::InvalidInput(format!("Server icon is not valid base64: {}", e)))?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| AppError::ImageProcessingError(format!("Invalid server icon: {}", e)))?;
+    if image.width() == SERVER_ICON_SIZE
+        && image.height() == SERVER_ICON_SIZE
+        && bytes.starts_with(b"\x89PNG")
+    {
+        return Ok(Some(icon.to_string()));
+    }
+
+    // The game drops icons that are not 64x64 PNGs
+    let mut png = Vec::new();
+    image
+        .resize_exact(
+            SERVER_ICON_SIZE,
+            SERVER_ICON_SIZE,
+            image::imageops::FilterType::Lanczos3,
+        )
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| {
+            AppError::ImageProcessingError(format!("Failed to encode server icon: {}", e))
+        })?;
+    Ok(Some(STANDARD.encode(png)))
+}
+
+/// Writes the entry into an NBT compound; keys the launcher does not know are kept
+fn apply_entry(
+    compound: &mut HashMap<String, Value>,
+    entry: &ServerListEntry,
+    icon: Option<String>,
+) {
+    compound.insert(
+        "name".to_string(),
+        Value::String(entry.name.trim().to_string()),
+    );
+    compound.insert(
+        "ip".to_string(),
+        Value::String(entry.address.trim().to_string()),
+    );
+    match icon {
+        Some(icon) => {
+            compound.insert("icon".to_string(), Value::String(icon));
+        }
+        None => {
+            compound.remove("icon");
+        }
+    }
+    match entry.resource_packs {
+        ResourcePackPolicy::Prompt => {
+            compound.remove("acceptTextures");
+        }
+        ResourcePackPolicy::Enabled => {
+            compound.insert("acceptTextures".to_string(), Value::Byte(1));
+        }
+        ResourcePackPolicy::Disabled => {
+            compound.insert("acceptTextures".to_string(), Value::Byte(0));
+        }
+    }
+}
+
+fn new_entry(entry: &ServerListEntry) -> Result<Value> {
+    let icon = prepare_entry(entry)?;
+    let mut compound = HashMap::new();
+    apply_entry(&mut compound, entry, icon);
+    Ok(Value::Compound(compound))
+}
+
+/// Puts the curated servers first. Without `replace`, the player's other servers stay
+/// below them, and existing entries for the same address keep their extra keys and icon.
+fn merge_curated_servers(
+    existing: Vec<Value>,
+    curated: &[ServerListEntry],
+    replace: bool,
+) -> Result<Vec<Value>> {
+    if replace {
+        return curated.iter().map(new_entry).collect();
+    }
+
+    let mut remaining = existing;
+    let mut merged = Vec::with_capacity(curated.len() + remaining.len());
+    for entry in curated {
+        let icon = prepare_entry(entry)?;
+        let address = normalize_address(&entry.address);
+        let position = remaining.iter().position(|server| match server {
+            Value::Compound(compound) => {
+                compound_string(compound, "ip").is_some_and(|ip| normalize_address(&ip) == address)
+            }
+            _ => false,
+        });
+
+        let mut compound = match position.map(|index| remaining.remove(index)) {
+            Some(Value::Compound(compound)) => compound,
+            _ => HashMap::new(),
+        };
+        let icon = icon.or_else(|| compound_string(&compound, "icon"));
+        apply_entry(&mut compound, entry, icon);
+        merged.push(Value::Compound(compound));
+    }
+    merged.extend(remaining);
+    Ok(merged)
+}
+
+fn check_index(servers: &[Value], index: usize) -> Result<()> {
+    if index >= servers.len() {
+        return Err(AppError::InvalidInput(format!(
+            "Server index {} is out of range ({} servers)",
+            index,
+            servers.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Refuses when a game runs in this profile or in another profile sharing its game directory
+async fn ensure_not_running(state: &State, profile_id: Uuid, instance_path: &Path) -> Result<()> {
+    let mut running = state.process_manager.is_profile_running(profile_id).await;
+    for process in state.process_manager.list_processes().await {
+        if running {
+            break;
+        }
+        running = state
+            .profile_manager
+            .get_profile_instance_path(process.profile_id)
+            .await
+            .is_ok_and(|path| path == instance_path);
+    }
+    if running {
+        return Err(AppError::InvalidOperation(
+            "Cannot edit the server list while the game is running".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn read_server_list(path: &Path) -> Result<ServerListFile> {
+    if !fs::try_exists(path).await? {
+        return Ok(ServerListFile {
+            root: HashMap::new(),
+            gzipped: false,
+        });
+    }
+    ServerListFile::decode(&fs::read(path).await?)
+}
+
+/// Loads servers.dat of the profile, lets `modify` change the server list and writes it
+/// back after backing up the old file
+async fn modify_server_list<F>(profile_id: Uuid, modify: F) -> Result<Vec<Value>>
+where
+    F: FnOnce(Vec<Value>) -> Result<Vec<Value>>,
+{
+    let state = State::get().await?;
+    let instance_path = state
+        .profile_manager
+        .get_profile_instance_path(profile_id)
+        .await?;
+    ensure_not_running(&state, profile_id, &instance_path).await?;
+
+    let servers_path: PathBuf = instance_path.join(SERVERS_FILE);
+    let mut file = read_server_list(&servers_path).await?;
+    let servers = modify(file.take_servers())?;
+    file.set_servers(servers.clone());
+    let bytes = file.encode()?;
+
+    fs::create_dir_all(&instance_path).await?;
+    let backup_category = format!("servers/{}", profile_id);
+    backup_utils::safe_write_with_backup(
+        &servers_path,
+        bytes,
+        Some(&backup_category),
+        &BackupConfig::default(),
+    )
+    .await?;
+
+    info!(
+        "[Servers] Wrote {} server entries to {}",
+        servers.len(),
+        servers_path.display()
+    );
+    Ok(servers)
+}
+
+fn server_infos(servers: &[Value]) -> Vec<ServerInfo> {
+    servers.iter().filter_map(to_server_info).collect()
+}
+
+/// Adds a server at `index`, or at the end of the list.
+pub async fn add_profile_server(
+    profile_id: Uuid,
+    entry: ServerListEntry,
+    index: Option<usize>,
+) -> Result<Vec<ServerInfo>> {
+    let server = new_entry(&entry)?;
+    let servers = modify_server_list(profile_id, |mut servers| {
+        let index = index.unwrap_or(servers.len()).min(servers.len());
+        servers.insert(index, server);
+        Ok(servers)
+    })
+    .await?;
+    Ok(server_infos(&servers))
+}
+
+/// Replaces name, address, icon and resource pack policy of the server at `index`.
+pub async fn update_profile_server(
+    profile_id: Uuid,
+    index: usize,
+    entry: ServerListEntry,
+) -> Result<Vec<ServerInfo>> {
+    let icon = prepare_entry(&entry)?;
+    let servers = modify_server_list(profile_id, |mut servers| {
+        check_index(&servers, index)?;
+        match &mut servers[index] {
+            Value::Compound(compound) => apply_entry(compound, &entry, icon),
+            other => *other = new_entry(&entry)?,
+        }
+        Ok(servers)
+    })
+    .await?;
+    Ok(server_infos(&servers))
+}
+
+pub async fn remove_profile_server(profile_id: Uuid, index: usize) -> Result<Vec<ServerInfo>> {
+    let servers = modify_server_list(profile_id, |mut servers| {
+        check_index(&servers, index)?;
+        servers.remove(index);
+        Ok(servers)
+    })
+    .await?;
+    Ok(server_infos(&servers))
+}
+
+/// Moves the server at `from` so that it ends up at position `to`.
+pub async fn move_profile_server(
+    profile_id: Uuid,
+    from: usize,
+    to: usize,
+) -> Result<Vec<ServerInfo>> {
+    let servers = modify_server_list(profile_id, |mut servers| {
+        check_index(&servers, from)?;
+        check_index(&servers, to)?;
+        let server = servers.remove(from);
+        servers.insert(to, server);
+        Ok(servers)
+    })
+    .await?;
+    Ok(server_infos(&servers))
+}
+
+/// Writes a curated server list into every profile of a group. Running profiles are
+/// skipped and reported, the others are still updated.
+pub async fn push_server_list_to_group(
+    group: &str,
+    servers: Vec<ServerListEntry>,
+    replace: bool,
+) -> Result<Vec<ServerListPushResult>> {
+    // Fail early on invalid entries instead of once per profile
+    for entry in &servers {
+        prepare_entry(entry)?;
+    }
+
+    let state = State::get().await?;
+    let mut profiles: Vec<_> = state
+        .profile_manager
+        .list_profiles()
+        .await?
+        .into_iter()
+        .filter(|profile| {
+            profile
+                .group
+                .as_deref()
+                .is_some_and(|g| g.eq_ignore_ascii_case(group))
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut updated_paths: HashMap<PathBuf, Uuid> = HashMap::new();
+    let mut results = Vec::with_capacity(profiles.len());
+    for profile in profiles {
+        let status = match state
+            .profile_manager
+            .get_profile_instance_path(profile.id)
+            .await
+        {
+            Ok(path) if updated_paths.contains_key(&path) => ServerListPushStatus::SharedFolder {
+                with_profile_id: updated_paths[&path],
+            },
+            Ok(path) if ensure_not_running(&state, profile.id, &path).await.is_err() => {
+                ServerListPushStatus::SkippedRunning
+            }
+            Ok(path) => {
+                let result = modify_server_list(profile.id, |existing| {
+                    merge_curated_servers(existing, &servers, replace)
+                })
+                .await;
+                match result {
+                    Ok(written) => {
+                        updated_paths.insert(path, profile.id);
+                        ServerListPushStatus::Updated {
+                            servers: written.len(),
+                        }
+                    }
+                    Err(e) => ServerListPushStatus::Failed {
+                        error: e.to_string(),
+                    },
+                }
+            }
+            Err(e) => ServerListPushStatus::Failed {
+                error: e.to_string(),
+            },
+        };
+        if let ServerListPushStatus::Failed { error } = &status {
+            warn!(
+                "[Servers] Failed to push server list to profile '{}': {}",
+                profile.name, error
+            );
+        }
+        results.push(ServerListPushResult {
+            profile_id: profile.id,
+            profile_name: profile.name,
+            status,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, address: &str) -> ServerListEntry {
+        ServerListEntry {
+            name: name.to_string(),
+            address: address.to_string(),
+            icon_base64: None,
+            resource_packs: ResourcePackPolicy::Prompt,
+        }
+    }
+
+    fn existing_server(name: &str, ip: &str) -> Value {
+        let mut compound = HashMap::new();
+        compound.insert("name".to_string(), Value::String(name.to_string()));
+        compound.insert("ip".to_string(), Value::String(ip.to_string()));
+        compound.insert("icon".to_string(), Value::String("aWNvbg==".to_string()));
+        compound.insert("hidden".to_string(), Value::Byte(0));
+        Value::Compound(compound)
+    }
+
+    #[test]
+    fn writes_valid_nbt_and_keeps_unknown_keys() {
+        let mut file = ServerListFile {
+            root: HashMap::new(),
+            gzipped: false,
+        };
+        file.set_servers(vec![existing_server("Old", "old.example.net")]);
+        let decoded = ServerListFile::decode(&file.encode().unwrap()).unwrap();
+        assert!(!decoded.gzipped);
+
+        let mut gzipped = decoded;
+        gzipped.gzipped = true;
+        let mut decoded = ServerListFile::decode(&gzipped.encode().unwrap()).unwrap();
+        assert!(decoded.gzipped);
+
+        let mut servers = decoded.take_servers();
+        let Value::Compound(compound) = &mut servers[0] else {
+            panic!("server entry is not a compound");
+        };
+        let mut edited = entry("Renamed", "old.example.net");
+        edited.resource_packs = ResourcePackPolicy::Disabled;
+        apply_entry(compound, &edited, None);
+        assert_eq!(compound.get("hidden"), Some(&Value::Byte(0)));
+        assert_eq!(compound.get("acceptTextures"), Some(&Value::Byte(0)));
+        assert!(!compound.contains_key("icon"));
+
+        // An empty list must still be readable
+        decoded.set_servers(Vec::new());
+        let mut empty = ServerListFile::decode(&decoded.encode().unwrap()).unwrap();
+        assert!(empty.take_servers().is_empty());
+    }
+
+    #[test]
+    fn merges_curated_servers_before_player_servers() {
+        let existing = vec![
+            existing_server("Mine", "my.server.net"),
+            existing_server("Hub", "Play.Example.net:25565"),
+        ];
+        let curated = vec![
+            entry("Example Hub", "play.example.net"),
+            entry("Event", "event.example.net"),
+        ];
+
+        let merged = merge_curated_servers(existing.clone(), &curated, false).unwrap();
+        let infos = server_infos(&merged);
+        let names: Vec<_> = infos.iter().map(|s| s.name.clone().unwrap()).collect();
+        assert_eq!(names, ["Example Hub", "Event", "Mine"]);
+        // The existing entry for the same address keeps its icon
+        assert_eq!(infos[0].icon_base64.as_deref(), Some("aWNvbg=="));
+        assert_eq!(infos[1].icon_base64, None);
+
+        let replaced = merge_curated_servers(existing, &curated, true).unwrap();
+        assert_eq!(replaced.len(), 2);
+
+        assert!(merge_curated_servers(Vec::new(), &[entry("", "a.net")], false).is_err());
+        assert!(merge_curated_servers(Vec::new(), &[entry("Bad", "a.net:port")], false).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use crate::config::{ProjectDirsExt, LAUNCHER_DIRECTORY};
+use crate::error::Result;
+use crate::state::process_state::ProcessMetadata;
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+const SESSIONS_FILENAME: &str = "sessions.jsonl";
+
+// Monitor tasks of parallel game processes append to the same file
+static APPEND_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// One finished game session, stored as a line in <root_dir>/sessions.jsonl
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionRecord {
+    /// Id of the process that ran this session
+    pub id: Uuid,
+    pub profile_id: Uuid,
+    pub profile_name: Option<String>,
+    pub account_uuid: Option<String>,
+    pub account_name: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub exit_code: Option<i32>,
+    /// Unexpected non-zero exit, stopping the game from the launcher does not count
+    pub crashed: bool,
+    /// The game ended while the launcher was not watching (e.g. after a launcher restart),
+    /// so whether it crashed is not known. Such sessions are left out of the crash rate.
+    #[serde(default)]
+    pub outcome_unknown: bool,
+    pub minecraft_version: Option<String>,
+    pub modloader: Option<String>,
+    pub modloader_version: Option<String>,
+}
+
+impl SessionRecord {
+    pub fn from_process(
+        metadata: &ProcessMetadata,
+        end_time: DateTime<Utc>,
+        exit_code: Option<i32>,
+        crashed: bool,
+    ) -> Self {
+        Self {
+            id: metadata.id,
+            profile_id: metadata.profile_id,
+            profile_name: metadata.profile_name.clone(),
+            account_uuid: metadata.account_uuid.clone(),
+            account_name: metadata.account_name.clone(),
+            start_time: metadata.start_time,
+            end_time,
+            exit_code,
+            crashed,
+            outcome_unknown: false,
+            minecraft_version: metadata.minecraft_version.clone(),
+            modloader: metadata.modloader.clone(),
+            modloader_version: metadata.modloader_version.clone(),
+        }
+    }
+
+    /// A session whose exit was never observed, it only counts towards playtime
+    pub fn from_unobserved_process(metadata: &ProcessMetadata, end_time: DateTime<Utc>) -> Self {
+        Self {
+            outcome_unknown: true,
+            ..Self::from_process(metadata, end_time, None, false)
+        }
+    }
+
+    pub fn duration_secs(&self) -> i64 {
+        (self.end_time - self.start_time).num_seconds().max(0)
+    }
+}
+
+/// Aggregated playtime of a set of sessions
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct PlaytimeStats {
+    pub sessions: usize,
+    pub total_playtime_secs: i64,
+    /// Sessions that started within the requested number of days
+    pub recent_sessions: usize,
+    pub recent_playtime_secs: i64,
+    pub crashes: usize,
+    /// Share of crashed sessions among those with a known outcome, 0.0 without any
+    pub crash_rate: f64,
+    pub last_played: Option<DateTime<Utc>>,
+}
+
+/// Playtime of one profile, used for the overview of all profiles
+#[derive(Serialize, Debug, Clone)]
+pub struct ProfilePlaytimeStats {
+    pub profile_id: Uuid,
+    pub profile_name: Option<String>,
+    #[serde(flatten)]
+    pub stats: PlaytimeStats,
+}
+
+fn sessions_path() -> PathBuf {
+    LAUNCHER_DIRECTORY.root_dir().join(SESSIONS_FILENAME)
+}
+
+/// Appends a finished session to the history file
+pub async fn append_session(record: &SessionRecord) -> Result<()> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+
+    let _guard = APPEND_LOCK.lock().await;
+    let path = sessions_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    file.flush().await?;
+
+    info!(
+        "Recorded session {} of profile {} ({}s, crashed: {})",
+        record.id,
+        record.profile_id,
+        record.duration_secs(),
+        record.crashed
+    );
+    Ok(())
+}
+
+fn parse_sessions(content: &str) -> Vec<SessionRecord> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                // A line cut off by a crash of the launcher must not hide the whole history
+                warn!("Skipping unreadable session record: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// All recorded sessions, oldest first
+pub async fn load_sessions() -> Result<Vec<SessionRecord>> {
+    let path = sessions_path();
+    if !fs::try_exists(&path).await? {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).await?;
+    Ok(parse_sessions(&content))
+}
+
+/// The latest sessions of a profile, newest first
+pub async fn get_profile_sessions(
+    profile_id: Uuid,
+    limit: Option<usize>,
+) -> Result<Vec<SessionRecord>> {
+    let mut sessions: Vec<SessionRecord> = load_sessions()
+        .await?
+        .into_iter()
+        .filter(|s| s.profile_id == profile_id)
+        .collect();
+    sessions.sort_by_key(|s| Reverse(s.start_time));
+    if let Some(limit) = limit {
+        sessions.truncate(limit);
+    }
+    Ok(sessions)
+}
+
+pub fn aggregate<'a>(
+    sessions: impl IntoIterator<Item = &'a SessionRecord>,
+    days: u32,
+    now: DateTime<Utc>,
+) -> PlaytimeStats {
+    let recent_since = now - Duration::days(i64::from(days));
+    let mut stats = PlaytimeStats::default();
+    let mut known_outcomes = 0;
+
+    for session in sessions {
+        let duration = session.duration_secs();
+        stats.sessions += 1;
+        stats.total_playtime_secs += duration;
+        if session.start_time >= recent_since {
+            stats.recent_sessions += 1;
+            stats.recent_playtime_secs += duration;
+        }
+        if !session.outcome_unknown {
+            known_outcomes += 1;
+            if session.crashed {
+                stats.crashes += 1;
+            }
+        }
+        if stats.last_played.is_none_or(|last| session.end_time > last) {
+            stats.last_played = Some(session.end_time);
+        }
+    }
+
+    if known_outcomes > 0 {
+        stats.crash_rate = stats.crashes as f64 / known_outcomes as f64;
+    }
+    stats
+}
+
+pub async fn get_profile_playtime_stats(profile_id: Uuid, days: u32) -> Result<PlaytimeStats> {
+    let sessions = load_sessions().await?;
+    Ok(aggregate(
+        sessions.iter().filter(|s| s.profile_id == profile_id),
+        days,
+        Utc::now(),
+    ))
+}
+
+pub async fn get_account_playtime_stats(account_uuid: &str, days: u32) -> Result<PlaytimeStats> {
+    let sessions = load_sessions().await?;
+    Ok(aggregate(
+        sessions
+            .iter()
+            .filter(|s| s.account_uuid.as_deref() == Some(account_uuid)),
+        days,
+        Utc::now(),
+    ))
+}
+
+fn stats_by_profile(
+    sessions: &[SessionRecord],
+    days: u32,
+    now: DateTime<Utc>,
+) -> Vec<ProfilePlaytimeStats> {
+    let mut grouped: HashMap<Uuid, Vec<&SessionRecord>> = HashMap::new();
+    for session in sessions {
+        grouped.entry(session.profile_id).or_default().push(session);
+    }
+
+    let mut result: Vec<ProfilePlaytimeStats> = grouped
+        .into_iter()
+        .map(|(profile_id, sessions)| ProfilePlaytimeStats {
+            profile_id,
+            // Newest name, the profile may have been renamed or deleted since
+            profile_name: sessions.iter().rev().find_map(|s| s.profile_name.clone()),
+            stats: aggregate(sessions.iter().copied(), days, now),
+        })
+        .collect();
+    result.sort_by_key(|p| Reverse(p.stats.total_playtime_secs));
+    result
+}
+
+/// Playtime of every profile that has recorded sessions, most played first
+pub async fn get_all_profile_playtime_stats(days: u32) -> Result<Vec<ProfilePlaytimeStats>> {
+    let sessions = load_sessions().await?;
+    Ok(stats_by_profile(&sessions, days, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(
+        profile_id: Uuid,
+        start: DateTime<Utc>,
+        minutes: i64,
+        crashed: bool,
+    ) -> SessionRecord {
+        SessionRecord {
+            id: Uuid::new_v4(),
+            profile_id,
+            profile_name: Some("Pack".to_string()),
+            account_uuid: Some("acc".to_string()),
+            account_name: None,
+            start_time: start,
+            end_time: start + Duration::minutes(minutes),
+            exit_code: Some(if crashed { 1 } else { 0 }),
+            crashed,
+            outcome_unknown: false,
+            minecraft_version: Some("1.21.1".to_string()),
+            modloader: Some("fabric".to_string()),
+            modloader_version: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_playtime_recent_sessions_and_crash_rate() {
+        let now = Utc::now();
+        let profile = Uuid::new_v4();
+        let sessions = vec![
+            session(profile, now - Duration::days(30), 60, false),
+            session(profile, now - Duration::days(2), 30, true),
+            session(profile, now - Duration::hours(3), 90, false),
+            session(profile, now - Duration::hours(1), 10, true),
+        ];
+
+        let stats = aggregate(&sessions, 7, now);
+        assert_eq!(stats.sessions, 4);
+        assert_eq!(stats.total_playtime_secs, 190 * 60);
+        assert_eq!(stats.recent_sessions, 3);
+        assert_eq!(stats.recent_playtime_secs, 130 * 60);
+        assert_eq!(stats.crashes, 2);
+        assert_eq!(stats.crash_rate, 0.5);
+        assert_eq!(stats.last_played, Some(sessions[3].end_time));
+
+        assert_eq!(aggregate(&[], 7, now), PlaytimeStats::default());
+
+        // Sessions that ended unobserved count as playtime, not towards the crash rate
+        let mut with_unknown = sessions.clone();
+        let mut unknown = session(profile, now - Duration::hours(2), 40, false);
+        unknown.exit_code = None;
+        unknown.outcome_unknown = true;
+        with_unknown.push(unknown);
+        let stats = aggregate(&with_unknown, 7, now);
+        assert_eq!(stats.sessions, 5);
+        assert_eq!(stats.total_playtime_secs, 230 * 60);
+        assert_eq!(stats.crashes, 2);
+        assert_eq!(stats.crash_rate, 0.5);
+    }
+
+    #[test]
+    fn parses_history_and_groups_by_profile() {
+        let now = Utc::now();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let records = [
+            session(a, now - Duration::days(1), 20, false),
+            session(b, now - Duration::days(1), 120, false),
+            session(a, now, 5, true),
+        ];
+        let mut content: String = records
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap() + "\n")
+            .collect();
+        content.push_str("{\"id\": \"truncated");
+
+        let parsed = parse_sessions(&content);
+        assert_eq!(parsed.len(), 3);
+
+        let by_profile = stats_by_profile(&parsed, 7, now);
+        assert_eq!(by_profile.len(), 2);
+        assert_eq!(by_profile[0].profile_id, b);
+        assert_eq!(by_profile[1].stats.sessions, 2);
+        assert_eq!(by_profile[1].stats.crashes, 1);
+    }
+}
@@ -0,0 +1,574 @@
+use crate::error::{AppError, Result};
+use crate::state::profile_state::{Profile, WorldBackupFormat};
+use crate::state::State;
+use crate::utils::backup_utils::get_backup_root;
+use crate::utils::profile_utils::collect_all_files_recursive;
+use crate::utils::world_utils::{check_world_session_lock, find_unique_world_folder_name};
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::fs;
+use uuid::Uuid;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// What caused a world snapshot to be taken
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldBackupTrigger {
+    Manual,
+    BeforeLaunch,
+    AfterExit,
+    BeforeModpackSwitch,
+}
+
+impl WorldBackupTrigger {
+    fn as_str(&self) -> &'static str {
+        match self {
+            WorldBackupTrigger::Manual => "manual",
+            WorldBackupTrigger::BeforeLaunch => "before_launch",
+            WorldBackupTrigger::AfterExit => "after_exit",
+            WorldBackupTrigger::BeforeModpackSwitch => "before_modpack_switch",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [
+            WorldBackupTrigger::Manual,
+            WorldBackupTrigger::BeforeLaunch,
+            WorldBackupTrigger::AfterExit,
+            WorldBackupTrigger::BeforeModpackSwitch,
+        ]
+        .into_iter()
+        .find(|trigger| trigger.as_str() == value)
+    }
+}
+
+impl WorldBackupFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            WorldBackupFormat::Zip => "zip",
+            WorldBackupFormat::TarZst => "tar.zst",
+        }
+    }
+}
+
+/// A snapshot archive of one world
+#[derive(Serialize, Debug, Clone)]
+pub struct WorldBackupInfo {
+    /// Archive filename, identifies the backup within its world
+    pub id: String,
+    pub profile_id: Uuid,
+    pub world_folder: String,
+    pub trigger: WorldBackupTrigger,
+    pub format: WorldBackupFormat,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+    pub path: PathBuf,
+}
+
+impl WorldBackupInfo {
+    /// Parses `<unix_millis>-<trigger>.<zip|tar.zst>`
+    fn from_path(
+        profile_id: Uuid,
+        world_folder: &str,
+        path: PathBuf,
+        size_bytes: u64,
+    ) -> Option<Self> {
+        let id = path.file_name()?.to_str()?.to_string();
+        let (stem, format) = if let Some(stem) = id.strip_suffix(".tar.zst") {
+            (stem, WorldBackupFormat::TarZst)
+        } else {
+            (id.strip_suffix(".zip")?, WorldBackupFormat::Zip)
+        };
+        let (timestamp, trigger) = stem.split_once('-')?;
+        let created_at = DateTime::from_timestamp_millis(timestamp.parse().ok()?)?;
+
+        Some(Self {
+            profile_id,
+            world_folder: world_folder.to_string(),
+            trigger: WorldBackupTrigger::parse(trigger)?,
+            format,
+            created_at,
+            size_bytes,
+            path,
+            id,
+        })
+    }
+}
+
+/// Directory holding the snapshots of one world: <backup_root>/worlds/<profile_id>/<world_folder>
+fn world_backup_dir(profile_id: Uuid, world_folder: &str) -> PathBuf {
+    get_backup_root()
+        .join("worlds")
+        .join(profile_id.to_string())
+        .join(world_folder)
+}
+
+fn validate_world_folder(world_folder: &str) -> Result<()> {
+    if world_folder.is_empty()
+        || world_folder.contains('/')
+        || world_folder.contains('\\')
+        || world_folder == "."
+        || world_folder == ".."
+    {
+        return Err(AppError::InvalidInput(
+            "Invalid world folder name provided.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn saves_dir(profile_id: Uuid) -> Result<PathBuf> {
+    let state = State::get().await?;
+    Ok(state
+        .profile_manager
+        .get_profile_instance_path(profile_id)
+        .await?
+        .join("saves"))
+}
+
+/// Snapshots a single world into a compressed archive and prunes old automatic snapshots.
+pub async fn create_world_backup(
+    profile_id: Uuid,
+    world_folder: &str,
+    trigger: WorldBackupTrigger,
+    format: WorldBackupFormat,
+    max_automatic_backups: usize,
+) -> Result<WorldBackupInfo> {
+    validate_world_folder(world_folder)?;
+    let world_path = saves_dir(profile_id).await?.join(world_folder);
+    if !world_path.is_dir() {
+        return Err(AppError::WorldNotFound {
+            profile_id,
+            world_folder: world_folder.to_string(),
+        });
+    }
+
+    let backup_dir = world_backup_dir(profile_id, world_folder);
+    fs::create_dir_all(&backup_dir).await?;
+    let archive_path = backup_dir.join(format!(
+        "{}-{}.{}",
+        Utc::now().timestamp_millis(),
+        trigger.as_str(),
+        format.extension()
+    ));
+    info!(
+        "Creating {} backup of world '{}' (profile {}) at {}",
+        trigger.as_str(),
+        world_folder,
+        profile_id,
+        archive_path.display()
+    );
+
+    let mut files = Vec::new();
+    collect_all_files_recursive(&world_path, &mut files).await?;
+    // session.lock is held by a running game and meaningless in a snapshot
+    files.retain(|file| file.file_name() != Some(OsStr::new("session.lock")));
+
+    let part_path = archive_path.with_extension("part");
+    let write_result = {
+        let world_path = world_path.clone();
+        let part_path = part_path.clone();
+        tokio::task::spawn_blocking(move || write_archive(&world_path, &files, &part_path, format))
+            .await
+            .map_err(|e| AppError::Other(format!("World backup task failed: {}", e)))?
+    };
+    if let Err(e) = write_result {
+        let _ = fs::remove_file(&part_path).await;
+        return Err(e);
+    }
+    fs::rename(&part_path, &archive_path).await?;
+
+    let size_bytes = fs::metadata(&archive_path).await?.len();
+    let backup = WorldBackupInfo::from_path(profile_id, world_folder, archive_path, size_bytes)
+        .ok_or_else(|| {
+            AppError::Other("Created world backup has an unexpected name".to_string())
+        })?;
+
+    prune_world_backups(profile_id, world_folder, max_automatic_backups).await?;
+    Ok(backup)
+}
+
+fn write_archive(
+    root: &Path,
+    files: &[PathBuf],
+    target: &Path,
+    format: WorldBackupFormat,
+) -> Result<()> {
+    let output = BufWriter::new(File::create(target)?);
+    match format {
+        WorldBackupFormat::Zip => {
+            let mut zip = zip::ZipWriter::new(output);
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated)
+                .large_file(true);
+            for file in files {
+                zip.start_file(archive_entry_name(root, file)?, options)
+                    .map_err(|e| AppError::Other(format!("Failed to write world backup: {}", e)))?;
+                std::io::copy(&mut BufReader::new(File::open(file)?), &mut zip)?;
+            }
+            zip.finish()
+                .map_err(|e| AppError::Other(format!("Failed to write world backup: {}", e)))?;
+        }
+        WorldBackupFormat::TarZst => {
+            let encoder = zstd::Encoder::new(output, ZSTD_LEVEL)?;
+            let mut tar = tar::Builder::new(encoder);
+            for file in files {
+                tar.append_path_with_name(file, archive_entry_name(root, file)?)?;
+            }
+            tar.into_inner()?.finish()?;
+        }
+    }
+    Ok(())
+}
+
+/// Relative path inside the archive, always with `/` separators
+fn archive_entry_name(root: &Path, file: &Path) -> Result<String> {
+    let relative = file.strip_prefix(root).map_err(|_| {
+        AppError::Other(format!(
+            "{} is outside of {}",
+            file.display(),
+            root.display()
+        ))
+    })?;
+    Ok(relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Lists the snapshots of one world, or of all worlds of the profile, newest first.
+pub async fn list_world_backups(
+    profile_id: Uuid,
+    world_folder: Option<&str>,
+) -> Result<Vec<WorldBackupInfo>> {
+    let profile_dir = get_backup_root()
+        .join("worlds")
+        .join(profile_id.to_string());
+    let world_folders = match world_folder {
+        Some(world_folder) => {
+            validate_world_folder(world_folder)?;
+            vec![world_folder.to_string()]
+        }
+        None => {
+            let mut folders = Vec::new();
+            if profile_dir.is_dir() {
+                let mut entries = fs::read_dir(&profile_dir).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    if entry.file_type().await?.is_dir() {
+                        folders.push(entry.file_name().to_string_lossy().to_string());
+                    }
+                }
+            }
+            folders
+        }
+    };
+
+    let mut backups = Vec::new();
+    for world_folder in world_folders {
+        let dir = world_backup_dir(profile_id, &world_folder);
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut entries = fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+            match WorldBackupInfo::from_path(profile_id, &world_folder, entry.path(), size) {
+                Some(backup) => backups.push(backup),
+                None => debug!("Ignoring unknown file in world backups: {:?}", entry.path()),
+            }
+        }
+    }
+
+    backups.sort_by_key(|backup| Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// Keeps the newest `max_automatic_backups` automatic snapshots of a world
+async fn prune_world_backups(
+    profile_id: Uuid,
+    world_folder: &str,
+    max_automatic_backups: usize,
+) -> Result<()> {
+    let automatic = list_world_backups(profile_id, Some(world_folder))
+        .await?
+        .into_iter()
+        .filter(|backup| backup.trigger != WorldBackupTrigger::Manual);
+
+    for backup in automatic.skip(max_automatic_backups) {
+        match fs::remove_file(&backup.path).await {
+            Ok(()) => info!("Removed old world backup {}", backup.path.display()),
+            Err(e) => warn!(
+                "Failed to remove old world backup {}: {}",
+                backup.path.display(),
+                e
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub async fn delete_world_backup(
+    profile_id: Uuid,
+    world_folder: &str,
+    backup_id: &str,
+) -> Result<()> {
+    let backup = find_world_backup(profile_id, world_folder, backup_id).await?;
+    fs::remove_file(&backup.path).await?;
+    info!("Deleted world backup {}", backup.path.display());
+    Ok(())
+}
+
+async fn find_world_backup(
+    profile_id: Uuid,
+    world_folder: &str,
+    backup_id: &str,
+) -> Result<WorldBackupInfo> {
+    list_world_backups(profile_id, Some(world_folder))
+        .await?
+        .into_iter()
+        .find(|backup| backup.id == backup_id)
+        .ok_or_else(|| {
+            AppError::Other(format!(
+                "World backup '{}' of '{}' not found",
+                backup_id, world_folder
+            ))
+        })
+}
+
+/// Restores a snapshot next to the existing worlds and returns the new world folder name.
+/// Refuses while the original world is in use; the original world is never overwritten.
+pub async fn restore_world_backup(
+    profile_id: Uuid,
+    world_folder: &str,
+    backup_id: &str,
+    target_world_name: Option<&str>,
+) -> Result<String> {
+    let backup = find_world_backup(profile_id, world_folder, backup_id).await?;
+    let saves_path = saves_dir(profile_id).await?;
+
+    let original_world = saves_path.join(world_folder);
+    if original_world.is_dir() {
+        if let Err(e) = check_world_session_lock(&original_world).await {
+            return Err(match e {
+                AppError::WorldLocked { .. } => AppError::WorldLocked {
+                    profile_id,
+                    world_folder: world_folder.to_string(),
+                },
+                other => other,
+            });
+        }
+    }
+
+    fs::create_dir_all(&saves_path).await?;
+    let desired_name = target_world_name
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} (restored)", world_folder));
+    let new_folder = find_unique_world_folder_name(&saves_path, &desired_name).await?;
+    info!(
+        "Restoring world backup {} of '{}' into '{}'",
+        backup.id, world_folder, new_folder
+    );
+
+    // Extract into a temporary folder first so a failed restore leaves no half world behind
+    let staging = saves_path.join(format!(".restore-{}", Uuid::new_v4().simple()));
+    let extract_result = {
+        let archive = backup.path.clone();
+        let staging = staging.clone();
+        let format = backup.format;
+        tokio::task::spawn_blocking(move || extract_archive(&archive, &staging, format))
+            .await
+            .map_err(|e| AppError::Other(format!("World restore task failed: {}", e)))?
+    };
+    if let Err(e) = extract_result {
+        error!("Failed to extract world backup {}: {}", backup.id, e);
+        let _ = fs::remove_dir_all(&staging).await;
+        return Err(e);
+    }
+    fs::rename(&staging, saves_path.join(&new_folder)).await?;
+
+    Ok(new_folder)
+}
+
+fn extract_archive(archive: &Path, target: &Path, format: WorldBackupFormat) -> Result<()> {
+    std::fs::create_dir_all(target)?;
+    let input = BufReader::new(File::open(archive)?);
+    match format {
+        WorldBackupFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(input)
+                .map_err(|e| AppError::Other(format!("Invalid world backup: {}", e)))?;
+            // extract() rejects entries escaping the target directory
+            zip.extract(target)
+                .map_err(|e| AppError::Other(format!("Failed to extract world backup: {}", e)))?;
+        }
+        WorldBackupFormat::TarZst => {
+            // unpack() skips entries with `..` or absolute paths
+            tar::Archive::new(zstd::Decoder::new(input)?).unpack(target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Automatic trigger: snapshots every world of the profile that changed since its last snapshot.
+/// Worlds whose session.lock is held by a running game are skipped. Failures are logged and never abort the surrounding launch, exit or modpack switch.
+pub async fn backup_profile_worlds(profile: &Profile, trigger: WorldBackupTrigger) {
+    let settings = &profile.settings.world_backups;
+    let saves_path = match saves_dir(profile.id).await {
+        Ok(path) if path.is_dir() => path,
+        Ok(_) => return,
+        Err(e) => {
+            warn!("Skipping world backups for profile {}: {}", profile.id, e);
+            return;
+        }
+    };
+
+    let mut entries = match fs::read_dir(&saves_path).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Could not read saves of profile {}: {}", profile.id, e);
+            return;
+        }
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let world_path = entry.path();
+        let level_dat = world_path.join("level.dat");
+        if !level_dat.is_file() {
+            continue;
+        }
+        let world_folder = entry.file_name().to_string_lossy().to_string();
+
+        // A running game may still be writing the world, a snapshot of it would be torn
+        match check_world_session_lock(&world_path).await {
+            Ok(()) => {}
+            Err(AppError::WorldLocked { .. }) => {
+                warn!(
+                    "World '{}' in profile {} is in use, skipping automatic {} backup",
+                    world_folder,
+                    profile.id,
+                    trigger.as_str()
+                );
+                continue;
+            }
+            Err(e) => {
+                warn!(
+                    "Could not check session lock of world '{}' in profile {}, skipping backup: {}",
+                    world_folder, profile.id, e
+                );
+                continue;
+            }
+        }
+
+        if !world_changed_since_last_backup(profile.id, &world_folder, &level_dat).await {
+            debug!(
+                "World '{}' unchanged since last backup, skipping",
+                world_folder
+            );
+            continue;
+        }
+
+        if let Err(e) = create_world_backup(
+            profile.id,
+            &world_folder,
+            trigger,
+            settings.format,
+            settings.max_backups_per_world,
+        )
+        .await
+        {
+            error!(
+                "Automatic {} backup of world '{}' in profile {} failed: {}",
+                trigger.as_str(),
+                world_folder,
+                profile.id,
+                e
+            );
+        }
+    }
+}
+
+/// Minecraft rewrites level.dat on every save, so its mtime tells whether the world changed
+async fn world_changed_since_last_backup(
+    profile_id: Uuid,
+    world_folder: &str,
+    level_dat: &Path,
+) -> bool {
+    let modified: Option<DateTime<Utc>> = fs::metadata(level_dat)
+        .await
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|time: SystemTime| time.into());
+    let latest_backup = list_world_backups(profile_id, Some(world_folder))
+        .await
+        .ok()
+        .and_then(|backups| backups.first().map(|b| b.created_at));
+
+    match (modified, latest_backup) {
+        (Some(modified), Some(latest_backup)) => modified > latest_backup,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip_in_both_formats() {
+        let world = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(world.path().join("region")).unwrap();
+        std::fs::write(world.path().join("level.dat"), b"level").unwrap();
+        std::fs::write(
+            world.path().join("region").join("r.0.0.mca"),
+            vec![7u8; 4096],
+        )
+        .unwrap();
+        let files = vec![
+            world.path().join("level.dat"),
+            world.path().join("region").join("r.0.0.mca"),
+        ];
+
+        for format in [WorldBackupFormat::Zip, WorldBackupFormat::TarZst] {
+            let out = tempfile::tempdir().unwrap();
+            let archive = out
+                .path()
+                .join(format!("1700000000000-manual.{}", format.extension()));
+            write_archive(world.path(), &files, &archive, format).unwrap();
+
+            let restored = out.path().join("restored");
+            extract_archive(&archive, &restored, format).unwrap();
+            assert_eq!(std::fs::read(restored.join("level.dat")).unwrap(), b"level");
+            assert_eq!(
+                std::fs::read(restored.join("region").join("r.0.0.mca")).unwrap(),
+                vec![7u8; 4096]
+            );
+
+            let info = WorldBackupInfo::from_path(Uuid::nil(), "World", archive, 0).unwrap();
+            assert_eq!(info.format, format);
+            assert_eq!(info.trigger, WorldBackupTrigger::Manual);
+            assert_eq!(info.created_at.timestamp(), 1_700_000_000);
+        }
+    }
+
+    #[test]
+    fn test_backup_names_are_parsed_strictly() {
+        let parse =
+            |name: &str| WorldBackupInfo::from_path(Uuid::nil(), "World", PathBuf::from(name), 0);
+        assert_eq!(
+            parse("1700000000000-before_modpack_switch.tar.zst")
+                .unwrap()
+                .trigger,
+            WorldBackupTrigger::BeforeModpackSwitch
+        );
+        assert!(parse("1700000000000-manual.part").is_none());
+        assert!(parse("1700000000000-unknown.zip").is_none());
+        assert!(parse("notes.txt").is_none());
+    }
+}
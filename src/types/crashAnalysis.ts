@@ -0,0 +1,40 @@
+export type CrashSignature =
+  | {
+      kind: "missing_dependency";
+      mod_id: string | null;
+      dependency: string;
+      version: string | null;
+    }
+  | { kind: "mixin_failure"; mod_id: string | null; mixin_config: string | null }
+  | { kind: "wrong_java_version"; required: number | null; found: number | null }
+  | { kind: "out_of_memory" }
+  | { kind: "duplicate_mod"; mod_id: string; files: string[] }
+  | { kind: "graphics_driver"; detail: string }
+  | { kind: "wrong_loader"; file: string; built_for: string };
+
+export type CrashModRef =
+  | { type: "profile"; id: string; name: string; enabled: boolean }
+  | { type: "custom"; filename: string; enabled: boolean };
+
+export type CrashSuggestedAction =
+  | { action: "disable_mod"; target: CrashModRef }
+  | { action: "enable_mod"; target: CrashModRef }
+  | { action: "install_dependency"; mod_id: string; version: string | null }
+  | { action: "raise_memory"; current_mb: number; suggested_mb: number }
+  | { action: "switch_java"; required_major: number | null }
+  | { action: "remove_duplicates"; mod_id: string; files: string[] }
+  | { action: "update_graphics_driver" };
+
+export interface CrashFinding {
+  signature: CrashSignature;
+  summary: string;
+  evidence: string;
+  mods: CrashModRef[];
+  actions: CrashSuggestedAction[];
+}
+
+export interface CrashDiagnosis {
+  profile_id: string;
+  sources: string[];
+  findings: CrashFinding[];
+}
@@ -1,4 +1,5 @@
 import { ProcessMetadata } from "./processState";
+import { CrashDiagnosis } from "./crashAnalysis";
 
 export enum EventType {
   InstallingJava = "installing_java",
@@ -31,6 +32,7 @@ export enum EventType {
   MigrationStarted = "migration_started",
   MigrationCompleted = "migration_completed",
   MigrationFailed = "migration_failed",
+  DependencyIssues = "dependency_issues",
 }
 
 export interface EventPayload {
@@ -49,6 +51,7 @@ export interface MinecraftProcessExitedPayload {
   success: boolean;
   process_metadata: ProcessMetadata | null;
   crash_report_content?: string;
+  crash_diagnosis?: CrashDiagnosis | null;
 }
 
 export interface CrashReportContentAvailablePayload {
@@ -0,0 +1,23 @@
+import type { ModLoader } from "./profile";
+
+export type ExternalLauncher =
+  | "prism_launcher"
+  | "multi_mc"
+  | "vanilla_launcher"
+  | "modrinth_app";
+
+export interface ExternalInstance {
+  launcher: ExternalLauncher;
+  name: string;
+  game_dir: string;
+  game_version: string | null;
+  loader: ModLoader;
+  loader_version: string | null;
+}
+
+export interface ExternalImportResult {
+  profile_id: string;
+  modrinth_mods: number;
+  curseforge_mods: number;
+  custom_mods: string[];
+}
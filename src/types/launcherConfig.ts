@@ -12,6 +12,12 @@ export interface MemorySettings {
   max: number; // u32
 }
 
+export interface GameOptionsSettings {
+  default_preset_id: string | null; // Option<Uuid>
+  sync_enabled: boolean;
+  sync_keys: string[]; // Vec<String>, trailing "*" matches a prefix
+}
+
 export interface LauncherConfig {
   version: number; // u32
   is_experimental: boolean;
@@ -27,4 +33,5 @@ export interface LauncherConfig {
   global_memory_settings: MemorySettings;
   custom_game_directory: string | null; // Option<PathBuf>
   default_modpack_installed: boolean; // New field for tracking default modpack installation
+  game_options: GameOptionsSettings;
 }
\ No newline at end of file
@@ -0,0 +1,50 @@
+import type { ModLoader } from "./profile";
+import type { ModPlatform, UnifiedVersion } from "./unified";
+
+export interface ProjectRef {
+  platform: ModPlatform;
+  project_id: string;
+}
+
+export interface DependencyMod {
+  mod_id: string | null;
+  name: string;
+}
+
+export interface MissingDependency {
+  project: ProjectRef;
+  required_by: DependencyMod[];
+  disabled_mod_id: string | null;
+  candidate: UnifiedVersion | null;
+}
+
+export interface ModConflict {
+  declared_by: DependencyMod;
+  incompatible_with: DependencyMod;
+}
+
+export interface DuplicateProject {
+  project: ProjectRef;
+  mods: DependencyMod[];
+}
+
+export interface MissingModId {
+  required_by: DependencyMod;
+  mod_id: string;
+  version_range: string | null;
+}
+
+export interface WrongLoaderJar {
+  jar: DependencyMod;
+  loaders: ModLoader[];
+}
+
+export interface DependencyReport {
+  profile_id: string;
+  missing: MissingDependency[];
+  conflicts: ModConflict[];
+  duplicates: DuplicateProject[];
+  missing_mod_ids: MissingModId[];
+  wrong_loader: WrongLoaderJar[];
+  unchecked: DependencyMod[];
+}
@@ -0,0 +1,54 @@
+import type { ModPlatform } from "./unified";
+
+export interface ChangelogEntry {
+  version: string;
+  date: string | null;
+  changelog: string;
+}
+
+export interface PlannedModUpdate {
+  mod_id: string;
+  name: string;
+  platform: ModPlatform;
+  project_id: string;
+  from_version: string | null;
+  to_version: string;
+  to_version_id: string;
+  file_name: string;
+  changelogs: ChangelogEntry[];
+  major_version_change: boolean;
+}
+
+export interface SkippedModUpdate {
+  mod_id: string;
+  name: string;
+  reason: string;
+}
+
+export interface ModUpdatePlan {
+  profile_id: string;
+  updates: PlannedModUpdate[];
+  skipped: SkippedModUpdate[];
+}
+
+export type ModUpdateStatus =
+  | { status: "updated" }
+  | { status: "not_applied" }
+  | { status: "failed"; error: string };
+
+export type ModUpdateResult = {
+  mod_id: string;
+  name: string;
+  from_version: string | null;
+  to_version: string;
+} & ModUpdateStatus;
+
+export interface BatchUpdateResult {
+  plan: ModUpdatePlan;
+  dry_run: boolean;
+  applied: boolean;
+  snapshot_id: string | null;
+  // Set if restoring snapshot_id after a failed update failed too
+  rollback_error: string | null;
+  results: ModUpdateResult[];
+}
@@ -0,0 +1,18 @@
+export interface OptionValue {
+  key: string;
+  value: string;
+}
+
+export interface OptionsPreset {
+  id: string;
+  name: string;
+  created_at: string;
+  options: OptionValue[];
+}
+
+export type OptionsApplyStatus =
+  | { status: "applied"; options: number }
+  | { status: "skipped_running" }
+  | { status: "failed"; error: string };
+
+export type OptionsApplyResult = { profile_id: string } & OptionsApplyStatus;
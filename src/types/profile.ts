@@ -97,6 +97,7 @@ export interface ModSourceUrl extends ModSourceBase {
   type: "url";
   url: string;
   file_name: string | null;
+  file_hash_sha1?: string | null;
 }
 
 export interface ModSourceMaven extends ModSourceBase {
@@ -177,10 +178,36 @@ export interface ModPackInfo {
   file_hash?: string | null;
 }
 
+export type JarDependencyKind = "required" | "optional" | "incompatible";
+
+export interface JarDependency {
+  mod_id: string;
+  version_range: string | null;
+  kind: JarDependencyKind;
+}
+
+export interface JarModMetadata {
+  mod_id: string;
+  name: string | null;
+  version: string | null;
+  description: string | null;
+  loader: ModLoader;
+  minecraft_versions: string | null;
+  dependencies: JarDependency[];
+  provides: string[];
+  icon: string | null;
+}
+
+export interface JarMetadata {
+  sha1: string;
+  mods: JarModMetadata[];
+}
+
 export interface CustomModInfo {
   filename: string;
   is_enabled: boolean;
   path: string;
+  metadata?: JarMetadata | null; // Filled by get_custom_mods
 }
 
 export interface Profile {
@@ -425,6 +452,7 @@ export interface LocalContentItem {
   // Neue Felder für ModPack-Integration
   modpack_origin?: string | null; // "modrinth:project_id" oder "curseforge:project_id:file_id"
   updates_enabled?: boolean | null; // null = Standard (true), true/false = explizit gesetzt
+  jar_metadata?: JarMetadata | null; // Loader metadata read from the jar (mods, needs hashes)
   // Frontend specific fields can be added here if needed, e.g., for UI state
   // local_icon_data_url?: string; // Example if we were to add this later
 }
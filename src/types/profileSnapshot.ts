@@ -0,0 +1,29 @@
+import type { Mod, ModLoader, ModPackInfo } from "./profile";
+
+export type SnapshotTrigger =
+  | "manual"
+  | "before_bulk_update"
+  | "before_modpack_switch"
+  | "before_rollback";
+
+export interface ProfileSnapshot {
+  id: string;
+  profile_id: string;
+  name: string;
+  trigger: SnapshotTrigger;
+  created_at: string;
+  game_version: string;
+  loader: ModLoader;
+  loader_version: string | null;
+  modpack_info: ModPackInfo | null;
+  mods: Mod[];
+  config_paths: string[];
+}
+
+export interface SnapshotRollbackResult {
+  undo_snapshot_id: string;
+  restored_mods: number;
+  restored_config_paths: string[];
+  cached_files: string[];
+  missing_files: string[];
+}
@@ -0,0 +1,20 @@
+export type ResourcePackPolicy = "prompt" | "enabled" | "disabled";
+
+export interface ServerListEntry {
+  name: string;
+  address: string;
+  /** Base64 PNG, resized to 64x64 by the backend if needed */
+  icon_base64?: string | null;
+  resource_packs?: ResourcePackPolicy;
+}
+
+export type ServerListPushStatus =
+  | { status: "updated"; servers: number }
+  | { status: "shared_folder"; with_profile_id: string }
+  | { status: "skipped_running" }
+  | { status: "failed"; error: string };
+
+export type ServerListPushResult = {
+  profile_id: string;
+  profile_name: string;
+} & ServerListPushStatus;
@@ -0,0 +1,31 @@
+export interface SessionRecord {
+  id: string;
+  profile_id: string;
+  profile_name: string | null;
+  account_uuid: string | null;
+  account_name: string | null;
+  start_time: string;
+  end_time: string;
+  exit_code: number | null;
+  crashed: boolean;
+  // Ended while the launcher was not watching, not part of the crash rate
+  outcome_unknown: boolean;
+  minecraft_version: string | null;
+  modloader: string | null;
+  modloader_version: string | null;
+}
+
+export interface PlaytimeStats {
+  sessions: number;
+  total_playtime_secs: number;
+  recent_sessions: number;
+  recent_playtime_secs: number;
+  crashes: number;
+  crash_rate: number;
+  last_played: string | null;
+}
+
+export interface ProfilePlaytimeStats extends PlaytimeStats {
+  profile_id: string;
+  profile_name: string | null;
+}